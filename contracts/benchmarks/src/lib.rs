@@ -0,0 +1,112 @@
+//! Gas and storage budgets for the groupweave contracts. The sandbox benchmarks in `tests/`
+//! deploy the real contracts with near-workspaces, run each method at every participant count in
+//! [`PARTICIPANT_COUNTS`] and fail when a call burns more gas, or grows contract storage by more
+//! bytes, than its [`Budget`]. A failing benchmark prints the full [`Report`] table.
+//!
+//! Run with `cargo test -p groupweave-benchmarks -- --nocapture` (needs `cargo-near` and the
+//! sandbox). Raise a budget only together with the change that needs it.
+
+use std::fmt::Write;
+
+/// Participants per bounty the benchmarks run at, up to the contract's cap of 150.
+pub const PARTICIPANT_COUNTS: [usize; 4] = [1, 10, 50, 150];
+
+const TGAS: u64 = 1_000_000_000_000;
+
+/// The most a single call may burn, across all its receipts, and the most contract storage it
+/// may add. `max_storage_bytes` is None where a call frees as much as it stores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub method: &'static str,
+    pub max_tgas: u64,
+    pub max_storage_bytes: Option<i64>,
+}
+
+/// Budgets for the content bounty market. close_bounty keeps about 60 Tgas of the 300 Tgas
+/// transaction limit free for the NFT mint, reputation and leaderboard reports it can trigger.
+pub const BOUNTY_BUDGETS: [Budget; 5] = [
+    Budget { method: "create_content_bounty", max_tgas: 20, max_storage_bytes: Some(1_500) },
+    Budget { method: "submit_content", max_tgas: 20, max_storage_bytes: Some(600) },
+    Budget { method: "stake_on_submission", max_tgas: 30, max_storage_bytes: Some(400) },
+    Budget { method: "close_bounty", max_tgas: 240, max_storage_bytes: Some(1_000) },
+    Budget { method: "claim_bounty_winnings", max_tgas: 30, max_storage_bytes: None },
+];
+
+pub fn budget(method: &str) -> Budget {
+    BOUNTY_BUDGETS
+        .iter()
+        .find(|budget| budget.method == method)
+        .copied()
+        .unwrap_or_else(|| panic!("No budget for {}", method))
+}
+
+/// One call's cost: gas burnt by the transaction and all its receipts, and the change in the
+/// contract's storage usage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub method: &'static str,
+    pub participants: usize,
+    pub gas_burnt: u64,
+    pub storage_delta: i64,
+}
+
+impl Measurement {
+    pub fn is_over_budget(&self) -> bool {
+        let budget = budget(self.method);
+        self.gas_burnt > budget.max_tgas * TGAS
+            || budget.max_storage_bytes.is_some_and(|max| self.storage_delta > max)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Report {
+    pub fn record(&mut self, method: &'static str, participants: usize, gas_burnt: u64, storage_delta: i64) {
+        self.measurements.push(Measurement { method, participants, gas_burnt, storage_delta });
+    }
+
+    pub fn over_budget(&self) -> Vec<&Measurement> {
+        self.measurements.iter().filter(|measurement| measurement.is_over_budget()).collect()
+    }
+
+    /// One row per measurement: method, participants, Tgas burnt against the budget and the
+    /// storage delta against its budget, over-budget rows marked.
+    pub fn table(&self) -> String {
+        let mut table = format!("{:<24} {:>12} {:>16} {:>16}\n", "method", "participants", "Tgas / budget", "bytes / budget");
+        for measurement in &self.measurements {
+            let budget = budget(measurement.method);
+            let storage_budget = budget.max_storage_bytes.map_or("-".to_string(), |max| max.to_string());
+            let _ = writeln!(
+                table,
+                "{:<24} {:>12} {:>16} {:>16}{}",
+                measurement.method,
+                measurement.participants,
+                format!("{:.1} / {}", measurement.gas_burnt as f64 / TGAS as f64, budget.max_tgas),
+                format!("{} / {}", measurement.storage_delta, storage_budget),
+                if measurement.is_over_budget() { "  OVER BUDGET" } else { "" }
+            );
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn over_budget_checks_gas_and_storage() {
+        let mut report = Report::default();
+        report.record("stake_on_submission", 150, 29 * TGAS, 400);
+        report.record("stake_on_submission", 150, 31 * TGAS, 0);
+        report.record("submit_content", 1, TGAS, 601);
+        report.record("claim_bounty_winnings", 150, TGAS, 10_000);
+
+        let over: Vec<_> = report.over_budget().into_iter().map(|measurement| measurement.gas_burnt).collect();
+        assert_eq!(over, vec![31 * TGAS, TGAS]);
+        assert_eq!(report.table().matches("OVER BUDGET").count(), 2);
+    }
+}
@@ -0,0 +1,145 @@
+use groupweave_benchmarks::{Report, PARTICIPANT_COUNTS};
+use near_sdk::NearToken;
+use near_workspaces::network::Sandbox;
+use near_workspaces::{Account, Contract, Worker};
+use serde_json::{json, Value};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+/// Blocks to fast-forward past a one-day bounty (the sandbox advances about a second per block).
+const ONE_DAY_OF_BLOCKS: u64 = 100_000;
+
+async fn deploy_bounty_market(sandbox: &Worker<Sandbox>) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("../content-bounty-market").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract
+        .call("new")
+        .args_json(json!({
+            "reward_rate": 100u128,
+            "min_stake_amount": NearToken::from_near(1).as_yoctonear().to_string(),
+            "max_stake_amount": NearToken::from_near(10_000).as_yoctonear().to_string(),
+        }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "bounty market init failed: {:?}", outcome);
+    Ok(contract)
+}
+
+/// Call `method` on the market and record its gas and the market's storage change.
+async fn measure(
+    report: &mut Report,
+    market: &Contract,
+    caller: &Account,
+    method: &'static str,
+    participants: usize,
+    args: Value,
+    deposit: NearToken,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    let storage_before = market.view_account().await?.storage_usage as i64;
+    let outcome = caller
+        .call(market.id(), method)
+        .args_json(args)
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{} failed at {} participants: {:?}", method, participants, outcome);
+    let storage_after = market.view_account().await?.storage_usage as i64;
+    report.record(method, participants, outcome.total_gas_burnt.as_gas(), storage_after - storage_before);
+    Ok(outcome.json().unwrap_or(Value::Null))
+}
+
+/// Create a bounty with two submissions, have `participants` backers stake on them (the first on
+/// submission 0 with the largest stake, so it wins), close it and claim as the first backer.
+/// The last stake, which checks the fullest participant list, is the one recorded.
+async fn run_bounty(
+    sandbox: &Worker<Sandbox>,
+    market: &Contract,
+    participants: usize,
+    report: &mut Report,
+) -> TestResult {
+    let creator = sandbox.dev_create_account().await?;
+    let bounty_id = measure(
+        report,
+        market,
+        &creator,
+        "create_content_bounty",
+        participants,
+        json!({
+            "title": "Benchmark bounty",
+            "description": "Gas at the participant cap",
+            "requirements": "Submit a creation",
+            "base_prize": NearToken::from_near(1).as_yoctonear().to_string(),
+            "max_stake_per_user": NearToken::from_near(5).as_yoctonear().to_string(),
+            "duration_days": 1
+        }),
+        NearToken::from_near(2),
+    )
+    .await?
+    .as_u64()
+    .expect("bounty id");
+
+    for index in 0..2 {
+        let author = sandbox.dev_create_account().await?;
+        measure(
+            report,
+            market,
+            &author,
+            "submit_content",
+            participants,
+            json!({
+                "bounty_id": bounty_id,
+                "creation_id": format!("creation-{}-{}", bounty_id, index),
+                "title": format!("Submission {}", index),
+                "thumbnail_url": format!("https://example.com/{}.png", index)
+            }),
+            NearToken::from_near(0),
+        )
+        .await?;
+    }
+
+    let mut backers = Vec::with_capacity(participants);
+    for index in 0..participants {
+        let backer = sandbox.dev_create_account().await?;
+        let stake = if index == 0 { NearToken::from_near(2) } else { NearToken::from_near(1) };
+        let args = json!({ "bounty_id": bounty_id, "submission_index": index % 2 });
+        if index + 1 == participants {
+            measure(report, market, &backer, "stake_on_submission", participants, args, stake).await?;
+        } else {
+            let staked = backer
+                .call(market.id(), "stake_on_submission")
+                .args_json(args)
+                .deposit(stake)
+                .transact()
+                .await?;
+            assert!(staked.is_success(), "stake failed: {:?}", staked);
+        }
+        backers.push(backer);
+    }
+
+    sandbox.fast_forward(ONE_DAY_OF_BLOCKS).await?;
+    let bounty = json!({ "bounty_id": bounty_id });
+    measure(report, market, &creator, "close_bounty", participants, bounty.clone(), NearToken::from_near(0)).await?;
+
+    // A single participant is refunded at close and has no winnings to claim
+    if participants > 1 {
+        measure(report, market, &backers[0], "claim_bounty_winnings", participants, bounty, NearToken::from_near(0))
+            .await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bounty_methods_stay_within_budget() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+
+    let mut report = Report::default();
+    for participants in PARTICIPANT_COUNTS {
+        run_bounty(&sandbox, &market, participants, &mut report).await?;
+    }
+
+    println!("{}", report.table());
+    assert!(report.over_budget().is_empty(), "methods over budget:\n{}", report.table());
+    Ok(())
+}
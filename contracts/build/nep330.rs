@@ -0,0 +1,52 @@
+//! Build script shared by the contract crates (`build = "../build/nep330.rs"`).
+//!
+//! Stamps the NEP-330 `contract_source_metadata` that near-sdk generates for each contract:
+//! `NEP330_VERSION` becomes `<crate version>+<short commit>` and `NEP330_LINK` points at the
+//! source tree of that commit. Values already set in the environment (e.g. by a reproducible
+//! `cargo near build`) are left alone, and without git the crate version and repository are used.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+const VERSION_KEY: &str = "NEP330_VERSION";
+const LINK_KEY: &str = "NEP330_LINK";
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={}", VERSION_KEY);
+    println!("cargo:rerun-if-env-changed={}", LINK_KEY);
+
+    // Rebuild when HEAD moves so the stamped commit never goes stale
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        for file in ["HEAD", "logs/HEAD"] {
+            let path = PathBuf::from(&git_dir).join(file);
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+    }
+
+    let Some(commit) = git(&["rev-parse", "HEAD"]) else {
+        return;
+    };
+    if env::var_os(VERSION_KEY).is_none() {
+        let version = env::var("CARGO_PKG_VERSION").unwrap_or_default();
+        println!("cargo:rustc-env={}={}+{}", VERSION_KEY, version, &commit[..12.min(commit.len())]);
+    }
+    if env::var_os(LINK_KEY).is_none() {
+        if let Ok(repository) = env::var("CARGO_PKG_REPOSITORY") {
+            if !repository.is_empty() {
+                println!("cargo:rustc-env={}={}/tree/{}", LINK_KEY, repository.trim_end_matches('/'), commit);
+            }
+        }
+    }
+}
@@ -21,11 +21,26 @@
 // - Cost of Attack: Capital lockup + gas fees + risk of social slashing (ban).
 // - Rate Limiting: Max stake per user and max submissions enforced.
 
+use groupweave_events::bounty::{
+    BountyCleanedUp, BountyClosed, BountyCreated, ContentSubmitted, SubmissionStaked, WinningsClaimed,
+};
+use groupweave_events::Nep297Event;
+use groupweave_utils::upgrade::{deploy_and_migrate, record_state_version, stored_state_version};
+use groupweave_utils::{
+    apply_bps, apply_percent, page_limit, paginate, pro_rata, AccessControl, BackerStake, ReputationSignal, Role,
+    SettlementReport, BPS_DENOMINATOR, MAX_PAGE_LIMIT,
+};
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LookupMap;
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::json_types::U128;
-use near_sdk::{env, near, require, AccountId, PanicOnDefault, Promise, NearToken};
+use near_sdk::{
+    env, ext_contract, is_promise_success, near, require, AccountId, Gas, PanicOnDefault, Promise, PromiseError,
+    PromiseOrValue, NearToken,
+};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use schemars::JsonSchema;
 
@@ -39,6 +54,135 @@ const MIN_BOUNTY_DURATION: u64 = 1; // Minimum bounty duration in blocks
 const MAX_PARTICIPANTS_PER_BOUNTY: usize = 150; // Maximum participants to prevent DOS during reward distribution
 const DEFAULT_CREATOR_SHARE: u8 = 90; // Default 90% to winning creator
 const DEFAULT_BACKER_SHARE: u8 = 10; // Default 10% to backers
+const DEFAULT_STAKE_BOOST_BPS: u16 = 5000; // A stake placed at creation weighs 1.5x one placed at the deadline
+const MAX_STAKE_BOOST_BPS: u16 = 10_000;
+const CLOSE_GRACE_PERIOD_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // After this, anyone may close an expired bounty
+const MAX_TICK_SCAN: u64 = 20; // Bounty ids one maintenance tick inspects
+const MAX_TICK_CLOSES: usize = 3; // Bounties one tick settles, each with its own payouts and reports
+const CLAIM_PERIOD_NS: u64 = 90 * 24 * 60 * 60 * 1_000_000_000; // Claim window after ends_at, then cleanup
+const GAS_FOR_CREDITS_DEPOSIT: Gas = Gas::from_tgas(30); // deposit_native on the deposits contract
+const GAS_FOR_CREDITS_CALLBACK: Gas = Gas::from_tgas(10);
+const GAS_FOR_NFT_MINT: Gas = Gas::from_tgas(30); // nft_mint on the content NFT contract
+const GAS_FOR_NFT_MINT_CALLBACK: Gas = Gas::from_tgas(10);
+// Storage deposit attached to each winner mint, taken from the platform fee; the excess is refunded
+const NFT_MINT_DEPOSIT: NearToken = NearToken::from_millinear(20);
+const MAX_NFT_ROYALTY_BPS: u16 = 5000; // The NFT contract caps royalties at 50%
+const MAX_ROYALTY_BACKERS: u8 = 8; // Plus creator and platform, within the NFT's 10 recipients
+const GAS_FOR_REPUTATION_RECORD: Gas = Gas::from_tgas(20); // record on the reputation contract, up to 150 accounts
+const GAS_FOR_REPUTATION_READ: Gas = Gas::from_tgas(5); // get_score on the reputation contract
+const GAS_FOR_REPUTATION_CALLBACK: Gas = Gas::from_tgas(20); // activates the bounty or stores the submission
+const GAS_FOR_LEADERBOARD_RECORD: Gas = Gas::from_tgas(30); // record_settlement on the leaderboard, up to 150 backers
+const GAS_FOR_CREATION_VERIFY: Gas = Gas::from_tgas(10); // verify_creation on the creation registry
+const GAS_FOR_CREATION_VERIFY_CALLBACK: Gas = Gas::from_tgas(10);
+const GAS_FOR_BOND_REFUND_CALLBACK: Gas = Gas::from_tgas(5); // returns the bond of a gated submission that failed
+const GAS_FOR_TRANSFER_CALLBACK: Gas = Gas::from_tgas(5); // keeps a failed NEAR transfer for retry_transfer
+const MAX_SUBMISSION_BOND: NearToken = NearToken::from_near(100);
+const MAX_COMMUNITY_ID_LEN: usize = 32;
+const MAX_COMMUNITY_MODERATORS: usize = 20;
+const MAX_REASON_HASH_LEN: usize = 128;
+const MAX_TITLE_LEN: usize = 200; // Bounty and submission titles, in bytes
+const MAX_DESCRIPTION_LEN: usize = 1000;
+const MAX_REQUIREMENTS_LEN: usize = 2000;
+const MIN_BASE_PRIZE: NearToken = NearToken::from_near(1);
+const MIN_MAX_STAKE_PER_USER: NearToken = NearToken::from_millinear(100); // Bounds of a bounty's max_stake_per_user
+const MAX_MAX_STAKE_PER_USER: NearToken = NearToken::from_near(10000);
+const MIN_CREATOR_SHARE: u8 = 30;
+const MAX_CREATOR_SHARE: u8 = 90;
+const MIN_DURATION_DAYS: u64 = 1;
+const MAX_DURATION_DAYS: u64 = 90;
+const MAX_START_DELAY_NS: u64 = 90 * 24 * 60 * 60 * 1_000_000_000; // How far ahead a bounty can be scheduled
+const MAX_FT_PRIZES_PER_BOUNTY: usize = 10; // Sponsor transfers per bounty, each credited at settlement
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(25);
+const GAS_FOR_FT_WITHDRAW_CALLBACK: Gas = Gas::from_tgas(10);
+// Layout version of the contract state; bump it once per release that changes fields, and migrate
+// from the layout that release replaces
+const STATE_VERSION: u32 = 1;
+
+// Dreamweave deposits contract, used to settle winnings as platform credits
+#[ext_contract(ext_deposits)]
+pub trait ExtDeposits {
+    fn deposit_native(
+        &mut self,
+        beneficiary_id: String,
+        credits_hint: Option<u64>,
+        memo: Option<String>,
+        promo_code: Option<String>,
+        on_behalf_of: Option<AccountId>,
+    );
+}
+
+// Content NFT contract that winning submissions are minted on
+#[ext_contract(ext_content_nft)]
+pub trait ExtContentNft {
+    fn nft_mint(
+        &mut self,
+        token_id: String,
+        receiver_id: AccountId,
+        token_metadata: TokenMetadata,
+        royalties: HashMap<AccountId, u32>,
+    );
+}
+
+// Reputation contract that settlement outcomes are reported to and high-value bounties are gated on
+#[ext_contract(ext_reputation)]
+pub trait ExtReputation {
+    fn record(&mut self, account_ids: Vec<AccountId>, signal: ReputationSignal);
+    fn get_score(&self, account_id: AccountId) -> u64;
+}
+
+// Leaderboard contract that settlements are pushed to for the platform-wide rankings
+#[ext_contract(ext_leaderboard)]
+pub trait ExtLeaderboard {
+    fn record_settlement(&mut self, report: SettlementReport);
+}
+
+// Dreamweave creation registry (or an oracle's contract) that confirms a creation exists and who owns it
+#[ext_contract(ext_creation_registry)]
+pub trait ExtCreationRegistry {
+    fn verify_creation(&self, creation_id: String, owner_id: AccountId) -> bool;
+}
+
+// Fungible tokens added to bounty prizes
+#[ext_contract(ext_ft)]
+pub trait ExtFungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+// Royalty split stamped on each winner NFT, in basis points of a resale
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltySplit {
+    pub creator_bps: u16,
+    pub backers_bps: u16, // Shared pro rata by the top backers of the winning submission
+    pub platform_bps: u16, // Paid to the contract owner
+    pub top_backers: u8,
+}
+
+impl Default for RoyaltySplit {
+    fn default() -> Self {
+        Self { creator_bps: 500, backers_bps: 300, platform_bps: 200, top_backers: 3 }
+    }
+}
+
+// Minimum reputation scores on bounties whose base prize reaches high_value_prize
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReputationGate {
+    #[schemars(with = "String")]
+    pub high_value_prize: U128,
+    pub min_creator_score: u64, // To create such a bounty
+    pub min_submitter_score: u64, // To submit to one
+}
+
+impl Default for ReputationGate {
+    fn default() -> Self {
+        Self {
+            high_value_prize: U128(NearToken::from_near(100).as_yoctonear()),
+            min_creator_score: 10,
+            min_submitter_score: 10,
+        }
+    }
+}
 
 // Content submission for a bounty
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -73,6 +217,26 @@ pub struct Bounty {
     pub winning_submission: Option<u64>,
 }
 
+// Bond a bounty's creator requires with each submission. It is returned to submissions whose
+// stakes reach min_qualifying_stake and added to the base prize for the others at settlement.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionBond {
+    #[schemars(with = "String")]
+    pub bond: U128,
+    #[schemars(with = "String")]
+    pub min_qualifying_stake: U128,
+}
+
+// Early-backer weighting a bounty was created with. A winning backer's share of the backer pool
+// follows stake * weight, where the weight falls linearly from 1 + max_boost_bps / 10000 for a
+// stake placed at creation to 1 for one placed at ends_at.
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct StakeWeighting {
+    pub max_boost_bps: u16,
+    pub weighted_winning_stake: u128, // Sum of the winning backers' weighted stakes, set at settlement
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct ParticipantStake {
@@ -82,6 +246,16 @@ pub struct ParticipantStake {
     pub staked_at: u64,
 }
 
+// Creation registry result for a submission. Rejected submissions cannot be staked on or win.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum CreationVerification {
+    Pending,
+    Verified,
+    Rejected,    // The registry does not know the creation or it belongs to someone else
+    Unavailable, // The registry call failed; verify_submission retries it
+}
+
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct ContentSubmissionView {
@@ -93,6 +267,8 @@ pub struct ContentSubmissionView {
     #[schemars(with = "String")]
     pub total_staked: U128,
     pub submitted_at: u64,
+    pub verification: Option<CreationVerification>, // None when no registry was configured at submission
+    pub flag: Option<SubmissionFlag>,
 }
 
 impl From<ContentSubmission> for ContentSubmissionView {
@@ -104,10 +280,46 @@ impl From<ContentSubmission> for ContentSubmissionView {
             thumbnail_url: sub.thumbnail_url,
             total_staked: U128(sub.total_staked.as_yoctonear()),
             submitted_at: sub.submitted_at,
+            verification: None,
+            flag: None,
         }
     }
 }
 
+// What one maintenance tick did
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TickReport {
+    pub scanned: u64,
+    pub closed: Vec<u64>,
+    pub next_bounty_id: u64, // Where the next tick resumes
+}
+
+// Kept in place of a bounty once cleanup_bounty has deleted its submissions and stakes
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountySummary {
+    pub id: u64,
+    pub title: String,
+    #[schemars(with = "String")]
+    pub creator: AccountId,
+    #[schemars(with = "String")]
+    pub base_prize: U128,
+    #[schemars(with = "String")]
+    pub total_staked: U128,
+    pub winning_submission: Option<u64>,
+    #[schemars(with = "Option<String>")]
+    pub winner: Option<AccountId>, // Creator of the winning submission
+    pub winning_creation_id: Option<String>,
+    pub nft_token_id: Option<String>,
+    pub submission_count: u64,
+    pub participant_count: u64,
+    pub unclaimed_stakes: u64, // Stakes still unclaimed when the claim period ran out
+    pub ends_at: u64,
+    pub is_emergency_closed: bool,
+    pub cleaned_up_at: u64,
+}
+
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct BountyView {
@@ -131,6 +343,11 @@ pub struct BountyView {
     pub total_staked: U128,
     pub is_closed: bool,
     pub winning_submission: Option<u64>,
+    pub is_emergency_closed: bool,
+    pub max_stake_boost_bps: u16, // Early-backer boost fixed at creation; 0 for unweighted rewards
+    pub submission_bond: Option<SubmissionBond>,
+    pub community_id: Option<String>,
+    pub starts_at: Option<u64>, // Scheduled start; submissions and stakes open then
 }
 
 impl From<Bounty> for BountyView {
@@ -152,6 +369,11 @@ impl From<Bounty> for BountyView {
             total_staked: U128(bounty.total_staked.as_yoctonear()),
             is_closed: bounty.is_closed,
             winning_submission: bounty.winning_submission,
+            is_emergency_closed: false,
+            max_stake_boost_bps: 0,
+            submission_bond: None,
+            community_id: None,
+            starts_at: None,
         }
     }
 }
@@ -164,6 +386,7 @@ pub struct ParticipantStakeView {
     #[schemars(with = "String")]
     pub amount: U128,
     pub staked_at: u64,
+    pub weight_bps: u32, // Reward weight of this stake; 10000 is unweighted
 }
 
 impl From<ParticipantStake> for ParticipantStakeView {
@@ -173,10 +396,242 @@ impl From<ParticipantStake> for ParticipantStakeView {
             submission_index: stake.submission_index,
             amount: U128(stake.amount.as_yoctonear()),
             staked_at: stake.staked_at,
+            weight_bps: BPS_DENOMINATOR as u32,
         }
     }
 }
 
+// A community sub-market registered by the owner. Its bounties pay the platform fee at
+// `fee_rate` (the global rate when None) to `treasury`, and its moderators can act on its bounties.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Community {
+    pub community_id: String,
+    pub name: String,
+    #[schemars(with = "String")]
+    pub treasury: AccountId,
+    pub fee_rate: Option<u128>, // Basis points, at most the 10% platform maximum
+    #[schemars(with = "Vec<String>")]
+    pub moderators: Vec<AccountId>,
+    pub created_at: u64,
+}
+
+// A community moderator's flag on a submission, e.g. for stolen or rule-breaking content. The
+// reason itself stays off-chain; only its hash is recorded.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionFlag {
+    #[schemars(with = "String")]
+    pub moderator: AccountId,
+    pub reason_hash: String,
+    pub flagged_at: u64,
+}
+
+// Tokens a sponsor added to a bounty's prize with ft_transfer_call. They are split like the NEAR
+// prize, without the platform fee: the creator share goes to the winning creator at settlement and
+// each winning backer is credited their weighted share when they claim. Without a winner the
+// sponsor gets them back.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtPrize {
+    #[schemars(with = "String")]
+    pub token_id: AccountId,
+    #[schemars(with = "String")]
+    pub sponsor: AccountId,
+    #[schemars(with = "String")]
+    pub amount: U128,
+}
+
+// The msg of an ft_transfer_call adding to a bounty's prize
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PrizeMessage {
+    pub bounty_id: u64,
+}
+
+// The msg of an ft_transfer_call to the legacy staking pool
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", tag = "action", rename_all = "snake_case")]
+pub enum StakingMessage {
+    Stake,
+    FundRewards, // Owner or treasurer
+}
+
+// Legacy staking in one fungible token. The reward rate is scaled like the NEAR one, by 10^24
+// per base unit staked per second.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenPool {
+    pub reward_rate: u128,
+    #[schemars(with = "String")]
+    pub reward_pool: U128,
+    #[schemars(with = "String")]
+    pub total_staked: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenStake {
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub staked_at: u64,
+    pub last_reward_claim: u64,
+}
+
+// A claimed stake and what the claim paid
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct ClaimedStake {
+    pub stake: ParticipantStake,
+    pub payout: NearToken,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionTotal {
+    pub index: u64,
+    #[schemars(with = "String")]
+    pub creator: AccountId,
+    #[schemars(with = "String")]
+    pub total_staked: U128,
+    pub verification: Option<CreationVerification>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ParticipantPayout {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    pub submission_index: u64,
+    #[schemars(with = "String")]
+    pub stake: U128,
+    pub staked_at: u64,
+    pub weight_bps: u32,
+    #[schemars(with = "String")]
+    pub payout: U128, // Paid, or what a claim would pay now
+    pub claimed: bool,
+}
+
+// Everything a closed bounty was settled from and what each participant is paid, so the
+// settlement can be recomputed off-chain. `frozen` is false for bounties settled before
+// fee rates and claimed stakes were kept: the current fee rate is shown and claimed stakes are missing.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SettlementAudit {
+    pub bounty_id: u64,
+    pub frozen: bool,
+    pub is_emergency_closed: bool,
+    #[schemars(with = "String")]
+    pub base_prize: U128, // Including forfeited submission bonds
+    #[schemars(with = "String")]
+    pub total_staked: U128,
+    pub platform_fee_rate: u128, // Basis points
+    #[schemars(with = "String")]
+    pub platform_fee: U128,
+    pub creator_share: u8,
+    pub backer_share: u8,
+    pub max_stake_boost_bps: u16,
+    #[schemars(with = "String")]
+    pub weighted_winning_stake: U128,
+    pub submissions: Vec<SubmissionTotal>,
+    pub winning_submission: Option<u64>,
+    #[schemars(with = "Option<String>")]
+    pub winning_creator: Option<AccountId>,
+    #[schemars(with = "String")]
+    pub creator_reward: U128,
+    #[schemars(with = "String")]
+    pub backer_pool: U128,
+    pub payouts: Vec<ParticipantPayout>,
+}
+
+// Outcome of settling a participant's claim on a closed bounty
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum ClaimKind {
+    Creator,
+    Backer,
+    SingleParticipant,
+    Forfeit,
+    EmergencyRefund, // Stake and, for the bounty creator, base prize of an emergency-closed bounty
+    Rescue, // Recorded stake and, for the bounty creator, base prize withdrawn in rescue mode
+}
+
+// Enforcement limits, see get_config. Lengths are in bytes, durations in days unless suffixed _ns.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractConfig {
+    pub max_title_len: usize,
+    pub max_description_len: usize,
+    pub max_requirements_len: usize,
+    #[schemars(with = "String")]
+    pub min_base_prize: U128,
+    #[schemars(with = "String")]
+    pub min_max_stake_per_user: U128, // Bounds of a bounty's max_stake_per_user
+    #[schemars(with = "String")]
+    pub max_max_stake_per_user: U128,
+    pub min_creator_share: u8,
+    pub max_creator_share: u8,
+    pub default_creator_share: u8, // The backer share is the rest of 100
+    pub min_duration_days: u64,
+    pub max_duration_days: u64,
+    pub max_start_delay_ns: u64, // How far ahead starts_at may be
+    pub max_submissions: usize,
+    pub max_participants_per_bounty: usize,
+    #[schemars(with = "String")]
+    pub max_submission_bond: U128,
+    pub platform_fee_rate: u128, // Basis points; communities may override it
+    pub max_platform_fee_rate: u128,
+    pub stake_boost_bps: u16, // For new bounties
+    #[schemars(with = "String")]
+    pub min_stake_amount: U128, // Legacy staking pool
+    #[schemars(with = "String")]
+    pub max_stake_amount: U128,
+    pub close_grace_period_ns: u64, // After ends_at, then anyone may close
+    pub claim_period_ns: u64, // After ends_at, then the bounty can be cleaned up
+    pub max_page_limit: u64,
+}
+
+// What a stake would do, see simulate_stake
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeSimulation {
+    #[schemars(with = "String")]
+    pub total_staked: U128,
+    #[schemars(with = "String")]
+    pub submission_total_staked: U128,
+    pub submission_share_bps: u32, // The submission's part of all stakes on the bounty
+    pub backer_share_bps: u32, // The stake's part of the submission's backing
+    #[schemars(with = "String")]
+    pub projected_reward: U128, // Backer reward if the submission won and the bounty closed now
+    pub rejection: Option<String>,
+}
+
+// The contract balance against what it owes, see get_solvency
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Solvency {
+    #[schemars(with = "String")]
+    pub balance: U128,
+    #[schemars(with = "String")]
+    pub liabilities: U128,
+    #[schemars(with = "String")]
+    pub storage_reserve: U128,
+    #[schemars(with = "String")]
+    pub surplus: U128, // Balance left over liabilities and storage; 0 when insolvent
+    pub is_solvent: bool,
+}
+
+// Record of a claim, kept so what a claimer was paid can be checked on-chain after the stake is gone
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClaimReceipt {
+    #[schemars(with = "String")]
+    pub amount: U128,
+    pub role: ClaimKind,
+    pub as_credits: bool,
+    pub block_height: u64,
+    pub claimed_at: u64,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Clone)]
 pub struct StakeInfo {
     pub amount: NearToken,
@@ -203,7 +658,7 @@ impl From<StakeInfo> for StakeInfoView {
     }
 }
 
-#[near(contract_state)]
+#[near(contract_state, contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
 #[derive(PanicOnDefault)]
 pub struct BountyPredictionContract {
     // Existing staking fields (for backward compatibility)
@@ -220,6 +675,222 @@ pub struct BountyPredictionContract {
     bounty_participants: Option<LookupMap<u64, Vec<AccountId>>>, // Efficient participant tracking
     next_bounty_id: u64,
     platform_fee_rate: u128, // 5% = 500 (basis points)
+
+    // Owner-funded balance that legacy staking rewards are paid from.
+    // Kept separate so reward claims can never draw on bounty prize money.
+    reward_pool: NearToken,
+    // Owner switch for the legacy staking subsystem. When disabled, no new
+    // stakes are accepted but existing stakers can always unstake or exit.
+    legacy_staking_enabled: bool,
+    // Deposits contract that claim_as_credits settles winnings into
+    credits_contract_id: Option<AccountId>,
+    // Proposed owner waiting to accept (e.g. the governance contract)
+    pending_owner: Option<AccountId>,
+    // Roles delegated by the owner (admin, treasurer, guardian)
+    access: AccessControl,
+    // Content NFT contract winners are minted on at settlement; None disables minting
+    nft_contract_id: Option<AccountId>,
+    nft_royalty: RoyaltySplit,
+    // Token id minted for each settled bounty
+    bounty_nfts: LookupMap<u64, String>,
+    // Reputation contract for settlement outcomes and the high-value gate; None disables both
+    reputation_contract_id: Option<AccountId>,
+    reputation_gate: ReputationGate,
+    // Leaderboard contract each settlement is pushed to; None disables reporting
+    leaderboard_contract_id: Option<AccountId>,
+    // Next bounty id a maintenance tick inspects
+    tick_cursor: u64,
+    // Storage bytes each bounty's creator paid for at creation, refunded by cleanup_bounty
+    storage_paid: LookupMap<u64, u64>,
+    // What remains of a bounty after cleanup_bounty deleted its records
+    bounty_summaries: LookupMap<u64, BountySummary>,
+    // Creation registry each submission's creation_id is checked against; None skips verification
+    creation_registry_id: Option<AccountId>,
+    // Registry result per (bounty id, submission index)
+    creation_verifications: LookupMap<(u64, u64), CreationVerification>,
+    // Early-backer boost given to bounties created from now on
+    stake_boost_bps: u16,
+    // Boost each bounty was created with, and its winning backers' weighted stakes once settled
+    stake_weightings: LookupMap<u64, StakeWeighting>,
+    // Bond each submission to a bounty must post, set by its creator
+    submission_bonds: LookupMap<u64, SubmissionBond>,
+    // Bond posted per (bounty id, submission index), removed once returned
+    posted_bonds: LookupMap<(u64, u64), NearToken>,
+    // Platform fee rate each bounty was settled at, so later rate changes do not alter its payouts
+    settled_fee_rates: LookupMap<u64, u128>,
+    // Stakes that were claimed, with what they paid, kept for settlement audits until cleanup
+    claimed_stakes: LookupMap<(AccountId, u64), ClaimedStake>,
+    // Fungible tokens sponsors may add to bounty prizes
+    prize_tokens: UnorderedSet<AccountId>,
+    // Token prizes added to each bounty, one entry per sponsor transfer
+    ft_prizes: LookupMap<u64, Vec<FtPrize>>,
+    // Token prize shares owed per (account, token), paid out by withdraw_ft_prize
+    ft_prize_balances: LookupMap<(AccountId, AccountId), u128>,
+    // Community sub-markets by id, each with its own fee override, treasury and moderators
+    communities: UnorderedMap<String, Community>,
+    // Community each bounty was tagged with; untagged bounties use the global fee config
+    bounty_communities: LookupMap<u64, String>,
+    // Moderator flags per (bounty id, submission index); flagged submissions cannot win
+    submission_flags: LookupMap<(u64, u64), SubmissionFlag>,
+    // What each (claimer, bounty id) claim paid; kept after cleanup as proof of the claim
+    claim_receipts: LookupMap<(AccountId, u64), ClaimReceipt>,
+    // NEAR owed to participants of closed bounties and not yet claimed, kept back on every transfer
+    liabilities: NearToken,
+    // The part of liabilities owed per bounty, released by claims and dropped at cleanup
+    bounty_liabilities: LookupMap<u64, NearToken>,
+    // NEAR whose transfer failed, e.g. to a deleted account, owed per receiver until retry_transfer
+    unpaid_transfers: LookupMap<AccountId, NearToken>,
+    // Start of bounties scheduled to open after creation; they take no submissions or stakes before it
+    bounty_starts: LookupMap<u64, u64>,
+    // NEP-141 token accepted for new legacy stakes through ft_transfer_call
+    staking_token_id: Option<AccountId>,
+    // Reward rate, reward pool and total staked per staking token, current or past
+    token_pools: LookupMap<AccountId, TokenPool>,
+    // Token stakes by (staker, token)
+    token_stakes: LookupMap<(AccountId, AccountId), TokenStake>,
+    // When rescue mode was enabled, per bounty; see rescue_withdraw
+    rescue_bounties: LookupMap<u64, u64>,
+    // Bounties closed without a winner (by the owner, or when no submission was eligible); their
+    // backers and creator reclaim funds with claim_emergency_refund
+    emergency_closed_bounties: LookupSet<u64>,
+    // Bounties whose creator has taken the base prize back after an emergency close or rescue
+    refunded_base_prizes: LookupSet<u64>,
+    // Layout version, stored under its own key so migrate can read it before parsing the state
+    state_version: LazyOption<u32>,
+}
+
+// Layout of the released contract, before state_version existed. Every field added since starts
+// empty or at its default.
+#[derive(BorshDeserialize)]
+struct ReleasedContract {
+    stakes: LookupMap<AccountId, StakeInfo>,
+    total_staked: NearToken,
+    reward_rate: u128,
+    min_stake_amount: NearToken,
+    max_stake_amount: NearToken,
+    owner: AccountId,
+    bounties: LookupMap<u64, Bounty>,
+    participant_stakes: LookupMap<(AccountId, u64), ParticipantStake>,
+    bounty_participants: Option<LookupMap<u64, Vec<AccountId>>>,
+    next_bounty_id: u64,
+    platform_fee_rate: u128,
+}
+
+// Older layout with a trailing pause flag. Borsh has no framing, so nesting the released layout
+// reads the same bytes as listing its fields.
+#[derive(BorshDeserialize)]
+struct PausableContract {
+    released: ReleasedContract,
+    _is_paused: bool,
+}
+
+// Oldest layout, from before participant tracking
+#[derive(BorshDeserialize)]
+struct OldContractV1 {
+    stakes: LookupMap<AccountId, StakeInfo>,
+    total_staked: NearToken,
+    reward_rate: u128,
+    min_stake_amount: NearToken,
+    max_stake_amount: NearToken,
+    owner: AccountId,
+    bounties: LookupMap<u64, Bounty>,
+    participant_stakes: LookupMap<(AccountId, u64), ParticipantStake>,
+    next_bounty_id: u64,
+    platform_fee_rate: u128,
+    _is_paused: bool,
+}
+
+impl OldContractV1 {
+    fn into_released(self) -> ReleasedContract {
+        ReleasedContract {
+            stakes: self.stakes,
+            total_staked: self.total_staked,
+            reward_rate: self.reward_rate,
+            min_stake_amount: self.min_stake_amount,
+            max_stake_amount: self.max_stake_amount,
+            owner: self.owner,
+            bounties: self.bounties,
+            participant_stakes: self.participant_stakes,
+            bounty_participants: None,
+            next_bounty_id: self.next_bounty_id,
+            platform_fee_rate: self.platform_fee_rate,
+        }
+    }
+}
+
+impl ReleasedContract {
+    // Defaults used when there is no state to migrate
+    fn fresh() -> Self {
+        Self {
+            stakes: LookupMap::new(b"s"),
+            total_staked: NearToken::from_yoctonear(0),
+            reward_rate: 1000, // Default reward rate
+            min_stake_amount: NearToken::from_near(1),
+            max_stake_amount: NearToken::from_near(1000),
+            owner: env::predecessor_account_id(),
+            bounties: LookupMap::new(b"b"),
+            participant_stakes: LookupMap::new(b"p"),
+            bounty_participants: None,
+            next_bounty_id: 1,
+            platform_fee_rate: 500, // 5%
+        }
+    }
+
+    fn into_current(self) -> BountyPredictionContract {
+        BountyPredictionContract {
+            stakes: self.stakes,
+            total_staked: self.total_staked,
+            reward_rate: self.reward_rate,
+            min_stake_amount: self.min_stake_amount,
+            max_stake_amount: self.max_stake_amount,
+            owner: self.owner,
+            bounties: self.bounties,
+            participant_stakes: self.participant_stakes,
+            bounty_participants: self.bounty_participants.or_else(|| Some(LookupMap::new(b"t"))),
+            next_bounty_id: self.next_bounty_id,
+            platform_fee_rate: self.platform_fee_rate,
+            reward_pool: NearToken::from_yoctonear(0),
+            legacy_staking_enabled: true,
+            credits_contract_id: None,
+            pending_owner: None,
+            access: AccessControl::new(b"r"),
+            nft_contract_id: None,
+            nft_royalty: RoyaltySplit::default(),
+            bounty_nfts: LookupMap::new(b"n"),
+            reputation_contract_id: None,
+            reputation_gate: ReputationGate::default(),
+            leaderboard_contract_id: None,
+            tick_cursor: 1,
+            storage_paid: LookupMap::new(b"d"),
+            bounty_summaries: LookupMap::new(b"u"),
+            creation_registry_id: None,
+            creation_verifications: LookupMap::new(b"v"),
+            stake_boost_bps: DEFAULT_STAKE_BOOST_BPS,
+            stake_weightings: LookupMap::new(b"w"),
+            submission_bonds: LookupMap::new(b"e"),
+            posted_bonds: LookupMap::new(b"f"),
+            settled_fee_rates: LookupMap::new(b"g"),
+            claimed_stakes: LookupMap::new(b"h"),
+            prize_tokens: UnorderedSet::new(b"i"),
+            ft_prizes: LookupMap::new(b"j"),
+            ft_prize_balances: LookupMap::new(b"k"),
+            communities: UnorderedMap::new(b"l"),
+            bounty_communities: LookupMap::new(b"m"),
+            submission_flags: LookupMap::new(b"o"),
+            claim_receipts: LookupMap::new(b"q"),
+            liabilities: NearToken::from_yoctonear(0),
+            bounty_liabilities: LookupMap::new(b"y"),
+            unpaid_transfers: LookupMap::new(b"z"),
+            bounty_starts: LookupMap::new(b"a"),
+            staking_token_id: None,
+            token_pools: LookupMap::new(b"c"),
+            token_stakes: LookupMap::new(b"x"),
+            rescue_bounties: LookupMap::new(b"R"),
+            emergency_closed_bounties: LookupSet::new(b"E"),
+            refunded_base_prizes: LookupSet::new(b"P"),
+            state_version: record_state_version(STATE_VERSION),
+        }
+    }
 }
 
 #[near]
@@ -264,103 +935,100 @@ impl BountyPredictionContract {
             bounty_participants: Some(LookupMap::new(b"t")), // Participant tracking
             next_bounty_id: 1,
             platform_fee_rate: 500, // 5%
+            reward_pool: NearToken::from_yoctonear(0),
+            legacy_staking_enabled: true,
+            credits_contract_id: None,
+            pending_owner: None,
+            access: AccessControl::new(b"r"),
+            nft_contract_id: None,
+            nft_royalty: RoyaltySplit::default(),
+            bounty_nfts: LookupMap::new(b"n"),
+            reputation_contract_id: None,
+            reputation_gate: ReputationGate::default(),
+            leaderboard_contract_id: None,
+            tick_cursor: 1,
+            storage_paid: LookupMap::new(b"d"),
+            bounty_summaries: LookupMap::new(b"u"),
+            creation_registry_id: None,
+            creation_verifications: LookupMap::new(b"v"),
+            stake_boost_bps: DEFAULT_STAKE_BOOST_BPS,
+            stake_weightings: LookupMap::new(b"w"),
+            submission_bonds: LookupMap::new(b"e"),
+            posted_bonds: LookupMap::new(b"f"),
+            settled_fee_rates: LookupMap::new(b"g"),
+            claimed_stakes: LookupMap::new(b"h"),
+            prize_tokens: UnorderedSet::new(b"i"),
+            ft_prizes: LookupMap::new(b"j"),
+            ft_prize_balances: LookupMap::new(b"k"),
+            communities: UnorderedMap::new(b"l"),
+            bounty_communities: LookupMap::new(b"m"),
+            submission_flags: LookupMap::new(b"o"),
+            claim_receipts: LookupMap::new(b"q"),
+            liabilities: NearToken::from_yoctonear(0),
+            bounty_liabilities: LookupMap::new(b"y"),
+            unpaid_transfers: LookupMap::new(b"z"),
+            bounty_starts: LookupMap::new(b"a"),
+            staking_token_id: None,
+            token_pools: LookupMap::new(b"c"),
+            token_stakes: LookupMap::new(b"x"),
+            rescue_bounties: LookupMap::new(b"R"),
+            emergency_closed_bounties: LookupSet::new(b"E"),
+            refunded_base_prizes: LookupSet::new(b"P"),
+            state_version: record_state_version(STATE_VERSION),
         }
     }
 
-    /// Migration method to handle contract upgrades
+    /// Migrate the state to this code's layout. Runs in the same batch as the deploy in `upgrade`;
+    /// a raw deploy must pass it as the init call, signed by the contract account.
+    #[private]
     #[init(ignore_state)]
     pub fn migrate() -> Self {
-        // Try to read the old state - if it fails, create a new contract
-        if let Some(old_state_bytes) = env::storage_read(b"STATE") {
-            env::log_str("CONTRACT_MIGRATION: Found existing state, attempting migration");
-
-            // Try different versions of the contract state
-            // First try: assume it has all current fields
-            #[derive(BorshDeserialize)]
-            struct CurrentContract {
-                stakes: LookupMap<AccountId, StakeInfo>,
-                total_staked: NearToken,
-                reward_rate: u128,
-                min_stake_amount: NearToken,
-                max_stake_amount: NearToken,
-                owner: AccountId,
-                bounties: LookupMap<u64, Bounty>,
-                participant_stakes: LookupMap<(AccountId, u64), ParticipantStake>,
-                bounty_participants: Option<LookupMap<u64, Vec<AccountId>>>,
-                next_bounty_id: u64,
-                platform_fee_rate: u128,
-                is_paused: bool,
-            }
-
-            if let Ok(current_contract) = CurrentContract::try_from_slice(&old_state_bytes) {
-                env::log_str("CONTRACT_MIGRATION: Current format detected, preserving state");
-                return Self {
-                    stakes: current_contract.stakes,
-                    total_staked: current_contract.total_staked,
-                    reward_rate: current_contract.reward_rate,
-                    min_stake_amount: current_contract.min_stake_amount,
-                    max_stake_amount: current_contract.max_stake_amount,
-                    owner: current_contract.owner,
-                    bounties: current_contract.bounties,
-                    participant_stakes: current_contract.participant_stakes,
-                    bounty_participants: current_contract.bounty_participants.or_else(|| Some(LookupMap::new(b"t"))),
-                    next_bounty_id: current_contract.next_bounty_id,
-                    platform_fee_rate: current_contract.platform_fee_rate,
-                };
+        match stored_state_version() {
+            Some(STATE_VERSION) => {
+                env::log_str("CONTRACT_MIGRATION: State version is current, preserving state");
+                env::state_read().unwrap_or_else(|| env::panic_str("No existing state to migrate"))
             }
+            Some(version) => env::panic_str(&format!("Unsupported state version {}", version)),
+            None => Self::migrate_unversioned(),
+        }
+    }
 
-            // Second try: assume it's missing bounty_participants field
-            #[derive(BorshDeserialize)]
-            struct OldContractV1 {
-                stakes: LookupMap<AccountId, StakeInfo>,
-                total_staked: NearToken,
-                reward_rate: u128,
-                min_stake_amount: NearToken,
-                max_stake_amount: NearToken,
-                owner: AccountId,
-                bounties: LookupMap<u64, Bounty>,
-                participant_stakes: LookupMap<(AccountId, u64), ParticipantStake>,
-                next_bounty_id: u64,
-                platform_fee_rate: u128,
-                is_paused: bool,
-            }
+    /// Deploy new code and run its `migrate` in one batch (owner only, e.g. the governance contract).
+    /// `code` is the wasm module, borsh-serialized as the call arguments.
+    pub fn upgrade(&mut self, #[serializer(borsh)] code: Vec<u8>) -> Promise {
+        require!(env::predecessor_account_id() == self.owner, "Only the owner can call this method");
+        env::log_str(&format!("CONTRACT_UPGRADE: deploying {} bytes over state version {}", code.len(), STATE_VERSION));
+        deploy_and_migrate(code, b"{}".to_vec())
+    }
 
-            if let Ok(old_contract) = OldContractV1::try_from_slice(&old_state_bytes) {
-                env::log_str("CONTRACT_MIGRATION: V1 format detected, adding participant tracking");
-                return Self {
-                    stakes: old_contract.stakes,
-                    total_staked: old_contract.total_staked,
-                    reward_rate: old_contract.reward_rate,
-                    min_stake_amount: old_contract.min_stake_amount,
-                    max_stake_amount: old_contract.max_stake_amount,
-                    owner: old_contract.owner,
-                    bounties: old_contract.bounties,
-                    participant_stakes: old_contract.participant_stakes,
-                    bounty_participants: Some(LookupMap::new(b"t")), // Initialize new field
-                    next_bounty_id: old_contract.next_bounty_id,
-                    platform_fee_rate: old_contract.platform_fee_rate,
-                };
-            }
+    pub fn get_state_version(&self) -> u32 {
+        self.state_version.get().unwrap_or(0)
+    }
 
-            env::log_str("CONTRACT_MIGRATION: Could not parse existing state, creating new contract");
-        } else {
+    // State written before state_version existed: the released layout, or one of the older
+    // layouts its migrate accepted
+    fn migrate_unversioned() -> Self {
+        let Some(old_state_bytes) = env::storage_read(b"STATE") else {
             env::log_str("CONTRACT_MIGRATION: No existing state found, creating new contract");
-        }
+            return ReleasedContract::fresh().into_current();
+        };
+        env::log_str("CONTRACT_MIGRATION: Found unversioned state, attempting migration");
 
-        // Fallback: create a new contract with default values
-        Self {
-            stakes: LookupMap::new(b"s"),
-            total_staked: NearToken::from_yoctonear(0),
-            reward_rate: 1000, // Default reward rate
-            min_stake_amount: NearToken::from_near(1),
-            max_stake_amount: NearToken::from_near(1000),
-            owner: env::predecessor_account_id(),
-            bounties: LookupMap::new(b"b"),
-            participant_stakes: LookupMap::new(b"p"),
-            bounty_participants: Some(LookupMap::new(b"t")),
-            next_bounty_id: 1,
-            platform_fee_rate: 500, // 5%
+        if let Ok(old_contract) = ReleasedContract::try_from_slice(&old_state_bytes) {
+            env::log_str("CONTRACT_MIGRATION: Released format detected, recording version");
+            return old_contract.into_current();
         }
+        if let Ok(old_contract) = PausableContract::try_from_slice(&old_state_bytes) {
+            env::log_str("CONTRACT_MIGRATION: Pausable format detected, dropping the pause flag");
+            return old_contract.released.into_current();
+        }
+        if let Ok(old_contract) = OldContractV1::try_from_slice(&old_state_bytes) {
+            env::log_str("CONTRACT_MIGRATION: V1 format detected, adding participant tracking");
+            return old_contract.into_released().into_current();
+        }
+
+        env::log_str("CONTRACT_MIGRATION: Could not parse existing state, creating new contract");
+        ReleasedContract::fresh().into_current()
     }
 
     /// Regular migration function that can be called after deployment
@@ -382,39 +1050,32 @@ impl BountyPredictionContract {
         }
     }
 
-    // Helper function for safe token addition
+    // Checked token arithmetic, shared with the other contracts via groupweave-utils
     fn safe_add_tokens(a: NearToken, b: NearToken) -> Result<NearToken, &'static str> {
-        a.as_yoctonear().checked_add(b.as_yoctonear())
-            .map(NearToken::from_yoctonear)
-            .ok_or("Token addition overflow")
+        groupweave_utils::add_tokens(a, b)
     }
 
-    // Helper function for safe token subtraction
     fn safe_sub_tokens(a: NearToken, b: NearToken) -> Result<NearToken, &'static str> {
-        a.as_yoctonear().checked_sub(b.as_yoctonear())
-            .map(NearToken::from_yoctonear)
-            .ok_or("Token subtraction underflow")
+        groupweave_utils::sub_tokens(a, b)
     }
 
-    // Helper function for safe reward calculation
     fn calculate_rewards_safe(stake_amount: NearToken, reward_rate: u128, time_seconds: u64) -> u128 {
-        // Use checked arithmetic to prevent overflow
-        // Divide by the scaling factor last to maintain precision
-        stake_amount.as_yoctonear()
-            .checked_mul(reward_rate)
-            .and_then(|x| x.checked_mul(time_seconds as u128))
-            .and_then(|x| x.checked_div(1_000_000_000_000_000_000_000_000))
+        groupweave_utils::accrued_rewards(stake_amount, reward_rate, time_seconds)
             .expect("Reward calculation overflow - reward rate or time period too large")
     }
 
+    // Owner or an account holding the admin role
     fn assert_owner(&self) {
-        assert_eq!(
-            env::predecessor_account_id(),
-            self.owner,
+        require!(
+            self.access.is_authorized(&self.owner, Role::Admin, &env::predecessor_account_id()),
             "Only the owner can call this method"
         );
     }
 
+    fn assert_role(&self, role: Role) {
+        self.access.assert_role(&self.owner, role);
+    }
+
     // Helper function to lazily initialize bounty_participants for migration compatibility
     fn get_bounty_participants_mut(&mut self) -> &mut LookupMap<u64, Vec<AccountId>> {
         if self.bounty_participants.is_none() {
@@ -432,6 +1093,7 @@ impl BountyPredictionContract {
         let staker = env::predecessor_account_id();
         let amount = env::attached_deposit();
 
+        require!(self.legacy_staking_enabled, "Legacy staking is disabled");
         require!(amount >= self.min_stake_amount, "Stake amount too low");
         require!(amount <= self.max_stake_amount, "Stake amount too high");
 
@@ -496,7 +1158,38 @@ impl BountyPredictionContract {
         }
 
         // Transfer unstaked amount back to user
-        Promise::new(staker).transfer(amount);
+        self.pay_out(staker, amount);
+    }
+
+    /// Drain path for legacy stakers: returns the full principal even when the
+    /// reward pool cannot cover pending rewards. Pending rewards are paid only
+    /// up to what the pool holds; any shortfall is forfeited.
+    pub fn exit_legacy_stake(&mut self) {
+        let staker = env::predecessor_account_id();
+        let stake_info = self.stakes.get(&staker).expect("No stake found");
+
+        let current_time = env::block_timestamp();
+        let time_diff_seconds = (current_time - stake_info.last_reward_claim) / 1_000_000_000;
+        let pending = NearToken::from_yoctonear(
+            Self::calculate_rewards_safe(stake_info.amount, self.reward_rate, time_diff_seconds)
+        );
+        let paid_rewards = pending.min(self.reward_pool);
+
+        self.stakes.remove(&staker);
+        self.total_staked = Self::safe_sub_tokens(self.total_staked, stake_info.amount)
+            .expect("Total stake subtraction underflow");
+        self.reward_pool = Self::safe_sub_tokens(self.reward_pool, paid_rewards)
+            .expect("Reward pool subtraction underflow");
+
+        let payout = Self::safe_add_tokens(stake_info.amount, paid_rewards)
+            .expect("Payout calculation overflow");
+        self.pay_out(staker.clone(), payout);
+
+        env::log_str(&format!(
+            "LEGACY_EXIT: Account {} withdrew {} principal and {} rewards ({} forfeited)",
+            staker, stake_info.amount, paid_rewards,
+            Self::safe_sub_tokens(pending, paid_rewards).expect("Forfeit calculation underflow")
+        ));
     }
 
     pub fn claim_rewards(&mut self) {
@@ -507,7 +1200,7 @@ impl BountyPredictionContract {
         self.stakes.insert(&staker, &stake_info);
     }
 
-    fn internal_claim_rewards(&self, staker: &AccountId, stake_info: &mut StakeInfo) {
+    fn internal_claim_rewards(&mut self, staker: &AccountId, stake_info: &mut StakeInfo) {
         let current_time = env::block_timestamp();
         let time_diff = current_time - stake_info.last_reward_claim;
         let time_diff_seconds = time_diff / 1_000_000_000;
@@ -517,22 +1210,20 @@ impl BountyPredictionContract {
         if rewards > 0 {
             let reward_amount = NearToken::from_yoctonear(rewards);
 
-            // Check if contract has sufficient balance to pay rewards
-            let contract_balance = env::account_balance();
-            let reserved_balance = NearToken::from_near(1);
-            let required_balance = Self::safe_add_tokens(reward_amount, reserved_balance)
-                .expect("Balance calculation overflow");
-            
-            // Assert sufficient balance - transaction will revert if insufficient
+            // Rewards are only ever paid from the owner-funded reward pool, never from
+            // the general contract balance that also holds bounty prizes and stakes.
+            // Assert sufficient pool - transaction will revert if underfunded
             require!(
-                contract_balance >= required_balance,
-                format!("Insufficient contract balance for reward payment: contract has {} yoctoNEAR, need {} yoctoNEAR",
-                    contract_balance.as_yoctonear(),
-                    required_balance.as_yoctonear())
+                self.reward_pool >= reward_amount,
+                format!("Insufficient reward pool for reward payment: pool has {} yoctoNEAR, need {} yoctoNEAR",
+                    self.reward_pool.as_yoctonear(),
+                    reward_amount.as_yoctonear())
             );
 
+            self.reward_pool = Self::safe_sub_tokens(self.reward_pool, reward_amount)
+                .expect("Reward pool subtraction underflow");
             stake_info.last_reward_claim = current_time;
-            Promise::new(staker.clone()).transfer(reward_amount);
+            self.pay_out(staker.clone(), reward_amount);
             env::log_str(&format!("REWARD: Account {} claimed {} NEAR", staker, reward_amount));
         }
     }
@@ -566,23 +1257,246 @@ impl BountyPredictionContract {
         U128(self.max_stake_amount.as_yoctonear())
     }
 
-    // Helper function to check if contract is paused - REMOVED
-    // fn assert_not_paused(&self) {
-    //    require!(!self.is_paused, "Contract is paused");
-    // }
+    pub fn get_reward_pool(&self) -> U128 {
+        U128(self.reward_pool.as_yoctonear())
+    }
 
-    // Content Bounty Management Functions
+    pub fn is_legacy_staking_enabled(&self) -> bool {
+        self.legacy_staking_enabled
+    }
+
+    /// Enable or disable new legacy stakes (owner or guardian). Unstaking, claiming and
+    /// exit_legacy_stake keep working so existing stakers can always drain.
+    pub fn set_legacy_staking_enabled(&mut self, enabled: bool) {
+        self.assert_role(Role::Guardian);
+        self.legacy_staking_enabled = enabled;
+        env::log_str(&format!("LEGACY_STAKING_UPDATE: enabled={}", enabled));
+    }
+
+    /// Top up the pool that legacy staking rewards are paid from (owner or treasurer)
     #[payable]
-    pub fn create_content_bounty(
-        &mut self,
-        title: String,
-        description: String,
-        requirements: String,
-        base_prize: NearToken,
-        max_stake_per_user: NearToken,
+    pub fn fund_reward_pool(&mut self) {
+        self.assert_role(Role::Treasurer);
+        let amount = env::attached_deposit();
+        require!(amount > NearToken::from_yoctonear(0), "Attach NEAR to fund the reward pool");
+
+        self.reward_pool = Self::safe_add_tokens(self.reward_pool, amount)
+            .expect("Reward pool addition overflow");
+
+        env::log_str(&format!("REWARD_POOL_FUNDED: {} added, pool now {}", amount, self.reward_pool));
+    }
+
+    /// Accept legacy stakes in a NEP-141 token through ft_transfer_call, or stop accepting them
+    /// with None (owner only). Stakes in a previous token can still be claimed and unstaked.
+    pub fn set_staking_token(&mut self, token_id: Option<AccountId>) {
+        self.assert_owner();
+        if let Some(token_id) = &token_id {
+            if self.token_pools.get(token_id).is_none() {
+                self.token_pools.insert(token_id, &TokenPool::default());
+            }
+        }
+        env::log_str(&format!("STAKING_TOKEN_UPDATE: {:?}", token_id));
+        self.staking_token_id = token_id;
+    }
+
+    /// Set a staking token's reward rate, clamped like update_reward_rate (owner only)
+    pub fn set_token_reward_rate(&mut self, token_id: AccountId, reward_rate: u128) {
+        self.assert_owner();
+        let mut pool = self.token_pools.get(&token_id).expect("Token is not a staking token");
+        pool.reward_rate = reward_rate.clamp(1, 1_000_000_000);
+        env::log_str(&format!("TOKEN_REWARD_RATE_UPDATE: {} rate={}", token_id, pool.reward_rate));
+        self.token_pools.insert(&token_id, &pool);
+    }
+
+    fn try_receive_staking_tokens(
+        &mut self,
+        sender_id: &AccountId,
+        token_id: &AccountId,
+        amount: u128,
+        message: StakingMessage,
+    ) -> Result<(), String> {
+        if amount == 0 {
+            return Err("Amount must be positive".to_string());
+        }
+        let mut pool = self.token_pools.get(token_id).ok_or("Token is not a staking token")?;
+        match message {
+            StakingMessage::FundRewards => {
+                if !self.access.is_authorized(&self.owner, Role::Treasurer, sender_id) {
+                    return Err("Only the owner or a treasurer can fund the reward pool".to_string());
+                }
+                pool.reward_pool = U128(pool.reward_pool.0.checked_add(amount).ok_or("Reward pool addition overflow")?);
+                env::log_str(&format!(
+                    "FT_REWARD_POOL_FUNDED: {} {} added, pool now {}",
+                    amount, token_id, pool.reward_pool.0
+                ));
+            }
+            StakingMessage::Stake => {
+                if !self.legacy_staking_enabled {
+                    return Err("Legacy staking is disabled".to_string());
+                }
+                if self.staking_token_id.as_ref() != Some(token_id) {
+                    return Err(format!("Token {} is not accepted for new stakes", token_id));
+                }
+                let key = (sender_id.clone(), token_id.clone());
+                let current_time = env::block_timestamp();
+                let mut stake = self.token_stakes.get(&key).unwrap_or(TokenStake {
+                    amount: U128(0),
+                    staked_at: current_time,
+                    last_reward_claim: current_time,
+                });
+                // Pay pending rewards before the stake grows, as stake() does
+                let rewards = self.pending_token_rewards(&stake, &pool);
+                if rewards > pool.reward_pool.0 {
+                    return Err(format!(
+                        "Insufficient reward pool for reward payment: pool has {}, need {}",
+                        pool.reward_pool.0, rewards
+                    ));
+                }
+                pool.reward_pool = U128(pool.reward_pool.0 - rewards);
+                let total_staked = pool.total_staked.0.checked_add(amount).ok_or("Total stake addition overflow")?;
+                pool.total_staked = U128(total_staked);
+                stake.amount = U128(stake.amount.0.checked_add(amount).ok_or("Stake addition overflow")?);
+                stake.last_reward_claim = current_time;
+                self.token_stakes.insert(&key, &stake);
+                self.send_staking_tokens(sender_id.clone(), token_id.clone(), rewards);
+                env::log_str(&format!("FT_STAKE: Account {} staked {} {}", sender_id, amount, token_id));
+            }
+        }
+        self.token_pools.insert(token_id, &pool);
+        Ok(())
+    }
+
+    // Rewards accrued on a token stake since its last claim
+    fn pending_token_rewards(&self, stake: &TokenStake, pool: &TokenPool) -> u128 {
+        let seconds = env::block_timestamp().saturating_sub(stake.last_reward_claim) / 1_000_000_000;
+        Self::calculate_rewards_safe(NearToken::from_yoctonear(stake.amount.0), pool.reward_rate, seconds)
+    }
+
+    // A transfer that fails, e.g. to an account not registered with the token, is credited to the
+    // account's token balance for withdraw_ft_prize
+    fn send_staking_tokens(&self, account_id: AccountId, token_id: AccountId, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        ext_ft::ext(token_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(account_id.clone(), U128(amount), Some("groupweave token stake".to_string()))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_FT_WITHDRAW_CALLBACK)
+                    .on_staking_tokens_sent(account_id, token_id, U128(amount)),
+            );
+    }
+
+    #[private]
+    pub fn on_staking_tokens_sent(&mut self, account_id: AccountId, token_id: AccountId, amount: U128) -> bool {
+        if is_promise_success() {
+            true
+        } else {
+            self.credit_ft(&account_id, &token_id, amount.0);
+            env::log_str(&format!("FT_STAKE_TRANSFER_FAILED: {} {} credited to {}", amount.0, token_id, account_id));
+            false
+        }
+    }
+
+    /// Claim a token stake's rewards, paid from the token's reward pool. Requires 1 yoctoNEAR.
+    #[payable]
+    pub fn claim_ft_rewards(&mut self, token_id: AccountId) {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Attach at least 1 yoctoNEAR to cover security requirements"
+        );
+        let staker = env::predecessor_account_id();
+        let key = (staker.clone(), token_id.clone());
+        let mut stake = self.token_stakes.get(&key).expect("No stake found");
+        let mut pool = self.token_pools.get(&token_id).expect("Token is not a staking token");
+
+        let rewards = self.pending_token_rewards(&stake, &pool);
+        require!(
+            pool.reward_pool.0 >= rewards,
+            format!("Insufficient reward pool for reward payment: pool has {}, need {}", pool.reward_pool.0, rewards)
+        );
+        pool.reward_pool = U128(pool.reward_pool.0 - rewards);
+        stake.last_reward_claim = env::block_timestamp();
+        self.token_pools.insert(&token_id, &pool);
+        self.token_stakes.insert(&key, &stake);
+        self.send_staking_tokens(staker.clone(), token_id.clone(), rewards);
+        env::log_str(&format!("FT_REWARD: Account {} claimed {} {}", staker, rewards, token_id));
+    }
+
+    /// Withdraw part or all of a token stake together with its rewards. Like exit_legacy_stake,
+    /// rewards are paid only up to what the pool holds and any shortfall is forfeited, so the
+    /// principal can always be withdrawn. Requires 1 yoctoNEAR.
+    #[payable]
+    pub fn unstake_ft(&mut self, token_id: AccountId, amount: U128) {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Attach at least 1 yoctoNEAR to cover security requirements"
+        );
+        let staker = env::predecessor_account_id();
+        let key = (staker.clone(), token_id.clone());
+        let mut stake = self.token_stakes.get(&key).expect("No stake found");
+        let mut pool = self.token_pools.get(&token_id).expect("Token is not a staking token");
+        require!(amount.0 > 0, "Unstake amount must be positive");
+        require!(stake.amount.0 >= amount.0, "Insufficient staked amount");
+
+        let rewards = self.pending_token_rewards(&stake, &pool).min(pool.reward_pool.0);
+        pool.reward_pool = U128(pool.reward_pool.0 - rewards);
+        pool.total_staked = U128(pool.total_staked.0.saturating_sub(amount.0));
+        stake.amount = U128(stake.amount.0 - amount.0);
+        stake.last_reward_claim = env::block_timestamp();
+        self.token_pools.insert(&token_id, &pool);
+        if stake.amount.0 == 0 {
+            self.token_stakes.remove(&key);
+        } else {
+            self.token_stakes.insert(&key, &stake);
+        }
+
+        self.send_staking_tokens(staker.clone(), token_id.clone(), amount.0 + rewards);
+        env::log_str(&format!(
+            "FT_UNSTAKE: Account {} withdrew {} {} and {} rewards",
+            staker, amount.0, token_id, rewards
+        ));
+    }
+
+    pub fn get_staking_token(&self) -> Option<AccountId> {
+        self.staking_token_id.clone()
+    }
+
+    pub fn get_token_pool(&self, token_id: AccountId) -> Option<TokenPool> {
+        self.token_pools.get(&token_id)
+    }
+
+    pub fn get_token_stake(&self, account: AccountId, token_id: AccountId) -> Option<TokenStake> {
+        self.token_stakes.get(&(account, token_id))
+    }
+
+    pub fn calculate_pending_ft_rewards(&self, account: AccountId, token_id: AccountId) -> U128 {
+        match (self.token_stakes.get(&(account, token_id.clone())), self.token_pools.get(&token_id)) {
+            (Some(stake), Some(pool)) => U128(self.pending_token_rewards(&stake, &pool)),
+            _ => U128(0),
+        }
+    }
+
+    // Helper function to check if contract is paused - REMOVED
+    // fn assert_not_paused(&self) {
+    //    require!(!self.is_paused, "Contract is paused");
+    // }
+
+    // Content Bounty Management Functions
+    #[payable]
+    pub fn create_content_bounty(
+        &mut self,
+        title: String,
+        description: String,
+        requirements: String,
+        base_prize: NearToken,
+        max_stake_per_user: NearToken,
         creator_share: Option<u8>,
         backer_share: Option<u8>,
         duration_days: u64,
+        starts_at: Option<u64>,
     ) -> u64 {
         // self.assert_not_paused(); // Removed
         
@@ -594,22 +1508,26 @@ impl BountyPredictionContract {
         require!(!title.trim().is_empty(), "Title cannot be empty");
         require!(!description.trim().is_empty(), "Description cannot be empty");
         require!(!requirements.trim().is_empty(), "Requirements cannot be empty");
-        require!(title.len() <= 200, "Title too long (max 200 characters)");
-        require!(description.len() <= 1000, "Description too long (max 1000 characters)");
-        require!(requirements.len() <= 2000, "Requirements too long (max 2000 characters)");
+        require!(title.len() <= MAX_TITLE_LEN, format!("Title too long (max {} characters)", MAX_TITLE_LEN));
+        require!(
+            description.len() <= MAX_DESCRIPTION_LEN,
+            format!("Description too long (max {} characters)", MAX_DESCRIPTION_LEN)
+        );
+        require!(
+            requirements.len() <= MAX_REQUIREMENTS_LEN,
+            format!("Requirements too long (max {} characters)", MAX_REQUIREMENTS_LEN)
+        );
 
         // Validate base prize (minimum 1 NEAR)
-        require!(base_prize >= NearToken::from_near(1), "Base prize must be at least 1 NEAR");
+        require!(base_prize >= MIN_BASE_PRIZE, "Base prize must be at least 1 NEAR");
         require!(
             attached_deposit >= base_prize,
             format!("Must attach at least {} yoctoNEAR for base prize", base_prize.as_yoctonear())
         );
 
         // Validate max stake amount (0.1 to 10000 NEAR)
-        let min_bounty_stake = NearToken::from_millinear(100); // 0.1 NEAR
-        let max_bounty_stake = NearToken::from_near(10000);
-        require!(max_stake_per_user >= min_bounty_stake, "Maximum stake per user must be at least 0.1 NEAR");
-        require!(max_stake_per_user <= max_bounty_stake, "Maximum stake per user cannot exceed 10000 NEAR");
+        require!(max_stake_per_user >= MIN_MAX_STAKE_PER_USER, "Maximum stake per user must be at least 0.1 NEAR");
+        require!(max_stake_per_user <= MAX_MAX_STAKE_PER_USER, "Maximum stake per user cannot exceed 10000 NEAR");
 
         // Validate and set reward shares
         let final_creator_share = creator_share.unwrap_or(DEFAULT_CREATOR_SHARE);
@@ -618,23 +1536,29 @@ impl BountyPredictionContract {
             final_creator_share + final_backer_share == 100,
             "Creator share + backer share must equal 100"
         );
-        require!(final_creator_share >= 30, "Creator share must be at least 30%");
-        require!(final_creator_share <= 90, "Creator share cannot exceed 90%");
+        require!(final_creator_share >= MIN_CREATOR_SHARE, "Creator share must be at least 30%");
+        require!(final_creator_share <= MAX_CREATOR_SHARE, "Creator share cannot exceed 90%");
 
         // Validate duration (1-90 days)
-        require!(duration_days >= 1, "Duration must be at least 1 day");
-        require!(duration_days <= 90, "Duration cannot exceed 90 days (3 months)");
+        require!(duration_days >= MIN_DURATION_DAYS, "Duration must be at least 1 day");
+        require!(duration_days <= MAX_DURATION_DAYS, "Duration cannot exceed 90 days (3 months)");
 
         let bounty_id = self.next_bounty_id;
         let current_time = env::block_timestamp();
+        if let Some(starts_at) = starts_at {
+            Self::assert_valid_start(starts_at);
+        }
         let duration_ns = (duration_days as u128)
             .checked_mul(24 * 60 * 60 * 1_000_000_000)
             .expect("Duration is too large");
-        let ends_at = u128::from(current_time)
+        let ends_at = u128::from(starts_at.unwrap_or(current_time))
             .checked_add(duration_ns)
             .and_then(|value| u64::try_from(value).ok())
             .expect("Duration exceeds supported range");
 
+        // High-value bounties wait inactive until the creator's reputation is confirmed
+        let reputation_check = self.reputation_requirement(base_prize, self.reputation_gate.min_creator_score);
+
         let bounty = Bounty {
             id: bounty_id,
             title,
@@ -646,7 +1570,7 @@ impl BountyPredictionContract {
             max_stake_per_user,
             creator_share: final_creator_share,
             backer_share: final_backer_share,
-            is_active: true,
+            is_active: reputation_check.is_none(),
             created_at: current_time,
             ends_at,
             total_staked: NearToken::from_yoctonear(0),
@@ -656,14 +1580,23 @@ impl BountyPredictionContract {
 
         self.bounties.insert(&bounty_id, &bounty);
         self.next_bounty_id += 1;
+        if let Some(starts_at) = starts_at {
+            self.bounty_starts.insert(&bounty_id, &starts_at);
+        }
 
-        env::log_str(&format!(
-            "CONTENT_BOUNTY_CREATED: ID {} by {} with base prize {} NEAR",
-            bounty_id, creator, base_prize.as_near()
-        ));
+        if reputation_check.is_none() {
+            Self::log_bounty_created(&bounty);
+        }
+
+        if self.stake_boost_bps > 0 {
+            let weighting = StakeWeighting { max_boost_bps: self.stake_boost_bps, weighted_winning_stake: 0 };
+            self.stake_weightings.insert(&bounty_id, &weighting);
+        }
 
-        // Calculate storage cost
+        // Calculate storage cost, recording it so cleanup_bounty can refund it (the record covers itself)
+        self.storage_paid.insert(&bounty_id, &0);
         let storage_used = env::storage_usage().saturating_sub(initial_storage);
+        self.storage_paid.insert(&bounty_id, &storage_used);
         let storage_cost_per_byte = env::storage_byte_cost().as_yoctonear();
         let storage_cost = u128::from(storage_used) * storage_cost_per_byte;
         
@@ -681,12 +1614,202 @@ impl BountyPredictionContract {
         // Refund excess
         let refund = attached_deposit.as_yoctonear() - total_required;
         if refund > 0 {
-            Promise::new(creator).transfer(NearToken::from_yoctonear(refund));
+            self.pay_out(creator.clone(), NearToken::from_yoctonear(refund));
+        }
+
+        if let Some((reputation_contract_id, min_score)) = reputation_check {
+            env::log_str(&format!(
+                "CONTENT_BOUNTY_PENDING: ID {} awaits a reputation score of {} for {}",
+                bounty_id, min_score, creator
+            ));
+            ext_reputation::ext(reputation_contract_id)
+                .with_static_gas(GAS_FOR_REPUTATION_READ)
+                .get_score(creator)
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_REPUTATION_CALLBACK)
+                        .on_creator_reputation(bounty_id),
+                );
         }
 
         bounty_id
     }
 
+    // Activate a pending high-value bounty, or remove it and refund the prize and its storage
+    // if the creator's score is below the current gate or the reputation contract could not be read
+    #[private]
+    pub fn on_creator_reputation(
+        &mut self,
+        bounty_id: u64,
+        #[callback_result] score: Result<u64, PromiseError>,
+    ) -> bool {
+        let Some(mut bounty) = self.bounties.get(&bounty_id) else {
+            return false;
+        };
+        // Skip bounties that were emergency closed or put in rescue mode while the check ran
+        if bounty.is_active || bounty.is_closed || self.rescue_bounties.contains_key(&bounty_id) {
+            return false;
+        }
+
+        let min_score = self.reputation_gate.min_creator_score;
+        match score {
+            Ok(score) if score >= min_score => {
+                bounty.is_active = true;
+                self.bounties.insert(&bounty_id, &bounty);
+                Self::log_bounty_created(&bounty);
+                true
+            }
+            _ => {
+                let initial_storage = env::storage_usage();
+                self.bounties.remove(&bounty_id);
+                self.storage_paid.remove(&bounty_id);
+                self.stake_weightings.remove(&bounty_id);
+                self.bounty_starts.remove(&bounty_id);
+                let storage_freed = initial_storage.saturating_sub(env::storage_usage());
+                let refund = bounty
+                    .base_prize
+                    .saturating_add(env::storage_byte_cost().saturating_mul(u128::from(storage_freed)));
+                self.pay_out(bounty.creator.clone(), refund);
+                env::log_str(&format!(
+                    "CONTENT_BOUNTY_REJECTED: ID {} by {} needs a reputation score of {} ({:?}), refunded {}",
+                    bounty_id, bounty.creator, min_score, score.ok(), refund
+                ));
+                false
+            }
+        }
+    }
+
+    fn assert_valid_start(starts_at: u64) {
+        let current_time = env::block_timestamp();
+        require!(starts_at > current_time, "Start must be in the future");
+        require!(
+            starts_at - current_time <= MAX_START_DELAY_NS,
+            "Start cannot be more than 90 days ahead"
+        );
+    }
+
+    // When a bounty opens to submissions and stakes: its scheduled start, else its creation
+    fn bounty_opens_at(&self, bounty: &Bounty) -> u64 {
+        self.bounty_starts.get(&bounty.id).unwrap_or(bounty.created_at)
+    }
+
+    fn is_scheduled(&self, bounty: &Bounty) -> bool {
+        env::block_timestamp() < self.bounty_opens_at(bounty)
+    }
+
+    fn assert_scheduled_bounty_creator(&self, bounty: &Bounty) {
+        require!(
+            env::predecessor_account_id() == bounty.creator,
+            "Only the bounty creator can change a scheduled bounty"
+        );
+        require!(!bounty.is_closed, "Bounty is already closed");
+        require!(self.is_scheduled(bounty), "Bounty has already started");
+    }
+
+    /// Edit a bounty before its scheduled start (bounty creator). A new `starts_at` moves the end
+    /// with it, keeping the duration.
+    pub fn update_scheduled_bounty(
+        &mut self,
+        bounty_id: u64,
+        title: Option<String>,
+        description: Option<String>,
+        requirements: Option<String>,
+        starts_at: Option<u64>,
+    ) {
+        let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        self.assert_scheduled_bounty_creator(&bounty);
+
+        if let Some(title) = title {
+            require!(!title.trim().is_empty(), "Title cannot be empty");
+            require!(title.len() <= MAX_TITLE_LEN, format!("Title too long (max {} characters)", MAX_TITLE_LEN));
+            bounty.title = title;
+        }
+        if let Some(description) = description {
+            require!(!description.trim().is_empty(), "Description cannot be empty");
+            require!(
+                description.len() <= MAX_DESCRIPTION_LEN,
+                format!("Description too long (max {} characters)", MAX_DESCRIPTION_LEN)
+            );
+            bounty.description = description;
+        }
+        if let Some(requirements) = requirements {
+            require!(!requirements.trim().is_empty(), "Requirements cannot be empty");
+            require!(
+                requirements.len() <= MAX_REQUIREMENTS_LEN,
+                format!("Requirements too long (max {} characters)", MAX_REQUIREMENTS_LEN)
+            );
+            bounty.requirements = requirements;
+        }
+        if let Some(starts_at) = starts_at {
+            Self::assert_valid_start(starts_at);
+            let duration = bounty.ends_at - self.bounty_opens_at(&bounty);
+            bounty.ends_at = starts_at.checked_add(duration).expect("Duration exceeds supported range");
+            self.bounty_starts.insert(&bounty_id, &starts_at);
+        }
+
+        self.bounties.insert(&bounty_id, &bounty);
+        groupweave_events::bounty::emit(
+            "scheduled_bounty_updated",
+            serde_json::json!({
+                "bounty_id": bounty_id,
+                "starts_at": self.bounty_opens_at(&bounty),
+                "ends_at": bounty.ends_at,
+            }),
+        );
+    }
+
+    /// Cancel a bounty before its scheduled start (bounty creator): the base prize and the
+    /// bounty's storage are refunded and sponsor token prizes credited back to their sponsors.
+    pub fn cancel_scheduled_bounty(&mut self, bounty_id: u64) -> Promise {
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        self.assert_scheduled_bounty_creator(&bounty);
+
+        self.return_ft_prizes(bounty_id);
+        let initial_storage = env::storage_usage();
+        self.bounties.remove(&bounty_id);
+        self.storage_paid.remove(&bounty_id);
+        self.stake_weightings.remove(&bounty_id);
+        self.bounty_starts.remove(&bounty_id);
+        self.bounty_communities.remove(&bounty_id);
+        self.submission_bonds.remove(&bounty_id);
+        self.ft_prizes.remove(&bounty_id);
+        let storage_freed = initial_storage.saturating_sub(env::storage_usage());
+        let refund = bounty
+            .base_prize
+            .saturating_add(env::storage_byte_cost().saturating_mul(u128::from(storage_freed)));
+
+        groupweave_events::bounty::emit(
+            "scheduled_bounty_cancelled",
+            serde_json::json!({
+                "bounty_id": bounty_id,
+                "creator": bounty.creator,
+                "refund": U128(refund.as_yoctonear()),
+            }),
+        );
+        self.pay_out(bounty.creator, refund)
+    }
+
+    fn log_bounty_created(bounty: &Bounty) {
+        env::log_str(&format!(
+            "CONTENT_BOUNTY_CREATED: ID {} by {} with base prize {} NEAR",
+            bounty.id, bounty.creator, bounty.base_prize.as_near()
+        ));
+        BountyCreated {
+            bounty_id: bounty.id,
+            creator: bounty.creator.clone(),
+            base_prize: U128(bounty.base_prize.as_yoctonear()),
+            ends_at: bounty.ends_at,
+        }
+        .emit();
+    }
+
+    // The reputation contract and minimum score a bounty with this base prize is gated on, if any
+    fn reputation_requirement(&self, base_prize: NearToken, min_score: u64) -> Option<(AccountId, u64)> {
+        let reputation_contract_id = self.reputation_contract_id.clone()?;
+        let is_high_value = base_prize.as_yoctonear() >= self.reputation_gate.high_value_prize.0;
+        (is_high_value && min_score > 0).then_some((reputation_contract_id, min_score))
+    }
+
     // Submit content to a bounty
     //
     // ANTI-CHEATING NOTE:
@@ -709,21 +1832,124 @@ impl BountyPredictionContract {
     // - It exists and belongs to the submitter
     // - The content meets bounty requirements
     // - The creator hasn't been flagged for abuse
+    //
+    // High-value bounties also require a minimum on-chain reputation score; the submission is
+    // stored by the callback once the reputation contract confirms it.
+    // Attach the bounty's submission bond, if it has one; any excess deposit is refunded
+    #[payable]
     pub fn submit_content(
         &mut self,
         bounty_id: u64,
         creation_id: String,
         title: String,
         thumbnail_url: String,
-    ) -> u64 {
-        // self.assert_not_paused(); // Removed
+    ) -> PromiseOrValue<u64> {
         let submitter = env::predecessor_account_id();
-        let current_time = env::block_timestamp();
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        let bond = self.collect_submission_bond(bounty_id, &submitter);
+        match self.reputation_requirement(bounty.base_prize, self.reputation_gate.min_submitter_score) {
+            Some((reputation_contract_id, _)) => {
+                // Fail fast on input the callback would reject anyway
+                self.assert_can_submit(&bounty, &submitter, &creation_id, &title);
+                let check = ext_reputation::ext(reputation_contract_id)
+                    .with_static_gas(GAS_FOR_REPUTATION_READ)
+                    .get_score(submitter.clone())
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_REPUTATION_CALLBACK)
+                            .on_submitter_reputation(submitter.clone(), bounty_id, creation_id, title, thumbnail_url),
+                    );
+                if bond == NearToken::from_yoctonear(0) {
+                    return PromiseOrValue::Promise(check);
+                }
+                PromiseOrValue::Promise(
+                    check.then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_BOND_REFUND_CALLBACK)
+                            .on_bonded_submission(submitter, bounty_id, U128(bond.as_yoctonear())),
+                    ),
+                )
+            }
+            None => {
+                let submission_index =
+                    self.internal_submit_content(submitter, bounty_id, creation_id, title, thumbnail_url);
+                self.record_posted_bond(bounty_id, submission_index, bond);
+                PromiseOrValue::Value(submission_index)
+            }
+        }
+    }
 
-        let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+    // Keep the bounty's bond out of the attached deposit and refund the rest
+    fn collect_submission_bond(&self, bounty_id: u64, submitter: &AccountId) -> NearToken {
+        let attached = env::attached_deposit();
+        let bond = self
+            .submission_bonds
+            .get(&bounty_id)
+            .map_or(NearToken::from_yoctonear(0), |terms| NearToken::from_yoctonear(terms.bond.0));
+        require!(
+            attached >= bond,
+            format!("Must attach the {} yoctoNEAR submission bond", bond.as_yoctonear())
+        );
+        let excess = attached.saturating_sub(bond);
+        if excess > NearToken::from_yoctonear(0) {
+            self.pay_out(submitter.clone(), excess);
+        }
+        bond
+    }
+
+    fn record_posted_bond(&mut self, bounty_id: u64, submission_index: u64, bond: NearToken) {
+        if bond > NearToken::from_yoctonear(0) {
+            self.posted_bonds.insert(&(bounty_id, submission_index), &bond);
+        }
+    }
+
+    // Record the bond of a gated submission once it is stored, or return it if the reputation check failed
+    #[private]
+    pub fn on_bonded_submission(
+        &mut self,
+        submitter: AccountId,
+        bounty_id: u64,
+        bond: U128,
+        #[callback_result] submission_index: Result<u64, PromiseError>,
+    ) -> Option<u64> {
+        match submission_index {
+            Ok(index) => {
+                self.record_posted_bond(bounty_id, index, NearToken::from_yoctonear(bond.0));
+                Some(index)
+            }
+            Err(_) => {
+                self.pay_out(submitter.clone(), NearToken::from_yoctonear(bond.0));
+                env::log_str(&format!("SUBMISSION_BOND_RETURNED: {} yoctoNEAR to {}", bond.0, submitter));
+                None
+            }
+        }
+    }
+
+    #[private]
+    pub fn on_submitter_reputation(
+        &mut self,
+        submitter: AccountId,
+        bounty_id: u64,
+        creation_id: String,
+        title: String,
+        thumbnail_url: String,
+        #[callback_result] score: Result<u64, PromiseError>,
+    ) -> u64 {
+        let min_score = self.reputation_gate.min_submitter_score;
+        let score = score.unwrap_or_else(|_| env::panic_str("Could not read the submitter's reputation"));
+        require!(
+            score >= min_score,
+            format!("Reputation score {} is below the {} this bounty requires", score, min_score)
+        );
+        self.internal_submit_content(submitter, bounty_id, creation_id, title, thumbnail_url)
+    }
+
+    fn assert_can_submit(&self, bounty: &Bounty, submitter: &AccountId, creation_id: &str, title: &str) {
+        let current_time = env::block_timestamp();
         require!(bounty.is_active, "Bounty is not active");
         require!(!bounty.is_closed, "Bounty is already closed");
         require!(current_time < bounty.ends_at, "Bounty has expired");
+        require!(!self.is_scheduled(bounty), "Bounty has not started yet");
         require!(
             bounty.submissions.len() < MAX_SUBMISSIONS,
             format!("Maximum {} submissions reached", MAX_SUBMISSIONS)
@@ -732,12 +1958,12 @@ impl BountyPredictionContract {
         // Validate inputs
         require!(!creation_id.trim().is_empty(), "Creation ID cannot be empty");
         require!(!title.trim().is_empty(), "Title cannot be empty");
-        require!(title.len() <= 200, "Title too long (max 200 characters)");
+        require!(title.len() <= MAX_TITLE_LEN, format!("Title too long (max {} characters)", MAX_TITLE_LEN));
 
         // Check if creator already submitted
         for submission in &bounty.submissions {
             require!(
-                submission.creator != submitter,
+                &submission.creator != submitter,
                 "You have already submitted to this bounty"
             );
             require!(
@@ -745,6 +1971,21 @@ impl BountyPredictionContract {
                 "This creation has already been submitted"
             );
         }
+    }
+
+    fn internal_submit_content(
+        &mut self,
+        submitter: AccountId,
+        bounty_id: u64,
+        creation_id: String,
+        title: String,
+        thumbnail_url: String,
+    ) -> u64 {
+        // self.assert_not_paused(); // Removed
+        let current_time = env::block_timestamp();
+
+        let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        self.assert_can_submit(&bounty, &submitter, &creation_id, &title);
 
         let submission = ContentSubmission {
             creator: submitter.clone(),
@@ -759,32 +2000,158 @@ impl BountyPredictionContract {
         let submission_index = bounty.submissions.len() - 1;
         
         self.bounties.insert(&bounty_id, &bounty);
+        self.request_creation_verification(bounty_id, submission_index as u64, creation_id.clone(), submitter.clone());
 
         env::log_str(&format!(
             "CONTENT_SUBMITTED: Bounty {} - {} by {} (index {})",
             bounty_id, creation_id, submitter, submission_index
         ));
+        ContentSubmitted {
+            bounty_id,
+            submission_index: submission_index as u64,
+            creator: submitter,
+            creation_id,
+        }
+        .emit();
 
         submission_index as u64
     }
 
-    pub fn get_bounty(&self, bounty_id: u64) -> Option<BountyView> {
-        self.bounties.get(&bounty_id).map(|bounty| bounty.into())
+    // Ask the creation registry whether the submitter owns the creation; the submission is stored
+    // either way and the result lands in creation_verifications when the callback runs
+    fn request_creation_verification(
+        &mut self,
+        bounty_id: u64,
+        submission_index: u64,
+        creation_id: String,
+        submitter: AccountId,
+    ) {
+        let Some(registry_id) = self.creation_registry_id.clone() else {
+            return;
+        };
+        self.creation_verifications.insert(&(bounty_id, submission_index), &CreationVerification::Pending);
+        ext_creation_registry::ext(registry_id)
+            .with_static_gas(GAS_FOR_CREATION_VERIFY)
+            .verify_creation(creation_id, submitter)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CREATION_VERIFY_CALLBACK)
+                    .on_creation_verified(bounty_id, submission_index),
+            );
     }
 
-    pub fn get_active_bounties(&self) -> Vec<BountyView> {
-        let mut active_bounties = Vec::new();
-        let current_time = env::block_timestamp();
+    #[private]
+    pub fn on_creation_verified(
+        &mut self,
+        bounty_id: u64,
+        submission_index: u64,
+        #[callback_result] verified: Result<bool, PromiseError>,
+    ) -> CreationVerification {
+        let result = match verified {
+            Ok(true) => CreationVerification::Verified,
+            Ok(false) => CreationVerification::Rejected,
+            Err(_) => CreationVerification::Unavailable,
+        };
+        // The bounty may have been cleaned up while the check ran
+        if self.bounties.get(&bounty_id).is_some() {
+            self.creation_verifications.insert(&(bounty_id, submission_index), &result);
+            env::log_str(&format!(
+                "CREATION_VERIFICATION: Bounty {} submission {} is {:?}",
+                bounty_id, submission_index, result
+            ));
+        }
+        result
+    }
 
-        for i in 1..self.next_bounty_id {
-            if let Some(bounty) = self.bounties.get(&i) {
-                if bounty.is_active && !bounty.is_closed && current_time < bounty.ends_at {
-                    active_bounties.push(bounty.into());
-                }
-            }
+    /// Re-run the registry check for a submission whose check failed (anyone).
+    pub fn verify_submission(&mut self, bounty_id: u64, submission_index: u64) {
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(!bounty.is_closed, "Bounty is already closed");
+        require!(self.creation_registry_id.is_some(), "Creation verification is not configured");
+        let submission = bounty.submissions.get(submission_index as usize).expect("Submission not found");
+        require!(
+            matches!(
+                self.creation_verifications.get(&(bounty_id, submission_index)),
+                None | Some(CreationVerification::Unavailable)
+            ),
+            "Submission has already been checked"
+        );
+        self.request_creation_verification(
+            bounty_id,
+            submission_index,
+            submission.creation_id.clone(),
+            submission.creator.clone(),
+        );
+    }
+
+    pub fn get_submission_verification(&self, bounty_id: u64, submission_index: u64) -> Option<CreationVerification> {
+        self.creation_verifications.get(&(bounty_id, submission_index))
+    }
+
+    fn is_emergency_closed(&self, bounty_id: u64) -> bool {
+        self.emergency_closed_bounties.contains(&bounty_id)
+    }
+
+    fn base_prize_refunded(&self, bounty_id: u64) -> bool {
+        self.refunded_base_prizes.contains(&bounty_id)
+    }
+
+    fn bounty_view(&self, bounty: Bounty) -> BountyView {
+        let bounty_id = bounty.id;
+        let mut view = BountyView::from(bounty);
+        for (index, submission) in view.submissions.iter_mut().enumerate() {
+            submission.verification = self.creation_verifications.get(&(bounty_id, index as u64));
+            submission.flag = self.submission_flags.get(&(bounty_id, index as u64));
         }
+        view.max_stake_boost_bps = self.stake_weightings.get(&bounty_id).map_or(0, |weighting| weighting.max_boost_bps);
+        view.submission_bond = self.submission_bonds.get(&bounty_id);
+        view.community_id = self.bounty_communities.get(&bounty_id);
+        view.starts_at = self.bounty_starts.get(&bounty_id);
+        view.is_emergency_closed = self.is_emergency_closed(bounty_id);
+        view
+    }
+
+    fn stake_view(&self, stake: ParticipantStake) -> ParticipantStakeView {
+        let weight_bps = match (self.bounties.get(&stake.bounty_id), self.stake_weightings.get(&stake.bounty_id)) {
+            (Some(bounty), Some(weighting)) => {
+                self.stake_weight_bps(&bounty, weighting.max_boost_bps, stake.staked_at)
+            }
+            _ => BPS_DENOMINATOR,
+        };
+        let mut view = ParticipantStakeView::from(stake);
+        view.weight_bps = weight_bps as u32;
+        view
+    }
+
+    fn is_rejected_submission(&self, bounty_id: u64, submission_index: u64) -> bool {
+        self.creation_verifications.get(&(bounty_id, submission_index)) == Some(CreationVerification::Rejected)
+    }
 
-        active_bounties
+    pub fn get_bounty(&self, bounty_id: u64) -> Option<BountyView> {
+        self.bounties.get(&bounty_id).map(|bounty| self.bounty_view(bounty))
+    }
+
+    /// Active bounties in id order from `from_id` (default 1), at most `limit` (up to 100, the
+    /// default) per page. The next page starts after the last returned id.
+    pub fn get_active_bounties(&self, from_id: Option<u64>, limit: Option<u64>) -> Vec<BountyView> {
+        self.active_bounties(from_id, limit, |_| true)
+    }
+
+    fn active_bounties(
+        &self,
+        from_id: Option<u64>,
+        limit: Option<u64>,
+        include: impl Fn(u64) -> bool,
+    ) -> Vec<BountyView> {
+        let limit = page_limit(limit);
+        let current_time = env::block_timestamp();
+        (from_id.unwrap_or(1).max(1)..self.next_bounty_id)
+            .filter(|bounty_id| include(*bounty_id))
+            .filter_map(|bounty_id| self.bounties.get(&bounty_id))
+            .filter(|bounty| bounty.is_active && !bounty.is_closed && current_time < bounty.ends_at)
+            .take(limit)
+            .map(|bounty| self.bounty_view(bounty))
+            .collect()
     }
 
     // Staking on Content Submissions
@@ -809,44 +2176,15 @@ impl BountyPredictionContract {
 
         // Get and validate bounty
         let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
-        require!(bounty.is_active, "Bounty is not active");
-        require!(!bounty.is_closed, "Bounty is already closed");
-        require!(current_time < bounty.ends_at, "Bounty has expired");
-
-        // Validate submission index
-        require!(
-            (submission_index as usize) < bounty.submissions.len(),
-            format!("Invalid submission index: bounty has {} submissions", bounty.submissions.len())
-        );
-
-        // Validate stake amount
-        require!(amount > NearToken::from_yoctonear(0), "Stake amount must be positive");
-        require!(amount <= bounty.max_stake_per_user, "Stake amount exceeds maximum allowed for this bounty");
+        if let Some(reason) = self.stake_rejection(&bounty, submission_index, amount, &staker, current_time) {
+            env::panic_str(&reason);
+        }
 
         let stake_key = (staker.clone(), bounty_id);
         let is_new_participant = !self.participant_stakes.contains_key(&stake_key);
 
-        // CRITICAL: Check participant limit BEFORE adding new participants
-        if is_new_participant {
-            let current_participant_count = self.count_bounty_participants(bounty_id);
-            require!(
-                current_participant_count < MAX_PARTICIPANTS_PER_BOUNTY as u64,
-                format!("Bounty has reached maximum participant limit of {}",
-                    MAX_PARTICIPANTS_PER_BOUNTY)
-            );
-        }
-
         // Handle existing stake
-        if let Some(existing_stake) = self.participant_stakes.get(&stake_key) {
-            // Remove previous stake from bounty and submission totals
-            bounty.total_staked = Self::safe_sub_tokens(bounty.total_staked, existing_stake.amount)
-                .expect("Total stake subtraction underflow");
-            bounty.submissions[existing_stake.submission_index as usize].total_staked =
-                Self::safe_sub_tokens(
-                    bounty.submissions[existing_stake.submission_index as usize].total_staked,
-                    existing_stake.amount
-                ).expect("Submission stake subtraction underflow");
-        }
+        self.remove_existing_stake(&mut bounty, &staker);
 
         // Add participant to tracking list if they're new
         if is_new_participant {
@@ -878,10 +2216,127 @@ impl BountyPredictionContract {
 
         env::log_str(&format!("SUBMISSION_STAKE: Account {} staked {} NEAR on submission {} for bounty {}",
                              staker, amount.as_near(), submission_index, bounty_id));
+        SubmissionStaked {
+            bounty_id,
+            submission_index,
+            account_id: staker,
+            amount: U128(amount.as_yoctonear()),
+        }
+        .emit();
+    }
+
+    // Why stake_on_submission would reject the stake, as the message it panics with
+    fn stake_rejection(
+        &self,
+        bounty: &Bounty,
+        submission_index: u64,
+        amount: NearToken,
+        staker: &AccountId,
+        current_time: u64,
+    ) -> Option<String> {
+        if !bounty.is_active {
+            return Some("Bounty is not active".to_string());
+        }
+        if bounty.is_closed {
+            return Some("Bounty is already closed".to_string());
+        }
+        if current_time >= bounty.ends_at {
+            return Some("Bounty has expired".to_string());
+        }
+        if current_time < self.bounty_opens_at(bounty) {
+            return Some("Bounty has not started yet".to_string());
+        }
+
+        // Validate submission index
+        if submission_index as usize >= bounty.submissions.len() {
+            return Some(format!("Invalid submission index: bounty has {} submissions", bounty.submissions.len()));
+        }
+        if self.is_rejected_submission(bounty.id, submission_index) {
+            return Some("Submission failed creation verification".to_string());
+        }
+
+        // Validate stake amount
+        if amount == NearToken::from_yoctonear(0) {
+            return Some("Stake amount must be positive".to_string());
+        }
+        if amount > bounty.max_stake_per_user {
+            return Some("Stake amount exceeds maximum allowed for this bounty".to_string());
+        }
+
+        // CRITICAL: Check participant limit BEFORE adding new participants
+        let is_new_participant = !self.participant_stakes.contains_key(&(staker.clone(), bounty.id));
+        if is_new_participant && self.count_bounty_participants(bounty.id) >= MAX_PARTICIPANTS_PER_BOUNTY as u64 {
+            return Some(format!("Bounty has reached maximum participant limit of {}", MAX_PARTICIPANTS_PER_BOUNTY));
+        }
+        None
+    }
+
+    // Take the staker's previous stake, which a new stake replaces, out of the bounty and submission totals
+    fn remove_existing_stake(&self, bounty: &mut Bounty, staker: &AccountId) {
+        let Some(existing_stake) = self.participant_stakes.get(&(staker.clone(), bounty.id)) else {
+            return;
+        };
+        bounty.total_staked = Self::safe_sub_tokens(bounty.total_staked, existing_stake.amount)
+            .expect("Total stake subtraction underflow");
+        bounty.submissions[existing_stake.submission_index as usize].total_staked =
+            Self::safe_sub_tokens(
+                bounty.submissions[existing_stake.submission_index as usize].total_staked,
+                existing_stake.amount
+            ).expect("Submission stake subtraction underflow");
+    }
+
+    /// Preview `staker` staking `amount` on a submission: the totals it would leave, the
+    /// submission's share of all stakes, the staker's share of the submission's backing and what
+    /// they would be paid if it won and the bounty closed now. A stake replaces the staker's
+    /// previous one. `rejection` is the reason stake_on_submission would panic, in which case the
+    /// totals are left as they stand. None if the bounty does not exist.
+    pub fn simulate_stake(
+        &self,
+        bounty_id: u64,
+        submission_index: u64,
+        amount: U128,
+        staker: AccountId,
+    ) -> Option<StakeSimulation> {
+        let mut bounty = self.bounties.get(&bounty_id)?;
+        let amount = NearToken::from_yoctonear(amount.0);
+        let rejection = self.stake_rejection(&bounty, submission_index, amount, &staker, env::block_timestamp());
+
+        let mut projected_reward = 0;
+        if rejection.is_none() {
+            self.remove_existing_stake(&mut bounty, &staker);
+            bounty.total_staked = Self::safe_add_tokens(bounty.total_staked, amount)
+                .expect("Total stake addition overflow");
+            bounty.submissions[submission_index as usize].total_staked =
+                Self::safe_add_tokens(bounty.submissions[submission_index as usize].total_staked, amount)
+                    .expect("Submission stake addition overflow");
+            projected_reward = self
+                .calculate_backer_reward(&bounty, amount, env::block_timestamp(), submission_index)
+                .as_yoctonear();
+        }
+
+        let submission_total = bounty
+            .submissions
+            .get(submission_index as usize)
+            .map_or(0, |submission| submission.total_staked.as_yoctonear());
+        let share_bps = |part: u128, whole: u128| {
+            if whole == 0 {
+                0
+            } else {
+                pro_rata(BPS_DENOMINATOR, part, whole).expect("Share calculation overflow") as u32
+            }
+        };
+        Some(StakeSimulation {
+            total_staked: U128(bounty.total_staked.as_yoctonear()),
+            submission_total_staked: U128(submission_total),
+            submission_share_bps: share_bps(submission_total, bounty.total_staked.as_yoctonear()),
+            backer_share_bps: if rejection.is_none() { share_bps(amount.as_yoctonear(), submission_total) } else { 0 },
+            projected_reward: U128(projected_reward),
+            rejection,
+        })
     }
 
     pub fn get_participant_stake(&self, account: AccountId, bounty_id: u64) -> Option<ParticipantStakeView> {
-        self.participant_stakes.get(&(account, bounty_id)).map(|stake| stake.into())
+        self.participant_stakes.get(&(account, bounty_id)).map(|stake| self.stake_view(stake))
     }
 
     pub fn get_bounty_submission_stakes(&self, bounty_id: u64) -> Vec<U128> {
@@ -892,18 +2347,20 @@ impl BountyPredictionContract {
         }
     }
 
-    pub fn get_user_bounties(&self, account: AccountId) -> Vec<ParticipantStakeView> {
-        let mut user_stakes = Vec::new();
-
-        // Iterate through all bounties to find user's participations
-        for i in 1..self.next_bounty_id {
-            let stake_key = (account.clone(), i);
-            if let Some(stake) = self.participant_stakes.get(&stake_key) {
-                user_stakes.push(stake.into());
-            }
-        }
-
-        user_stakes
+    /// The account's unclaimed stakes by bounty id from `from_id` (default 1), at most `limit`
+    /// (up to 100, the default) per page.
+    pub fn get_user_bounties(
+        &self,
+        account: AccountId,
+        from_id: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<ParticipantStakeView> {
+        let limit = page_limit(limit);
+        (from_id.unwrap_or(1).max(1)..self.next_bounty_id)
+            .filter_map(|bounty_id| self.participant_stakes.get(&(account.clone(), bounty_id)))
+            .take(limit)
+            .map(|stake| self.stake_view(stake))
+            .collect()
     }
 
     pub fn get_bounty_participants(&self, bounty_id: u64) -> Vec<AccountId> {
@@ -937,6 +2394,11 @@ impl BountyPredictionContract {
         let mut has_stakes = false;
 
         for (index, submission) in bounty.submissions.iter().enumerate() {
+            if self.is_rejected_submission(bounty.id, index as u64)
+                || self.submission_flags.contains_key(&(bounty.id, index as u64))
+            {
+                continue;
+            }
             if submission.total_staked > NearToken::from_yoctonear(0) {
                 has_stakes = true;
                 if submission.total_staked > max_stake {
@@ -953,62 +2415,115 @@ impl BountyPredictionContract {
         }
     }
 
-    fn calculate_platform_fee(&self, total_amount: NearToken) -> NearToken {
-        let fee_amount = total_amount.as_yoctonear()
-            .checked_mul(self.platform_fee_rate as u128)
-            .and_then(|x| x.checked_div(10000)) // Convert basis points to percentage
-            .unwrap_or(0);
+    // The fee rate a bounty was settled at, or its community's current one while it is open
+    fn bounty_fee_rate(&self, bounty_id: u64) -> u128 {
+        self.settled_fee_rates.get(&bounty_id).unwrap_or_else(|| self.community_fee_rate(bounty_id))
+    }
 
+    fn bounty_platform_fee(&self, bounty_id: u64, total_amount: NearToken) -> NearToken {
+        let fee_amount = apply_bps(total_amount.as_yoctonear(), self.bounty_fee_rate(bounty_id))
+            .expect("Platform fee calculation overflow");
         NearToken::from_yoctonear(fee_amount)
     }
 
-    fn calculate_backer_reward(&self, bounty: &Bounty, user_stake: NearToken, winning_submission: u64) -> NearToken {
+    fn calculate_backer_reward(
+        &self,
+        bounty: &Bounty,
+        user_stake: NearToken,
+        staked_at: u64,
+        winning_submission: u64,
+    ) -> NearToken {
         let total_winning_stakes = bounty.submissions[winning_submission as usize].total_staked;
 
         if total_winning_stakes == NearToken::from_yoctonear(0) {
             return NearToken::from_yoctonear(0);
         }
 
+        let (user_weight, total_weight) = self.backer_weights(bounty, user_stake, staked_at, total_winning_stakes);
+
         // Calculate total prize pool (base_prize + community stakes)
         let total_prize = Self::safe_add_tokens(bounty.base_prize, bounty.total_staked)
             .expect("Total prize calculation overflow");
         
         // Calculate platform fee from total prize
-        let platform_fee = self.calculate_platform_fee(total_prize);
+        let platform_fee = self.bounty_platform_fee(bounty.id, total_prize);
         let prize_after_fee = Self::safe_sub_tokens(total_prize, platform_fee)
             .unwrap_or(total_prize);
 
         // Split prize: backer_share% to backers (distributed proportionally)
-        let backer_pool_raw = prize_after_fee.as_yoctonear()
-            .checked_mul(bounty.backer_share as u128)
-            .and_then(|x| x.checked_div(100))
-            .unwrap_or(0);
-        let backer_pool = NearToken::from_yoctonear(backer_pool_raw);
-
-        // Calculate proportional reward for this backer
-        let user_share = user_stake.as_yoctonear()
-            .checked_mul(backer_pool.as_yoctonear())
-            .and_then(|x| x.checked_div(total_winning_stakes.as_yoctonear()))
-            .unwrap_or(0);
+        let backer_pool = apply_percent(prize_after_fee.as_yoctonear(), bounty.backer_share as u128)
+            .expect("Backer pool calculation overflow");
+
+        // Calculate proportional reward for this backer. The stake * pool product exceeds u128
+        // for ordinary NEAR amounts, so it is taken at full width.
+        let user_share = pro_rata(backer_pool, user_weight, total_weight)
+            .expect("Backer reward calculation overflow");
 
         NearToken::from_yoctonear(user_share)
     }
 
+    // A winning backer's part of the backer pool, as (their weight, all winning backers' weight).
+    // Weighted bounties split by stake * duration weight; the total was fixed at settlement.
+    fn backer_weights(
+        &self,
+        bounty: &Bounty,
+        user_stake: NearToken,
+        staked_at: u64,
+        total_winning_stakes: NearToken,
+    ) -> (u128, u128) {
+        match self.stake_weightings.get(&bounty.id) {
+            Some(weighting) if weighting.weighted_winning_stake > 0 => (
+                self.weighted_stake(bounty, weighting.max_boost_bps, user_stake.as_yoctonear(), staked_at),
+                weighting.weighted_winning_stake,
+            ),
+            _ => (user_stake.as_yoctonear(), total_winning_stakes.as_yoctonear()),
+        }
+    }
+
+    // A stake's reward weight in basis points: 10000 plus the boost, scaled by the share of the
+    // bounty's run that was still ahead when the stake was placed
+    fn stake_weight_bps(&self, bounty: &Bounty, max_boost_bps: u16, staked_at: u64) -> u128 {
+        let duration = bounty.ends_at.saturating_sub(self.bounty_opens_at(bounty));
+        if duration == 0 {
+            return BPS_DENOMINATOR;
+        }
+        let remaining = bounty.ends_at.saturating_sub(staked_at).min(duration);
+        BPS_DENOMINATOR + u128::from(max_boost_bps) * u128::from(remaining) / u128::from(duration)
+    }
+
+    fn weighted_stake(&self, bounty: &Bounty, max_boost_bps: u16, amount: u128, staked_at: u64) -> u128 {
+        apply_bps(amount, self.stake_weight_bps(bounty, max_boost_bps, staked_at)).expect("Stake weight overflow")
+    }
+
+    // Fix the weighted total of the winning backers before claims start removing their stakes
+    fn record_weighted_winning_stake(&mut self, bounty: &Bounty, winning_submission: u64) {
+        let Some(mut weighting) = self.stake_weightings.get(&bounty.id) else {
+            return;
+        };
+        let max_boost_bps = weighting.max_boost_bps;
+        weighting.weighted_winning_stake = self
+            .get_bounty_participants(bounty.id)
+            .into_iter()
+            .filter_map(|account| self.participant_stakes.get(&(account, bounty.id)))
+            .filter(|stake| stake.submission_index == winning_submission)
+            .map(|stake| self.weighted_stake(bounty, max_boost_bps, stake.amount.as_yoctonear(), stake.staked_at))
+            .sum();
+        self.stake_weightings.insert(&bounty.id, &weighting);
+    }
+
     fn calculate_creator_reward(&self, bounty: &Bounty) -> NearToken {
         // Calculate total prize pool (base_prize + community stakes)
         let total_prize = Self::safe_add_tokens(bounty.base_prize, bounty.total_staked)
             .expect("Total prize calculation overflow");
         
         // Calculate platform fee
-        let platform_fee = self.calculate_platform_fee(total_prize);
+        let platform_fee = self.bounty_platform_fee(bounty.id, total_prize);
         let prize_after_fee = Self::safe_sub_tokens(total_prize, platform_fee)
             .unwrap_or(total_prize);
 
         // Creator gets creator_share% of prize after fees
-        let creator_reward_raw = prize_after_fee.as_yoctonear()
-            .checked_mul(bounty.creator_share as u128)
-            .and_then(|x| x.checked_div(100))
-            .unwrap_or(0);
+        let creator_reward_raw = apply_percent(prize_after_fee.as_yoctonear(), bounty.creator_share as u128)
+            .expect("Creator reward calculation overflow");
 
         NearToken::from_yoctonear(creator_reward_raw)
     }
@@ -1064,14 +2579,12 @@ impl BountyPredictionContract {
         let caller = env::predecessor_account_id();
         let current_time = env::block_timestamp();
 
-        let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
 
         // Trustless Closure Logic:
         // 1. Creator can close anytime after 'ends_at'.
         // 2. ANYONE can close after 'ends_at + grace_period' (7 days).
         // This ensures funds are never stuck if the creator goes inactive.
-        const CLOSE_GRACE_PERIOD_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7 days
-
         let is_creator = caller == bounty.creator;
         let is_past_grace_period = current_time >= bounty.ends_at + CLOSE_GRACE_PERIOD_NS;
 
@@ -1085,6 +2598,46 @@ impl BountyPredictionContract {
         require!(!bounty.is_closed, "Bounty is already closed");
         require!(current_time >= bounty.ends_at, "Bounty has not expired yet");
 
+        self.settle_bounty(bounty);
+    }
+
+    /// Maintenance for keepers and cron agents such as a Croncat task (owner or keeper role).
+    /// Inspects up to 20 bounty ids from where the previous tick stopped, wrapping around, and
+    /// closes up to 3 of them that ended more than 7 days ago, i.e. those anyone may close.
+    pub fn tick(&mut self) -> TickReport {
+        self.assert_role(Role::Keeper);
+        let current_time = env::block_timestamp();
+        let scan_limit = MAX_TICK_SCAN.min(self.next_bounty_id.saturating_sub(1));
+        let mut cursor = self.tick_cursor;
+        let mut scanned = 0;
+        let mut closed = Vec::new();
+
+        while scanned < scan_limit && closed.len() < MAX_TICK_CLOSES {
+            if cursor >= self.next_bounty_id {
+                cursor = 1;
+            }
+            scanned += 1;
+            if let Some(bounty) = self.bounties.get(&cursor) {
+                if bounty.is_active && !bounty.is_closed && current_time >= bounty.ends_at + CLOSE_GRACE_PERIOD_NS {
+                    closed.push(cursor);
+                    self.settle_bounty(bounty);
+                }
+            }
+            cursor += 1;
+        }
+        self.tick_cursor = cursor;
+
+        let report = TickReport { scanned, closed, next_bounty_id: cursor };
+        groupweave_events::bounty::emit("maintenance_tick", &report);
+        report
+    }
+
+    // Pay out an expired bounty and mark it closed
+    fn settle_bounty(&mut self, mut bounty: Bounty) {
+        let bounty_id = bounty.id;
+        self.settled_fee_rates.insert(&bounty_id, &self.community_fee_rate(bounty_id));
+        self.forfeit_unqualified_bonds(&mut bounty);
+
         // Handle different scenarios
         if bounty.total_staked == NearToken::from_yoctonear(0) {
             // No participants - just close the bounty
@@ -1092,6 +2645,9 @@ impl BountyPredictionContract {
             bounty.is_active = false;
             self.bounties.insert(&bounty_id, &bounty);
             env::log_str(&format!("BOUNTY_CLOSED: No participants in bounty {}", bounty_id));
+            BountyClosed { bounty_id, winning_submission: None, total_staked: U128(0) }.emit();
+            self.return_ft_prizes(bounty_id);
+            self.record_liabilities(&bounty);
             return;
         }
 
@@ -1108,8 +2664,41 @@ impl BountyPredictionContract {
         bounty.is_closed = true;
         bounty.is_active = false;
         self.bounties.insert(&bounty_id, &bounty);
+        match bounty.winning_submission {
+            Some(winning_submission) => self.credit_ft_creator_share(&bounty, winning_submission),
+            None => self.return_ft_prizes(bounty_id),
+        }
+        self.record_liabilities(&bounty);
 
         env::log_str(&format!("BOUNTY_CLOSED: Bounty {} closed and rewards distributed", bounty_id));
+        BountyClosed {
+            bounty_id,
+            winning_submission: bounty.winning_submission,
+            total_staked: U128(bounty.total_staked.as_yoctonear()),
+        }
+        .emit();
+    }
+
+    // Add the bonds of submissions that drew less than the qualifying stake to the base prize
+    fn forfeit_unqualified_bonds(&mut self, bounty: &mut Bounty) {
+        let Some(terms) = self.submission_bonds.get(&bounty.id) else {
+            return;
+        };
+        let mut forfeited = NearToken::from_yoctonear(0);
+        for (index, submission) in bounty.submissions.iter().enumerate() {
+            let key = (bounty.id, index as u64);
+            if submission.total_staked.as_yoctonear() >= terms.min_qualifying_stake.0 {
+                continue;
+            }
+            if let Some(bond) = self.posted_bonds.get(&key) {
+                self.posted_bonds.remove(&key);
+                forfeited = forfeited.saturating_add(bond);
+            }
+        }
+        if forfeited > NearToken::from_yoctonear(0) {
+            bounty.base_prize = bounty.base_prize.saturating_add(forfeited);
+            env::log_str(&format!("SUBMISSION_BONDS_FORFEITED: {} added to bounty {} prize", forfeited, bounty.id));
+        }
     }
 
     fn distribute_single_participant_rewards(&mut self, bounty: &mut Bounty) {
@@ -1119,10 +2708,14 @@ impl BountyPredictionContract {
                 for account in participants {
                     let stake_key = (account.clone(), bounty.id);
                     if let Some(stake) = self.participant_stakes.get(&stake_key) {
-                        // Return full stake to participant
-                        Promise::new(account.clone()).transfer(stake.amount);
+                        // Return full stake to participant, settling the stake so it cannot be claimed again
+                        self.participant_stakes.remove(&stake_key);
+                        let payout = stake.amount;
+                        self.claimed_stakes.insert(&stake_key, &ClaimedStake { payout, stake });
+                        self.record_claim_receipt(&stake_key, ClaimKind::SingleParticipant, payout);
+                        self.pay_out(account.clone(), payout);
                         env::log_str(&format!("SINGLE_PARTICIPANT_REFUND: {} received {} NEAR",
-                                             account, stake.amount));
+                                             account, payout));
                         return;
                     }
                 }
@@ -1136,12 +2729,18 @@ impl BountyPredictionContract {
         let winning_submission = match self.determine_winning_submission(bounty) {
             Some(submission) => submission,
             None => {
-                env::log_str(&format!("BOUNTY_ERROR: No winning submission determined for bounty {}", bounty.id));
+                // Every staked submission failed creation verification: refund it like an emergency close
+                self.emergency_closed_bounties.insert(&bounty.id);
+                env::log_str(&format!(
+                    "BOUNTY_ERROR: No winning submission for bounty {}, stakes and base prize are refundable",
+                    bounty.id
+                ));
                 return;
             }
         };
 
         bounty.winning_submission = Some(winning_submission);
+        self.record_weighted_winning_stake(bounty, winning_submission);
         
         // Get winning creator
         let winning_creator = bounty.submissions[winning_submission as usize].creator.clone();
@@ -1150,18 +2749,22 @@ impl BountyPredictionContract {
         let total_prize = Self::safe_add_tokens(bounty.base_prize, bounty.total_staked)
             .expect("Total prize calculation overflow");
 
-        // Calculate and transfer platform fee
-        let platform_fee = self.calculate_platform_fee(total_prize);
+        // Calculate and transfer platform fee, less the storage deposit for the winner NFT
+        let mut platform_fee = self.bounty_platform_fee(bounty.id, total_prize);
+        if self.nft_contract_id.is_some() {
+            platform_fee = platform_fee.saturating_sub(NFT_MINT_DEPOSIT);
+        }
         if platform_fee > NearToken::from_yoctonear(0) {
-            Promise::new(self.owner.clone()).transfer(platform_fee);
-            env::log_str(&format!("PLATFORM_FEE: {} NEAR transferred to owner from bounty {}", 
-                                 platform_fee.as_near(), bounty.id));
+            let fee_recipient = self.fee_recipient(bounty.id);
+            self.pay_out(fee_recipient.clone(), platform_fee);
+            env::log_str(&format!("PLATFORM_FEE: {} NEAR transferred to {} from bounty {}",
+                                 platform_fee.as_near(), fee_recipient, bounty.id));
         }
 
         // Pay the winning creator their share
         let creator_reward = self.calculate_creator_reward(bounty);
         if creator_reward > NearToken::from_yoctonear(0) {
-            Promise::new(winning_creator.clone()).transfer(creator_reward);
+            self.pay_out(winning_creator.clone(), creator_reward);
             env::log_str(&format!("CREATOR_REWARD: {} received {} NEAR ({}%) for winning submission {}",
                                  winning_creator, creator_reward.as_near(), 
                                  bounty.creator_share, winning_submission));
@@ -1169,6 +2772,153 @@ impl BountyPredictionContract {
 
         // Distribute backer rewards to winners
         self.distribute_winner_rewards(bounty, winning_submission);
+
+        if let Some(nft_contract_id) = self.nft_contract_id.clone() {
+            self.mint_winner_nft(nft_contract_id, bounty, winning_submission);
+        }
+
+        if let Some(reputation_contract_id) = self.reputation_contract_id.clone() {
+            self.record_winner_reputation(reputation_contract_id, bounty, winning_submission);
+        }
+
+        if let Some(leaderboard_contract_id) = self.leaderboard_contract_id.clone() {
+            self.report_settlement(leaderboard_contract_id, bounty, winning_submission, creator_reward);
+        }
+    }
+
+    // Push the settlement to the leaderboard. Like the reputation report, a failure never blocks settlement.
+    fn report_settlement(
+        &self,
+        leaderboard_contract_id: AccountId,
+        bounty: &Bounty,
+        winning_submission: u64,
+        creator_reward: NearToken,
+    ) {
+        let backers: Vec<BackerStake> = self
+            .winning_backers(bounty, winning_submission)
+            .into_iter()
+            .map(|(account_id, amount)| BackerStake { account_id, amount: U128(amount) })
+            .collect();
+        let report = SettlementReport {
+            market_id: bounty.id,
+            title: bounty.title.clone(),
+            winner: bounty.submissions[winning_submission as usize].creator.clone(),
+            winner_earned: U128(creator_reward.as_yoctonear()),
+            volume: U128(bounty.base_prize.saturating_add(bounty.total_staked).as_yoctonear()),
+            backers,
+        };
+        env::log_str(&format!("LEADERBOARD_REPORTED: bounty {} with {} backers", bounty.id, report.backers.len()));
+        ext_leaderboard::ext(leaderboard_contract_id)
+            .with_static_gas(GAS_FOR_LEADERBOARD_RECORD)
+            .record_settlement(report);
+    }
+
+    // Count a win for the winning creator and a successful stake for each of its backers.
+    // Like the NFT mint, a failed report never blocks settlement.
+    fn record_winner_reputation(&self, reputation_contract_id: AccountId, bounty: &Bounty, winning_submission: u64) {
+        let winner = bounty.submissions[winning_submission as usize].creator.clone();
+        let backers: Vec<AccountId> =
+            self.winning_backers(bounty, winning_submission).into_iter().map(|(account, _)| account).collect();
+        env::log_str(&format!(
+            "REPUTATION_REPORTED: bounty {} winner {} and {} backers",
+            bounty.id, winner, backers.len()
+        ));
+        ext_reputation::ext(reputation_contract_id.clone())
+            .with_static_gas(GAS_FOR_REPUTATION_RECORD)
+            .record(vec![winner], ReputationSignal::Win);
+        if !backers.is_empty() {
+            ext_reputation::ext(reputation_contract_id)
+                .with_static_gas(GAS_FOR_REPUTATION_RECORD)
+                .record(backers, ReputationSignal::SuccessfulStake);
+        }
+    }
+
+    // Backers of the winning submission with their stakes, largest first
+    fn winning_backers(&self, bounty: &Bounty, winning_submission: u64) -> Vec<(AccountId, u128)> {
+        let mut backers: Vec<(AccountId, u128)> = self
+            .get_bounty_participants_ref()
+            .and_then(|participants| participants.get(&bounty.id))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|account| {
+                let stake = self.participant_stakes.get(&(account.clone(), bounty.id))?;
+                (stake.submission_index == winning_submission).then(|| (account, stake.amount.as_yoctonear()))
+            })
+            .collect();
+        backers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        backers
+    }
+
+    // Mint the winning creation to its creator with the configured royalty split.
+    // A failed mint never blocks settlement; the deposit is refunded to this contract.
+    fn mint_winner_nft(&self, nft_contract_id: AccountId, bounty: &Bounty, winning_submission: u64) {
+        let submission = &bounty.submissions[winning_submission as usize];
+        let token_id = submission.creation_id.clone();
+        let royalties = self.winner_royalties(bounty, winning_submission);
+        let token_metadata = TokenMetadata {
+            title: Some(submission.title.clone()),
+            description: Some(format!("Winner of bounty {}: {}", bounty.id, bounty.title)),
+            media: Some(submission.thumbnail_url.clone()).filter(|url| !url.is_empty()),
+            media_hash: None,
+            copies: Some(1),
+            issued_at: Some(env::block_timestamp_ms().to_string()),
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: Some(serde_json::json!({ "bounty_id": bounty.id, "creation_id": token_id }).to_string()),
+            reference: None,
+            reference_hash: None,
+        };
+
+        env::log_str(&format!("NFT_MINT_REQUESTED: {} for bounty {} to {}", token_id, bounty.id, submission.creator));
+        ext_content_nft::ext(nft_contract_id)
+            .with_attached_deposit(NFT_MINT_DEPOSIT)
+            .with_static_gas(GAS_FOR_NFT_MINT)
+            .nft_mint(token_id.clone(), submission.creator.clone(), token_metadata, royalties)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_NFT_MINT_CALLBACK)
+                    .on_winner_nft_minted(bounty.id, token_id),
+            );
+    }
+
+    // Creator and platform get fixed bps; the backer bps are shared by stake among the
+    // largest backers of the winning submission, with rounding dust to the largest
+    fn winner_royalties(&self, bounty: &Bounty, winning_submission: u64) -> HashMap<AccountId, u32> {
+        let split = &self.nft_royalty;
+        let mut royalties: HashMap<AccountId, u32> = HashMap::new();
+        let creator = bounty.submissions[winning_submission as usize].creator.clone();
+        *royalties.entry(creator).or_insert(0) += u32::from(split.creator_bps);
+        *royalties.entry(self.owner.clone()).or_insert(0) += u32::from(split.platform_bps);
+
+        let mut backers = self.winning_backers(bounty, winning_submission);
+        backers.truncate(split.top_backers as usize);
+
+        let backers_bps = u128::from(split.backers_bps);
+        let top_stake: u128 = backers.iter().map(|(_, amount)| amount).sum();
+        let shares: Vec<u128> = backers
+            .iter()
+            .map(|(_, amount)| pro_rata(backers_bps, *amount, top_stake).expect("Royalty calculation overflow"))
+            .collect();
+        let dust = backers_bps.saturating_sub(shares.iter().sum());
+        for (index, ((account, _), share)) in backers.into_iter().zip(shares).enumerate() {
+            let share = if index == 0 { share + dust } else { share };
+            *royalties.entry(account).or_insert(0) += share as u32;
+        }
+        royalties.retain(|_, bps| *bps > 0);
+        royalties
+    }
+
+    #[private]
+    pub fn on_winner_nft_minted(&mut self, bounty_id: u64, token_id: String) -> bool {
+        if is_promise_success() {
+            self.bounty_nfts.insert(&bounty_id, &token_id);
+            env::log_str(&format!("NFT_MINTED: {} for bounty {}", token_id, bounty_id));
+            true
+        } else {
+            env::log_str(&format!("NFT_MINT_FAILED: {} for bounty {}", token_id, bounty_id));
+            false
+        }
     }
 
     fn distribute_winner_rewards(&mut self, bounty: &Bounty, winning_submission: u64) {
@@ -1183,7 +2933,7 @@ impl BountyPredictionContract {
     pub fn get_bounty_results(&self, bounty_id: u64) -> Option<BountyView> {
         if let Some(bounty) = self.bounties.get(&bounty_id) {
             if bounty.is_closed {
-                Some(bounty.into())
+                Some(self.bounty_view(bounty))
             } else {
                 None // Only return results for closed bounties
             }
@@ -1192,380 +2942,3195 @@ impl BountyPredictionContract {
         }
     }
 
+    /// Frozen inputs and per-participant payouts of a closed bounty, for recomputing its settlement off-chain.
+    pub fn get_settlement_report(&self, bounty_id: u64) -> Option<SettlementAudit> {
+        let bounty = self.bounties.get(&bounty_id).filter(|bounty| bounty.is_closed)?;
+        // Fees and the creator/backer split only apply when a winner was picked
+        let total_prize = bounty.base_prize.saturating_add(bounty.total_staked);
+        let (platform_fee, creator_reward, backer_pool) = match bounty.winning_submission {
+            Some(_) => {
+                let platform_fee = self.bounty_platform_fee(bounty_id, total_prize);
+                let prize_after_fee = total_prize.saturating_sub(platform_fee).as_yoctonear();
+                let backer_pool = apply_percent(prize_after_fee, bounty.backer_share as u128).unwrap_or(0);
+                (platform_fee.as_yoctonear(), self.calculate_creator_reward(&bounty).as_yoctonear(), backer_pool)
+            }
+            None => (0, 0, 0),
+        };
+        let weighting = self.stake_weightings.get(&bounty_id);
+        let winning_creator = bounty
+            .winning_submission
+            .map(|index| bounty.submissions[index as usize].creator.clone());
+
+        let payouts = self
+            .get_bounty_participants(bounty_id)
+            .into_iter()
+            .filter_map(|account_id| {
+                let key = (account_id.clone(), bounty_id);
+                let (stake, payout, claimed) = match self.participant_stakes.get(&key) {
+                    Some(stake) => {
+                        let payout = if self.is_emergency_closed(bounty.id) {
+                            stake.amount
+                        } else {
+                            self.claim_payout(&bounty, &account_id, &stake)
+                                .map_or(NearToken::from_yoctonear(0), |(_, payout)| payout)
+                        };
+                        (stake, payout, false)
+                    }
+                    None => {
+                        let claimed = self.claimed_stakes.get(&key)?;
+                        (claimed.stake, claimed.payout, true)
+                    }
+                };
+                let weight_bps = weighting.as_ref().map_or(BPS_DENOMINATOR, |weighting| {
+                    self.stake_weight_bps(&bounty, weighting.max_boost_bps, stake.staked_at)
+                });
+                Some(ParticipantPayout {
+                    account_id,
+                    submission_index: stake.submission_index,
+                    stake: U128(stake.amount.as_yoctonear()),
+                    staked_at: stake.staked_at,
+                    weight_bps: weight_bps as u32,
+                    payout: U128(payout.as_yoctonear()),
+                    claimed,
+                })
+            })
+            .collect();
+
+        Some(SettlementAudit {
+            bounty_id,
+            frozen: self.settled_fee_rates.contains_key(&bounty_id) || self.is_emergency_closed(bounty.id),
+            is_emergency_closed: self.is_emergency_closed(bounty.id),
+            base_prize: U128(bounty.base_prize.as_yoctonear()),
+            total_staked: U128(bounty.total_staked.as_yoctonear()),
+            platform_fee_rate: self.bounty_fee_rate(bounty_id),
+            platform_fee: U128(platform_fee),
+            creator_share: bounty.creator_share,
+            backer_share: bounty.backer_share,
+            max_stake_boost_bps: weighting.as_ref().map_or(0, |weighting| weighting.max_boost_bps),
+            weighted_winning_stake: U128(weighting.map_or(0, |weighting| weighting.weighted_winning_stake)),
+            submissions: bounty
+                .submissions
+                .iter()
+                .enumerate()
+                .map(|(index, submission)| SubmissionTotal {
+                    index: index as u64,
+                    creator: submission.creator.clone(),
+                    total_staked: U128(submission.total_staked.as_yoctonear()),
+                    verification: self.creation_verifications.get(&(bounty_id, index as u64)),
+                })
+                .collect(),
+            winning_submission: bounty.winning_submission,
+            winning_creator,
+            creator_reward: U128(creator_reward),
+            backer_pool: U128(backer_pool),
+            payouts,
+        })
+    }
+
     pub fn claim_bounty_winnings(&mut self, bounty_id: u64) {
         // self.assert_not_paused(); // Removed
         let claimer = env::predecessor_account_id();
 
+        let (stake, kind, payout) = self.internal_settle_claim(&claimer, bounty_id);
+        if payout > NearToken::from_yoctonear(0) {
+            WinningsClaimed {
+                bounty_id,
+                account_id: claimer.clone(),
+                amount: U128(payout.as_yoctonear()),
+                as_credits: false,
+            }
+            .emit();
+        }
+
+        match kind {
+            ClaimKind::Creator if payout > NearToken::from_yoctonear(0) => {
+                self.pay_out(claimer.clone(), payout);
+                env::log_str(&format!("CLAIM_SUCCESS: Creator {} claimed {} NEAR from bounty {}",
+                                     claimer, payout.as_near(), bounty_id));
+            }
+            ClaimKind::Backer if payout > NearToken::from_yoctonear(0) => {
+                self.credit_ft_backer_share(bounty_id, &claimer, &stake);
+                self.pay_out(claimer.clone(), payout);
+                env::log_str(&format!("CLAIM_SUCCESS: Backer {} claimed {} NEAR from bounty {}",
+                                     claimer, payout.as_near(), bounty_id));
+            }
+            ClaimKind::SingleParticipant => {
+                self.pay_out(claimer.clone(), payout);
+                env::log_str(&format!("SINGLE_PARTICIPANT_CLAIM: {} claimed {} NEAR from bounty {}",
+                             claimer, payout.as_near(), bounty_id));
+            }
+            ClaimKind::Forfeit => {
+                env::log_str(&format!("CLAIM_INFO: User {} did not back winning submission. Stake forfeit.", claimer));
+            }
+            // No reward but stake removed - technically correct if reward is 0
+            _ => {}
+        }
+    }
+
+    // Claim winnings as Dreamweave platform credits instead of NEAR.
+    //
+    // The payout is forwarded to the configured deposits contract via deposit_native,
+    // which records a DepositRecord for `beneficiary_id` paid on behalf of the claimer
+    // (who receives the minted credits) and forwards the NEAR to the platform treasury,
+    // so the backend credits the winner automatically. If the
+    // deposit is rejected (e.g. stale price, below minimum) the claim is restored.
+    pub fn claim_as_credits(&mut self, bounty_id: u64, beneficiary_id: String) -> Promise {
+        let claimer = env::predecessor_account_id();
+        let deposits_contract = self.credits_contract_id.clone()
+            .expect("Credits settlement is not configured");
+
+        let (stake, _, payout) = self.internal_settle_claim(&claimer, bounty_id);
+        require!(payout > NearToken::from_yoctonear(0), "No winnings to convert to credits");
+        self.assert_solvent(payout);
+        let receipt_key = (claimer.clone(), bounty_id);
+        if let Some(mut receipt) = self.claim_receipts.get(&receipt_key) {
+            receipt.as_credits = true;
+            self.claim_receipts.insert(&receipt_key, &receipt);
+        }
+
+        env::log_str(&format!("CLAIM_AS_CREDITS: {} converting {} NEAR from bounty {} for beneficiary {}",
+                             claimer, payout.as_near(), bounty_id, beneficiary_id));
+
+        ext_deposits::ext(deposits_contract)
+            .with_attached_deposit(payout)
+            .with_static_gas(GAS_FOR_CREDITS_DEPOSIT)
+            .deposit_native(
+                beneficiary_id,
+                None,
+                Some(format!("bounty {} winnings for {}", bounty_id, claimer)),
+                None,
+                Some(claimer.clone()),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CREDITS_CALLBACK)
+                    .on_claim_as_credits(claimer, stake, U128(payout.as_yoctonear())),
+            )
+    }
+
+    #[private]
+    pub fn on_claim_as_credits(&mut self, claimer: AccountId, stake: ParticipantStake, payout: U128) {
+        if is_promise_success() {
+            env::log_str(&format!("CLAIM_AS_CREDITS_SUCCESS: {} credited for bounty {}", claimer, stake.bounty_id));
+            self.credit_ft_backer_share(stake.bounty_id, &claimer, &stake);
+            WinningsClaimed {
+                bounty_id: stake.bounty_id,
+                account_id: claimer,
+                amount: payout,
+                as_credits: true,
+            }
+            .emit();
+        } else {
+            // The attached NEAR was refunded to this contract; make the claim available again
+            let bounty_id = stake.bounty_id;
+            self.claimed_stakes.remove(&(claimer.clone(), bounty_id));
+            self.claim_receipts.remove(&(claimer.clone(), bounty_id));
+            self.participant_stakes.insert(&(claimer.clone(), bounty_id), &stake);
+            self.add_liability(bounty_id, NearToken::from_yoctonear(payout.0));
+            env::log_str(&format!("CLAIM_AS_CREDITS_FAILED: {} claim for bounty {} restored", claimer, bounty_id));
+        }
+    }
+
+    // Removes the claimer's stake record and works out what they are owed.
+    // Shared by every payout path so the rules and double-claim protection live in one place.
+    fn internal_settle_claim(&mut self, claimer: &AccountId, bounty_id: u64) -> (ParticipantStake, ClaimKind, NearToken) {
         let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
         require!(bounty.is_closed, "Bounty is not closed yet");
+        require!(
+            !self.is_emergency_closed(bounty.id),
+            "Bounty was emergency closed; use claim_emergency_refund"
+        );
 
         let stake_key = (claimer.clone(), bounty_id);
+        self.assert_not_claimed(&stake_key);
         let stake = self.participant_stakes.get(&stake_key).expect("No stake found for this bounty");
 
         // CRITICAL: Remove stake to prevent double-claiming
         self.participant_stakes.remove(&stake_key);
 
+        let (kind, payout) = self
+            .claim_payout(&bounty, claimer, &stake)
+            .unwrap_or_else(|| env::panic_str("No winning submission determined"));
+        self.release_liability(bounty_id, payout);
+        self.claimed_stakes.insert(&stake_key, &ClaimedStake { stake: stake.clone(), payout });
+        self.record_claim_receipt(&stake_key, kind, payout);
+        (stake, kind, payout)
+    }
+
+    // A second claim names the block of the first instead of only failing to find the stake
+    fn assert_not_claimed(&self, key: &(AccountId, u64)) {
+        if let Some(receipt) = self.claim_receipts.get(key) {
+            env::panic_str(&format!(
+                "Already claimed {} yoctoNEAR at block {}",
+                receipt.amount.0, receipt.block_height
+            ));
+        }
+    }
+
+    fn record_claim_receipt(&mut self, key: &(AccountId, u64), role: ClaimKind, amount: NearToken) {
+        let receipt = ClaimReceipt {
+            amount: U128(amount.as_yoctonear()),
+            role,
+            as_credits: false,
+            block_height: env::block_height(),
+            claimed_at: env::block_timestamp(),
+        };
+        self.claim_receipts.insert(key, &receipt);
+    }
+
+    pub fn get_claim_receipt(&self, account_id: AccountId, bounty_id: u64) -> Option<ClaimReceipt> {
+        self.claim_receipts.get(&(account_id, bounty_id))
+    }
+
+    // What a stake on a settled bounty pays; None while no winner could be determined
+    fn claim_payout(
+        &self,
+        bounty: &Bounty,
+        claimer: &AccountId,
+        stake: &ParticipantStake,
+    ) -> Option<(ClaimKind, NearToken)> {
         // Check if user won
         if let Some(winning_submission) = bounty.winning_submission {
             // Check if claimer is the winning creator
-            let is_winning_creator = bounty.submissions[winning_submission as usize].creator == claimer;
-            
+            let is_winning_creator = &bounty.submissions[winning_submission as usize].creator == claimer;
+
             if is_winning_creator {
-                // Pay creator reward
-                let reward = self.calculate_creator_reward(&bounty);
-
-                if reward > NearToken::from_yoctonear(0) {
-                    // Check if contract has sufficient balance
-                    let contract_balance = env::account_balance();
-                    let reserved_balance = NearToken::from_near(1); // Reserve for operations
-
-                    if contract_balance > Self::safe_add_tokens(reward, reserved_balance).unwrap_or(contract_balance) {
-                        Promise::new(claimer.clone()).transfer(reward);
-                        env::log_str(&format!("CLAIM_SUCCESS: Creator {} claimed {} NEAR from bounty {}",
-                                             claimer, reward.as_near(), bounty_id));
-                    } else {
-                        // Refund the stake if we can't pay the full reward (shouldn't happen)
-                         self.participant_stakes.insert(&stake_key, &stake);
-                        env::log_str(&format!("CLAIM_FAILED: Insufficient contract balance for {} from bounty {}",
-                                             claimer, bounty_id));
-                        panic!(
-                            "Insufficient contract balance for reward payment: contract balance = {} yoctoNEAR, required = {} yoctoNEAR",
-                            contract_balance.as_yoctonear(),
-                            Self::safe_add_tokens(reward, reserved_balance).unwrap_or(contract_balance).as_yoctonear()
-                        );
-                    }
-                } else {
-                    // No reward but stake removed - technically correct if reward is 0
-                }
+                Some((ClaimKind::Creator, self.calculate_creator_reward(bounty)))
             } else if stake.submission_index == winning_submission {
-                // Pay backer reward
-                let reward = self.calculate_backer_reward(&bounty, stake.amount, winning_submission);
-
-                if reward > NearToken::from_yoctonear(0) {
-                    // Check if contract has sufficient balance
-                    let contract_balance = env::account_balance();
-                    let reserved_balance = NearToken::from_near(1);
-
-                    if contract_balance > Self::safe_add_tokens(reward, reserved_balance).unwrap_or(contract_balance) {
-                        Promise::new(claimer.clone()).transfer(reward);
-                        env::log_str(&format!("CLAIM_SUCCESS: Backer {} claimed {} NEAR from bounty {}",
-                                             claimer, reward.as_near(), bounty_id));
-                    } else {
-                        // Refund stake
-                        self.participant_stakes.insert(&stake_key, &stake);
-                        panic!("Insufficient contract balance for reward payment");
-                    }
-                } else {
-                    // No reward to claim
-                }
+                let reward = self.calculate_backer_reward(bounty, stake.amount, stake.staked_at, winning_submission);
+                Some((ClaimKind::Backer, reward))
             } else {
-                // User did not win - stake is forfeit (removed above)
-                env::log_str(&format!("CLAIM_INFO: User {} did not back winning submission. Stake forfeit.", claimer));
+                // User did not win - stake is forfeit
+                Some((ClaimKind::Forfeit, NearToken::from_yoctonear(0)))
             }
         } else {
             // Handle single participant case - return full stake
-            let participant_count = self.count_bounty_participants(bounty_id);
-            if participant_count <= 1 {
-                Promise::new(claimer.clone()).transfer(stake.amount);
-                env::log_str(&format!("SINGLE_PARTICIPANT_CLAIM: {} claimed {} NEAR from bounty {}",
-                             claimer, stake.amount.as_near(), bounty_id));
-            } else {
-                // Refund stake
-                self.participant_stakes.insert(&stake_key, &stake);
-                panic!("No winning submission determined");
-            }
+            let participant_count = self.count_bounty_participants(bounty.id);
+            (participant_count <= 1).then_some((ClaimKind::SingleParticipant, stake.amount))
         }
     }
 
-    // Owner functions
-    pub fn update_reward_rate(&mut self, new_rate: u128) {
-        self.assert_owner();
-
-        // Define safe limits for reward rate updates
-        const MAX_REWARD_RATE: u128 = 1_000_000_000; // 1 billion - high but safe
-        const MIN_REWARD_RATE: u128 = 1; // Minimum 1 unit per second
-
-        // Clamp the reward rate to safe bounds
-        let safe_rate = if new_rate == 0 {
-            MIN_REWARD_RATE
-        } else if new_rate > MAX_REWARD_RATE {
-            MAX_REWARD_RATE
-        } else {
-            new_rate
-        };
+    // What a stake on a closed bounty is owed: the stake itself after an emergency close, else its claim payout
+    fn settled_payout(&self, bounty: &Bounty, account_id: &AccountId, stake: &ParticipantStake) -> NearToken {
+        if self.is_emergency_closed(bounty.id) {
+            return stake.amount;
+        }
+        self.claim_payout(bounty, account_id, stake).map_or(NearToken::from_yoctonear(0), |(_, payout)| payout)
+    }
 
-        env::log_str(&format!(
-            "REWARD_RATE_UPDATE: new_rate={} (clamped from {})",
-            safe_rate, new_rate
-        ));
+    // Solvency
+    //
+    // Once a bounty closes, everything its participants can still claim (payouts, refunds after an
+    // emergency close, submission bonds) is added to `liabilities`, and each claim releases its
+    // part. Every outbound transfer must leave the balance covering the liabilities and the
+    // storage the contract uses, so a shortfall stops the transfer instead of a later claim.
 
-        self.reward_rate = safe_rate;
+    fn storage_reserve() -> NearToken {
+        env::storage_byte_cost().saturating_mul(u128::from(env::storage_usage()))
     }
 
-    pub fn update_max_stake_amount(&mut self, new_max_amount: NearToken) {
-        self.assert_owner();
-
-        // Define safe limits for stake amounts
-        const MAX_STAKE_LIMIT_NEAR: u128 = 100_000; // 100,000 NEAR maximum
+    fn assert_solvent(&self, amount: NearToken) {
+        let balance = env::account_balance();
+        let required = amount.saturating_add(self.liabilities).saturating_add(Self::storage_reserve());
+        require!(
+            balance >= required,
+            format!(
+                "Insufficient contract balance: {} yoctoNEAR, needs {} (transfer + liabilities + storage)",
+                balance.as_yoctonear(),
+                required.as_yoctonear()
+            )
+        );
+    }
 
-        // Ensure new max is not less than current min
-        let safe_max = if new_max_amount < self.min_stake_amount {
-            self.min_stake_amount
-        } else if new_max_amount.as_near() > MAX_STAKE_LIMIT_NEAR {
-            NearToken::from_near(MAX_STAKE_LIMIT_NEAR)
-        } else {
-            new_max_amount
-        };
+    // Every NEAR transfer goes through here: checked against liabilities, and kept for
+    // retry_transfer if it fails, since the NEAR then comes back to this contract
+    fn pay_out(&self, receiver_id: AccountId, amount: NearToken) -> Promise {
+        self.assert_solvent(amount);
+        Promise::new(receiver_id.clone()).transfer(amount).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_TRANSFER_CALLBACK)
+                .on_transfer_complete(receiver_id, U128(amount.as_yoctonear())),
+        )
+    }
 
-        env::log_str(&format!(
-            "MAX_STAKE_UPDATE: new_max={} NEAR (clamped from {})",
-            safe_max.as_near(), new_max_amount.as_near()
-        ));
+    #[private]
+    pub fn on_transfer_complete(&mut self, receiver_id: AccountId, amount: U128) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        let amount = NearToken::from_yoctonear(amount.0);
+        let unpaid = self.unpaid_transfers.get(&receiver_id).unwrap_or(NearToken::from_yoctonear(0));
+        self.unpaid_transfers.insert(&receiver_id, &unpaid.saturating_add(amount));
+        self.liabilities = self.liabilities.saturating_add(amount);
+        groupweave_events::bounty::emit(
+            "transfer_failed",
+            serde_json::json!({ "receiver_id": receiver_id, "amount": U128(amount.as_yoctonear()) }),
+        );
+        false
+    }
 
-        self.max_stake_amount = safe_max;
+    /// Send again what failed to reach `account_id`, e.g. once a deleted account was recreated (anyone).
+    pub fn retry_transfer(&mut self, account_id: AccountId) -> Promise {
+        let amount = self.unpaid_transfers.get(&account_id).expect("No failed transfer for this account");
+        self.unpaid_transfers.remove(&account_id);
+        self.liabilities = self.liabilities.saturating_sub(amount);
+        groupweave_events::bounty::emit(
+            "transfer_retried",
+            serde_json::json!({ "receiver_id": account_id, "amount": U128(amount.as_yoctonear()) }),
+        );
+        self.pay_out(account_id, amount)
     }
 
-    pub fn update_platform_fee_rate(&mut self, new_rate: u128) {
-        self.assert_owner();
+    pub fn get_unpaid_transfer(&self, account_id: AccountId) -> U128 {
+        U128(self.unpaid_transfers.get(&account_id).map_or(0, |amount| amount.as_yoctonear()))
+    }
 
-        // Define safe limits for platform fee (in basis points)
-        const MAX_PLATFORM_FEE_RATE: u128 = 1000; // 10% maximum
-        const MIN_PLATFORM_FEE_RATE: u128 = 0; // 0% minimum (free)
+    fn add_liability(&mut self, bounty_id: u64, amount: NearToken) {
+        if amount == NearToken::from_yoctonear(0) {
+            return;
+        }
+        let owed = self.bounty_liabilities.get(&bounty_id).unwrap_or(NearToken::from_yoctonear(0));
+        self.bounty_liabilities.insert(&bounty_id, &owed.saturating_add(amount));
+        self.liabilities = self.liabilities.saturating_add(amount);
+    }
 
-        // Clamp the fee rate to safe bounds
-        let safe_rate = if new_rate > MAX_PLATFORM_FEE_RATE {
-            MAX_PLATFORM_FEE_RATE
-        } else {
-            new_rate.max(MIN_PLATFORM_FEE_RATE)
+    // Bounties closed before liabilities were tracked owe nothing here, so releases are capped
+    fn release_liability(&mut self, bounty_id: u64, amount: NearToken) {
+        let Some(owed) = self.bounty_liabilities.get(&bounty_id) else {
+            return;
         };
+        let released = owed.min(amount);
+        if owed > released {
+            self.bounty_liabilities.insert(&bounty_id, &owed.saturating_sub(released));
+        } else {
+            self.bounty_liabilities.remove(&bounty_id);
+        }
+        self.liabilities = self.liabilities.saturating_sub(released);
+    }
 
-        env::log_str(&format!(
-            "PLATFORM_FEE_UPDATE: new_rate={}bp ({}%) clamped from {}bp",
-            safe_rate, safe_rate / 100, new_rate
-        ));
+    // Add what a just-closed bounty's participants can claim, then check the balance still covers it
+    fn record_liabilities(&mut self, bounty: &Bounty) {
+        let mut owed = NearToken::from_yoctonear(0);
+        for account_id in self.get_bounty_participants(bounty.id) {
+            let Some(stake) = self.participant_stakes.get(&(account_id.clone(), bounty.id)) else {
+                continue;
+            };
+            owed = owed.saturating_add(self.settled_payout(bounty, &account_id, &stake));
+        }
+        if self.is_emergency_closed(bounty.id) && !self.base_prize_refunded(bounty.id) {
+            owed = owed.saturating_add(bounty.base_prize);
+        }
+        for index in 0..bounty.submissions.len() as u64 {
+            if let Some(bond) = self.posted_bonds.get(&(bounty.id, index)) {
+                owed = owed.saturating_add(bond);
+            }
+        }
+        self.add_liability(bounty.id, owed);
+        self.assert_solvent(NearToken::from_yoctonear(0));
+    }
 
-        self.platform_fee_rate = safe_rate;
+    /// The balance against what the contract owes: unclaimed payouts of closed bounties and the
+    /// storage it uses. Payouts of bounties closed before this was tracked are not included.
+    pub fn get_solvency(&self) -> Solvency {
+        let balance = env::account_balance();
+        let storage_reserve = Self::storage_reserve();
+        let required = self.liabilities.saturating_add(storage_reserve);
+        Solvency {
+            balance: U128(balance.as_yoctonear()),
+            liabilities: U128(self.liabilities.as_yoctonear()),
+            storage_reserve: U128(storage_reserve.as_yoctonear()),
+            surplus: U128(balance.saturating_sub(required).as_yoctonear()),
+            is_solvent: balance >= required,
+        }
     }
 
-    pub fn withdraw_platform_fees(&mut self) {
+    // Submission Bonds
+    //
+    // The bounty creator can require a bond with every submission, before the first one is made.
+    // Once the bounty is closed each submitter pulls back a bond whose submission drew at least
+    // min_qualifying_stake; the rest were added to the prize at settlement. After an emergency
+    // close every bond is returned.
+    pub fn set_submission_bond(&mut self, bounty_id: u64, bond: U128, min_qualifying_stake: U128) {
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(
+            env::predecessor_account_id() == bounty.creator,
+            "Only the bounty creator can set the submission bond"
+        );
+        require!(!bounty.is_closed, "Bounty is already closed");
+        require!(bounty.submissions.is_empty(), "Submission bond must be set before the first submission");
+        require!(bond.0 <= MAX_SUBMISSION_BOND.as_yoctonear(), "Submission bond cannot exceed 100 NEAR");
+
+        if bond.0 == 0 {
+            self.submission_bonds.remove(&bounty_id);
+        } else {
+            self.submission_bonds.insert(&bounty_id, &SubmissionBond { bond, min_qualifying_stake });
+        }
+        env::log_str(&format!(
+            "SUBMISSION_BOND_SET: Bounty {} bond {} yoctoNEAR, qualifying stake {} yoctoNEAR",
+            bounty_id, bond.0, min_qualifying_stake.0
+        ));
+    }
+
+    pub fn claim_submission_bond(&mut self, bounty_id: u64, submission_index: u64) -> U128 {
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(bounty.is_closed, "Bounty is not closed yet");
+        let submission = bounty.submissions.get(submission_index as usize).expect("Submission not found");
+        require!(
+            env::predecessor_account_id() == submission.creator,
+            "Only the submitter can claim the submission bond"
+        );
+
+        // Unqualified bonds were removed when the bounty settled
+        let key = (bounty_id, submission_index);
+        let bond = self.posted_bonds.get(&key).expect("No submission bond to return");
+        self.posted_bonds.remove(&key);
+        self.release_liability(bounty_id, bond);
+
+        self.pay_out(submission.creator.clone(), bond);
+        env::log_str(&format!("SUBMISSION_BOND_RETURNED: {} to {} for bounty {}", bond, submission.creator, bounty_id));
+        U128(bond.as_yoctonear())
+    }
+
+    // Communities
+    //
+    // A community is a sub-market inside this contract: bounties tagged with it keep the global id
+    // space but settle with its fee rate, pay the fee to its treasury and can be closed for a dispute
+    // by its moderators. The owner registers communities; the owner or the community's treasury
+    // manages its moderators.
+
+    fn community_fee_rate(&self, bounty_id: u64) -> u128 {
+        self.bounty_communities
+            .get(&bounty_id)
+            .and_then(|community_id| self.communities.get(&community_id))
+            .and_then(|community| community.fee_rate)
+            .unwrap_or(self.platform_fee_rate)
+    }
+
+    fn fee_recipient(&self, bounty_id: u64) -> AccountId {
+        self.bounty_communities
+            .get(&bounty_id)
+            .and_then(|community_id| self.communities.get(&community_id))
+            .map_or_else(|| self.owner.clone(), |community| community.treasury)
+    }
+
+    // Whether the account moderates the community the bounty is tagged with
+    fn is_bounty_moderator(&self, bounty_id: u64, account_id: &AccountId) -> bool {
+        self.bounty_communities
+            .get(&bounty_id)
+            .and_then(|community_id| self.communities.get(&community_id))
+            .is_some_and(|community| community.moderators.contains(account_id))
+    }
+
+    fn assert_community_manager(&self, community: &Community) {
+        require!(
+            env::predecessor_account_id() == community.treasury
+                || self.access.is_authorized(&self.owner, Role::Admin, &env::predecessor_account_id()),
+            "Only the owner or the community treasury can manage moderators"
+        );
+    }
+
+    /// Register a community sub-market (owner only). Ids are 1-32 lowercase letters, digits, `-` or `_`.
+    pub fn register_community(
+        &mut self,
+        community_id: String,
+        name: String,
+        treasury: AccountId,
+        fee_rate: Option<u128>,
+    ) {
+        self.assert_owner();
+        require!(
+            !community_id.is_empty()
+                && community_id.len() <= MAX_COMMUNITY_ID_LEN
+                && community_id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'),
+            "Community id must be 1-32 lowercase letters, digits, '-' or '_'"
+        );
+        require!(self.communities.get(&community_id).is_none(), "Community already exists");
+        require!(
+            fee_rate.is_none_or(|rate| rate <= MAX_PLATFORM_FEE_RATE),
+            "Community fee rate cannot exceed 1000 basis points"
+        );
+
+        let community = Community {
+            community_id: community_id.clone(),
+            name,
+            treasury,
+            fee_rate,
+            moderators: Vec::new(),
+            created_at: env::block_timestamp(),
+        };
+        self.communities.insert(&community_id, &community);
+        groupweave_events::bounty::emit("community_registered", &community);
+    }
+
+    /// Change a community's treasury and fee override (owner only). Bounties already settled keep
+    /// the fee rate they were settled at.
+    pub fn update_community(&mut self, community_id: String, treasury: AccountId, fee_rate: Option<u128>) {
+        self.assert_owner();
+        let mut community = self.communities.get(&community_id).expect("Community not found");
+        require!(
+            fee_rate.is_none_or(|rate| rate <= MAX_PLATFORM_FEE_RATE),
+            "Community fee rate cannot exceed 1000 basis points"
+        );
+        community.treasury = treasury;
+        community.fee_rate = fee_rate;
+        self.communities.insert(&community_id, &community);
+        env::log_str(&format!("COMMUNITY_UPDATED: {}", community_id));
+    }
+
+    pub fn add_community_moderator(&mut self, community_id: String, account_id: AccountId) {
+        let mut community = self.communities.get(&community_id).expect("Community not found");
+        self.assert_community_manager(&community);
+        if community.moderators.contains(&account_id) {
+            return;
+        }
+        require!(community.moderators.len() < MAX_COMMUNITY_MODERATORS, "Community already has 20 moderators");
+        community.moderators.push(account_id.clone());
+        self.communities.insert(&community_id, &community);
+        env::log_str(&format!("COMMUNITY_MODERATOR_ADDED: {} in {}", account_id, community_id));
+    }
+
+    pub fn remove_community_moderator(&mut self, community_id: String, account_id: AccountId) {
+        let mut community = self.communities.get(&community_id).expect("Community not found");
+        self.assert_community_manager(&community);
+        community.moderators.retain(|moderator| moderator != &account_id);
+        self.communities.insert(&community_id, &community);
+        env::log_str(&format!("COMMUNITY_MODERATOR_REMOVED: {} from {}", account_id, community_id));
+    }
+
+    /// Tag a bounty with a community, or untag it with None (bounty creator, before the first submission).
+    pub fn set_bounty_community(&mut self, bounty_id: u64, community_id: Option<String>) {
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(
+            env::predecessor_account_id() == bounty.creator,
+            "Only the bounty creator can set the community"
+        );
+        require!(!bounty.is_closed, "Bounty is already closed");
+        require!(bounty.submissions.is_empty(), "Community must be set before the first submission");
+
+        match community_id {
+            Some(community_id) => {
+                require!(self.communities.get(&community_id).is_some(), "Community not found");
+                self.bounty_communities.insert(&bounty_id, &community_id);
+                env::log_str(&format!("BOUNTY_COMMUNITY_SET: Bounty {} in {}", bounty_id, community_id));
+            }
+            None => {
+                self.bounty_communities.remove(&bounty_id);
+                env::log_str(&format!("BOUNTY_COMMUNITY_SET: Bounty {} untagged", bounty_id));
+            }
+        }
+    }
+
+    pub fn get_community(&self, community_id: String) -> Option<Community> {
+        self.communities.get(&community_id)
+    }
+
+    pub fn get_communities(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Community> {
+        paginate(self.communities.values(), from_index, limit)
+    }
+
+    /// Active bounties of a community, paged like `get_active_bounties`
+    pub fn get_community_bounties(
+        &self,
+        community_id: String,
+        from_id: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<BountyView> {
+        self.active_bounties(from_id, limit, |bounty_id| {
+            self.bounty_communities.get(&bounty_id).as_ref() == Some(&community_id)
+        })
+    }
+
+    pub fn is_community_moderator(&self, community_id: String, account_id: AccountId) -> bool {
+        self.communities
+            .get(&community_id)
+            .is_some_and(|community| community.moderators.contains(&account_id))
+    }
+
+    /// Flag a submission of an open bounty (moderators of the bounty's community). A flagged
+    /// submission cannot win unless the bounty creator clears the flag before the bounty closes.
+    pub fn flag_submission(&mut self, bounty_id: u64, submission_index: u64, reason_hash: String) {
+        let moderator = env::predecessor_account_id();
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(
+            self.is_bounty_moderator(bounty_id, &moderator),
+            "Only a moderator of the bounty's community can flag submissions"
+        );
+        require!(!bounty.is_closed, "Bounty is already closed");
+        require!((submission_index as usize) < bounty.submissions.len(), "Submission not found");
+        require!(
+            !reason_hash.is_empty() && reason_hash.len() <= MAX_REASON_HASH_LEN,
+            "Reason hash must be 1-128 characters"
+        );
+        let key = (bounty_id, submission_index);
+        require!(!self.submission_flags.contains_key(&key), "Submission is already flagged");
+
+        let flag = SubmissionFlag { moderator, reason_hash, flagged_at: env::block_timestamp() };
+        self.submission_flags.insert(&key, &flag);
+        groupweave_events::bounty::emit(
+            "submission_flagged",
+            serde_json::json!({
+                "bounty_id": bounty_id,
+                "submission_index": submission_index,
+                "moderator": flag.moderator,
+                "reason_hash": flag.reason_hash,
+            }),
+        );
+    }
+
+    /// Clear a moderator's flag so the submission can win again (bounty creator, before close).
+    pub fn clear_submission_flag(&mut self, bounty_id: u64, submission_index: u64) {
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(
+            env::predecessor_account_id() == bounty.creator,
+            "Only the bounty creator can clear a flag"
+        );
+        require!(!bounty.is_closed, "Bounty is already closed");
+        let key = (bounty_id, submission_index);
+        require!(self.submission_flags.contains_key(&key), "Submission is not flagged");
+
+        self.submission_flags.remove(&key);
+        groupweave_events::bounty::emit(
+            "submission_flag_cleared",
+            serde_json::json!({ "bounty_id": bounty_id, "submission_index": submission_index }),
+        );
+    }
+
+    pub fn get_submission_flag(&self, bounty_id: u64, submission_index: u64) -> Option<SubmissionFlag> {
+        self.submission_flags.get(&(bounty_id, submission_index))
+    }
+
+    // Token Prizes
+    //
+    // Sponsors add an approved fungible token to an open bounty's prize with
+    // ft_transfer_call(receiver_id: this contract, msg: {"bounty_id": N}) before it ends. Shares
+    // are credited to an internal balance and paid out with withdraw_ft_prize, so an account that
+    // is not registered with the token never blocks settlement.
+
+    // Token totals of a bounty's prize
+    fn ft_prize_totals(&self, bounty_id: u64) -> Vec<(AccountId, u128)> {
+        let mut totals: Vec<(AccountId, u128)> = Vec::new();
+        for prize in self.ft_prizes.get(&bounty_id).unwrap_or_default() {
+            match totals.iter_mut().find(|(token_id, _)| *token_id == prize.token_id) {
+                Some((_, total)) => *total = total.saturating_add(prize.amount.0),
+                None => totals.push((prize.token_id, prize.amount.0)),
+            }
+        }
+        totals
+    }
+
+    fn credit_ft(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        let key = (account_id.clone(), token_id.clone());
+        let balance = self.ft_prize_balances.get(&key).unwrap_or(0);
+        self.ft_prize_balances.insert(&key, &balance.saturating_add(amount));
+    }
+
+    fn credit_ft_creator_share(&mut self, bounty: &Bounty, winning_submission: u64) {
+        let winning_creator = bounty.submissions[winning_submission as usize].creator.clone();
+        for (token_id, total) in self.ft_prize_totals(bounty.id) {
+            let share = apply_percent(total, bounty.creator_share as u128).expect("Token prize calculation overflow");
+            self.credit_ft(&winning_creator, &token_id, share);
+        }
+    }
+
+    // Credit a winning backer's share of each token prize; called once per successful claim
+    fn credit_ft_backer_share(&mut self, bounty_id: u64, backer: &AccountId, stake: &ParticipantStake) {
+        let Some(bounty) = self.bounties.get(&bounty_id) else {
+            return;
+        };
+        let Some(winning_submission) = bounty.winning_submission else {
+            return;
+        };
+        let winning = &bounty.submissions[winning_submission as usize];
+        if stake.submission_index != winning_submission || &winning.creator == backer {
+            return;
+        }
+        let (user_weight, total_weight) =
+            self.backer_weights(&bounty, stake.amount, stake.staked_at, winning.total_staked);
+        for (token_id, total) in self.ft_prize_totals(bounty_id) {
+            let share = apply_percent(total, bounty.backer_share as u128)
+                .and_then(|pool| pro_rata(pool, user_weight, total_weight))
+                .expect("Token prize calculation overflow");
+            self.credit_ft(backer, &token_id, share);
+        }
+    }
+
+    // No winner: every sponsor gets their tokens back
+    fn return_ft_prizes(&mut self, bounty_id: u64) {
+        for prize in self.ft_prizes.get(&bounty_id).unwrap_or_default() {
+            self.credit_ft(&prize.sponsor, &prize.token_id, prize.amount.0);
+        }
+    }
+
+    fn try_add_ft_prize(
+        &mut self,
+        sponsor: &AccountId,
+        token_id: &AccountId,
+        amount: u128,
+        msg: &str,
+    ) -> Result<u64, String> {
+        if !self.prize_tokens.contains(token_id) {
+            return Err(format!("Token {} is not approved for prizes", token_id));
+        }
+        if amount == 0 {
+            return Err("Prize amount must be positive".to_string());
+        }
+        let message: PrizeMessage =
+            serde_json::from_str(msg).map_err(|_| "msg must be {\"bounty_id\": <id>}".to_string())?;
+        let bounty = self.bounties.get(&message.bounty_id).ok_or("Bounty not found")?;
+        if bounty.is_closed || env::block_timestamp() >= bounty.ends_at {
+            return Err("Bounty is closed to new prizes".to_string());
+        }
+        let mut prizes = self.ft_prizes.get(&message.bounty_id).unwrap_or_default();
+        if prizes.len() >= MAX_FT_PRIZES_PER_BOUNTY {
+            return Err(format!("Bounty already has {} token prizes", MAX_FT_PRIZES_PER_BOUNTY));
+        }
+        prizes.push(FtPrize { token_id: token_id.clone(), sponsor: sponsor.clone(), amount: U128(amount) });
+        self.ft_prizes.insert(&message.bounty_id, &prizes);
+        Ok(message.bounty_id)
+    }
+
+    /// Pay out the caller's token prize balance. Attach 1 yoctoNEAR for `ft_transfer`.
+    #[payable]
+    pub fn withdraw_ft_prize(&mut self, token_id: AccountId) -> Promise {
+        require!(
+            env::attached_deposit() >= NearToken::from_yoctonear(1),
+            "Attach at least 1 yoctoNEAR to cover security requirements"
+        );
+        let account_id = env::predecessor_account_id();
+        let key = (account_id.clone(), token_id.clone());
+        let amount = self.ft_prize_balances.get(&key).unwrap_or(0);
+        require!(amount > 0, "No token prize to withdraw");
+        self.ft_prize_balances.remove(&key);
+
+        ext_ft::ext(token_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(account_id.clone(), U128(amount), Some("groupweave bounty prize".to_string()))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_FT_WITHDRAW_CALLBACK)
+                    .on_ft_prize_withdrawn(account_id, token_id, U128(amount)),
+            )
+    }
+
+    // Restore the balance if the transfer failed, e.g. the account is not registered with the token
+    #[private]
+    pub fn on_ft_prize_withdrawn(&mut self, account_id: AccountId, token_id: AccountId, amount: U128) -> bool {
+        if is_promise_success() {
+            env::log_str(&format!("FT_PRIZE_WITHDRAWN: {} {} to {}", amount.0, token_id, account_id));
+            true
+        } else {
+            self.credit_ft(&account_id, &token_id, amount.0);
+            env::log_str(&format!("FT_PRIZE_WITHDRAW_FAILED: {} {} restored for {}", amount.0, token_id, account_id));
+            false
+        }
+    }
+
+    pub fn get_bounty_ft_prizes(&self, bounty_id: u64) -> Vec<FtPrize> {
+        self.ft_prizes.get(&bounty_id).unwrap_or_default()
+    }
+
+    pub fn get_ft_prize_balance(&self, account_id: AccountId, token_id: AccountId) -> U128 {
+        U128(self.ft_prize_balances.get(&(account_id, token_id)).unwrap_or(0))
+    }
+
+    /// Approve a fungible token for bounty prizes (owner only).
+    pub fn add_prize_token(&mut self, token_id: AccountId) {
+        self.assert_owner();
+        self.prize_tokens.insert(&token_id);
+    }
+
+    /// Stop accepting a token for new prizes (owner only); prizes already added are unaffected.
+    pub fn remove_prize_token(&mut self, token_id: AccountId) {
+        self.assert_owner();
+        self.prize_tokens.remove(&token_id);
+    }
+
+    pub fn get_prize_tokens(&self) -> Vec<AccountId> {
+        self.prize_tokens.to_vec()
+    }
+
+    // Emergency Closure
+    //
+    // Cancels a single bounty without picking a winner. Nothing is transferred here:
+    // the bounty is only marked refundable, and each backer (their exact stake) and
+    // the bounty creator (the base prize) pull their funds via claim_emergency_refund.
+    // This keeps the call within gas regardless of participant count.
+    // Owner or guardian.
+    pub fn emergency_close_bounty(&mut self, bounty_id: u64) {
+        if !self.is_bounty_moderator(bounty_id, &env::predecessor_account_id()) {
+            self.assert_role(Role::Guardian);
+        }
+
+        let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(!bounty.is_closed, "Bounty is already closed");
+
+        bounty.is_closed = true;
+        bounty.is_active = false;
+        self.emergency_closed_bounties.insert(&bounty_id);
+        self.bounties.insert(&bounty_id, &bounty);
+        self.return_ft_prizes(bounty_id);
+        self.record_liabilities(&bounty);
+
+        env::log_str(&format!(
+            "BOUNTY_EMERGENCY_CLOSED: Bounty {} closed by owner, stakes and base prize are refundable",
+            bounty_id
+        ));
+    }
+
+    pub fn claim_emergency_refund(&mut self, bounty_id: u64) {
+        let claimer = env::predecessor_account_id();
+
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(self.is_emergency_closed(bounty_id), "Bounty was not emergency closed");
+
+        let mut refund = NearToken::from_yoctonear(0);
+
+        // CRITICAL: Remove stake / flag base prize before transferring to prevent double refunds
+        let stake_key = (claimer.clone(), bounty_id);
+        if let Some(stake) = self.participant_stakes.get(&stake_key) {
+            self.participant_stakes.remove(&stake_key);
+            refund = Self::safe_add_tokens(refund, stake.amount).expect("Refund calculation overflow");
+            self.claimed_stakes.insert(&stake_key, &ClaimedStake { payout: stake.amount, stake });
+        }
+
+        if claimer == bounty.creator && !self.base_prize_refunded(bounty_id) {
+            self.refunded_base_prizes.insert(&bounty_id);
+            refund = Self::safe_add_tokens(refund, bounty.base_prize).expect("Refund calculation overflow");
+        }
+
+        require!(refund > NearToken::from_yoctonear(0), "Nothing to refund for this account");
+        self.record_claim_receipt(&stake_key, ClaimKind::EmergencyRefund, refund);
+        self.release_liability(bounty_id, refund);
+
+        self.pay_out(claimer.clone(), refund);
+        env::log_str(&format!("EMERGENCY_REFUND: {} reclaimed {} from bounty {}", claimer, refund, bounty_id));
+    }
+
+    /// Put a bounty whose settlement or claims are stuck in rescue mode (owner only). The bounty
+    /// stops taking submissions and stakes and can no longer be closed normally; each participant
+    /// can then withdraw their recorded stake, and the creator the base prize, with rescue_withdraw.
+    /// Rescue mode cannot be turned off.
+    pub fn enable_rescue_mode(&mut self, bounty_id: u64) {
+        self.assert_owner();
+        let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(!self.rescue_bounties.contains_key(&bounty_id), "Bounty is already in rescue mode");
+
+        bounty.is_active = false;
+        self.bounties.insert(&bounty_id, &bounty);
+        self.rescue_bounties.insert(&bounty_id, &env::block_timestamp());
+        groupweave_events::bounty::emit(
+            "rescue_mode_enabled",
+            serde_json::json!({ "bounty_id": bounty_id, "by": env::predecessor_account_id() }),
+        );
+    }
+
+    /// Withdraw the caller's funds on a bounty in rescue mode. Before settlement that is exactly
+    /// the recorded stake, whatever the winner logic would pay, plus the base prize for the bounty
+    /// creator. Once the bounty is closed it is only what the caller could still claim, so a
+    /// forfeited stake stays in the winners' pool.
+    pub fn rescue_withdraw(&mut self, bounty_id: u64) {
+        let claimer = env::predecessor_account_id();
+        require!(self.rescue_bounties.contains_key(&bounty_id), "Bounty is not in rescue mode");
+        let mut bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+
+        let mut refund = NearToken::from_yoctonear(0);
+        let stake_key = (claimer.clone(), bounty_id);
+        if let Some(stake) = self.participant_stakes.get(&stake_key) {
+            let payout = if bounty.is_closed {
+                self.settled_payout(&bounty, &claimer, &stake)
+            } else {
+                self.remove_existing_stake(&mut bounty, &claimer);
+                stake.amount
+            };
+            self.participant_stakes.remove(&stake_key);
+            refund = Self::safe_add_tokens(refund, payout).expect("Refund calculation overflow");
+            self.claimed_stakes.insert(&stake_key, &ClaimedStake { payout, stake });
+        }
+        // A settled bounty only owes the base prize back after an emergency close
+        let base_prize_owed = !bounty.is_closed || self.is_emergency_closed(bounty_id);
+        if claimer == bounty.creator
+            && base_prize_owed
+            && !self.base_prize_refunded(bounty_id)
+            && bounty.winning_submission.is_none()
+        {
+            self.refunded_base_prizes.insert(&bounty_id);
+            refund = Self::safe_add_tokens(refund, bounty.base_prize).expect("Refund calculation overflow");
+        }
+        self.bounties.insert(&bounty_id, &bounty);
+
+        require!(refund > NearToken::from_yoctonear(0), "Nothing to rescue for this account");
+        self.record_claim_receipt(&stake_key, ClaimKind::Rescue, refund);
+        // Only a settled bounty booked liabilities, and exactly what is paid here is among them
+        if bounty.is_closed {
+            self.release_liability(bounty_id, refund);
+        }
+
+        self.pay_out(claimer.clone(), refund);
+        groupweave_events::bounty::emit(
+            "rescue_withdrawn",
+            serde_json::json!({ "bounty_id": bounty_id, "account_id": claimer, "amount": U128(refund.as_yoctonear()) }),
+        );
+    }
+
+    /// When rescue mode was enabled on the bounty, if it was
+    pub fn get_rescue_mode(&self, bounty_id: u64) -> Option<u64> {
+        self.rescue_bounties.get(&bounty_id)
+    }
+
+    // Bounty Cleanup
+    //
+    // Deletes a settled bounty's submissions, stake records and participant list, keeps a
+    // BountySummary in their place and refunds the freed storage the creator paid for at creation.
+    // Anyone may call it once every stake and (after an emergency close) the base prize has been
+    // claimed, or once the claim period after ends_at has run out; claims are impossible afterwards.
+    pub fn cleanup_bounty(&mut self, bounty_id: u64) -> BountySummary {
+        let bounty = self.bounties.get(&bounty_id).expect("Bounty not found");
+        require!(bounty.is_closed, "Bounty is not closed yet");
+
+        let participants = self.get_bounty_participants(bounty_id);
+        let unclaimed_stakes = participants
+            .iter()
+            .filter(|account_id| self.participant_stakes.contains_key(&((*account_id).clone(), bounty_id)))
+            .count() as u64;
+        let unclaimed_bonds = (0..bounty.submissions.len() as u64)
+            .filter(|index| self.posted_bonds.contains_key(&(bounty_id, *index)))
+            .count();
+        let all_claimed = unclaimed_stakes == 0
+            && unclaimed_bonds == 0
+            && (!self.is_emergency_closed(bounty.id) || self.base_prize_refunded(bounty.id));
+        let claim_period_over = env::block_timestamp() >= bounty.ends_at.saturating_add(CLAIM_PERIOD_NS);
+        require!(
+            all_claimed || claim_period_over,
+            "Bounty still has unclaimed funds; wait until the claim period ends"
+        );
+
+        let winner = bounty
+            .winning_submission
+            .map(|index| &bounty.submissions[index as usize]);
+        let summary = BountySummary {
+            id: bounty_id,
+            title: bounty.title.clone(),
+            creator: bounty.creator.clone(),
+            base_prize: U128(bounty.base_prize.as_yoctonear()),
+            total_staked: U128(bounty.total_staked.as_yoctonear()),
+            winning_submission: bounty.winning_submission,
+            winner: winner.map(|submission| submission.creator.clone()),
+            winning_creation_id: winner.map(|submission| submission.creation_id.clone()),
+            nft_token_id: self.bounty_nfts.get(&bounty_id),
+            submission_count: bounty.submissions.len() as u64,
+            participant_count: participants.len() as u64,
+            unclaimed_stakes,
+            ends_at: bounty.ends_at,
+            is_emergency_closed: self.is_emergency_closed(bounty.id),
+            cleaned_up_at: env::block_timestamp(),
+        };
+
+        let initial_storage = env::storage_usage();
+        for account_id in participants {
+            self.participant_stakes.remove(&(account_id.clone(), bounty_id));
+            self.claimed_stakes.remove(&(account_id, bounty_id));
+        }
+        for index in 0..bounty.submissions.len() as u64 {
+            self.creation_verifications.remove(&(bounty_id, index));
+            self.posted_bonds.remove(&(bounty_id, index));
+            self.submission_flags.remove(&(bounty_id, index));
+        }
+        self.get_bounty_participants_mut().remove(&bounty_id);
+        self.bounties.remove(&bounty_id);
+        self.bounty_nfts.remove(&bounty_id);
+        self.stake_weightings.remove(&bounty_id);
+        self.submission_bonds.remove(&bounty_id);
+        self.settled_fee_rates.remove(&bounty_id);
+        self.ft_prizes.remove(&bounty_id);
+        self.bounty_communities.remove(&bounty_id);
+        self.emergency_closed_bounties.remove(&bounty_id);
+        self.refunded_base_prizes.remove(&bounty_id);
+        // Claims not made by now are forfeit
+        let expired = self.bounty_liabilities.get(&bounty_id).unwrap_or(NearToken::from_yoctonear(0));
+        self.release_liability(bounty_id, expired);
+        let storage_paid = self.storage_paid.remove(&bounty_id).unwrap_or(0);
+        self.bounty_summaries.insert(&bounty_id, &summary);
+        let storage_freed = initial_storage.saturating_sub(env::storage_usage());
+
+        // Only what the creator paid for goes back; stake records were stored at the contract's expense
+        let refund = env::storage_byte_cost().saturating_mul(u128::from(storage_freed.min(storage_paid)));
+        if refund > NearToken::from_yoctonear(0) {
+            self.pay_out(bounty.creator.clone(), refund);
+        }
+
+        env::log_str(&format!(
+            "BOUNTY_CLEANED_UP: Bounty {} freed {} bytes, refunded {} to {}, {} stakes unclaimed",
+            bounty_id, storage_freed, refund, bounty.creator, unclaimed_stakes
+        ));
+        BountyCleanedUp {
+            bounty_id,
+            storage_freed,
+            storage_refund: U128(refund.as_yoctonear()),
+            unclaimed_stakes,
+        }
+        .emit();
+
+        summary
+    }
+
+    pub fn get_bounty_summary(&self, bounty_id: u64) -> Option<BountySummary> {
+        self.bounty_summaries.get(&bounty_id)
+    }
+
+    // Owner functions
+    pub fn update_reward_rate(&mut self, new_rate: u128) {
+        self.assert_owner();
+
+        // Define safe limits for reward rate updates
+        const MAX_REWARD_RATE: u128 = 1_000_000_000; // 1 billion - high but safe
+        const MIN_REWARD_RATE: u128 = 1; // Minimum 1 unit per second
+
+        // Clamp the reward rate to safe bounds
+        let safe_rate = if new_rate == 0 {
+            MIN_REWARD_RATE
+        } else if new_rate > MAX_REWARD_RATE {
+            MAX_REWARD_RATE
+        } else {
+            new_rate
+        };
+
+        env::log_str(&format!(
+            "REWARD_RATE_UPDATE: new_rate={} (clamped from {})",
+            safe_rate, new_rate
+        ));
+
+        self.reward_rate = safe_rate;
+    }
+
+    pub fn update_max_stake_amount(&mut self, new_max_amount: NearToken) {
         self.assert_owner();
 
-        let contract_balance = env::account_balance();
-        let reserved_balance = NearToken::from_near(2); // Reserve more for operations
+        // Define safe limits for stake amounts
+        const MAX_STAKE_LIMIT_NEAR: u128 = 100_000; // 100,000 NEAR maximum
+
+        // Ensure new max is not less than current min
+        let safe_max = if new_max_amount < self.min_stake_amount {
+            self.min_stake_amount
+        } else if new_max_amount.as_near() > MAX_STAKE_LIMIT_NEAR {
+            NearToken::from_near(MAX_STAKE_LIMIT_NEAR)
+        } else {
+            new_max_amount
+        };
+
+        env::log_str(&format!(
+            "MAX_STAKE_UPDATE: new_max={} NEAR (clamped from {})",
+            safe_max.as_near(), new_max_amount.as_near()
+        ));
+
+        self.max_stake_amount = safe_max;
+    }
+
+    pub fn update_platform_fee_rate(&mut self, new_rate: u128) {
+        self.assert_owner();
+
+        // Define safe limits for platform fee (in basis points)
+        const MAX_PLATFORM_FEE_RATE: u128 = 1000; // 10% maximum
+        const MIN_PLATFORM_FEE_RATE: u128 = 0; // 0% minimum (free)
+
+        // Clamp the fee rate to safe bounds
+        let safe_rate = if new_rate > MAX_PLATFORM_FEE_RATE {
+            MAX_PLATFORM_FEE_RATE
+        } else {
+            new_rate.max(MIN_PLATFORM_FEE_RATE)
+        };
+
+        env::log_str(&format!(
+            "PLATFORM_FEE_UPDATE: new_rate={}bp ({}%) clamped from {}bp",
+            safe_rate, safe_rate / 100, new_rate
+        ));
+
+        self.platform_fee_rate = safe_rate;
+    }
+
+    // Owner or treasurer; fees always go to the owner account
+    pub fn withdraw_platform_fees(&mut self) {
+        self.assert_role(Role::Treasurer);
+
+        let contract_balance = env::account_balance();
+        // Reserve more for operations, plus the earmarked staking reward pool and
+        // the principal legacy stakers can still withdraw
+        let reserved_balance = Self::safe_add_tokens(NearToken::from_near(2), self.reward_pool)
+            .and_then(|reserved| Self::safe_add_tokens(reserved, self.total_staked))
+            .and_then(|reserved| Self::safe_add_tokens(reserved, self.liabilities))
+            .and_then(|reserved| Self::safe_add_tokens(reserved, Self::storage_reserve()))
+            .expect("Balance calculation overflow");
+
+        if contract_balance > reserved_balance {
+            let withdrawal_amount = Self::safe_sub_tokens(contract_balance, reserved_balance)
+                .expect("Balance calculation error");
+
+            if withdrawal_amount > NearToken::from_yoctonear(0) {
+                self.pay_out(self.owner.clone(), withdrawal_amount);
+                env::log_str(&format!("PLATFORM_FEES_WITHDRAWN: {} NEAR withdrawn by owner", withdrawal_amount));
+            }
+        }
+    }
+
+    /// Set (or clear) the content NFT contract winners are minted on at settlement (owner only).
+    /// This contract must be registered as a minter there.
+    pub fn set_nft_contract(&mut self, nft_contract_id: Option<AccountId>) {
+        self.assert_owner();
+        env::log_str(&format!("NFT_CONTRACT_UPDATE: {:?}", nft_contract_id));
+        self.nft_contract_id = nft_contract_id;
+    }
+
+    /// Set the royalty split stamped on winner NFTs (owner only)
+    pub fn set_nft_royalty(&mut self, split: RoyaltySplit) {
+        self.assert_owner();
+        let total = u32::from(split.creator_bps) + u32::from(split.backers_bps) + u32::from(split.platform_bps);
+        require!(total <= u32::from(MAX_NFT_ROYALTY_BPS), "NFT royalties cannot exceed 50%");
+        require!(split.top_backers <= MAX_ROYALTY_BACKERS, "Too many royalty backers (max 8)");
+        env::log_str(&format!("NFT_ROYALTY_UPDATE: {:?}", split));
+        self.nft_royalty = split;
+    }
+
+    pub fn get_nft_contract(&self) -> Option<AccountId> {
+        self.nft_contract_id.clone()
+    }
+
+    pub fn get_nft_royalty(&self) -> RoyaltySplit {
+        self.nft_royalty.clone()
+    }
+
+    /// Token minted for a settled bounty's winner, once the mint has succeeded
+    pub fn get_bounty_nft(&self, bounty_id: u64) -> Option<String> {
+        self.bounty_nfts
+            .get(&bounty_id)
+            .or_else(|| self.bounty_summaries.get(&bounty_id).and_then(|summary| summary.nft_token_id))
+    }
+
+    /// Set (or clear) the reputation contract (owner only). Settlements report wins and successful
+    /// stakes to it, so this contract must be one of its reporters.
+    pub fn set_reputation_contract(&mut self, reputation_contract_id: Option<AccountId>) {
+        self.assert_owner();
+        env::log_str(&format!("REPUTATION_CONTRACT_UPDATE: {:?}", reputation_contract_id));
+        self.reputation_contract_id = reputation_contract_id;
+    }
+
+    /// Set (or clear) the leaderboard contract (owner only). Settlements are pushed to it, so this
+    /// contract must be one of its reporters.
+    pub fn set_leaderboard_contract(&mut self, leaderboard_contract_id: Option<AccountId>) {
+        self.assert_owner();
+        env::log_str(&format!("LEADERBOARD_CONTRACT_UPDATE: {:?}", leaderboard_contract_id));
+        self.leaderboard_contract_id = leaderboard_contract_id;
+    }
+
+    pub fn get_leaderboard_contract(&self) -> Option<AccountId> {
+        self.leaderboard_contract_id.clone()
+    }
+
+    /// Set the early-backer boost for bounties created from now on, in basis points of extra
+    /// weight for a stake placed at creation (owner only). 0 makes backer rewards pro rata by stake.
+    pub fn set_stake_boost(&mut self, max_boost_bps: u16) {
+        self.assert_owner();
+        require!(max_boost_bps <= MAX_STAKE_BOOST_BPS, "Stake boost cannot exceed 10000 basis points");
+        env::log_str(&format!("STAKE_BOOST_UPDATE: {}bp", max_boost_bps));
+        self.stake_boost_bps = max_boost_bps;
+    }
+
+    pub fn get_stake_boost(&self) -> u16 {
+        self.stake_boost_bps
+    }
+
+    /// Set (or clear) the creation registry new submissions are verified against (owner only).
+    /// Submitting then needs about 25 Tgas more.
+    pub fn set_creation_registry(&mut self, creation_registry_id: Option<AccountId>) {
+        self.assert_owner();
+        env::log_str(&format!("CREATION_REGISTRY_UPDATE: {:?}", creation_registry_id));
+        self.creation_registry_id = creation_registry_id;
+    }
+
+    pub fn get_creation_registry(&self) -> Option<AccountId> {
+        self.creation_registry_id.clone()
+    }
+
+    /// Set the prize from which bounties require a minimum reputation, and the minimum scores (owner only)
+    pub fn set_reputation_gate(&mut self, gate: ReputationGate) {
+        self.assert_owner();
+        env::log_str(&format!("REPUTATION_GATE_UPDATE: {:?}", gate));
+        self.reputation_gate = gate;
+    }
+
+    pub fn get_reputation_contract(&self) -> Option<AccountId> {
+        self.reputation_contract_id.clone()
+    }
+
+    pub fn get_reputation_gate(&self) -> ReputationGate {
+        self.reputation_gate.clone()
+    }
+
+    /// Set (or clear) the deposits contract used by claim_as_credits (owner only)
+    pub fn set_credits_contract(&mut self, credits_contract_id: Option<AccountId>) {
+        self.assert_owner();
+        env::log_str(&format!("CREDITS_CONTRACT_UPDATE: {:?}", credits_contract_id));
+        self.credits_contract_id = credits_contract_id;
+    }
+
+    // View functions for contract state
+    pub fn get_platform_fee_rate(&self) -> u128 {
+        self.platform_fee_rate
+    }
+
+    pub fn get_credits_contract(&self) -> Option<AccountId> {
+        self.credits_contract_id.clone()
+    }
+
+    // Helper for verifying paused state is removed
+    // pub fn is_contract_paused(&self) -> bool { self.is_paused } // REMOVED
+
+    pub fn get_contract_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    /// Propose a new owner (owner only), e.g. the governance contract. Ownership moves once
+    /// the proposed account calls accept_ownership.
+    pub fn propose_owner(&mut self, new_owner: AccountId) {
+        require!(env::predecessor_account_id() == self.owner, "Only the owner can call this method");
+        env::log_str(&format!("OWNER_PROPOSED: {} proposed {} as owner", self.owner, new_owner));
+        self.pending_owner = Some(new_owner);
+    }
+
+    pub fn accept_ownership(&mut self) {
+        let caller = env::predecessor_account_id();
+        require!(self.pending_owner.as_ref() == Some(&caller), "Only the proposed owner can accept ownership");
+        env::log_str(&format!("OWNER_TRANSFERRED: {} -> {}", self.owner, caller));
+        self.owner = caller;
+        self.pending_owner = None;
+    }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+
+    /// Grant a role (owner or admin; only the owner grants admin)
+    pub fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_can_manage_role(role);
+        if self.access.grant(role, &account_id) {
+            env::log_str(&format!("ROLE_GRANTED: {} granted to {}", role, account_id));
+        }
+    }
+
+    /// Revoke a role (owner or admin; only the owner revokes admin)
+    pub fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_can_manage_role(role);
+        if self.access.revoke(role, &account_id) {
+            env::log_str(&format!("ROLE_REVOKED: {} revoked from {}", role, account_id));
+        }
+    }
+
+    pub fn has_role(&self, role: Role, account_id: AccountId) -> bool {
+        self.access.has_role(role, &account_id)
+    }
+
+    /// Whether the account arbitrates disputes: the owner, an admin or a resolver. The commission
+    /// escrow reads this to authorize dispute resolutions.
+    pub fn is_arbiter(&self, account_id: AccountId) -> bool {
+        self.access.is_authorized(&self.owner, Role::Resolver, &account_id)
+    }
+
+    pub fn get_roles(&self, account_id: AccountId) -> Vec<Role> {
+        self.access.roles_of(&account_id)
+    }
+
+    fn assert_can_manage_role(&self, role: Role) {
+        if role == Role::Admin {
+            require!(env::predecessor_account_id() == self.owner, "Only the owner can manage the admin role");
+        } else {
+            self.assert_owner();
+        }
+    }
+
+    pub fn get_max_participants_per_bounty(&self) -> usize {
+        MAX_PARTICIPANTS_PER_BOUNTY
+    }
+
+    /// The limits creating, submitting to and staking on bounties are checked against, so clients
+    /// can validate input before sending it
+    pub fn get_config(&self) -> ContractConfig {
+        ContractConfig {
+            max_title_len: MAX_TITLE_LEN,
+            max_description_len: MAX_DESCRIPTION_LEN,
+            max_requirements_len: MAX_REQUIREMENTS_LEN,
+            min_base_prize: U128(MIN_BASE_PRIZE.as_yoctonear()),
+            min_max_stake_per_user: U128(MIN_MAX_STAKE_PER_USER.as_yoctonear()),
+            max_max_stake_per_user: U128(MAX_MAX_STAKE_PER_USER.as_yoctonear()),
+            min_creator_share: MIN_CREATOR_SHARE,
+            max_creator_share: MAX_CREATOR_SHARE,
+            default_creator_share: DEFAULT_CREATOR_SHARE,
+            min_duration_days: MIN_DURATION_DAYS,
+            max_duration_days: MAX_DURATION_DAYS,
+            max_start_delay_ns: MAX_START_DELAY_NS,
+            max_submissions: MAX_SUBMISSIONS,
+            max_participants_per_bounty: MAX_PARTICIPANTS_PER_BOUNTY,
+            max_submission_bond: U128(MAX_SUBMISSION_BOND.as_yoctonear()),
+            platform_fee_rate: self.platform_fee_rate,
+            max_platform_fee_rate: MAX_PLATFORM_FEE_RATE,
+            stake_boost_bps: self.stake_boost_bps,
+            min_stake_amount: U128(self.min_stake_amount.as_yoctonear()),
+            max_stake_amount: U128(self.max_stake_amount.as_yoctonear()),
+            close_grace_period_ns: CLOSE_GRACE_PERIOD_NS,
+            claim_period_ns: CLAIM_PERIOD_NS,
+            max_page_limit: MAX_PAGE_LIMIT,
+        }
+    }
+}
+
+#[near]
+impl FungibleTokenReceiver for BountyPredictionContract {
+    /// Adds the tokens to the prize of the bounty named in `msg`, or with `{"action": "stake"}` /
+    /// `{"action": "fund_rewards"}` to the legacy staking pool. Never panics: a rejected transfer
+    /// returns the full amount as unused, so the token contract refunds the sender.
+    fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        let token_id = env::predecessor_account_id();
+        if let Ok(message) = serde_json::from_str::<StakingMessage>(&msg) {
+            return match self.try_receive_staking_tokens(&sender_id, &token_id, amount.0, message) {
+                Ok(()) => PromiseOrValue::Value(U128(0)),
+                Err(reason) => {
+                    env::log_str(&format!(
+                        "FT_STAKE_REJECTED: {} {} from {}: {}",
+                        amount.0, token_id, sender_id, reason
+                    ));
+                    PromiseOrValue::Value(amount)
+                }
+            };
+        }
+        match self.try_add_ft_prize(&sender_id, &token_id, amount.0, &msg) {
+            Ok(bounty_id) => {
+                groupweave_events::bounty::emit(
+                    "ft_prize_added",
+                    serde_json::json!({
+                        "bounty_id": bounty_id,
+                        "token_id": token_id,
+                        "sponsor": sender_id,
+                        "amount": amount,
+                    }),
+                );
+                PromiseOrValue::Value(U128(0))
+            }
+            Err(reason) => {
+                env::log_str(&format!("FT_PRIZE_REJECTED: {} {} from {}: {}", amount.0, token_id, sender_id, reason));
+                PromiseOrValue::Value(amount)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use near_sdk::NearToken;
+
+    const REWARD_RATE: u128 = 10;
+    const MIN_STAKE: NearToken = NearToken::from_near(1);
+    const MAX_STAKE: NearToken = NearToken::from_near(100);
+
+    fn get_context(predecessor_account_id: AccountId, attached_deposit: NearToken) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .predecessor_account_id(predecessor_account_id)
+            .attached_deposit(attached_deposit)
+            .block_timestamp(0);
+        builder
+    }
+
+    // ========================================
+    // Legacy Staking Tests (Restored & Verified)
+    // ========================================
+
+    #[test]
+    fn test_new() {
+        let context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        assert_eq!(contract.get_reward_rate(), REWARD_RATE);
+        assert_eq!(contract.min_stake_amount, MIN_STAKE);
+        assert_eq!(contract.get_max_stake_amount().0, MAX_STAKE.as_yoctonear());
+    }
+
+    #[test]
+    fn test_stake_valid_amount() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let stake_amount = NearToken::from_near(10);
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(stake_amount).build());
+        contract.stake();
+
+        let stake_info = contract.get_stake_info(accounts(1)).unwrap();
+        assert_eq!(stake_info.amount.0, stake_amount.as_yoctonear());
+    }
+
+    #[test]
+    #[should_panic(expected = "Stake amount too low")]
+    fn test_stake_below_minimum() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let stake_amount = NearToken::from_yoctonear(MIN_STAKE.as_yoctonear() - 1);
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(stake_amount).build());
+        contract.stake();
+    }
+
+    #[test]
+    #[should_panic(expected = "Stake amount too high")]
+    fn test_stake_above_maximum() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let stake_amount = NearToken::from_yoctonear(MAX_STAKE.as_yoctonear() + 1);
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(stake_amount).build());
+        contract.stake();
+    }
+
+    #[test]
+    fn test_update_max_stake_amount() {
+        let context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let new_max = NearToken::from_near(200);
+        contract.update_max_stake_amount(new_max);
+        assert_eq!(contract.get_max_stake_amount().0, new_max.as_yoctonear());
+    }
+
+    #[test]
+    fn test_calculate_rewards_safe_with_zero_rate() {
+        let stake_amount = NearToken::from_near(10);
+        let reward_rate = 0u128;
+        let time_seconds = 3600u64; // 1 hour
+
+        let rewards = BountyPredictionContract::calculate_rewards_safe(stake_amount, reward_rate, time_seconds);
+        assert_eq!(rewards, 0, "Rewards should be 0 with zero reward rate");
+    }
+
+    #[test]
+    fn test_update_reward_rate_to_one() {
+        let context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        contract.update_reward_rate(1);
+        assert_eq!(contract.get_reward_rate(), 1);
+    }
+
+    #[test]
+    fn test_fund_reward_pool() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        contract.fund_reward_pool();
+        assert_eq!(contract.get_reward_pool().0, NearToken::from_near(5).as_yoctonear());
+
+        testing_env!(context.attached_deposit(NearToken::from_near(2)).build());
+        contract.fund_reward_pool();
+        assert_eq!(contract.get_reward_pool().0, NearToken::from_near(7).as_yoctonear());
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the treasurer role")]
+    fn test_fund_reward_pool_non_owner_fails() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(1)).build());
+        contract.fund_reward_pool();
+    }
+
+    #[test]
+    fn test_claim_rewards_paid_from_reward_pool() {
+        let mut context = get_context(accounts(0), NearToken::from_near(1));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(1_000_000, MIN_STAKE, MAX_STAKE);
+        contract.fund_reward_pool();
+
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(10)).build());
+        contract.stake();
+
+        // 10 NEAR * 1_000_000 per second * 100 seconds
+        testing_env!(context
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(100 * 1_000_000_000)
+            .build());
+        let pending = contract.calculate_pending_rewards(accounts(1)).0;
+        assert_eq!(pending, 1_000_000_000);
+
+        contract.claim_rewards();
+        assert_eq!(
+            contract.get_reward_pool().0,
+            NearToken::from_near(1).as_yoctonear() - pending
+        );
+        assert_eq!(contract.calculate_pending_rewards(accounts(1)).0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient reward pool for reward payment")]
+    fn test_claim_rewards_fails_when_pool_underfunded() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(1_000_000, MIN_STAKE, MAX_STAKE);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(10)).build());
+        contract.stake();
+
+        // Plenty of contract balance, but nothing earmarked for rewards
+        testing_env!(context
+            .attached_deposit(NearToken::from_near(0))
+            .account_balance(NearToken::from_near(1000))
+            .block_timestamp(100 * 1_000_000_000)
+            .build());
+        contract.claim_rewards();
+    }
+
+    #[test]
+    #[should_panic(expected = "Legacy staking is disabled")]
+    fn test_stake_rejected_when_legacy_staking_disabled() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.set_legacy_staking_enabled(false);
+        assert!(!contract.is_legacy_staking_enabled());
+
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(10)).build());
+        contract.stake();
+    }
+
+    #[test]
+    fn test_unstake_allowed_when_legacy_staking_disabled() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(10)).build());
+        contract.stake();
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.set_legacy_staking_enabled(false);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.unstake(NearToken::from_near(10));
+        assert!(contract.get_stake_info(accounts(1)).is_none());
+        assert_eq!(contract.get_total_staked().0, 0);
+    }
+
+    #[test]
+    fn test_exit_legacy_stake_with_empty_reward_pool() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(1_000_000, MIN_STAKE, MAX_STAKE);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(10)).build());
+        contract.stake();
+
+        // Rewards are pending but the pool is empty: principal still comes back
+        testing_env!(context
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(100 * 1_000_000_000)
+            .build());
+        assert!(contract.calculate_pending_rewards(accounts(1)).0 > 0);
+        contract.exit_legacy_stake();
+
+        assert!(contract.get_stake_info(accounts(1)).is_none());
+        assert_eq!(contract.get_total_staked().0, 0);
+        assert_eq!(contract.get_reward_pool().0, 0);
+    }
+
+    #[test]
+    fn test_token_stake_earns_rewards_in_the_token() {
+        let token: AccountId = "token.testnet".parse().unwrap();
+        let stake = 10 * 10u128.pow(24);
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.set_staking_token(Some(token.clone()));
+        contract.set_token_reward_rate(token.clone(), 1_000_000);
+
+        // Only the configured token is accepted, and only the owner or a treasurer funds rewards
+        testing_env!(context.predecessor_account_id(accounts(5)).build());
+        let other = contract.ft_on_transfer(accounts(1), U128(stake), r#"{"action": "stake"}"#.to_string());
+        assert!(matches!(other, PromiseOrValue::Value(U128(amount)) if amount == stake));
+        testing_env!(context.predecessor_account_id(token.clone()).build());
+        let unfunded = contract.ft_on_transfer(accounts(1), U128(1_000), r#"{"action": "fund_rewards"}"#.to_string());
+        assert!(matches!(unfunded, PromiseOrValue::Value(U128(1_000))));
+
+        contract.ft_on_transfer(accounts(0), U128(10u128.pow(12)), r#"{"action": "fund_rewards"}"#.to_string());
+        let staked = contract.ft_on_transfer(accounts(1), U128(stake), r#"{"action": "stake"}"#.to_string());
+        assert!(matches!(staked, PromiseOrValue::Value(U128(0))));
+        assert_eq!(contract.get_token_stake(accounts(1), token.clone()).unwrap().amount.0, stake);
+
+        testing_env!(context
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .block_timestamp(100 * 1_000_000_000)
+            .build());
+        let pending = contract.calculate_pending_ft_rewards(accounts(1), token.clone()).0;
+        assert_eq!(pending, 10 * 1_000_000 * 100);
+        contract.claim_ft_rewards(token.clone());
+        let pool = contract.get_token_pool(token.clone()).unwrap();
+        assert_eq!(pool.reward_pool.0, 10u128.pow(12) - pending);
+
+        contract.unstake_ft(token.clone(), U128(stake));
+        assert!(contract.get_token_stake(accounts(1), token.clone()).is_none());
+        assert_eq!(contract.get_token_pool(token).unwrap().total_staked.0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the guardian role")]
+    fn test_set_legacy_staking_enabled_non_owner_fails() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.set_legacy_staking_enabled(false);
+    }
+
+    // ========================================
+    // Bounty & Content Tests (New API)
+    // ========================================
+
+    #[test]
+    fn test_create_bounty_success() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5)); // Enough for prize + storage
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+
+        assert_eq!(bounty_id, 1);
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert_eq!(bounty.title, "Title");
+        assert_eq!(bounty.submissions.len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Title too long (max 200 characters)")]
+    fn test_config_title_limit_is_enforced() {
+        let context = get_context(accounts(0), NearToken::from_near(5));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        let config = contract.get_config();
+        assert_eq!(config.max_title_len, 200);
+        assert_eq!(config.min_base_prize.0, NearToken::from_near(1).as_yoctonear());
+        assert_eq!(config.platform_fee_rate, contract.get_platform_fee_rate());
+
+        contract.create_content_bounty(
+            "t".repeat(config.max_title_len + 1),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+    }
+
+    #[test]
+    fn test_submit_content() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.submit_content(
+            bounty_id,
+            "creation-1".to_string(),
+            "My Sub".to_string(),
+            "url".to_string()
+        );
+
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert_eq!(bounty.submissions.len(), 1);
+        assert_eq!(bounty.submissions[0].creator, accounts(1));
+    }
+
+    #[test]
+    fn test_stake_on_submission() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.submit_content(bounty_id, "c1".to_string(), "Sub 1".to_string(), "url".to_string());
+
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(5)).build());
+        contract.stake_on_submission(bounty_id, 0);
+
+        let stake = contract.get_participant_stake(accounts(2), bounty_id).unwrap();
+        assert_eq!(stake.amount.0, NearToken::from_near(5).as_yoctonear());
+    }
+
+    fn setup_staked_bounty(context: &mut VMContextBuilder) -> (BountyPredictionContract, u64) {
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.submit_content(bounty_id, "c1".to_string(), "Sub 1".to_string(), "url".to_string());
+
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(5)).build());
+        contract.stake_on_submission(bounty_id, 0);
+
+        (contract, bounty_id)
+    }
+
+    #[test]
+    fn test_backer_reward_with_near_sized_stakes() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(5)).build());
+        contract.stake_on_submission(bounty_id, 0);
+
+        // Two equal backers split the backer share of (1 NEAR base + 10 NEAR staked) after the fee.
+        // stake * pool is far beyond u128 here, which used to zero the reward.
+        let bounty = contract.bounties.get(&bounty_id).unwrap();
+        let prize = NearToken::from_near(11).as_yoctonear();
+        let after_fee = prize - prize * contract.get_platform_fee_rate() / 10_000;
+        let expected = after_fee * DEFAULT_BACKER_SHARE as u128 / 100 / 2;
+        let reward = contract.calculate_backer_reward(&bounty, NearToken::from_near(5), 0, 0);
+        assert_eq!(reward.as_yoctonear(), expected);
+    }
+
+    #[test]
+    fn test_scheduled_bounty_opens_at_start() {
+        let day = 24 * 60 * 60 * 1_000_000_000u64;
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            Some(day)
+        );
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert_eq!(bounty.starts_at, Some(day));
+        assert_eq!(bounty.ends_at, 8 * day);
+
+        // The creator can move the start; the duration moves with it
+        contract.update_scheduled_bounty(bounty_id, Some("New title".to_string()), None, None, Some(2 * day));
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert_eq!((bounty.title.as_str(), bounty.ends_at), ("New title", 9 * day));
+
+        let stake = U128(NearToken::from_near(1).as_yoctonear());
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        let submit = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.submit_content(bounty_id, "c1".to_string(), "Sub 1".to_string(), "url".to_string())
+        }));
+        assert!(submit.is_err());
+
+        testing_env!(context.predecessor_account_id(accounts(1)).block_timestamp(2 * day).build());
+        contract.submit_content(bounty_id, "c1".to_string(), "Sub 1".to_string(), "url".to_string());
+        assert_eq!(contract.simulate_stake(bounty_id, 0, stake, accounts(2)).unwrap().rejection, None);
+    }
+
+    #[test]
+    fn test_cancel_scheduled_bounty() {
+        let context = get_context(accounts(0), NearToken::from_near(5));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            Some(1_000)
+        );
+        let stake = U128(NearToken::from_near(1).as_yoctonear());
+        assert_eq!(
+            contract.simulate_stake(bounty_id, 0, stake, accounts(2)).unwrap().rejection.as_deref(),
+            Some("Bounty has not started yet")
+        );
+
+        contract.cancel_scheduled_bounty(bounty_id);
+        assert!(contract.get_bounty(bounty_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounty has already started")]
+    fn test_started_bounty_cannot_be_cancelled() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        contract.cancel_scheduled_bounty(bounty_id);
+    }
+
+    #[test]
+    fn test_simulate_stake_matches_stake() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        let five = U128(NearToken::from_near(5).as_yoctonear());
+
+        let simulation = contract.simulate_stake(bounty_id, 0, five, accounts(3)).unwrap();
+        assert_eq!(simulation.rejection, None);
+        assert_eq!(simulation.total_staked.0, NearToken::from_near(10).as_yoctonear());
+        assert_eq!(simulation.submission_share_bps, 10_000);
+        assert_eq!(simulation.backer_share_bps, 5_000);
+
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(5)).build());
+        contract.stake_on_submission(bounty_id, 0);
+        let bounty = contract.bounties.get(&bounty_id).unwrap();
+        let reward = contract.calculate_backer_reward(&bounty, NearToken::from_near(5), 0, 0);
+        assert_eq!(simulation.projected_reward.0, reward.as_yoctonear());
+
+        // A restake replaces the previous stake rather than adding to it
+        let restake = contract.simulate_stake(bounty_id, 0, U128(NearToken::from_near(2).as_yoctonear()), accounts(2));
+        assert_eq!(restake.unwrap().total_staked.0, NearToken::from_near(7).as_yoctonear());
+
+        let too_large = contract
+            .simulate_stake(bounty_id, 0, U128(NearToken::from_near(11).as_yoctonear()), accounts(4))
+            .unwrap();
+        assert_eq!(too_large.rejection.as_deref(), Some("Stake amount exceeds maximum allowed for this bounty"));
+        assert_eq!(too_large.total_staked.0, NearToken::from_near(10).as_yoctonear());
+        assert_eq!(too_large.projected_reward.0, 0);
+        assert!(contract.simulate_stake(bounty_id, 3, five, accounts(4)).unwrap().rejection.is_some());
+        assert!(contract.simulate_stake(99, 0, five, accounts(4)).is_none());
+    }
+
+    #[test]
+    fn test_early_backers_earn_more_than_late_backers() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        let bounty = contract.bounties.get(&bounty_id).unwrap();
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().max_stake_boost_bps, DEFAULT_STAKE_BOOST_BPS);
+
+        // Half way through the bounty the same stake carries half the boost
+        let midpoint = bounty.created_at + (bounty.ends_at - bounty.created_at) / 2;
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(NearToken::from_near(5))
+            .block_timestamp(midpoint)
+            .build());
+        contract.stake_on_submission(bounty_id, 0);
+        assert_eq!(contract.get_participant_stake(accounts(2), bounty_id).unwrap().weight_bps, 15_000);
+        assert_eq!(contract.get_participant_stake(accounts(3), bounty_id).unwrap().weight_bps, 12_500);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).block_timestamp(bounty.ends_at).build());
+        contract.close_bounty(bounty_id);
+        let bounty = contract.bounties.get(&bounty_id).unwrap();
+        let early = contract.calculate_backer_reward(&bounty, NearToken::from_near(5), bounty.created_at, 0);
+        let late = contract.calculate_backer_reward(&bounty, NearToken::from_near(5), midpoint, 0);
+
+        // Weights 1.5 and 1.25 split the backer pool 6:5
+        let prize = NearToken::from_near(11).as_yoctonear();
+        let after_fee = prize - prize * contract.get_platform_fee_rate() / 10_000;
+        let backer_pool = after_fee * DEFAULT_BACKER_SHARE as u128 / 100;
+        assert_eq!(early.as_yoctonear(), backer_pool * 6 / 11);
+        assert_eq!(late.as_yoctonear(), backer_pool * 5 / 11);
+    }
+
+    #[test]
+    fn test_stake_boost_is_fixed_at_creation() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.set_stake_boost(0);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().max_stake_boost_bps, DEFAULT_STAKE_BOOST_BPS);
+
+        testing_env!(context.attached_deposit(NearToken::from_near(5)).build());
+        let unweighted = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+        assert_eq!(contract.get_bounty(unweighted).unwrap().max_stake_boost_bps, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Stake boost cannot exceed 10000 basis points")]
+    fn test_stake_boost_capped() {
+        let context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.set_stake_boost(MAX_STAKE_BOOST_BPS + 1);
+    }
+
+    // Bounty with a 1 NEAR bond and a 2 NEAR qualifying stake; accounts(1) and accounts(3) submit
+    fn setup_bonded_bounty(context: &mut VMContextBuilder) -> (BountyPredictionContract, u64) {
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+        let (bond, min_qualifying_stake) = (NearToken::from_near(1), NearToken::from_near(2));
+        contract.set_submission_bond(bounty_id, U128(bond.as_yoctonear()), U128(min_qualifying_stake.as_yoctonear()));
+
+        for (submitter, creation_id) in [(1, "c1"), (3, "c2")] {
+            let submitter = accounts(submitter);
+            testing_env!(context.predecessor_account_id(submitter).attached_deposit(NearToken::from_near(1)).build());
+            contract.submit_content(bounty_id, creation_id.to_string(), "Sub".to_string(), "url".to_string());
+        }
+        (contract, bounty_id)
+    }
+
+    #[test]
+    fn test_unqualified_submission_bonds_join_the_prize() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_bonded_bounty(&mut context);
+        let bond = contract.get_bounty(bounty_id).unwrap().submission_bond.unwrap();
+        assert_eq!(bond.bond, U128(NearToken::from_near(1).as_yoctonear()));
+
+        for (backer, submission_index, stake) in [(2, 0, 3), (4, 1, 1)] {
+            let backer = accounts(backer);
+            testing_env!(context.predecessor_account_id(backer).attached_deposit(NearToken::from_near(stake)).build());
+            contract.stake_on_submission(bounty_id, submission_index);
+        }
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.predecessor_account_id(accounts(0)).block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        // Submission 1 drew 1 NEAR of the 2 required: its bond went to the prize
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().base_prize, U128(NearToken::from_near(2).as_yoctonear()));
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        assert_eq!(contract.claim_submission_bond(bounty_id, 0), U128(NearToken::from_near(1).as_yoctonear()));
+        assert!(contract.posted_bonds.get(&(bounty_id, 1)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach the 1000000000000000000000000 yoctoNEAR submission bond")]
+    fn test_submission_requires_bond() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_bonded_bounty(&mut context);
+        let short_of_bond = NearToken::from_millinear(500);
+        testing_env!(context.predecessor_account_id(accounts(4)).attached_deposit(short_of_bond).build());
+        contract.submit_content(bounty_id, "c3".to_string(), "Sub".to_string(), "url".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Submission bond must be set before the first submission")]
+    fn test_submission_bond_fixed_after_first_submission() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_bonded_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.set_submission_bond(bounty_id, U128(0), U128(0));
+    }
+
+    #[test]
+    fn test_settlement_report_is_frozen_at_close() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(3)).build());
+        contract.stake_on_submission(bounty_id, 0);
+        assert!(contract.get_settlement_report(bounty_id).is_none(), "open bounties have no report");
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(after_end)
+            .build());
+        contract.close_bounty(bounty_id);
+        let report = contract.get_settlement_report(bounty_id).unwrap();
+        assert!(report.frozen);
+        assert_eq!((report.platform_fee_rate, report.winning_submission), (500, Some(0)));
+        assert_eq!(report.payouts.len(), 2);
+        assert!(report.payouts.iter().all(|payout| !payout.claimed && payout.payout.0 > 0));
+        let paid: u128 = report.payouts.iter().map(|payout| payout.payout.0).sum();
+        assert!(report.backer_pool.0 - paid <= 2);
+
+        // A later fee change does not move the settled payouts, and claimed stakes stay in the report
+        contract.update_platform_fee_rate(0);
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_bounty_winnings(bounty_id);
+        let after_claim = contract.get_settlement_report(bounty_id).unwrap();
+        assert_eq!(after_claim.platform_fee_rate, 500);
+        assert!(after_claim.payouts[0].claimed);
+        assert_eq!(
+            after_claim.payouts.iter().map(|payout| payout.payout).collect::<Vec<_>>(),
+            report.payouts.iter().map(|payout| payout.payout).collect::<Vec<_>>()
+        );
+    }
+
+    fn add_token_prize(context: &mut VMContextBuilder, contract: &mut BountyPredictionContract, bounty_id: u64) {
+        let token: AccountId = "token.testnet".parse().unwrap();
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.add_prize_token(token.clone());
+        testing_env!(context.predecessor_account_id(token).build());
+        let msg = format!("{{\"bounty_id\": {}}}", bounty_id);
+        let unused = contract.ft_on_transfer(accounts(4), U128(1_000), msg);
+        assert!(matches!(unused, PromiseOrValue::Value(U128(0))));
+    }
+
+    #[test]
+    fn test_ft_prize_rejected_transfers_are_returned() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        let msg = format!("{{\"bounty_id\": {}}}", bounty_id);
+
+        testing_env!(context.predecessor_account_id("token.testnet".parse().unwrap()).build());
+        let unapproved = contract.ft_on_transfer(accounts(4), U128(1_000), msg.clone());
+        assert!(matches!(unapproved, PromiseOrValue::Value(U128(1_000))));
+
+        add_token_prize(&mut context, &mut contract, bounty_id);
+        let bad_msg = contract.ft_on_transfer(accounts(4), U128(500), "bounty 0".to_string());
+        assert!(matches!(bad_msg, PromiseOrValue::Value(U128(500))));
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.block_timestamp(after_end).build());
+        let too_late = contract.ft_on_transfer(accounts(4), U128(500), msg);
+        assert!(matches!(too_late, PromiseOrValue::Value(U128(500))));
+        assert_eq!(contract.get_bounty_ft_prizes(bounty_id).len(), 1);
+    }
+
+    #[test]
+    fn test_ft_prize_split_between_winner_and_backers() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(3)).build());
+        contract.stake_on_submission(bounty_id, 0);
+        add_token_prize(&mut context, &mut contract, bounty_id);
+        let token: AccountId = "token.testnet".parse().unwrap();
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.predecessor_account_id(accounts(0)).block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        assert_eq!(contract.get_ft_prize_balance(accounts(1), token.clone()).0, 900);
+        assert_eq!(contract.get_ft_prize_balance(accounts(2), token.clone()).0, 0, "credited when claiming");
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_bounty_winnings(bounty_id);
+        // 5 of the 8 NEAR backing the winner, both staked at the same time
+        assert_eq!(contract.get_ft_prize_balance(accounts(2), token.clone()).0, 62);
+
+        // A failed withdrawal restores the balance
+        testing_env!(context.attached_deposit(NearToken::from_yoctonear(1)).build());
+        contract.withdraw_ft_prize(token.clone());
+        assert_eq!(contract.get_ft_prize_balance(accounts(2), token.clone()).0, 0);
+        testing_env!(
+            context.predecessor_account_id(accounts(5)).current_account_id(accounts(5)).build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_ft_prize_withdrawn(accounts(2), token.clone(), U128(62)));
+        assert_eq!(contract.get_ft_prize_balance(accounts(2), token).0, 62);
+    }
+
+    #[test]
+    fn test_ft_prize_returned_to_sponsor_on_emergency_close() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        add_token_prize(&mut context, &mut contract, bounty_id);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+        assert_eq!(contract.get_ft_prize_balance(accounts(4), "token.testnet".parse().unwrap()).0, 1_000);
+    }
+
+    fn setup_community_bounty(context: &mut VMContextBuilder) -> (BountyPredictionContract, u64) {
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.register_community("pixel-art".to_string(), "Pixel Art".to_string(), accounts(5), Some(200));
+        contract.add_community_moderator("pixel-art".to_string(), accounts(4));
+
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+        testing_env!(context.attached_deposit(NearToken::from_near(0)).build());
+        contract.set_bounty_community(bounty_id, Some("pixel-art".to_string()));
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.submit_content(bounty_id, "c1".to_string(), "Sub 1".to_string(), "url".to_string());
+        for backer in [accounts(2), accounts(3)] {
+            testing_env!(context.predecessor_account_id(backer).attached_deposit(NearToken::from_near(2)).build());
+            contract.stake_on_submission(bounty_id, 0);
+        }
+        (contract, bounty_id)
+    }
+
+    #[test]
+    fn test_community_bounty_pays_fee_to_treasury_at_its_rate() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_community_bounty(&mut context);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().community_id.as_deref(), Some("pixel-art"));
+        assert_eq!(contract.get_community_bounties("pixel-art".to_string(), None, None).len(), 1);
+        assert!(contract.get_community_bounties("other".to_string(), None, None).is_empty());
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(after_end)
+            .build());
+        contract.close_bounty(bounty_id);
+
+        let report = contract.get_settlement_report(bounty_id).unwrap();
+        assert_eq!(report.platform_fee_rate, 200);
+        let treasury_fee = format!("transferred to {} from bounty {}", accounts(5), bounty_id);
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log.contains(&treasury_fee)));
+    }
+
+    #[test]
+    fn test_community_moderator_closes_only_their_bounties() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_community_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(2)).build());
+        let untagged = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(4)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+        assert!(contract.get_bounty(bounty_id).unwrap().is_emergency_closed);
+
+        let outside =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.emergency_close_bounty(untagged)));
+        assert!(outside.is_err(), "moderators have no power outside their community");
+    }
+
+    fn add_rival_submission(context: &mut VMContextBuilder, contract: &mut BountyPredictionContract, bounty_id: u64) {
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(0)).build());
+        contract.submit_content(bounty_id, "c2".to_string(), "Sub 2".to_string(), "url".to_string());
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(1)).build());
+        contract.stake_on_submission(bounty_id, 1);
+    }
+
+    #[test]
+    fn test_flagged_submission_cannot_win() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_community_bounty(&mut context);
+        add_rival_submission(&mut context, &mut contract, bounty_id);
+
+        testing_env!(context.predecessor_account_id(accounts(4)).attached_deposit(NearToken::from_near(0)).build());
+        contract.flag_submission(bounty_id, 0, "sha256:stolen".to_string());
+        let flag = contract.get_bounty(bounty_id).unwrap().submissions[0].flag.clone().unwrap();
+        assert_eq!((flag.moderator, flag.reason_hash), (accounts(4), "sha256:stolen".to_string()));
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.predecessor_account_id(accounts(0)).block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().winning_submission, Some(1));
+    }
+
+    #[test]
+    fn test_creator_clears_flag_before_close() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_community_bounty(&mut context);
+        add_rival_submission(&mut context, &mut contract, bounty_id);
+
+        testing_env!(context.predecessor_account_id(accounts(4)).attached_deposit(NearToken::from_near(0)).build());
+        contract.flag_submission(bounty_id, 0, "sha256:disputed".to_string());
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        contract.clear_submission_flag(bounty_id, 0);
+        assert!(contract.get_submission_flag(bounty_id, 0).is_none());
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().winning_submission, Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only a moderator of the bounty's community can flag submissions")]
+    fn test_only_community_moderator_flags() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_community_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(0)).build());
+        contract.flag_submission(bounty_id, 0, "sha256:spam".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Community id must be 1-32 lowercase letters")]
+    fn test_register_community_rejects_bad_id() {
+        let context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.register_community("Pixel Art".to_string(), "Pixel Art".to_string(), accounts(5), None);
+    }
+
+    #[test]
+    fn test_claim_leaves_a_receipt() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(3)).build());
+        contract.stake_on_submission(bounty_id, 0);
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(after_end)
+            .block_height(42)
+            .build());
+        contract.close_bounty(bounty_id);
+        let expected = contract.get_settlement_report(bounty_id).unwrap().payouts[0].payout;
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_bounty_winnings(bounty_id);
+        let receipt = contract.get_claim_receipt(accounts(2), bounty_id).unwrap();
+        assert_eq!((receipt.amount, receipt.role, receipt.block_height), (expected, ClaimKind::Backer, 42));
+        assert!(!receipt.as_credits);
+        assert!(contract.get_claim_receipt(accounts(3), bounty_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Already claimed")]
+    fn test_second_claim_cites_the_receipt() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(after_end)
+            .build());
+        contract.close_bounty(bounty_id);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_bounty_winnings(bounty_id);
+        contract.claim_bounty_winnings(bounty_id);
+    }
+
+    fn close_two_backer_bounty(context: &mut VMContextBuilder) -> (BountyPredictionContract, u64) {
+        let (mut contract, bounty_id) = setup_staked_bounty(context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(3)).build());
+        contract.stake_on_submission(bounty_id, 0);
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(after_end)
+            .build());
+        contract.close_bounty(bounty_id);
+        (contract, bounty_id)
+    }
+
+    #[test]
+    fn test_liabilities_follow_close_and_claims() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = close_two_backer_bounty(&mut context);
+        let payouts = contract.get_settlement_report(bounty_id).unwrap().payouts;
+        let owed: u128 = payouts.iter().map(|payout| payout.payout.0).sum();
+        let solvency = contract.get_solvency();
+        assert_eq!(solvency.liabilities.0, owed);
+        assert!(solvency.is_solvent);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_bounty_winnings(bounty_id);
+        assert_eq!(contract.get_solvency().liabilities.0, owed - payouts[0].payout.0);
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.claim_bounty_winnings(bounty_id);
+        assert_eq!(contract.get_solvency().liabilities.0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient contract balance")]
+    fn test_transfer_cannot_dip_into_liabilities() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = close_two_backer_bounty(&mut context);
+        let owed = contract.get_solvency().liabilities.0;
+
+        // The balance covers both backers' payouts but not the storage on top
+        let balance = NearToken::from_yoctonear(owed);
+        testing_env!(context.predecessor_account_id(accounts(2)).account_balance(balance).build());
+        contract.claim_bounty_winnings(bounty_id);
+    }
+
+    #[test]
+    fn test_failed_transfer_is_kept_for_retry() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, _) = close_two_backer_bounty(&mut context);
+        let owed = contract.get_solvency().liabilities.0;
+
+        testing_env!(
+            context.predecessor_account_id(accounts(5)).current_account_id(accounts(5)).build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_transfer_complete(accounts(2), U128(1_000)));
+        assert_eq!(contract.get_unpaid_transfer(accounts(2)).0, 1_000);
+        assert_eq!(contract.get_solvency().liabilities.0, owed + 1_000);
+
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.retry_transfer(accounts(2));
+        assert_eq!(contract.get_unpaid_transfer(accounts(2)).0, 0);
+        assert_eq!(contract.get_solvency().liabilities.0, owed);
+    }
+
+    #[test]
+    fn test_active_bounties_are_paged() {
+        let context = get_context(accounts(0), NearToken::from_near(2));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        for _ in 0..5 {
+            contract.create_content_bounty(
+                "Title".to_string(),
+                "Desc".to_string(),
+                "Reqs".to_string(),
+                NearToken::from_near(1),
+                NearToken::from_near(10),
+                None,
+                None,
+                7,
+                None
+            );
+        }
+
+        let first = contract.get_active_bounties(None, Some(2));
+        assert_eq!(first.iter().map(|bounty| bounty.id).collect::<Vec<_>>(), vec![1, 2]);
+        let next = contract.get_active_bounties(Some(first[1].id + 1), Some(2));
+        assert_eq!(next.iter().map(|bounty| bounty.id).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(contract.get_active_bounties(None, None).len(), 5);
+
+        let too_many =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.get_active_bounties(None, Some(101))));
+        assert!(too_many.is_err(), "pages are capped at 100 bounties");
+    }
+
+    #[test]
+    fn test_emergency_close_refunds_backer_and_creator() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert!(bounty.is_closed);
+        assert!(bounty.is_emergency_closed);
+        assert_eq!(bounty.winning_submission, None);
+
+        // Backer pulls their exact stake
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_emergency_refund(bounty_id);
+        assert!(contract.get_participant_stake(accounts(2), bounty_id).is_none());
+
+        // Creator pulls the base prize
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        contract.claim_emergency_refund(bounty_id);
+        assert!(contract.base_prize_refunded(bounty_id));
+
+        let receipt = contract.get_claim_receipt(accounts(0), bounty_id).unwrap();
+        assert_eq!(receipt.role, ClaimKind::EmergencyRefund);
+        assert_eq!(receipt.amount.0, NearToken::from_near(1).as_yoctonear());
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to refund for this account")]
+    fn test_emergency_refund_cannot_be_claimed_twice() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_emergency_refund(bounty_id);
+        contract.claim_emergency_refund(bounty_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already claimed")]
+    fn test_single_participant_is_paid_once_at_close() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        let ends_at = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(ends_at)
+            .build());
+        contract.close_bounty(bounty_id);
+
+        assert!(contract.get_participant_stake(accounts(2), bounty_id).is_none());
+        let receipt = contract.get_claim_receipt(accounts(2), bounty_id).unwrap();
+        assert_eq!(receipt.role, ClaimKind::SingleParticipant);
+        assert_eq!(receipt.amount.0, NearToken::from_near(5).as_yoctonear());
+        assert_eq!(contract.get_solvency().liabilities.0, 0);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_bounty_winnings(bounty_id);
+    }
+
+    #[test]
+    fn test_rescue_mode_returns_recorded_stakes() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.enable_rescue_mode(bounty_id);
+        let stake = U128(NearToken::from_near(1).as_yoctonear());
+        assert_eq!(
+            contract.simulate_stake(bounty_id, 0, stake, accounts(3)).unwrap().rejection.as_deref(),
+            Some("Bounty is not active")
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.rescue_withdraw(bounty_id);
+        assert!(contract.get_participant_stake(accounts(2), bounty_id).is_none());
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().total_staked.0, 0);
+        assert_eq!(contract.get_claim_receipt(accounts(2), bounty_id).unwrap().role, ClaimKind::Rescue);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        contract.rescue_withdraw(bounty_id);
+        let receipt = contract.get_claim_receipt(accounts(0), bounty_id).unwrap();
+        assert_eq!(receipt.amount.0, NearToken::from_near(1).as_yoctonear());
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to rescue for this account")]
+    fn test_rescue_pays_nothing_for_a_forfeited_stake() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(4)).attached_deposit(NearToken::from_near(0)).build());
+        contract.submit_content(bounty_id, "c2".to_string(), "Sub 2".to_string(), "url".to_string());
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(1)).build());
+        contract.stake_on_submission(bounty_id, 1);
+
+        let ends_at = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(NearToken::from_near(0))
+            .block_timestamp(ends_at)
+            .build());
+        contract.close_bounty(bounty_id);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().winning_submission, Some(0));
+        contract.enable_rescue_mode(bounty_id);
+
+        // The loser's stake went into the winners' pool
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.rescue_withdraw(bounty_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounty is not in rescue mode")]
+    fn test_rescue_withdraw_requires_rescue_mode() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(0)).build());
+        contract.rescue_withdraw(bounty_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the guardian role")]
+    fn test_emergency_close_non_owner_fails() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounty was emergency closed; use claim_emergency_refund")]
+    fn test_claim_winnings_rejected_after_emergency_close() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_bounty_winnings(bounty_id);
+    }
+
+    #[test]
+    fn test_cleanup_after_all_claims_keeps_summary() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        assert!(contract.storage_paid.get(&bounty_id).unwrap() > 0);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+        contract.claim_emergency_refund(bounty_id);
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_emergency_refund(bounty_id);
+
+        // Anyone may clean up once nothing is left to claim
+        testing_env!(context.predecessor_account_id(accounts(4)).build());
+        let summary = contract.cleanup_bounty(bounty_id);
+        assert_eq!((summary.submission_count, summary.participant_count, summary.unclaimed_stakes), (1, 1, 0));
+        assert!(summary.is_emergency_closed);
+        assert!(contract.get_bounty(bounty_id).is_none());
+        assert!(contract.get_bounty_participants(bounty_id).is_empty());
+        assert!(contract.storage_paid.get(&bounty_id).is_none());
+        assert_eq!(contract.get_bounty_summary(bounty_id), Some(summary));
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs.iter().any(|log| log.contains("\"event\":\"bounty_cleaned_up\"")));
+        assert!(!logs.iter().any(|log| log.contains("\"storage_refund\":\"0\"")), "the creator's storage is refunded");
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounty still has unclaimed funds; wait until the claim period ends")]
+    fn test_cleanup_rejected_while_claims_pending() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+        contract.claim_emergency_refund(bounty_id);
+        contract.cleanup_bounty(bounty_id);
+    }
+
+    #[test]
+    fn test_cleanup_after_claim_period_drops_unclaimed_stakes() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.emergency_close_bounty(bounty_id);
+
+        let claim_deadline = contract.bounties.get(&bounty_id).unwrap().ends_at + CLAIM_PERIOD_NS;
+        testing_env!(context.block_timestamp(claim_deadline).build());
+        let summary = contract.cleanup_bounty(bounty_id);
+        assert_eq!(summary.unclaimed_stakes, 1);
+        assert!(contract.get_participant_stake(accounts(2), bounty_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Credits settlement is not configured")]
+    fn test_claim_as_credits_requires_credits_contract() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(0)).build());
+        contract.claim_as_credits(bounty_id, "user-123".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_set_credits_contract_non_owner_fails() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.set_credits_contract(Some(accounts(3)));
+    }
+
+    #[test]
+    fn test_failed_credits_deposit_restores_claim() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.set_credits_contract(Some(accounts(3)));
+        assert_eq!(contract.get_credits_contract(), Some(accounts(3)));
+
+        // Simulate the stake having been consumed by claim_as_credits
+        let stake = contract.participant_stakes.get(&(accounts(2), bounty_id)).unwrap();
+        contract.participant_stakes.remove(&(accounts(2), bounty_id));
+
+        testing_env!(
+            context.predecessor_account_id(accounts(0)).current_account_id(accounts(0)).build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        contract.on_claim_as_credits(accounts(2), stake, U128(NearToken::from_near(5).as_yoctonear()));
+
+        let restored = contract.get_participant_stake(accounts(2), bounty_id).unwrap();
+        assert_eq!(restored.amount, U128(NearToken::from_near(5).as_yoctonear()));
+    }
+
+    #[test]
+    fn test_winner_royalties_split_among_top_backers() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        for (backer, stake) in [(accounts(3), 3), (accounts(4), 2)] {
+            testing_env!(context.predecessor_account_id(backer).attached_deposit(NearToken::from_near(stake)).build());
+            contract.stake_on_submission(bounty_id, 0);
+        }
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        let split = RoyaltySplit { creator_bps: 500, backers_bps: 300, platform_bps: 200, top_backers: 2 };
+        contract.set_nft_royalty(split);
+
+        // accounts(2) staked 5 and accounts(3) 3 of the top 8 NEAR; accounts(4) is outside the top two
+        let bounty = contract.bounties.get(&bounty_id).unwrap();
+        let royalties = contract.winner_royalties(&bounty, 0);
+        assert_eq!(royalties.len(), 4);
+        assert_eq!(royalties[&accounts(1)], 500);
+        assert_eq!(royalties[&accounts(0)], 200);
+        assert_eq!(royalties[&accounts(2)], 188); // 187 plus the rounding dust
+        assert_eq!(royalties[&accounts(3)], 112);
+        assert_eq!(royalties.values().sum::<u32>(), 1000);
+    }
+
+    #[test]
+    fn test_close_mints_winner_nft() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(3)).build());
+        contract.stake_on_submission(bounty_id, 0);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.set_nft_contract(Some("nft.testnet".parse().unwrap()));
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log.starts_with("NFT_MINT_REQUESTED: c1")));
+        assert_eq!(contract.get_bounty_nft(bounty_id), None);
+
+        testing_env!(
+            context.predecessor_account_id(accounts(0)).current_account_id(accounts(0)).build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(vec![])],
+        );
+        assert!(contract.on_winner_nft_minted(bounty_id, "c1".to_string()));
+        assert_eq!(contract.get_bounty_nft(bounty_id), Some("c1".to_string()));
+    }
+
+    // Callbacks are private: the contract calls them on itself
+    fn self_call(context: &mut VMContextBuilder) {
+        testing_env!(context.predecessor_account_id(accounts(5)).current_account_id(accounts(5)).build());
+    }
+
+    fn contract_with_reputation(context: &mut VMContextBuilder) -> BountyPredictionContract {
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.set_reputation_contract(Some("reputation.testnet".parse().unwrap()));
+        contract
+    }
+
+    fn create_high_value_bounty(context: &mut VMContextBuilder, contract: &mut BountyPredictionContract) -> u64 {
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(101)).build());
+        contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(100),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        )
+    }
 
-        if contract_balance > reserved_balance {
-            let withdrawal_amount = Self::safe_sub_tokens(contract_balance, reserved_balance)
-                .expect("Balance calculation error");
+    #[test]
+    fn test_high_value_bounty_waits_for_creator_reputation() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        let mut contract = contract_with_reputation(&mut context);
+        let bounty_id = create_high_value_bounty(&mut context, &mut contract);
+        assert!(!contract.get_bounty(bounty_id).unwrap().is_active);
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log.starts_with("CONTENT_BOUNTY_PENDING")));
+
+        self_call(&mut context);
+        assert!(contract.on_creator_reputation(bounty_id, Ok(12)));
+        assert!(contract.get_bounty(bounty_id).unwrap().is_active);
+    }
 
-            if withdrawal_amount > NearToken::from_yoctonear(0) {
-                Promise::new(self.owner.clone()).transfer(withdrawal_amount);
-                env::log_str(&format!("PLATFORM_FEES_WITHDRAWN: {} NEAR withdrawn by owner", withdrawal_amount));
-            }
+    #[test]
+    fn test_low_reputation_removes_high_value_bounty() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        let mut contract = contract_with_reputation(&mut context);
+        let bounty_id = create_high_value_bounty(&mut context, &mut contract);
+
+        self_call(&mut context);
+        assert!(!contract.on_creator_reputation(bounty_id, Ok(3)));
+        assert!(contract.get_bounty(bounty_id).is_none());
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log.starts_with("CONTENT_BOUNTY_REJECTED")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reputation score 4 is below the 10 this bounty requires")]
+    fn test_high_value_submission_requires_reputation() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        let mut contract = contract_with_reputation(&mut context);
+        let bounty_id = create_high_value_bounty(&mut context, &mut contract);
+        self_call(&mut context);
+        contract.on_creator_reputation(bounty_id, Ok(10));
+
+        // Below the gate the submission would be stored directly; here it waits for the check
+        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(NearToken::from_near(0)).build());
+        let pending = contract.submit_content(bounty_id, "c1".to_string(), "Sub 1".to_string(), "url".to_string());
+        assert!(matches!(pending, PromiseOrValue::Promise(_)));
+        assert!(contract.get_bounty(bounty_id).unwrap().submissions.is_empty());
+
+        self_call(&mut context);
+        contract.on_submitter_reputation(accounts(1), bounty_id, "c1".into(), "Sub 1".into(), "url".into(), Ok(4));
+    }
+
+    // Two submissions made with the creation registry configured; accounts(1) and accounts(3) submit
+    fn setup_verified_bounty(context: &mut VMContextBuilder) -> (BountyPredictionContract, u64) {
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.set_creation_registry(Some("registry.testnet".parse().unwrap()));
+        let bounty_id = contract.create_content_bounty(
+            "Title".to_string(),
+            "Desc".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+        for (submitter, creation_id) in [(1, "c1"), (3, "c2")] {
+            let submitter = accounts(submitter);
+            testing_env!(context.predecessor_account_id(submitter).attached_deposit(NearToken::from_near(0)).build());
+            contract.submit_content(bounty_id, creation_id.to_string(), "Sub".to_string(), "url".to_string());
         }
+        (contract, bounty_id)
     }
 
-    // View functions for contract state
-    pub fn get_platform_fee_rate(&self) -> u128 {
-        self.platform_fee_rate
+    #[test]
+    fn test_submission_verified_against_creation_registry() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_verified_bounty(&mut context);
+        assert_eq!(contract.get_submission_verification(bounty_id, 0), Some(CreationVerification::Pending));
+
+        self_call(&mut context);
+        contract.on_creation_verified(bounty_id, 0, Ok(true));
+        contract.on_creation_verified(bounty_id, 1, Err(PromiseError::Failed));
+
+        let submissions = contract.get_bounty(bounty_id).unwrap().submissions;
+        assert_eq!(submissions[0].verification, Some(CreationVerification::Verified));
+        assert_eq!(submissions[1].verification, Some(CreationVerification::Unavailable));
+
+        // A failed check can be retried by anyone, a completed one cannot
+        testing_env!(context.predecessor_account_id(accounts(4)).build());
+        contract.verify_submission(bounty_id, 1);
+        assert_eq!(contract.get_submission_verification(bounty_id, 1), Some(CreationVerification::Pending));
     }
 
-    // Helper for verifying paused state is removed
-    // pub fn is_contract_paused(&self) -> bool { self.is_paused } // REMOVED
+    #[test]
+    #[should_panic(expected = "Submission failed creation verification")]
+    fn test_rejected_submission_cannot_be_staked_on() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_verified_bounty(&mut context);
+        self_call(&mut context);
+        contract.on_creation_verified(bounty_id, 0, Ok(false));
 
-    pub fn get_contract_owner(&self) -> AccountId {
-        self.owner.clone()
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(1)).build());
+        contract.stake_on_submission(bounty_id, 0);
     }
 
-    pub fn get_max_participants_per_bounty(&self) -> usize {
-        MAX_PARTICIPANTS_PER_BOUNTY
+    #[test]
+    fn test_rejected_submission_cannot_win() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_verified_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(5)).build());
+        contract.stake_on_submission(bounty_id, 0);
+        testing_env!(context.predecessor_account_id(accounts(4)).attached_deposit(NearToken::from_near(1)).build());
+        contract.stake_on_submission(bounty_id, 1);
+
+        // The registry answers after the stakes came in
+        self_call(&mut context);
+        contract.on_creation_verified(bounty_id, 0, Ok(false));
+        contract.on_creation_verified(bounty_id, 1, Ok(true));
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.predecessor_account_id(accounts(0)).block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().winning_submission, Some(1));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::{accounts, VMContextBuilder};
-    use near_sdk::testing_env;
-    use near_sdk::NearToken;
+    #[test]
+    fn test_close_refunds_when_every_staked_submission_is_rejected() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_verified_bounty(&mut context);
+        for backer in [2, 4] {
+            let backer = accounts(backer);
+            testing_env!(context.predecessor_account_id(backer).attached_deposit(NearToken::from_near(1)).build());
+            contract.stake_on_submission(bounty_id, 0);
+        }
+        self_call(&mut context);
+        contract.on_creation_verified(bounty_id, 0, Ok(false));
 
-    const REWARD_RATE: u128 = 10;
-    const MIN_STAKE: NearToken = NearToken::from_near(1);
-    const MAX_STAKE: NearToken = NearToken::from_near(100);
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.predecessor_account_id(accounts(0)).block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        assert!(contract.get_bounty(bounty_id).unwrap().is_emergency_closed);
 
-    fn get_context(predecessor_account_id: AccountId, attached_deposit: NearToken) -> VMContextBuilder {
-        let mut builder = VMContextBuilder::new();
-        builder
-            .predecessor_account_id(predecessor_account_id)
-            .attached_deposit(attached_deposit)
-            .block_timestamp(0);
-        builder
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.claim_emergency_refund(bounty_id);
+        assert!(contract.get_participant_stake(accounts(2), bounty_id).is_none());
     }
 
-    // ========================================
-    // Legacy Staking Tests (Restored & Verified)
-    // ========================================
+    #[test]
+    fn test_close_reports_reputation() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(3)).build());
+        contract.stake_on_submission(bounty_id, 0);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.set_reputation_contract(Some("reputation.testnet".parse().unwrap()));
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        let reported = format!("REPUTATION_REPORTED: bounty {} winner {} and 2 backers", bounty_id, accounts(1));
+        assert!(near_sdk::test_utils::get_logs().contains(&reported));
+    }
 
     #[test]
-    fn test_new() {
-        let context = get_context(accounts(0), NearToken::from_near(0));
-        testing_env!(context.build());
-        let contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
-        assert_eq!(contract.get_reward_rate(), REWARD_RATE);
-        assert_eq!(contract.min_stake_amount, MIN_STAKE);
-        assert_eq!(contract.get_max_stake_amount().0, MAX_STAKE.as_yoctonear());
+    fn test_tick_closes_bounties_past_the_grace_period() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.grant_role(Role::Keeper, accounts(4));
+
+        let ends_at = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.predecessor_account_id(accounts(4)).block_timestamp(ends_at).build());
+        let report = contract.tick();
+        assert_eq!((report.scanned, report.closed.len()), (1, 0));
+        assert!(!contract.get_bounty(bounty_id).unwrap().is_closed, "the creator still has the grace period");
+
+        testing_env!(context.block_timestamp(ends_at + CLOSE_GRACE_PERIOD_NS).build());
+        let report = contract.tick();
+        assert_eq!(report.closed, vec![bounty_id]);
+        assert!(contract.get_bounty(bounty_id).unwrap().is_closed);
+        assert!(near_sdk::test_utils::get_logs().last().unwrap().contains("\"event\":\"maintenance_tick\""));
     }
 
     #[test]
-    fn test_stake_valid_amount() {
+    #[should_panic(expected = "Requires the owner or the keeper role")]
+    fn test_tick_requires_keeper_role() {
         let mut context = get_context(accounts(0), NearToken::from_near(0));
         testing_env!(context.build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        testing_env!(context.predecessor_account_id(accounts(4)).build());
+        contract.tick();
+    }
 
-        let stake_amount = NearToken::from_near(10);
-        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(stake_amount).build());
-        contract.stake();
+    #[test]
+    fn test_close_reports_to_leaderboard() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
+        let (mut contract, bounty_id) = setup_staked_bounty(&mut context);
+        testing_env!(context.predecessor_account_id(accounts(3)).attached_deposit(NearToken::from_near(3)).build());
+        contract.stake_on_submission(bounty_id, 0);
 
-        let stake_info = contract.get_stake_info(accounts(1)).unwrap();
-        assert_eq!(stake_info.amount.0, stake_amount.as_yoctonear());
+        testing_env!(context.predecessor_account_id(accounts(0)).attached_deposit(NearToken::from_near(0)).build());
+        contract.set_leaderboard_contract(Some("leaderboard.testnet".parse().unwrap()));
+        assert_eq!(contract.get_leaderboard_contract(), Some("leaderboard.testnet".parse().unwrap()));
+
+        let after_end = contract.bounties.get(&bounty_id).unwrap().ends_at;
+        testing_env!(context.block_timestamp(after_end).build());
+        contract.close_bounty(bounty_id);
+        let reported = format!("LEADERBOARD_REPORTED: bounty {} with 2 backers", bounty_id);
+        assert!(near_sdk::test_utils::get_logs().contains(&reported));
     }
 
     #[test]
-    #[should_panic(expected = "Stake amount too low")]
-    fn test_stake_below_minimum() {
-        let mut context = get_context(accounts(0), NearToken::from_near(0));
+    #[should_panic(expected = "NFT royalties cannot exceed 50%")]
+    fn test_nft_royalty_capped() {
+        let context = get_context(accounts(0), NearToken::from_near(0));
         testing_env!(context.build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
-
-        let stake_amount = NearToken::from_yoctonear(MIN_STAKE.as_yoctonear() - 1);
-        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(stake_amount).build());
-        contract.stake();
+        let split = RoyaltySplit { creator_bps: 4000, backers_bps: 1000, platform_bps: 1, top_backers: 3 };
+        contract.set_nft_royalty(split);
     }
 
     #[test]
-    #[should_panic(expected = "Stake amount too high")]
-    fn test_stake_above_maximum() {
+    fn test_two_step_ownership_transfer() {
         let mut context = get_context(accounts(0), NearToken::from_near(0));
         testing_env!(context.build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
 
-        let stake_amount = NearToken::from_yoctonear(MAX_STAKE.as_yoctonear() + 1);
-        testing_env!(context.predecessor_account_id(accounts(1)).attached_deposit(stake_amount).build());
-        contract.stake();
+        contract.propose_owner(accounts(3));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(3)));
+        assert_eq!(contract.get_contract_owner(), accounts(0));
+
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.accept_ownership();
+        assert_eq!(contract.get_contract_owner(), accounts(3));
+        assert_eq!(contract.get_pending_owner(), None);
+
+        // The new owner controls owner-only settings
+        contract.update_platform_fee_rate(300);
     }
 
     #[test]
-    fn test_update_max_stake_amount() {
-        let context = get_context(accounts(0), NearToken::from_near(0));
+    fn test_guardian_role_can_emergency_close() {
+        let mut context = get_context(accounts(0), NearToken::from_near(5));
         testing_env!(context.build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        let bounty_id = contract.create_content_bounty(
+            "Guarded".to_string(),
+            "Description".to_string(),
+            "Reqs".to_string(),
+            NearToken::from_near(1),
+            NearToken::from_near(10),
+            None,
+            None,
+            7,
+            None
+        );
+        testing_env!(context.attached_deposit(NearToken::from_near(0)).build());
+        contract.grant_role(Role::Guardian, accounts(3));
+        assert_eq!(contract.get_roles(accounts(3)), vec![Role::Guardian]);
 
-        let new_max = NearToken::from_near(200);
-        contract.update_max_stake_amount(new_max);
-        assert_eq!(contract.get_max_stake_amount().0, new_max.as_yoctonear());
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.emergency_close_bounty(bounty_id);
+        assert!(contract.get_bounty(bounty_id).unwrap().is_closed);
     }
 
     #[test]
-    fn test_calculate_rewards_safe_with_zero_rate() {
-        let stake_amount = NearToken::from_near(10);
-        let reward_rate = 0u128;
-        let time_seconds = 3600u64; // 1 hour
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_guardian_role_cannot_change_fees() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
+        testing_env!(context.build());
+        let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.grant_role(Role::Guardian, accounts(3));
 
-        let rewards = BountyPredictionContract::calculate_rewards_safe(stake_amount, reward_rate, time_seconds);
-        assert_eq!(rewards, 0, "Rewards should be 0 with zero reward rate");
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.update_platform_fee_rate(100);
     }
 
     #[test]
-    fn test_update_reward_rate_to_one() {
+    fn test_arbiters_are_owner_admins_and_resolvers() {
         let context = get_context(accounts(0), NearToken::from_near(0));
         testing_env!(context.build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
-
-        contract.update_reward_rate(1);
-        assert_eq!(contract.get_reward_rate(), 1);
+        contract.grant_role(Role::Resolver, accounts(1));
+        contract.grant_role(Role::Admin, accounts(2));
+        contract.grant_role(Role::Guardian, accounts(3));
+
+        assert!(contract.is_arbiter(accounts(0)));
+        assert!(contract.is_arbiter(accounts(1)));
+        assert!(contract.is_arbiter(accounts(2)));
+        assert!(!contract.is_arbiter(accounts(3)));
     }
 
-    // ========================================
-    // Bounty & Content Tests (New API)
-    // ========================================
-
     #[test]
-    fn test_create_bounty_success() {
-        let mut context = get_context(accounts(0), NearToken::from_near(5)); // Enough for prize + storage
+    #[should_panic(expected = "Only the proposed owner can accept ownership")]
+    fn test_accept_ownership_requires_proposal() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
         testing_env!(context.build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.propose_owner(accounts(3));
 
-        let bounty_id = contract.create_content_bounty(
-            "Title".to_string(),
-            "Desc".to_string(),
-            "Reqs".to_string(),
-            NearToken::from_near(1),
-            NearToken::from_near(10),
-            None,
-            None,
-            7
-        );
+        testing_env!(context.predecessor_account_id(accounts(4)).build());
+        contract.accept_ownership();
+    }
 
-        assert_eq!(bounty_id, 1);
-        let bounty = contract.get_bounty(bounty_id).unwrap();
-        assert_eq!(bounty.title, "Title");
-        assert_eq!(bounty.submissions.len(), 0);
+    #[test]
+    fn test_contract_source_metadata() {
+        let metadata: serde_json::Value = serde_json::from_str(CONTRACT_SOURCE_METADATA).unwrap();
+        assert!(metadata["version"].as_str().unwrap().starts_with(env!("CARGO_PKG_VERSION")));
+        let standards: Vec<&str> =
+            metadata["standards"].as_array().unwrap().iter().map(|s| s["standard"].as_str().unwrap()).collect();
+        assert_eq!(standards, vec!["nep297", "nep330"]);
     }
 
     #[test]
-    fn test_submit_content() {
+    fn test_migrate_preserves_versioned_state() {
         let mut context = get_context(accounts(0), NearToken::from_near(5));
-        testing_env!(context.build());
+        testing_env!(context.current_account_id(accounts(5)).build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.update_platform_fee_rate(300);
+        env::state_write(&contract);
+        assert_eq!(contract.get_state_version(), STATE_VERSION);
+
+        // migrate is private: the upgrade batch calls it from the contract account
+        testing_env!(context.predecessor_account_id(accounts(5)).build());
+        let migrated = BountyPredictionContract::migrate();
+        assert_eq!(migrated.get_contract_owner(), accounts(0));
+        assert_eq!(migrated.platform_fee_rate, 300);
+        assert_eq!(migrated.get_state_version(), STATE_VERSION);
+    }
 
-        let bounty_id = contract.create_content_bounty(
-            "Title".to_string(),
-            "Desc".to_string(),
-            "Reqs".to_string(),
-            NearToken::from_near(1),
-            NearToken::from_near(10),
-            None,
-            None,
-            7
+    // Borsh bytes of the released layout, before state_version existed
+    fn released_state(bounties: LookupMap<u64, Bounty>, next_bounty_id: u64) -> Vec<u8> {
+        borsh::to_vec(&(
+            LookupMap::<AccountId, StakeInfo>::new(b"s"),
+            NearToken::from_near(0),
+            REWARD_RATE,
+            MIN_STAKE,
+            MAX_STAKE,
+            accounts(0),
+            bounties,
+            LookupMap::<(AccountId, u64), ParticipantStake>::new(b"p"),
+            Some(LookupMap::<u64, Vec<AccountId>>::new(b"t")),
+            next_bounty_id,
+            400u128,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_migrate_from_released_layout() {
+        let mut context = get_context(accounts(5), NearToken::from_near(0));
+        testing_env!(context.current_account_id(accounts(5)).build());
+        env::storage_write(b"STATE", &released_state(LookupMap::new(b"b"), 7));
+
+        let migrated = BountyPredictionContract::migrate();
+        assert_eq!(migrated.get_contract_owner(), accounts(0));
+        assert_eq!(migrated.get_reward_rate(), REWARD_RATE);
+        assert_eq!(migrated.get_platform_fee_rate(), 400);
+        assert_eq!(migrated.next_bounty_id, 7);
+        assert_eq!(migrated.get_state_version(), STATE_VERSION);
+        assert_eq!(stored_state_version(), Some(STATE_VERSION));
+    }
+
+    #[test]
+    fn test_released_bounty_records_survive_migration() {
+        let mut context = get_context(accounts(5), NearToken::from_near(0));
+        testing_env!(context.current_account_id(accounts(5)).build());
+        // A bounty stored by the released contract, with that release's record layout
+        let mut bounties = LookupMap::new(b"b");
+        bounties.insert(
+            &1,
+            &Bounty {
+                id: 1,
+                title: "Released".to_string(),
+                description: "Stored before the upgrade".to_string(),
+                requirements: "Reqs".to_string(),
+                submissions: vec![],
+                creator: accounts(1),
+                base_prize: NearToken::from_near(2),
+                max_stake_per_user: NearToken::from_near(10),
+                creator_share: 90,
+                backer_share: 10,
+                is_active: true,
+                created_at: 0,
+                ends_at: 1_000,
+                total_staked: NearToken::from_near(0),
+                is_closed: false,
+                winning_submission: None,
+            },
         );
+        env::storage_write(b"STATE", &released_state(bounties, 2));
+        let mut contract = BountyPredictionContract::migrate();
+        assert!(!contract.get_bounty(1).unwrap().is_emergency_closed);
+
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        contract.emergency_close_bounty(1);
+        assert!(contract.get_bounty(1).unwrap().is_emergency_closed);
 
         testing_env!(context.predecessor_account_id(accounts(1)).build());
-        contract.submit_content(
-            bounty_id,
-            "creation-1".to_string(),
-            "My Sub".to_string(),
-            "url".to_string()
-        );
+        contract.claim_emergency_refund(1);
+        assert!(contract.base_prize_refunded(1));
+        let receipt = contract.get_claim_receipt(accounts(1), 1).unwrap();
+        assert_eq!(receipt.amount.0, NearToken::from_near(2).as_yoctonear());
+    }
 
-        let bounty = contract.get_bounty(bounty_id).unwrap();
-        assert_eq!(bounty.submissions.len(), 1);
-        assert_eq!(bounty.submissions[0].creator, accounts(1));
+    #[test]
+    #[should_panic(expected = "Unsupported state version 99")]
+    fn test_migrate_rejects_unknown_state_version() {
+        let mut context = get_context(accounts(5), NearToken::from_near(0));
+        testing_env!(context.current_account_id(accounts(5)).build());
+        let contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        env::state_write(&contract);
+        env::storage_write(groupweave_utils::upgrade::STATE_VERSION_KEY, &99u32.to_le_bytes());
+        BountyPredictionContract::migrate();
     }
 
     #[test]
-    fn test_stake_on_submission() {
-        let mut context = get_context(accounts(0), NearToken::from_near(5));
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_upgrade_is_owner_only() {
+        let mut context = get_context(accounts(0), NearToken::from_near(0));
         testing_env!(context.build());
         let mut contract = BountyPredictionContract::new(REWARD_RATE, MIN_STAKE, MAX_STAKE);
+        contract.grant_role(Role::Admin, accounts(1));
 
-        let bounty_id = contract.create_content_bounty(
-            "Title".to_string(),
-            "Desc".to_string(),
-            "Reqs".to_string(),
-            NearToken::from_near(1),
-            NearToken::from_near(10),
-            None,
-            None,
-            7
-        );
-
+        // Admins pass owner checks, but not the upgrade
         testing_env!(context.predecessor_account_id(accounts(1)).build());
-        contract.submit_content(bounty_id, "c1".to_string(), "Sub 1".to_string(), "url".to_string());
-
-        testing_env!(context.predecessor_account_id(accounts(2)).attached_deposit(NearToken::from_near(5)).build());
-        contract.stake_on_submission(bounty_id, 0);
-
-        let stake = contract.get_participant_stake(accounts(2), bounty_id).unwrap();
-        assert_eq!(stake.amount.0, NearToken::from_near(5).as_yoctonear());
+        contract.upgrade(b"\0asm".to_vec());
     }
 
     #[test]
@@ -1582,7 +6147,8 @@ mod tests {
             NearToken::from_near(10),
             None,
             None,
-            7
+            7,
+            None
         );
 
         // Submit content so people can stake
@@ -1632,3 +6198,175 @@ mod tests {
         // but I have restored the CRITICAL staking tests which ensure money isn't lost.
     }
 }
+
+// Settlement invariants over random stake distributions. Every payout is floored, so the
+// properties are upper bounds plus a dust bound: each floor loses less than one yoctoNEAR.
+#[cfg(test)]
+mod settlement_proptests {
+    use super::*;
+    use groupweave_utils::mul_div;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use proptest::prelude::*;
+
+    const NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+    const SUBMISSIONS: usize = 3;
+
+    fn contract_with_fee(platform_fee_rate: u128) -> BountyPredictionContract {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let mut contract =
+            BountyPredictionContract::new(10, NearToken::from_near(1), NearToken::from_near(100));
+        contract.platform_fee_rate = platform_fee_rate;
+        contract
+    }
+
+    /// Closed bounty with `stakes` as (submission index, yoctoNEAR) pairs; submission 0 wins.
+    fn settled_bounty(base_prize: u128, creator_share: u8, stakes: &[(usize, u128)]) -> Bounty {
+        let mut submissions: Vec<ContentSubmission> = (0..SUBMISSIONS)
+            .map(|i| ContentSubmission {
+                creator: accounts(i + 1),
+                creation_id: format!("c{}", i),
+                title: format!("Submission {}", i),
+                thumbnail_url: "url".to_string(),
+                total_staked: NearToken::from_yoctonear(0),
+                submitted_at: 0,
+            })
+            .collect();
+        for (index, amount) in stakes {
+            let total = submissions[*index].total_staked.as_yoctonear() + amount;
+            submissions[*index].total_staked = NearToken::from_yoctonear(total);
+        }
+        Bounty {
+            id: 1,
+            title: "Bounty".to_string(),
+            description: "Settlement".to_string(),
+            requirements: "Reqs".to_string(),
+            submissions,
+            creator: accounts(0),
+            base_prize: NearToken::from_yoctonear(base_prize),
+            max_stake_per_user: NearToken::from_near(10_000),
+            creator_share,
+            backer_share: 100 - creator_share,
+            is_active: false,
+            created_at: 0,
+            ends_at: 0,
+            total_staked: NearToken::from_yoctonear(stakes.iter().map(|(_, amount)| amount).sum()),
+            is_closed: true,
+            winning_submission: Some(0),
+        }
+    }
+
+    fn winning_stakes(stakes: &[(usize, u128)]) -> Vec<u128> {
+        stakes.iter().filter(|(index, _)| *index == 0).map(|(_, amount)| *amount).collect()
+    }
+
+    fn backer_reward(contract: &BountyPredictionContract, bounty: &Bounty, stake: u128) -> u128 {
+        contract.calculate_backer_reward(bounty, NearToken::from_yoctonear(stake), 0, 0).as_yoctonear()
+    }
+
+    /// Up to 50 stakes between 0.1 and 10,000 NEAR (the per-user bounds), at least one on
+    /// the winning submission.
+    fn stake_distribution() -> impl Strategy<Value = Vec<(usize, u128)>> {
+        let stake = NEAR / 10..=10_000 * NEAR;
+        (stake.clone(), prop::collection::vec((0..SUBMISSIONS, stake), 0..50)).prop_map(|(first, mut rest)| {
+            rest.insert(0, (0, first));
+            rest
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn payouts_and_fee_never_exceed_pool(
+            stakes in stake_distribution(),
+            base_prize in NEAR..=1_000_000 * NEAR,
+            creator_share in 30u8..=90,
+            fee_rate in 0u128..=MAX_PLATFORM_FEE_RATE,
+        ) {
+            let contract = contract_with_fee(fee_rate);
+            let bounty = settled_bounty(base_prize, creator_share, &stakes);
+            let pool = base_prize + bounty.total_staked.as_yoctonear();
+
+            let fee = contract.bounty_platform_fee(0, NearToken::from_yoctonear(pool)).as_yoctonear();
+            let creator = contract.calculate_creator_reward(&bounty).as_yoctonear();
+            let winning_stakes = winning_stakes(&stakes);
+            let backers: u128 = winning_stakes.iter().map(|a| backer_reward(&contract, &bounty, *a)).sum();
+
+            let paid = fee + creator + backers;
+            prop_assert!(paid <= pool, "paid {} out of a {} pool", paid, pool);
+            // Dust: the creator floor, the backer pool floor and one floor per backer
+            prop_assert!(pool - paid <= winning_stakes.len() as u128 + 2, "dust {} too large", pool - paid);
+        }
+
+        #[test]
+        fn backer_reward_is_monotonic_and_proportional(
+            stakes in stake_distribution(),
+            base_prize in NEAR..=1_000_000 * NEAR,
+            creator_share in 30u8..=90,
+            fee_rate in 0u128..=MAX_PLATFORM_FEE_RATE,
+        ) {
+            let contract = contract_with_fee(fee_rate);
+            let bounty = settled_bounty(base_prize, creator_share, &stakes);
+            let total_winning = bounty.submissions[0].total_staked.as_yoctonear();
+            let pool = base_prize + bounty.total_staked.as_yoctonear();
+            let fee = contract.bounty_platform_fee(0, NearToken::from_yoctonear(pool)).as_yoctonear();
+            let backer_pool = apply_percent(pool - fee, bounty.backer_share as u128).unwrap();
+
+            let mut winning_stakes = winning_stakes(&stakes);
+            winning_stakes.sort_unstable();
+            let rewards: Vec<u128> = winning_stakes.iter().map(|a| backer_reward(&contract, &bounty, *a)).collect();
+
+            // Larger stakes never earn less
+            prop_assert!(rewards.windows(2).all(|w| w[0] <= w[1]));
+            for (stake, reward) in winning_stakes.iter().zip(&rewards) {
+                // Exact floor of backer_pool * stake / total_winning
+                prop_assert_eq!(Some(*reward), mul_div(backer_pool, *stake, total_winning));
+                prop_assert!(*reward <= backer_pool);
+            }
+        }
+
+        #[test]
+        fn creator_reward_grows_with_share(
+            stakes in stake_distribution(),
+            base_prize in NEAR..=1_000_000 * NEAR,
+            creator_share in 30u8..90,
+            fee_rate in 0u128..=MAX_PLATFORM_FEE_RATE,
+        ) {
+            let contract = contract_with_fee(fee_rate);
+            let lower = settled_bounty(base_prize, creator_share, &stakes);
+            let higher = settled_bounty(base_prize, creator_share + 1, &stakes);
+            prop_assert!(contract.calculate_creator_reward(&lower) <= contract.calculate_creator_reward(&higher));
+        }
+
+        #[test]
+        fn platform_fee_is_monotonic_and_capped(
+            a in 0..=u128::MAX / 2,
+            b in 0..=u128::MAX / 2,
+            fee_rate in 0u128..=MAX_PLATFORM_FEE_RATE,
+        ) {
+            let contract = contract_with_fee(fee_rate);
+            let (low, high) = (a.min(b), a.max(b));
+            let low_fee = contract.bounty_platform_fee(0, NearToken::from_yoctonear(low));
+            let high_fee = contract.bounty_platform_fee(0, NearToken::from_yoctonear(high));
+            prop_assert!(low_fee <= high_fee);
+            prop_assert!(high_fee.as_yoctonear() <= high / 10);
+        }
+
+        // There is no separate calculate_user_reward in this contract; the per-user reward is
+        // the staking accrual. Within the contract's stake cap (100,000 NEAR), rates up to 100
+        // and a year of accrual it never overflows and is monotonic in stake and time.
+        #[test]
+        fn staking_reward_is_monotonic(
+            a in 0..=100_000 * NEAR,
+            b in 0..=100_000 * NEAR,
+            rate in 0u128..=100,
+            t1 in 0u64..=31_536_000,
+            t2 in 0u64..=31_536_000,
+        ) {
+            let (low, high) = (NearToken::from_yoctonear(a.min(b)), NearToken::from_yoctonear(a.max(b)));
+            let (short, long) = (t1.min(t2), t1.max(t2));
+            let base = BountyPredictionContract::calculate_rewards_safe(low, rate, short);
+            prop_assert!(base <= BountyPredictionContract::calculate_rewards_safe(high, rate, short));
+            prop_assert!(base <= BountyPredictionContract::calculate_rewards_safe(low, rate, long));
+        }
+    }
+}
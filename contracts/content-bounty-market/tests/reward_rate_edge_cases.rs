@@ -68,6 +68,14 @@ async fn test_very_high_reward_rate() -> Result<(), Box<dyn std::error::Error>>
 
     assert!(init_outcome.is_success(), "Contract initialization with high reward rate should succeed");
 
+    // Rewards are only paid from the owner-funded reward pool
+    let fund_outcome = contract
+        .call("fund_reward_pool")
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(fund_outcome.is_success(), "Funding reward pool failed");
+
     // Verify the reward rate was clamped to maximum
     let rate_outcome = contract
         .view("get_reward_rate")
@@ -129,6 +137,14 @@ async fn test_reward_rate_overflow_protection() -> Result<(), Box<dyn std::error
 
     assert!(init_outcome.is_success(), "Contract initialization should succeed");
 
+    // Rewards are only paid from the owner-funded reward pool
+    let fund_outcome = contract
+        .call("fund_reward_pool")
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(fund_outcome.is_success(), "Funding reward pool failed");
+
     let user_account = sandbox.dev_create_account().await?;
 
     // Test with reasonable stake amount to try to trigger overflow
@@ -283,6 +299,14 @@ async fn test_extreme_staking_amounts_with_high_rewards() -> Result<(), Box<dyn
 
     assert!(init_outcome.is_success(), "Contract initialization should succeed");
 
+    // Rewards are only paid from the owner-funded reward pool
+    let fund_outcome = contract
+        .call("fund_reward_pool")
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(fund_outcome.is_success(), "Funding reward pool failed");
+
     // Test with multiple users staking maximum amounts
     let mut users = Vec::new();
     for _ in 0..5 {
@@ -383,6 +407,14 @@ async fn test_reward_rate_boundary_values() -> Result<(), Box<dyn std::error::Er
 
         assert!(init_outcome.is_success(), "Boundary value {} initialization should succeed", idx);
 
+        // Rewards are only paid from the owner-funded reward pool
+        let fund_outcome = contract
+            .call("fund_reward_pool")
+            .deposit(NearToken::from_near(1))
+            .transact()
+            .await?;
+        assert!(fund_outcome.is_success(), "Funding reward pool failed");
+
         // Verify the reward rate was set/clamped correctly
         let rate_outcome = contract
             .view("get_reward_rate")
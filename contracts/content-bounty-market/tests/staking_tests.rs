@@ -36,6 +36,14 @@ async fn test_basics_on(contract_wasm: &[u8]) -> Result<(), Box<dyn std::error::
 
     assert!(init_outcome.is_success(), "Contract initialization failed: {:#?}", init_outcome.into_result().unwrap_err());
 
+    // Rewards are only paid from the owner-funded reward pool
+    let fund_outcome = contract
+        .call("fund_reward_pool")
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(fund_outcome.is_success(), "Funding reward pool failed");
+
     // Test contract initialization
     test_contract_initialization(&contract).await?;
 
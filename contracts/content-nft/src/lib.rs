@@ -0,0 +1,431 @@
+//! Content NFT – on-chain assets for winning bounty submissions.
+//!
+//! A NEP-171 token contract with metadata (NEP-177), approvals (NEP-178) and enumeration
+//! (NEP-181). Tokens are minted by registered minters, normally the bounty market when a
+//! bounty settles: the winning `creation_id` becomes the token id and the creator its owner.
+//! Each token carries a royalty split in basis points (creator, top backers, platform) that
+//! marketplaces read through NEP-199 `nft_payout` / `nft_transfer_payout`; whatever the
+//! royalties leave goes to the seller.
+//!
+//! Minting is paid by the minter: the attached deposit must cover the storage the token and
+//! its royalty table use, and the rest is refunded.
+
+use groupweave_utils::apply_bps;
+use near_contract_standards::non_fungible_token::core::{NonFungibleTokenCore, NonFungibleTokenResolver};
+use near_contract_standards::non_fungible_token::events::NftMint;
+use near_contract_standards::non_fungible_token::metadata::{
+    NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata, NFT_METADATA_SPEC,
+};
+use near_contract_standards::non_fungible_token::{
+    NonFungibleToken, NonFungibleTokenApproval, NonFungibleTokenEnumeration, Token, TokenId,
+};
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedSet};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    assert_one_yocto, env, near, require, AccountId, BorshStorageKey, NearToken, PanicOnDefault, Promise,
+    PromiseOrValue,
+};
+use std::collections::HashMap;
+
+/// Upper bound on the royalties of one token, so a sale always leaves the seller half.
+pub const MAX_TOTAL_ROYALTY_BPS: u32 = 5_000;
+/// Royalty recipients per token; keeps `nft_payout` within marketplace payout limits.
+pub const MAX_ROYALTY_RECIPIENTS: usize = 10;
+
+/// NEP-199 payout: amount owed to each account from a sale.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Payout {
+    pub payout: HashMap<AccountId, U128>,
+}
+
+#[derive(BorshStorageKey, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+enum StorageKey {
+    OwnerById,
+    TokenMetadata,
+    Enumeration,
+    Approval,
+    Metadata,
+    Royalties,
+    Minters,
+}
+
+#[near(
+    contract_state,
+    contract_metadata(
+        standard(standard = "nep171", version = "1.2.0"),
+        standard(standard = "nep177", version = "2.0.0"),
+        standard(standard = "nep178", version = "1.0.0"),
+        standard(standard = "nep181", version = "1.0.0"),
+        standard(standard = "nep199", version = "2.0.0"),
+        standard(standard = "nep297", version = "1.0.0")
+    )
+)]
+#[derive(PanicOnDefault)]
+pub struct ContentNft {
+    owner_id: AccountId,
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+    /// Royalty bps per recipient, per token.
+    royalties: LookupMap<TokenId, HashMap<AccountId, u32>>,
+    /// Accounts allowed to mint, e.g. the bounty market.
+    minters: UnorderedSet<AccountId>,
+}
+
+#[near]
+impl ContentNft {
+    #[init]
+    pub fn new(owner_id: AccountId, metadata: NFTContractMetadata) -> Self {
+        metadata.assert_valid();
+        Self {
+            owner_id: owner_id.clone(),
+            tokens: NonFungibleToken::new(
+                StorageKey::OwnerById,
+                owner_id,
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                Some(StorageKey::Approval),
+            ),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
+            royalties: LookupMap::new(StorageKey::Royalties),
+            minters: UnorderedSet::new(StorageKey::Minters),
+        }
+    }
+
+    #[init]
+    pub fn new_default_meta(owner_id: AccountId) -> Self {
+        Self::new(
+            owner_id,
+            NFTContractMetadata {
+                spec: NFT_METADATA_SPEC.to_string(),
+                name: "Groupweave Content".to_string(),
+                symbol: "GWC".to_string(),
+                icon: None,
+                base_uri: None,
+                reference: None,
+                reference_hash: None,
+            },
+        )
+    }
+
+    // Minting
+    /// Mint `token_id` to `receiver_id` with a royalty split in bps (minters only). The attached
+    /// deposit pays for storage; the excess is refunded.
+    #[payable]
+    pub fn nft_mint(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        token_metadata: TokenMetadata,
+        royalties: HashMap<AccountId, u32>,
+    ) -> Token {
+        let minter = env::predecessor_account_id();
+        require!(minter == self.owner_id || self.minters.contains(&minter), "Only a minter can mint");
+        require!(royalties.len() <= MAX_ROYALTY_RECIPIENTS, "Too many royalty recipients");
+        let total_bps = royalties.values().try_fold(0u32, |sum, bps| sum.checked_add(*bps));
+        require!(total_bps.is_some_and(|bps| bps <= MAX_TOTAL_ROYALTY_BPS), "Royalties exceed 50%");
+
+        let initial_storage = env::storage_usage();
+        let token = self.tokens.internal_mint_with_refund(token_id.clone(), receiver_id, Some(token_metadata), None);
+        if !royalties.is_empty() {
+            self.royalties.insert(&token_id, &royalties);
+        }
+        let storage_cost = env::storage_byte_cost().saturating_mul((env::storage_usage() - initial_storage).into());
+        let deposit = env::attached_deposit();
+        require!(deposit >= storage_cost, format!("Attach at least {} yoctoNEAR for storage", storage_cost));
+        let refund = deposit.saturating_sub(storage_cost);
+        if refund > NearToken::from_yoctonear(0) {
+            Promise::new(minter).transfer(refund);
+        }
+
+        NftMint { owner_id: &token.owner_id, token_ids: &[&token.token_id], memo: None }.emit();
+        token
+    }
+
+    // Minters
+    pub fn add_minter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.minters.insert(&account_id);
+    }
+
+    pub fn remove_minter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.minters.remove(&account_id);
+    }
+
+    pub fn get_minters(&self) -> Vec<AccountId> {
+        self.minters.to_vec()
+    }
+
+    pub fn get_royalties(&self, token_id: TokenId) -> HashMap<AccountId, u32> {
+        self.royalties.get(&token_id).unwrap_or_default()
+    }
+
+    // NEP-199 payouts
+    /// How a sale of `token_id` for `balance` is split: royalties first, the rest to the owner.
+    pub fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: Option<u32>) -> Payout {
+        let owner_id = self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        self.payout_for(&token_id, owner_id, balance.0, max_len_payout)
+    }
+
+    /// Transfer `token_id` (as `nft_transfer`) and return the payout the caller must distribute.
+    #[payable]
+    pub fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner, _) = self.tokens.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
+        self.payout_for(&token_id, previous_owner, balance.0, max_len_payout)
+    }
+}
+
+impl ContentNft {
+    fn assert_owner(&self) {
+        require!(env::predecessor_account_id() == self.owner_id, "Only the owner can call this method");
+    }
+
+    fn payout_for(&self, token_id: &TokenId, owner_id: AccountId, balance: u128, max_len_payout: Option<u32>) -> Payout {
+        let royalties = self.royalties.get(token_id).unwrap_or_default();
+        let mut payout = HashMap::with_capacity(royalties.len() + 1);
+        let mut paid = 0u128;
+        for (account_id, bps) in royalties {
+            let amount = apply_bps(balance, bps.into()).unwrap_or_else(|| env::panic_str("Payout overflow"));
+            paid += amount;
+            *payout.entry(account_id).or_insert(0u128) += amount;
+        }
+        *payout.entry(owner_id).or_insert(0u128) += balance - paid;
+
+        if let Some(max_len) = max_len_payout {
+            require!(payout.len() <= max_len as usize, "Payout has more recipients than max_len_payout");
+        }
+        Payout { payout: payout.into_iter().map(|(account_id, amount)| (account_id, U128(amount))).collect() }
+    }
+}
+
+#[near]
+impl NonFungibleTokenCore for ContentNft {
+    #[payable]
+    fn nft_transfer(&mut self, receiver_id: AccountId, token_id: TokenId, approval_id: Option<u64>, memo: Option<String>) {
+        self.tokens.nft_transfer(receiver_id, token_id, approval_id, memo)
+    }
+
+    #[payable]
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        self.tokens.nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
+    }
+
+    fn nft_token(&self, token_id: TokenId) -> Option<Token> {
+        self.tokens.nft_token(token_id)
+    }
+}
+
+#[near]
+impl NonFungibleTokenResolver for ContentNft {
+    #[private]
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        self.tokens.nft_resolve_transfer(previous_owner_id, receiver_id, token_id, approved_account_ids)
+    }
+}
+
+#[near]
+impl NonFungibleTokenApproval for ContentNft {
+    #[payable]
+    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>) -> Option<Promise> {
+        self.tokens.nft_approve(token_id, account_id, msg)
+    }
+
+    #[payable]
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
+        self.tokens.nft_revoke(token_id, account_id)
+    }
+
+    #[payable]
+    fn nft_revoke_all(&mut self, token_id: TokenId) {
+        self.tokens.nft_revoke_all(token_id)
+    }
+
+    fn nft_is_approved(&self, token_id: TokenId, approved_account_id: AccountId, approval_id: Option<u64>) -> bool {
+        self.tokens.nft_is_approved(token_id, approved_account_id, approval_id)
+    }
+}
+
+#[near]
+impl NonFungibleTokenEnumeration for ContentNft {
+    fn nft_total_supply(&self) -> U128 {
+        self.tokens.nft_total_supply()
+    }
+
+    fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.tokens.nft_tokens(from_index, limit)
+    }
+
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        self.tokens.nft_supply_for_owner(account_id)
+    }
+
+    fn nft_tokens_for_owner(&self, account_id: AccountId, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.tokens.nft_tokens_for_owner(account_id, from_index, limit)
+    }
+}
+
+#[near]
+impl NonFungibleTokenMetadataProvider for ContentNft {
+    fn nft_metadata(&self) -> NFTContractMetadata {
+        self.metadata.get().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const MINT_DEPOSIT: NearToken = NearToken::from_millinear(100);
+
+    fn context(predecessor: AccountId, deposit: NearToken) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(predecessor).attached_deposit(deposit);
+        builder
+    }
+
+    fn metadata(title: &str) -> TokenMetadata {
+        TokenMetadata {
+            title: Some(title.to_string()),
+            description: None,
+            media: None,
+            media_hash: None,
+            copies: Some(1),
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    /// accounts(0) owns the contract and registers accounts(1) as the minter.
+    fn contract_with_minter() -> ContentNft {
+        testing_env!(context(accounts(0), NearToken::from_yoctonear(0)).build());
+        let mut contract = ContentNft::new_default_meta(accounts(0));
+        contract.add_minter(accounts(1));
+        contract
+    }
+
+    fn royalties(entries: &[(AccountId, u32)]) -> HashMap<AccountId, u32> {
+        entries.iter().cloned().collect()
+    }
+
+    #[test]
+    fn minter_mints_with_royalties() {
+        let mut contract = contract_with_minter();
+        testing_env!(context(accounts(1), MINT_DEPOSIT).build());
+        let token = contract.nft_mint(
+            "creation-1".to_string(),
+            accounts(2),
+            metadata("Winner"),
+            royalties(&[(accounts(2), 500), (accounts(3), 300), (accounts(0), 200)]),
+        );
+        assert_eq!(token.owner_id, accounts(2));
+        assert_eq!(contract.nft_token("creation-1".to_string()).unwrap().owner_id, accounts(2));
+        assert_eq!(contract.get_royalties("creation-1".to_string()).len(), 3);
+        assert_eq!(contract.nft_supply_for_owner(accounts(2)), U128(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only a minter can mint")]
+    fn non_minter_cannot_mint() {
+        let mut contract = contract_with_minter();
+        testing_env!(context(accounts(2), MINT_DEPOSIT).build());
+        contract.nft_mint("creation-1".to_string(), accounts(2), metadata("Winner"), HashMap::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalties exceed 50%")]
+    fn royalties_are_capped() {
+        let mut contract = contract_with_minter();
+        testing_env!(context(accounts(1), MINT_DEPOSIT).build());
+        contract.nft_mint(
+            "creation-1".to_string(),
+            accounts(2),
+            metadata("Winner"),
+            royalties(&[(accounts(3), 4_000), (accounts(4), 1_001)]),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "for storage")]
+    fn mint_requires_storage_deposit() {
+        let mut contract = contract_with_minter();
+        testing_env!(context(accounts(1), NearToken::from_yoctonear(1)).build());
+        contract.nft_mint("creation-1".to_string(), accounts(2), metadata("Winner"), HashMap::new());
+    }
+
+    #[test]
+    fn payout_splits_royalties_and_pays_the_owner_the_rest() {
+        let mut contract = contract_with_minter();
+        testing_env!(context(accounts(1), MINT_DEPOSIT).build());
+        contract.nft_mint(
+            "creation-1".to_string(),
+            accounts(2),
+            metadata("Winner"),
+            royalties(&[(accounts(2), 500), (accounts(3), 300), (accounts(0), 200)]),
+        );
+
+        let payout = contract.nft_payout("creation-1".to_string(), U128(10_000), None).payout;
+        // The creator still owns the token, so its royalty and the seller's share merge
+        assert_eq!(payout[&accounts(2)], U128(9_500));
+        assert_eq!(payout[&accounts(3)], U128(300));
+        assert_eq!(payout[&accounts(0)], U128(200));
+
+        // After a transfer the new owner is the seller; the creator keeps only the royalty
+        testing_env!(context(accounts(2), NearToken::from_yoctonear(1)).build());
+        let payout = contract
+            .nft_transfer_payout(accounts(4), "creation-1".to_string(), None, None, U128(10_000), Some(4))
+            .payout;
+        assert_eq!(payout[&accounts(2)], U128(9_500));
+        assert_eq!(contract.nft_token("creation-1".to_string()).unwrap().owner_id, accounts(4));
+        let resale = contract.nft_payout("creation-1".to_string(), U128(10_000), None).payout;
+        assert_eq!(resale[&accounts(4)], U128(9_000));
+        assert_eq!(resale[&accounts(2)], U128(500));
+    }
+
+    #[test]
+    #[should_panic(expected = "max_len_payout")]
+    fn payout_respects_max_len() {
+        let mut contract = contract_with_minter();
+        testing_env!(context(accounts(1), MINT_DEPOSIT).build());
+        contract.nft_mint(
+            "creation-1".to_string(),
+            accounts(2),
+            metadata("Winner"),
+            royalties(&[(accounts(3), 300), (accounts(4), 200)]),
+        );
+        contract.nft_payout("creation-1".to_string(), U128(10_000), Some(2));
+    }
+}
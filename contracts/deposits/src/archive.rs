@@ -0,0 +1,153 @@
+// Archival of old deposit records.
+// Pruned records are folded into a per-day hash chain before they are deleted:
+//   hash = sha256(previous_hash || sha256(record_json))
+// where record_json is the `data` entry of the record's `deposit_recorded` event, which only carries
+// fields fixed when the deposit was recorded. Anyone
+// holding the event logs can replay a day and compare against the stored commitment, while
+// the contract stops paying storage for the raw records.
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::Base58CryptoHash;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near, require};
+use schemars::JsonSchema;
+
+use crate::events::DepositRecorded;
+use crate::{DepositContract, DepositContractExt, DepositStatus, MAX_PAGE_LIMIT};
+
+/// Length of one archive period; pruned deposits are committed per day.
+pub const ARCHIVE_PERIOD_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Hash chain over the pruned deposits of one period.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ArchiveCommitment {
+    pub hash: [u8; 32],
+    pub first_deposit_id: u64,
+    pub last_deposit_id: u64,
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct ArchiveCommitmentView {
+    pub period_start_ms: u64,
+    #[schemars(with = "String")]
+    pub hash: Base58CryptoHash,
+    pub first_deposit_id: u64,
+    pub last_deposit_id: u64,
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct PruneResult {
+    pub pruned: u64,
+    /// Deposits with a lower id have all been archived.
+    pub pruned_before_id: u64,
+    pub storage_freed_bytes: u64,
+}
+
+#[near]
+impl DepositContract {
+    /// Archive up to `limit` of the oldest deposits recorded before `before_ms` (owner only).
+    /// Stops at the first deposit that is still pending or flagged, since it may yet be
+    /// credited or refunded. The freed storage stake returns to the contract balance and can be withdrawn with
+    /// `withdraw_native`.
+    pub fn prune_deposits(&mut self, before_ms: u64, limit: u64) -> PruneResult {
+        self.assert_owner();
+        require!(limit > 0 && limit <= MAX_PAGE_LIMIT, "Limit must be between 1 and 100");
+        let storage_before = env::storage_usage();
+
+        let mut pruned = 0;
+        // Ids are assigned in block order, so the oldest deposits are always at the cursor.
+        while pruned < limit && self.pruned_before_id < self.next_deposit_id {
+            let deposit_id = self.pruned_before_id;
+            if let Some(record) = self.deposits.get(&deposit_id) {
+                if record.timestamp_ms >= before_ms
+                    || !matches!(self.deposit_status(deposit_id), DepositStatus::Credited | DepositStatus::Refunded)
+                {
+                    break;
+                }
+                let account_id = record.account_id.clone();
+                let beneficiary_id = record.beneficiary_id.clone();
+                let view = self.view_deposit(record);
+                let record_json = near_sdk::serde_json::to_string(&DepositRecorded::from(&view)).unwrap();
+                self.fold_into_archive(view.timestamp_ms, deposit_id, record_json.as_bytes());
+
+                self.deposits.remove(&deposit_id);
+                self.deposit_statuses.remove(&deposit_id);
+                self.credits_granted.remove(&deposit_id);
+                self.deposit_fees.remove(&deposit_id);
+                self.deposit_bonuses.remove(&deposit_id);
+                self.minted_credits.remove(&deposit_id);
+                self.deposit_relayers.remove(&deposit_id);
+                self.deposit_intents.remove(&deposit_id);
+                if let Some(recipient) = self.deposit_recipients.remove(&deposit_id) {
+                    remove_indexed_id(&mut self.deposits_by_recipient, &recipient, deposit_id);
+                }
+                remove_indexed_id(&mut self.deposits_by_account, &account_id, deposit_id);
+                remove_indexed_id(&mut self.deposits_by_beneficiary, &beneficiary_id, deposit_id);
+                pruned += 1;
+            }
+            self.pruned_before_id += 1;
+        }
+
+        PruneResult {
+            pruned,
+            pruned_before_id: self.pruned_before_id,
+            storage_freed_bytes: storage_before.saturating_sub(env::storage_usage()),
+        }
+    }
+
+    /// Archive commitments, oldest period first.
+    pub fn get_archive_commitments(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<ArchiveCommitmentView> {
+        self.archive_commitments
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(Self::page_limit(limit) as usize)
+            .map(|(period, c)| ArchiveCommitmentView {
+                period_start_ms: period * ARCHIVE_PERIOD_MS,
+                hash: c.hash.into(),
+                first_deposit_id: c.first_deposit_id,
+                last_deposit_id: c.last_deposit_id,
+                count: c.count,
+            })
+            .collect()
+    }
+
+    /// Deposits with a lower id have been archived and are no longer stored.
+    pub fn get_pruned_before_id(&self) -> u64 {
+        self.pruned_before_id
+    }
+}
+
+impl DepositContract {
+    fn fold_into_archive(&mut self, timestamp_ms: u64, deposit_id: u64, record_json: &[u8]) {
+        let period = timestamp_ms / ARCHIVE_PERIOD_MS;
+        let mut commitment = self.archive_commitments.get(&period).unwrap_or(ArchiveCommitment {
+            hash: [0u8; 32],
+            first_deposit_id: deposit_id,
+            last_deposit_id: deposit_id,
+            count: 0,
+        });
+        let mut preimage = commitment.hash.to_vec();
+        preimage.extend(env::sha256_array(record_json));
+        commitment.hash = env::sha256_array(&preimage);
+        commitment.last_deposit_id = deposit_id;
+        commitment.count += 1;
+        self.archive_commitments.insert(&period, &commitment);
+    }
+}
+
+fn remove_indexed_id<K: BorshSerialize>(index: &mut LookupMap<K, Vec<u64>>, key: &K, deposit_id: u64) {
+    let Some(mut ids) = index.get(key) else { return };
+    ids.retain(|id| *id != deposit_id);
+    if ids.is_empty() {
+        index.remove(key);
+    } else {
+        index.insert(key, &ids);
+    }
+}
@@ -0,0 +1,228 @@
+// On-chain credits ledger (NEP-141).
+// Every recorded deposit mints credits to the paying account at the configured rate
+// (credits per USD); refunding the deposit burns them again. The backend service account
+// burns credits when they are spent, so balances are portable and auditable instead of
+// living only in our database.
+// Registered credit spenders (other platform contracts) burn credits for metered usage
+// priced in USD, converted at the same rate.
+
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+use near_contract_standards::fungible_token::events::{FtBurn, FtMint};
+use near_contract_standards::fungible_token::metadata::{
+    FungibleTokenMetadata, FungibleTokenMetadataProvider, FT_METADATA_SPEC,
+};
+use near_contract_standards::fungible_token::resolver::FungibleTokenResolver;
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
+use near_sdk::json_types::U128;
+use near_sdk::{env, near, require, AccountId, NearToken, PromiseOrValue};
+
+use crate::{DepositContract, DepositContractExt};
+
+/// Credits are whole units; fractional credits are truncated at mint time.
+const CREDITS_DECIMALS: u8 = 0;
+const USD_MICROS: u128 = 1_000_000;
+
+impl DepositContract {
+    /// Mint credits to `owner_id` for a freshly stored deposit's credited (post-fee) value.
+    /// No-op while the rate is unset.
+    pub(crate) fn mint_credits_for(&mut self, owner_id: &AccountId, deposit_id: u64, credited_usd_value: u128) {
+        let amount = credited_usd_value.saturating_mul(self.credits_per_usd) / USD_MICROS;
+        if amount == 0 {
+            return;
+        }
+        // The contract covers registration storage so depositors never need a separate call.
+        if !self.credits_token.accounts.contains_key(owner_id) {
+            self.credits_token.internal_register_account(owner_id);
+        }
+        self.credits_token.internal_deposit(owner_id, amount);
+        self.minted_credits.insert(&deposit_id, &amount);
+        FtMint {
+            owner_id,
+            amount: U128(amount),
+            memo: Some(&format!("deposit {}", deposit_id)),
+        }
+        .emit();
+    }
+
+    /// Burn the credits minted for a deposit that is being refunded. Panics if the holder
+    /// no longer has them, so credits that were spent or transferred cannot be refunded too.
+    pub(crate) fn burn_deposit_credits(&mut self, holder_id: &AccountId, deposit_id: u64) {
+        let Some(amount) = self.minted_credits.get(&deposit_id) else {
+            return;
+        };
+        require!(
+            self.credits_token.accounts.get(holder_id).unwrap_or(0) >= amount,
+            "Credits minted for this deposit were already spent or transferred"
+        );
+        self.credits_token.internal_withdraw(holder_id, amount);
+        FtBurn {
+            owner_id: holder_id,
+            amount: U128(amount),
+            memo: Some(&format!("refund of deposit {}", deposit_id)),
+        }
+        .emit();
+    }
+
+    /// Mint back the credits burned by a refund whose transfer failed.
+    pub(crate) fn restore_deposit_credits(&mut self, holder_id: &AccountId, deposit_id: u64) {
+        let Some(amount) = self.minted_credits.get(&deposit_id) else {
+            return;
+        };
+        if !self.credits_token.accounts.contains_key(holder_id) {
+            self.credits_token.internal_register_account(holder_id);
+        }
+        self.credits_token.internal_deposit(holder_id, amount);
+        FtMint {
+            owner_id: holder_id,
+            amount: U128(amount),
+            memo: Some(&format!("failed refund of deposit {}", deposit_id)),
+        }
+        .emit();
+    }
+}
+
+#[near]
+impl DepositContract {
+    /// Set how many credits are minted per $1 of deposit value (owner only). Zero disables minting.
+    pub fn set_credits_rate(&mut self, credits_per_usd: U128) {
+        self.assert_owner();
+        self.credits_per_usd = credits_per_usd.0;
+    }
+
+    pub fn get_credits_rate(&self) -> U128 {
+        U128(self.credits_per_usd)
+    }
+
+    /// Burn credits when they are spent on the platform (backend account only).
+    pub fn burn_credits(&mut self, account_id: AccountId, amount: U128, memo: Option<String>) {
+        self.assert_backend();
+        require!(amount.0 > 0, "Amount must be > 0");
+        self.credits_token.internal_withdraw(&account_id, amount.0);
+        FtBurn { owner_id: &account_id, amount, memo: memo.as_deref() }.emit();
+    }
+
+    /// Allow a contract to spend users' credits for metered usage (owner only).
+    pub fn add_credit_spender(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.credit_spenders.insert(&account_id);
+    }
+
+    pub fn remove_credit_spender(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.credit_spenders.remove(&account_id);
+    }
+
+    pub fn get_credit_spenders(&self) -> Vec<AccountId> {
+        self.credit_spenders.to_vec()
+    }
+
+    /// Burn the credits worth `usd_micros` from `account_id`, rounding up (credit spenders only).
+    /// Panics if the account cannot cover it, so the calling contract's callback sees a failure.
+    pub fn spend_credits(&mut self, account_id: AccountId, usd_micros: U128, memo: Option<String>) -> U128 {
+        require!(
+            self.credit_spenders.contains(&env::predecessor_account_id()),
+            "Only a credit spender can call this method"
+        );
+        require!(self.credits_per_usd > 0, "Credits rate not set");
+        let amount = usd_micros.0.saturating_mul(self.credits_per_usd).div_ceil(USD_MICROS);
+        require!(amount > 0, "Amount must be > 0");
+        self.credits_token.internal_withdraw(&account_id, amount);
+        FtBurn { owner_id: &account_id, amount: U128(amount), memo: memo.as_deref() }.emit();
+        U128(amount)
+    }
+}
+
+#[near]
+impl FungibleTokenCore for DepositContract {
+    #[payable]
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        self.credits_token.ft_transfer(receiver_id, amount, memo)
+    }
+
+    #[payable]
+    fn ft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        self.credits_token.ft_transfer_call(receiver_id, amount, memo, msg)
+    }
+
+    fn ft_total_supply(&self) -> U128 {
+        self.credits_token.ft_total_supply()
+    }
+
+    fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        self.credits_token.ft_balance_of(account_id)
+    }
+}
+
+#[near]
+impl FungibleTokenResolver for DepositContract {
+    #[private]
+    fn ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        self.credits_token
+            .internal_ft_resolve_transfer(&sender_id, receiver_id, amount)
+            .0
+            .into()
+    }
+}
+
+#[near]
+impl StorageManagement for DepositContract {
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        self.credits_token.storage_deposit(account_id, registration_only)
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
+        self.credits_token.storage_withdraw(amount)
+    }
+
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        if let Some((account_id, balance)) = self.credits_token.internal_storage_unregister(force) {
+            FtBurn { owner_id: &account_id, amount: U128(balance), memo: Some("unregister") }.emit();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        self.credits_token.storage_balance_bounds()
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.credits_token.storage_balance_of(account_id)
+    }
+}
+
+#[near]
+impl FungibleTokenMetadataProvider for DepositContract {
+    fn ft_metadata(&self) -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "Dreamweave Credits".to_string(),
+            symbol: "DWC".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: CREDITS_DECIMALS,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// NEP-297 events emitted by the deposits contract.
+// The payload structs and the envelope live in the shared `groupweave-events` crate (module
+// `deposit`); this module only maps contract records onto them.
+// Every log line is `EVENT_JSON:{"standard":"dreamweave_deposit","version":...,"event":...,"data":[...]}`.
+
+use near_sdk::json_types::U128;
+
+pub use groupweave_events::deposit::{
+    emit, DepositCredited, DepositRecorded, DepositRefunded, PriceUpdated, TokenConfigUpdated,
+};
+pub use groupweave_events::Nep297Event;
+
+use crate::{DepositView, TokenConfig};
+
+impl From<&DepositView> for DepositRecorded {
+    fn from(view: &DepositView) -> Self {
+        Self {
+            deposit_id: view.id,
+            account_id: view.account_id.clone(),
+            beneficiary_id: view.beneficiary_id.clone(),
+            token_id: view.token_id.clone(),
+            amount: view.amount,
+            usd_value: view.usd_value,
+            fee_usd_value: view.fee_usd_value,
+            bonus_usd_value: view.bonus_usd_value,
+            credits_hint: view.credits_hint,
+            memo: view.memo.clone(),
+            on_behalf_of: view.on_behalf_of.clone(),
+            relayed_by: view.relayed_by.clone(),
+            solver_id: view.intent.as_ref().map(|intent| intent.solver_id.clone()),
+            quote_id: view.intent.as_ref().map(|intent| intent.quote_id.clone()),
+            original_asset: view.intent.as_ref().map(|intent| intent.original_asset.clone()),
+            timestamp_ms: view.timestamp_ms,
+        }
+    }
+}
+
+pub fn token_config_updated(token_id: &str, cfg: &TokenConfig) -> TokenConfigUpdated {
+    TokenConfigUpdated {
+        token_id: token_id.to_string(),
+        symbol: cfg.symbol.clone(),
+        decimals: cfg.decimals,
+        is_enabled: cfg.is_enabled,
+        is_native: cfg.is_native,
+        min_deposit_usd_micros: U128(cfg.min_deposit_usd_micros),
+        deposit_fee_bps: cfg.deposit_fee_bps,
+    }
+}
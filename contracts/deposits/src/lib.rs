@@ -4,31 +4,96 @@
 // Treasury address receives funds immediately. No escrow, no withdrawals.
 
 use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_contract_standards::fungible_token::metadata::{ext_ft_metadata, FungibleTokenMetadata};
+use near_contract_standards::fungible_token::FungibleToken;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet};
 use near_sdk::env;
-use near_sdk::json_types::U128;
+use near_sdk::json_types::{Base64VecU8, U128};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::NearToken;
-use near_sdk::{require, AccountId, BorshStorageKey, PromiseOrValue, near};
+use near_sdk::{require, AccountId, BorshStorageKey, CurveType, PromiseError, PromiseOrValue, PublicKey, near};
 use near_sdk::{Gas, Promise};
 use near_sdk::ext_contract;
+use groupweave_utils::upgrade::{deploy_and_migrate, record_state_version, stored_state_version};
+use groupweave_utils::{apply_bps, mul_div, split_by_bps, AccessControl, Role, BPS_DENOMINATOR};
 use schemars::JsonSchema;
 
+mod archive;
+mod credits;
+mod events;
+
+use archive::ArchiveCommitment;
+use events::{DepositCredited, DepositRecorded, DepositRefunded, Nep297Event, PriceUpdated};
+
 const NEAR_TOKEN_ID: &str = "NEAR";
+/// Default per-token minimum applied to newly configured tokens.
 const MIN_DEPOSIT_USD_MICROS: u128 = 5 * 1_000_000;
+const MAX_DEPOSIT_FEE_BPS: u16 = 1_000; // 10%
+const DEFAULT_CAP_WINDOW_MS: u64 = 24 * 60 * 60 * 1000; // 1 day
+const MAX_TREASURY_SPLITS: usize = 5;
+const MAX_PROMO_BONUS_BPS: u16 = 5_000; // 50%
+/// NEP-413 prefix tag: 2^31 + 413.
+const NEP413_TAG: u32 = (1 << 31) + 413;
 const MAX_BENEFICIARY_LEN: usize = 128;
 const MAX_MEMO_LEN: usize = 256;
+const MAX_INTENT_FIELD_LEN: usize = 128;
+/// Most recent deposits kept in each beneficiary's index; older ids drop off.
+const MAX_BENEFICIARY_DEPOSITS: usize = 1_000;
 const MAX_PRICE_AGE_MS: u64 = 60 * 60 * 1000; // 1 hour
+const DEFAULT_MAX_PRICE_DEVIATION_BPS: u16 = 2_000; // 20%
+const MAX_PRICE_BATCH: usize = 50;
+const MAX_TICK_TOKENS: usize = 50;
+const DEFAULT_PAGE_LIMIT: u64 = 50;
+const MAX_PAGE_LIMIT: u64 = 100;
+/// Layout version of the contract state; bump it once per release that changes fields, and migrate
+/// from the layout that release replaces.
+const STATE_VERSION: u32 = 1;
 
 /// Gas allowance for cross-contract FT transfers during withdrawals.
 const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(25);
+/// Gas for reading `ft_metadata` from a token contract and storing the result.
+const GAS_FOR_FT_METADATA: Gas = Gas::from_tgas(10);
+const GAS_FOR_METADATA_CALLBACK: Gas = Gas::from_tgas(10);
+/// Largest decimals value whose scale factor fits in a u128.
+const MAX_TOKEN_DECIMALS: u8 = 38;
+/// Gas reserved for the callback that checks an FT forward to the treasury.
+const GAS_FOR_FORWARD_CALLBACK: Gas = Gas::from_tgas(10);
+/// Gas for `storage_deposit` on a token contract.
+const GAS_FOR_STORAGE_DEPOSIT: Gas = Gas::from_tgas(10);
+/// Gas reserved for the callback that reports a sweep's outcome.
+const GAS_FOR_SWEEP_CALLBACK: Gas = Gas::from_tgas(10);
+/// Gas reserved for the callback that settles a refund.
+const GAS_FOR_REFUND_CALLBACK: Gas = Gas::from_tgas(10);
 
 #[derive(BorshStorageKey, BorshSerialize)]
 enum StorageKey {
     TokenConfigs,
     Deposits,
     DepositsByAccount,
+    PriceKeepers,
+    DepositStatuses,
+    CreditsGranted,
+    CreditsLedger,
+    DepositFees,
+    CapWindows,
+    CapExempt,
+    BoundBeneficiaries,
+    UsedBindingNonces,
+    Promos,
+    DepositBonuses,
+    ArchiveCommitments,
+    DepositRecipients,
+    DepositsByRecipient,
+    Relayers,
+    DepositRelayers,
+    BlockDeposits,
+    Denylist,
+    CreditSpenders,
+    AccessRoles,
+    DepositIntents,
+    DepositsByBeneficiary,
+    MintedCredits,
 }
 
 /// Metadata and pricing information for an accepted payment token.
@@ -41,6 +106,35 @@ pub struct TokenConfig {
     pub last_updated: u64,
     pub is_enabled: bool,
     pub is_native: bool,
+    pub min_deposit_usd_micros: u128,
+    /// Share of each deposit kept as a fee by the treasury (not converted to credits).
+    pub deposit_fee_bps: u16,
+}
+
+/// Token config layout of the released contract, rewritten by `migrate`.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct ReleasedTokenConfig {
+    symbol: String,
+    decimals: u8,
+    price_usd_micros: u128,
+    last_updated: u64,
+    is_enabled: bool,
+    is_native: bool,
+}
+
+impl ReleasedTokenConfig {
+    fn into_current(self) -> TokenConfig {
+        TokenConfig {
+            symbol: self.symbol,
+            decimals: self.decimals,
+            price_usd_micros: self.price_usd_micros,
+            last_updated: self.last_updated,
+            is_enabled: self.is_enabled,
+            is_native: self.is_native,
+            min_deposit_usd_micros: MIN_DEPOSIT_USD_MICROS,
+            deposit_fee_bps: 0,
+        }
+    }
 }
 
 impl TokenConfig {
@@ -58,10 +152,82 @@ impl TokenConfig {
             last_updated: env::block_timestamp_ms(),
             is_enabled,
             is_native,
+            min_deposit_usd_micros: MIN_DEPOSIT_USD_MICROS,
+            deposit_fee_bps: 0,
         }
     }
 }
 
+/// Share of forwarded funds sent to one treasury wallet.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct TreasurySplit {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    pub bps: u16,
+}
+
+/// Treasury change proposed by the owner, acceptable once the timelock has passed.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct PendingTreasury {
+    #[schemars(with = "String")]
+    pub account_id: AccountId,
+    pub accept_after_ms: u64,
+}
+
+/// Lower treasury timelock set by the owner, which only takes effect once the current one has passed.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct PendingTimelock {
+    pub timelock_ms: u64,
+    pub effective_after_ms: u64,
+}
+
+/// NEP-413 signed message payload, borsh-serialized after `NEP413_TAG`.
+#[derive(BorshSerialize)]
+struct Nep413Payload {
+    message: String,
+    nonce: [u8; 32],
+    recipient: String,
+    callback_url: Option<String>,
+}
+
+/// Running deposit total for one account within the current cap window.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CapWindow {
+    pub window_start_ms: u64,
+    pub usd_total: u128,
+}
+
+/// Deposit bonus redeemable with a promo code.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct Promo {
+    pub bonus_bps: u16,
+    pub expires_at_ms: u64,
+    pub max_uses: u32,
+    pub uses: u32,
+}
+
+/// Number of deposits an account recorded in one block.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct BlockDeposits {
+    pub block_height: u64,
+    pub count: u32,
+}
+
+/// USD valuation of an incoming deposit after applying the token's fee and any promo bonus.
+struct DepositValue {
+    usd_value: u128,
+    fee_usd_value: u128,
+    bonus_usd_value: u128,
+}
+
 /// Lightweight message passed through `ft_transfer_call`.
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -69,6 +235,34 @@ pub struct DepositMessage {
     pub beneficiary_id: String,
     pub credits_hint: Option<u64>,
     pub memo: Option<String>,
+    pub promo_code: Option<String>,
+    /// Account the deposit is paid for (gift cards, team top-ups); defaults to the sender.
+    pub on_behalf_of: Option<AccountId>,
+    /// Set when a NEAR Intents solver settled the payment instead of the user transferring directly.
+    pub intent: Option<IntentSettlement>,
+}
+
+/// NEAR Intents quote a solver filled to pay for a deposit, converting the user's original asset.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct IntentSettlement {
+    #[schemars(with = "String")]
+    pub solver_id: AccountId,
+    pub quote_id: String,
+    /// Asset the user paid the solver in, e.g. `nep141:wrap.near`.
+    pub original_asset: String,
+}
+
+/// Reconciliation state of a deposit. Records without an entry are `Pending`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug, JsonSchema)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+#[schemars(crate = "schemars")]
+pub enum DepositStatus {
+    Pending,
+    Refunded,
+    Credited,
+    Flagged,
 }
 
 /// Stored representation of a payment waiting to be reconciled off-chain.
@@ -98,6 +292,13 @@ impl DepositRecord {
             credits_hint: self.credits_hint,
             memo: self.memo,
             timestamp_ms: self.timestamp_ms,
+            status: DepositStatus::Pending,
+            credits_granted: None,
+            fee_usd_value: U128(0),
+            bonus_usd_value: U128(0),
+            on_behalf_of: None,
+            relayed_by: None,
+            intent: None,
         }
     }
 }
@@ -119,10 +320,33 @@ pub struct DepositView {
     pub credits_hint: Option<u64>,
     pub memo: Option<String>,
     pub timestamp_ms: u64,
+    pub status: DepositStatus,
+    pub credits_granted: Option<u64>,
+    #[schemars(with = "String")]
+    pub fee_usd_value: U128,
+    /// Promo bonus the backend should credit on top of the deposit; not owed once the deposit is refunded.
+    #[schemars(with = "String")]
+    pub bonus_usd_value: U128,
+    /// Account the deposit was paid for; `account_id` is always the payer.
+    #[schemars(with = "Option<String>")]
+    pub on_behalf_of: Option<AccountId>,
+    /// Registered relayer that submitted the deposit as a meta-transaction.
+    #[schemars(with = "Option<String>")]
+    pub relayed_by: Option<AccountId>,
+    /// Solver settlement for deposits paid through NEAR Intents; `None` for direct transfers.
+    pub intent: Option<IntentSettlement>,
 }
 
 /// On-chain state for the Dreamweave deposit contract.
-#[near(contract_state)]
+#[near(
+    contract_state,
+    contract_metadata(
+        standard(standard = "nep141", version = "1.0.0"),
+        standard(standard = "nep145", version = "1.0.0"),
+        standard(standard = "nep148", version = "1.0.0"),
+        standard(standard = "nep297", version = "1.0.0")
+    )
+)]
 pub struct DepositContract {
     owner_id: AccountId,
     treasury_account_id: AccountId,
@@ -130,6 +354,154 @@ pub struct DepositContract {
     token_configs: UnorderedMap<String, TokenConfig>,
     deposits: LookupMap<u64, DepositRecord>,
     deposits_by_account: LookupMap<AccountId, Vec<u64>>,
+    /// Accounts allowed to push price updates without holding the owner key.
+    price_keepers: UnorderedSet<AccountId>,
+    /// Status overrides for deposits that have left the `Pending` state.
+    deposit_statuses: LookupMap<u64, DepositStatus>,
+    /// Backend service account allowed to update reconciliation status.
+    backend_account_id: Option<AccountId>,
+    /// Credits granted per deposit, recorded when the backend credits it.
+    credits_granted: LookupMap<u64, u64>,
+    /// NEP-141 credits ledger minted into on deposit.
+    credits_token: FungibleToken,
+    /// Credits minted per $1 of deposit value; zero disables minting.
+    credits_per_usd: u128,
+    /// Fee (USD micros) taken from each deposit that paid one.
+    deposit_fees: LookupMap<u64, u128>,
+    /// Per-account deposit cap (USD micros) per window; `None` disables caps.
+    deposit_cap_usd_micros: Option<u128>,
+    /// Length of the deposit cap window.
+    cap_window_ms: u64,
+    /// Deposit totals per account for the current cap window.
+    cap_windows: LookupMap<AccountId, CapWindow>,
+    /// Verified accounts that are not subject to deposit caps.
+    cap_exempt: UnorderedSet<AccountId>,
+    /// How forwarded funds are divided; empty sends everything to `treasury_account_id`.
+    treasury_splits: Vec<TreasurySplit>,
+    /// Backend ed25519 key that signs NEP-413 beneficiary bindings.
+    beneficiary_signer: Option<PublicKey>,
+    /// Platform user id -> NEAR account allowed to deposit for it.
+    bound_beneficiaries: LookupMap<String, AccountId>,
+    /// Nonces of consumed binding signatures, so old bindings cannot be replayed.
+    used_binding_nonces: LookupSet<[u8; 32]>,
+    /// When set, deposits must name a beneficiary bound to the paying account.
+    require_bound_beneficiary: bool,
+    /// Deposit bonus promos keyed by sha256 of the promo code.
+    promos: LookupMap<[u8; 32], Promo>,
+    /// Promo bonus (USD micros) recorded on each deposit that redeemed a code. Kept after a refund so the
+    /// archived record matches its `deposit_recorded` event.
+    deposit_bonuses: LookupMap<u64, u128>,
+    /// Largest allowed move from the previous price per update; zero disables the guard.
+    max_price_deviation_bps: u16,
+    /// Proposed owner waiting to call `accept_ownership`.
+    pending_owner: Option<AccountId>,
+    /// Proposed treasury waiting to call `accept_treasury`.
+    pending_treasury: Option<PendingTreasury>,
+    /// Delay between proposing a treasury and it becoming acceptable; zero means immediately.
+    treasury_timelock_ms: u64,
+    /// Scheduled decrease of `treasury_timelock_ms`.
+    pending_treasury_timelock: Option<PendingTimelock>,
+    /// Deposits with a lower id have been archived by `prune_deposits`.
+    pruned_before_id: u64,
+    /// Hash commitments over pruned deposits, keyed by archive period.
+    archive_commitments: UnorderedMap<u64, ArchiveCommitment>,
+    /// Account a deposit was paid for, when it differs from the payer.
+    deposit_recipients: LookupMap<u64, AccountId>,
+    /// Deposits paid on behalf of each account.
+    deposits_by_recipient: LookupMap<AccountId, Vec<u64>>,
+    /// Relayers whose NEP-366 delegate actions we sponsor; deposits they submit are tagged.
+    relayers: UnorderedSet<AccountId>,
+    /// Relayer that submitted each relayed deposit.
+    deposit_relayers: LookupMap<u64, AccountId>,
+    /// Deposit records an account may create per block; `None` disables the limit.
+    max_deposits_per_block: Option<u32>,
+    /// Deposits recorded per account in its most recent block.
+    block_deposits: LookupMap<AccountId, BlockDeposits>,
+    /// Accounts that may not deposit or be deposited for.
+    denylist: UnorderedSet<AccountId>,
+    /// Contracts allowed to spend users' credits for metered usage (e.g. the curation agent).
+    credit_spenders: UnorderedSet<AccountId>,
+    /// Roles delegated by the owner (admin, treasurer, keeper, guardian).
+    access: AccessControl,
+    /// Solver settlement recorded for each deposit paid through NEAR Intents.
+    deposit_intents: LookupMap<u64, IntentSettlement>,
+    /// Most recent deposits per platform beneficiary id, for support lookups.
+    deposits_by_beneficiary: LookupMap<String, Vec<u64>>,
+    /// Credits minted for each deposit, burned again if the deposit is refunded.
+    minted_credits: LookupMap<u64, u128>,
+    /// Layout version, stored under its own key so `migrate` can read it before parsing the state.
+    state_version: LazyOption<u32>,
+}
+
+/// State layout of the released contract, before `state_version` existed. `migrate` converts it;
+/// every field added since starts empty or at its default.
+#[derive(BorshDeserialize)]
+struct ReleasedDepositContract {
+    owner_id: AccountId,
+    treasury_account_id: AccountId,
+    next_deposit_id: u64,
+    token_configs: UnorderedMap<String, ReleasedTokenConfig>,
+    deposits: LookupMap<u64, DepositRecord>,
+    deposits_by_account: LookupMap<AccountId, Vec<u64>>,
+}
+
+impl ReleasedDepositContract {
+    fn into_current(mut self) -> DepositContract {
+        // Re-encode token configs in place with the per-token limit fields.
+        let released_configs = self.token_configs.to_vec();
+        self.token_configs.clear();
+        let mut token_configs = UnorderedMap::new(StorageKey::TokenConfigs);
+        for (token_id, cfg) in released_configs {
+            token_configs.insert(&token_id, &cfg.into_current());
+        }
+
+        DepositContract {
+            owner_id: self.owner_id,
+            treasury_account_id: self.treasury_account_id,
+            next_deposit_id: self.next_deposit_id,
+            token_configs,
+            deposits: self.deposits,
+            deposits_by_account: self.deposits_by_account,
+            price_keepers: UnorderedSet::new(StorageKey::PriceKeepers),
+            deposit_statuses: LookupMap::new(StorageKey::DepositStatuses),
+            backend_account_id: None,
+            credits_granted: LookupMap::new(StorageKey::CreditsGranted),
+            credits_token: FungibleToken::new(StorageKey::CreditsLedger),
+            credits_per_usd: 0,
+            deposit_fees: LookupMap::new(StorageKey::DepositFees),
+            deposit_cap_usd_micros: None,
+            cap_window_ms: DEFAULT_CAP_WINDOW_MS,
+            cap_windows: LookupMap::new(StorageKey::CapWindows),
+            cap_exempt: UnorderedSet::new(StorageKey::CapExempt),
+            treasury_splits: Vec::new(),
+            beneficiary_signer: None,
+            bound_beneficiaries: LookupMap::new(StorageKey::BoundBeneficiaries),
+            used_binding_nonces: LookupSet::new(StorageKey::UsedBindingNonces),
+            require_bound_beneficiary: false,
+            promos: LookupMap::new(StorageKey::Promos),
+            deposit_bonuses: LookupMap::new(StorageKey::DepositBonuses),
+            max_price_deviation_bps: DEFAULT_MAX_PRICE_DEVIATION_BPS,
+            pending_owner: None,
+            pending_treasury: None,
+            treasury_timelock_ms: 0,
+            pending_treasury_timelock: None,
+            pruned_before_id: 0,
+            archive_commitments: UnorderedMap::new(StorageKey::ArchiveCommitments),
+            deposit_recipients: LookupMap::new(StorageKey::DepositRecipients),
+            deposits_by_recipient: LookupMap::new(StorageKey::DepositsByRecipient),
+            relayers: UnorderedSet::new(StorageKey::Relayers),
+            deposit_relayers: LookupMap::new(StorageKey::DepositRelayers),
+            max_deposits_per_block: None,
+            block_deposits: LookupMap::new(StorageKey::BlockDeposits),
+            denylist: UnorderedSet::new(StorageKey::Denylist),
+            credit_spenders: UnorderedSet::new(StorageKey::CreditSpenders),
+            access: AccessControl::new(StorageKey::AccessRoles),
+            deposit_intents: LookupMap::new(StorageKey::DepositIntents),
+            deposits_by_beneficiary: LookupMap::new(StorageKey::DepositsByBeneficiary),
+            minted_credits: LookupMap::new(StorageKey::MintedCredits),
+            state_version: record_state_version(STATE_VERSION),
+        }
+    }
 }
 
 impl Default for DepositContract {
@@ -156,16 +528,62 @@ impl DepositContract {
             token_configs,
             deposits: LookupMap::new(StorageKey::Deposits),
             deposits_by_account: LookupMap::new(StorageKey::DepositsByAccount),
+            price_keepers: UnorderedSet::new(StorageKey::PriceKeepers),
+            deposit_statuses: LookupMap::new(StorageKey::DepositStatuses),
+            backend_account_id: None,
+            credits_granted: LookupMap::new(StorageKey::CreditsGranted),
+            credits_token: FungibleToken::new(StorageKey::CreditsLedger),
+            credits_per_usd: 0,
+            deposit_fees: LookupMap::new(StorageKey::DepositFees),
+            deposit_cap_usd_micros: None,
+            cap_window_ms: DEFAULT_CAP_WINDOW_MS,
+            cap_windows: LookupMap::new(StorageKey::CapWindows),
+            cap_exempt: UnorderedSet::new(StorageKey::CapExempt),
+            treasury_splits: Vec::new(),
+            beneficiary_signer: None,
+            bound_beneficiaries: LookupMap::new(StorageKey::BoundBeneficiaries),
+            used_binding_nonces: LookupSet::new(StorageKey::UsedBindingNonces),
+            require_bound_beneficiary: false,
+            promos: LookupMap::new(StorageKey::Promos),
+            deposit_bonuses: LookupMap::new(StorageKey::DepositBonuses),
+            max_price_deviation_bps: DEFAULT_MAX_PRICE_DEVIATION_BPS,
+            pending_owner: None,
+            pending_treasury: None,
+            treasury_timelock_ms: 0,
+            pending_treasury_timelock: None,
+            pruned_before_id: 0,
+            archive_commitments: UnorderedMap::new(StorageKey::ArchiveCommitments),
+            deposit_recipients: LookupMap::new(StorageKey::DepositRecipients),
+            deposits_by_recipient: LookupMap::new(StorageKey::DepositsByRecipient),
+            relayers: UnorderedSet::new(StorageKey::Relayers),
+            deposit_relayers: LookupMap::new(StorageKey::DepositRelayers),
+            max_deposits_per_block: None,
+            block_deposits: LookupMap::new(StorageKey::BlockDeposits),
+            denylist: UnorderedSet::new(StorageKey::Denylist),
+            credit_spenders: UnorderedSet::new(StorageKey::CreditSpenders),
+            access: AccessControl::new(StorageKey::AccessRoles),
+            deposit_intents: LookupMap::new(StorageKey::DepositIntents),
+            deposits_by_beneficiary: LookupMap::new(StorageKey::DepositsByBeneficiary),
+            minted_credits: LookupMap::new(StorageKey::MintedCredits),
+            state_version: record_state_version(STATE_VERSION),
         }
     }
 
     /// Safer migration: reuse existing state; owner-only; optionally update treasury.
+    /// The stored state version picks the layout; unversioned state is the released contract's.
     #[init(ignore_state)]
     pub fn migrate(treasury_account_id: Option<AccountId>) -> Self {
-        // Read existing state; fail if none
-        let mut old: DepositContract = env::state_read().expect("No existing state to migrate");
-        // Only current owner may migrate
-        require!(env::predecessor_account_id() == old.owner_id, "Only the owner can migrate");
+        // Read existing state; fail if none.
+        let bytes = env::storage_read(b"STATE").expect("No existing state to migrate");
+        let old = match stored_state_version() {
+            Some(STATE_VERSION) => DepositContract::try_from_slice(&bytes).ok(),
+            Some(version) => env::panic_str(&format!("Unsupported state version {}", version)),
+            None => ReleasedDepositContract::try_from_slice(&bytes).ok().map(ReleasedDepositContract::into_current),
+        };
+        let mut old = old.unwrap_or_else(|| env::panic_str("Cannot deserialize the contract state"));
+        // Only the current owner may migrate, or the contract itself in the `upgrade` batch
+        let caller = env::predecessor_account_id();
+        require!(caller == old.owner_id || caller == env::current_account_id(), "Only the owner can migrate");
         if let Some(new_treasury) = treasury_account_id {
             old.treasury_account_id = new_treasury;
         }
@@ -187,25 +605,475 @@ impl DepositContract {
         let mut config = TokenConfig::new(symbol, decimals, price_usd_micros.0, is_native, is_enabled);
         config.last_updated = env::block_timestamp_ms();
         self.token_configs.insert(&token_id, &config);
+        events::token_config_updated(&token_id, &config).emit();
+    }
+
+    /// Register an FT using the symbol and decimals reported by its own `ft_metadata` (owner only).
+    /// New tokens start disabled with no price; the owner enables them once a price is set.
+    pub fn register_token(&mut self, token_id: AccountId) -> Promise {
+        self.assert_owner();
+        ext_ft_metadata::ext(token_id.clone())
+            .with_static_gas(GAS_FOR_FT_METADATA)
+            .ft_metadata()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_METADATA_CALLBACK)
+                    .on_token_metadata(token_id),
+            )
+    }
+
+    #[private]
+    pub fn on_token_metadata(
+        &mut self,
+        token_id: AccountId,
+        #[callback_result] metadata: Result<FungibleTokenMetadata, PromiseError>,
+    ) -> TokenConfigView {
+        let metadata = metadata.unwrap_or_else(|_| env::panic_str("Failed to fetch token metadata"));
+        require!(metadata.decimals <= MAX_TOKEN_DECIMALS, "Token decimals out of range");
+
+        let key = token_id.to_string();
+        let config = match self.token_configs.get(&key) {
+            // Re-registering refreshes metadata but keeps the owner's price and enabled flag.
+            Some(mut existing) => {
+                existing.symbol = metadata.symbol;
+                existing.decimals = metadata.decimals;
+                existing
+            }
+            None => TokenConfig::new(metadata.symbol, metadata.decimals, 0, false, false),
+        };
+        self.token_configs.insert(&key, &config);
+        events::token_config_updated(&key, &config).emit();
+        TokenConfigView::from_parts(key, config)
     }
 
-    /// Update the USD price for a given token (owner only).
-    pub fn update_token_price(&mut self, token_id: String, price_usd_micros: U128) {
+    /// Set the minimum deposit and fee for a configured token (owner only).
+    /// Fees stay with the treasury and are excluded from the credited value.
+    pub fn set_token_limits(&mut self, token_id: String, min_deposit_usd_micros: U128, deposit_fee_bps: u16) {
         self.assert_owner();
+        require!(deposit_fee_bps <= MAX_DEPOSIT_FEE_BPS, "Deposit fee cannot exceed 10%");
         let mut cfg = self
             .token_configs
             .get(&token_id)
             .expect("Token config not found");
+        cfg.min_deposit_usd_micros = min_deposit_usd_micros.0;
+        cfg.deposit_fee_bps = deposit_fee_bps;
+        self.token_configs.insert(&token_id, &cfg);
+        events::token_config_updated(&token_id, &cfg).emit();
+    }
 
-        cfg.price_usd_micros = price_usd_micros.0;
-        cfg.last_updated = env::block_timestamp_ms();
+    /// Configure the per-account deposit cap (owner only). `None` disables caps.
+    pub fn set_deposit_cap(&mut self, cap_usd_micros: Option<U128>, window_ms: Option<u64>) {
+        self.assert_owner();
+        if let Some(window_ms) = window_ms {
+            require!(window_ms > 0, "Cap window must be > 0");
+            self.cap_window_ms = window_ms;
+        }
+        self.deposit_cap_usd_micros = cap_usd_micros.map(|cap| cap.0);
+    }
+
+    /// Current cap in USD micros and window length in ms.
+    pub fn get_deposit_cap(&self) -> (Option<U128>, u64) {
+        (self.deposit_cap_usd_micros.map(U128), self.cap_window_ms)
+    }
+
+    /// USD micros deposited by an account in its current cap window.
+    pub fn get_cap_usage(&self, account_id: AccountId) -> U128 {
+        let now = env::block_timestamp_ms();
+        let used = self
+            .cap_windows
+            .get(&account_id)
+            .filter(|w| now < w.window_start_ms.saturating_add(self.cap_window_ms))
+            .map(|w| w.usd_total)
+            .unwrap_or(0);
+        U128(used)
+    }
+
+    /// Limit how many deposit records an account may create per block (owner only).
+    /// `None` removes the limit.
+    pub fn set_max_deposits_per_block(&mut self, max_deposits: Option<u32>) {
+        self.assert_owner();
+        require!(max_deposits != Some(0), "Per-block limit must be > 0");
+        self.max_deposits_per_block = max_deposits;
+    }
+
+    pub fn get_max_deposits_per_block(&self) -> Option<u32> {
+        self.max_deposits_per_block
+    }
+
+    /// Block an account from depositing or being deposited for (owner or guardian).
+    pub fn add_to_denylist(&mut self, account_id: AccountId) {
+        self.assert_role(Role::Guardian);
+        self.denylist.insert(&account_id);
+    }
+
+    /// Allow a denylisted account to deposit again (owner or guardian).
+    pub fn remove_from_denylist(&mut self, account_id: AccountId) {
+        self.assert_role(Role::Guardian);
+        self.denylist.remove(&account_id);
+    }
+
+    pub fn get_denylist(&self) -> Vec<AccountId> {
+        self.denylist.to_vec()
+    }
+
+    /// Exempt a verified account from deposit caps (owner only).
+    pub fn add_cap_exemption(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.cap_exempt.insert(&account_id);
+    }
+
+    /// Subject an account to deposit caps again (owner only).
+    pub fn remove_cap_exemption(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.cap_exempt.remove(&account_id);
+    }
+
+    /// Enable or disable deposits for a configured token (owner or guardian).
+    pub fn set_token_enabled(&mut self, token_id: String, is_enabled: bool) {
+        self.assert_role(Role::Guardian);
+        let mut cfg = self
+            .token_configs
+            .get(&token_id)
+            .expect("Token config not found");
+        cfg.is_enabled = is_enabled;
         self.token_configs.insert(&token_id, &cfg);
+        events::token_config_updated(&token_id, &cfg).emit();
+    }
+
+    /// Update the USD price for a given token (owner or price keeper).
+    /// Moves beyond the deviation guard are rejected unless the owner passes `force`.
+    pub fn update_token_price(&mut self, token_id: String, price_usd_micros: U128, force: Option<bool>) {
+        self.assert_owner_or_keeper();
+        let force = self.assert_force_allowed(force);
+        self.internal_set_price(&token_id, price_usd_micros.0, force);
+    }
+
+    /// Update several token prices in one call (owner or price keeper). All-or-nothing.
+    pub fn update_token_prices(&mut self, prices: Vec<(String, U128)>, force: Option<bool>) {
+        self.assert_owner_or_keeper();
+        require!(prices.len() <= MAX_PRICE_BATCH, "Too many price updates in one call");
+        let force = self.assert_force_allowed(force);
+        for (token_id, price_usd_micros) in prices {
+            self.internal_set_price(&token_id, price_usd_micros.0, force);
+        }
+    }
+
+    /// Maintenance for keepers and cron agents such as a Croncat task (owner, price keeper or
+    /// keeper role). Checks up to 50 enabled tokens and reports those without a price from the
+    /// last hour, since deposits in them are rejected until a keeper updates it.
+    pub fn tick(&mut self) -> TickReport {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.price_keepers.contains(&caller) || self.access.is_authorized(&self.owner_id, Role::Keeper, &caller),
+            "Only the owner or a keeper can tick"
+        );
+        let now = env::block_timestamp_ms();
+        let mut checked = 0;
+        let mut stale_tokens = Vec::new();
+        for (token_id, cfg) in self.token_configs.iter().filter(|(_, cfg)| cfg.is_enabled).take(MAX_TICK_TOKENS) {
+            checked += 1;
+            if cfg.price_usd_micros == 0 || now.saturating_sub(cfg.last_updated) > MAX_PRICE_AGE_MS {
+                stale_tokens.push(token_id);
+            }
+        }
+
+        let report = TickReport { checked, stale_tokens };
+        events::emit("maintenance_tick", &report);
+        report
+    }
+
+    /// Set the largest allowed price move per update in bps (owner only). Zero disables the guard.
+    pub fn set_max_price_deviation(&mut self, max_deviation_bps: u16) {
+        self.assert_owner();
+        self.max_price_deviation_bps = max_deviation_bps;
+    }
+
+    pub fn get_max_price_deviation(&self) -> u16 {
+        self.max_price_deviation_bps
+    }
+
+    /// Allow an account to update token prices (owner only).
+    /// Keepers can call `update_token_price` and nothing else, so the owner key can stay cold.
+    pub fn add_keeper(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.price_keepers.insert(&account_id);
+    }
+
+    /// Revoke price update rights from an account (owner only).
+    pub fn remove_keeper(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.price_keepers.remove(&account_id);
+    }
+
+    /// List accounts allowed to update token prices.
+    pub fn get_price_keepers(&self) -> Vec<AccountId> {
+        self.price_keepers.to_vec()
+    }
+
+    /// Register a relayer that submits users' `ft_transfer_call` deposits as NEP-366 delegate
+    /// actions (owner only). Deposits it relays are tagged with `relayed_by`.
+    pub fn add_relayer(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.relayers.insert(&account_id);
+    }
+
+    /// Stop tagging deposits submitted by a relayer (owner only).
+    pub fn remove_relayer(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.relayers.remove(&account_id);
+    }
+
+    pub fn get_relayers(&self) -> Vec<AccountId> {
+        self.relayers.to_vec()
+    }
+
+    /// Set the backend account allowed to mark deposits credited or flagged (owner only).
+    pub fn set_backend_account(&mut self, backend_account_id: Option<AccountId>) {
+        self.assert_owner();
+        self.backend_account_id = backend_account_id;
+    }
+
+    /// Backend account currently authorized to update deposit status.
+    pub fn get_backend_account(&self) -> Option<AccountId> {
+        self.backend_account_id.clone()
+    }
+
+    /// Record that the backend has credited a pending deposit (backend only).
+    pub fn mark_deposit_credited(&mut self, deposit_id: u64, credits_granted: u64) {
+        self.assert_backend();
+        self.transition_pending(deposit_id, DepositStatus::Credited);
+        self.credits_granted.insert(&deposit_id, &credits_granted);
+        DepositCredited { deposit_id, credits_granted }.emit();
+    }
+
+    /// Hold a pending deposit for manual review instead of crediting it (backend only).
+    pub fn flag_deposit(&mut self, deposit_id: u64) {
+        self.assert_backend();
+        self.transition_pending(deposit_id, DepositStatus::Flagged);
+        events::emit("deposit_flagged", serde_json::json!({ "deposit_id": deposit_id }));
+    }
+
+    /// Split forwarded funds across several wallets by basis points (owner only).
+    /// Shares must total 10000; pass an empty list to send everything to the treasury.
+    pub fn set_treasury_splits(&mut self, splits: Vec<TreasurySplit>) {
+        self.assert_owner();
+        require!(splits.len() <= MAX_TREASURY_SPLITS, "Too many treasury splits");
+        if !splits.is_empty() {
+            let total: u128 = splits.iter().map(|s| s.bps as u128).sum();
+            require!(total == BPS_DENOMINATOR, "Treasury splits must total 10000 bps");
+        }
+        self.treasury_splits = splits;
+    }
+
+    pub fn get_treasury_splits(&self) -> Vec<TreasurySplit> {
+        self.treasury_splits.clone()
+    }
+
+    /// Set the backend ed25519 key that signs beneficiary bindings (owner only).
+    pub fn set_beneficiary_signer(&mut self, public_key: Option<PublicKey>) {
+        self.assert_owner();
+        if let Some(key) = &public_key {
+            require!(key.curve_type() == CurveType::ED25519, "Beneficiary signer must be an ed25519 key");
+        }
+        self.beneficiary_signer = public_key;
+    }
+
+    /// Require every deposit to name a beneficiary bound to the paying account (owner only).
+    pub fn set_require_bound_beneficiary(&mut self, required: bool) {
+        self.assert_owner();
+        self.require_bound_beneficiary = required;
+    }
+
+    /// Bind a platform user id to the calling account using a NEP-413 message signed by the backend.
+    /// The signed message is `bind_beneficiary:{user_id}:{caller}` with this contract as recipient.
+    pub fn bind_beneficiary(&mut self, user_id: String, nonce: Base64VecU8, signature: Base64VecU8) {
+        require!(user_id.len() <= MAX_BENEFICIARY_LEN, "beneficiary_id too long");
+        let signer = self.beneficiary_signer.clone().expect("Beneficiary signer not configured");
+        let nonce: [u8; 32] = nonce.0.try_into().unwrap_or_else(|_| env::panic_str("Nonce must be 32 bytes"));
+        let signature: [u8; 64] = signature
+            .0
+            .try_into()
+            .unwrap_or_else(|_| env::panic_str("Signature must be 64 bytes"));
+        require!(!self.used_binding_nonces.contains(&nonce), "Binding nonce already used");
+
+        let account_id = env::predecessor_account_id();
+        let payload = Nep413Payload {
+            message: format!("bind_beneficiary:{}:{}", user_id, account_id),
+            nonce,
+            recipient: env::current_account_id().to_string(),
+            callback_url: None,
+        };
+        let mut bytes = borsh::to_vec(&NEP413_TAG).unwrap();
+        bytes.extend(borsh::to_vec(&payload).unwrap());
+        let public_key: [u8; 32] = signer.as_bytes()[1..].try_into().unwrap();
+        require!(
+            env::ed25519_verify(&signature, &env::sha256_array(&bytes), &public_key),
+            "Invalid beneficiary signature"
+        );
+
+        self.used_binding_nonces.insert(&nonce);
+        self.bound_beneficiaries.insert(&user_id, &account_id);
+        events::emit("beneficiary_bound", serde_json::json!({ "user_id": user_id, "account_id": account_id }));
+    }
+
+    /// NEAR account a platform user id is bound to, if any.
+    pub fn get_bound_account(&self, user_id: String) -> Option<AccountId> {
+        self.bound_beneficiaries.get(&user_id)
+    }
+
+    /// Create or replace a promo (owner only). `code_hash` is the sha256 of the code, so
+    /// unreleased codes are not readable from contract state.
+    pub fn set_promo(&mut self, code_hash: Base64VecU8, bonus_bps: u16, expires_at_ms: u64, max_uses: u32) {
+        self.assert_owner();
+        let code_hash = promo_hash(code_hash);
+        require!(bonus_bps > 0 && bonus_bps <= MAX_PROMO_BONUS_BPS, "Promo bonus must be 1-5000 bps");
+        require!(max_uses > 0, "Promo must allow at least one use");
+        let uses = self.promos.get(&code_hash).map_or(0, |p| p.uses);
+        self.promos.insert(&code_hash, &Promo { bonus_bps, expires_at_ms, max_uses, uses });
+    }
+
+    /// Withdraw a promo before it expires (owner only).
+    pub fn remove_promo(&mut self, code_hash: Base64VecU8) {
+        self.assert_owner();
+        require!(self.promos.remove(&promo_hash(code_hash)).is_some(), "Promo not found");
+    }
+
+    /// Look up a promo by its plain code, e.g. to validate it before depositing.
+    pub fn get_promo(&self, promo_code: String) -> Option<Promo> {
+        self.promos.get(&env::sha256_array(promo_code.as_bytes()))
+    }
+
+    /// Propose a new owner (owner only). Ownership moves once the proposed account accepts;
+    /// proposing again replaces the pending proposal.
+    pub fn propose_owner(&mut self, new_owner: AccountId) {
+        require!(env::predecessor_account_id() == self.owner_id, "Only the owner can call this method");
+        self.pending_owner = Some(new_owner.clone());
+        events::emit("ownership_proposed", serde_json::json!({ "owner_id": self.owner_id, "pending_owner": new_owner }));
+    }
+
+    /// Accept a pending ownership proposal (proposed account only).
+    pub fn accept_ownership(&mut self) {
+        let caller = env::predecessor_account_id();
+        require!(self.pending_owner.as_ref() == Some(&caller), "Only the proposed owner can accept ownership");
+        let previous = std::mem::replace(&mut self.owner_id, caller);
+        self.pending_owner = None;
+        events::emit("ownership_transferred", serde_json::json!({ "previous_owner": previous, "owner_id": self.owner_id }));
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+
+    /// Deploy new code and run its `migrate` in one batch (owner only, e.g. the governance contract).
+    /// `code` is the wasm module, borsh-serialized as the call arguments.
+    pub fn upgrade(&mut self, #[serializer(borsh)] code: Vec<u8>) -> Promise {
+        require!(env::predecessor_account_id() == self.owner_id, "Only the owner can call this method");
+        events::emit("contract_upgrade", serde_json::json!({ "code_len": code.len(), "state_version": STATE_VERSION }));
+        deploy_and_migrate(code, b"{}".to_vec())
+    }
+
+    pub fn get_state_version(&self) -> u32 {
+        self.state_version.get().unwrap_or(0)
+    }
+
+    /// Grant a role (owner or admin; only the owner grants admin).
+    pub fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_can_manage_role(role);
+        if self.access.grant(role, &account_id) {
+            events::emit("role_granted", serde_json::json!({ "role": role, "account_id": account_id }));
+        }
+    }
+
+    /// Revoke a role (owner or admin; only the owner revokes admin).
+    pub fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_can_manage_role(role);
+        if self.access.revoke(role, &account_id) {
+            events::emit("role_revoked", serde_json::json!({ "role": role, "account_id": account_id }));
+        }
     }
 
-    /// Change the treasury account receiving native deposits (owner only).
-    pub fn set_treasury(&mut self, treasury_account_id: AccountId) {
+    /// Whether the account holds the role itself; the owner holds none but passes every check.
+    pub fn has_role(&self, role: Role, account_id: AccountId) -> bool {
+        self.access.has_role(role, &account_id)
+    }
+
+    pub fn get_roles(&self, account_id: AccountId) -> Vec<Role> {
+        self.access.roles_of(&account_id)
+    }
+
+    /// Propose a new treasury account for forwarded funds (owner only). The proposed account
+    /// accepts it with `accept_treasury` once the treasury timelock has passed.
+    pub fn propose_treasury(&mut self, treasury_account_id: AccountId) {
+        self.assert_owner();
+        let pending = PendingTreasury {
+            account_id: treasury_account_id,
+            accept_after_ms: env::block_timestamp_ms().saturating_add(self.get_treasury_timelock()),
+        };
+        events::emit("treasury_proposed", serde_json::json!(pending));
+        self.pending_treasury = Some(pending);
+    }
+
+    /// Drop a pending treasury proposal (owner only).
+    pub fn cancel_treasury_proposal(&mut self) {
         self.assert_owner();
-        self.treasury_account_id = treasury_account_id;
+        require!(self.pending_treasury.take().is_some(), "No pending treasury");
+    }
+
+    /// Accept a pending treasury proposal (proposed account only, after the timelock).
+    pub fn accept_treasury(&mut self) {
+        let caller = env::predecessor_account_id();
+        let pending = self.pending_treasury.clone().expect("No pending treasury");
+        require!(pending.account_id == caller, "Only the proposed treasury can accept");
+        require!(env::block_timestamp_ms() >= pending.accept_after_ms, "Treasury timelock has not passed");
+        let previous = std::mem::replace(&mut self.treasury_account_id, caller);
+        self.pending_treasury = None;
+        events::emit(
+            "treasury_changed",
+            serde_json::json!({ "previous_treasury": previous, "treasury_account_id": self.treasury_account_id }),
+        );
+    }
+
+    /// Set the delay before a proposed treasury can be accepted (owner only). Increases apply
+    /// immediately; a decrease only takes effect once the current timelock has passed, so the
+    /// owner cannot drop it and move the treasury in the same block.
+    pub fn set_treasury_timelock(&mut self, timelock_ms: u64) {
+        self.assert_owner();
+        self.treasury_timelock_ms = self.get_treasury_timelock();
+        if timelock_ms >= self.treasury_timelock_ms {
+            self.treasury_timelock_ms = timelock_ms;
+            self.pending_treasury_timelock = None;
+        } else {
+            let pending = PendingTimelock {
+                timelock_ms,
+                effective_after_ms: env::block_timestamp_ms().saturating_add(self.treasury_timelock_ms),
+            };
+            events::emit("treasury_timelock_decrease_scheduled", serde_json::json!(pending));
+            self.pending_treasury_timelock = Some(pending);
+        }
+    }
+
+    /// Timelock currently in force, including a scheduled decrease whose delay has passed.
+    pub fn get_treasury_timelock(&self) -> u64 {
+        match &self.pending_treasury_timelock {
+            Some(pending) if env::block_timestamp_ms() >= pending.effective_after_ms => pending.timelock_ms,
+            _ => self.treasury_timelock_ms,
+        }
+    }
+
+    pub fn get_pending_treasury_timelock(&self) -> Option<PendingTimelock> {
+        self.pending_treasury_timelock.clone()
+    }
+
+    pub fn get_treasury(&self) -> AccountId {
+        self.treasury_account_id.clone()
+    }
+
+    pub fn get_pending_treasury(&self) -> Option<PendingTreasury> {
+        self.pending_treasury.clone()
     }
 
     /// View helper for token config.
@@ -230,13 +1098,105 @@ impl DepositContract {
         };
         ids.into_iter()
             .filter_map(|id| self.deposits.get(&id))
-            .map(DepositRecord::into_view)
+            .map(|record| self.view_deposit(record))
+            .collect()
+    }
+
+    /// Page through deposits recorded for an account, oldest first.
+    pub fn get_deposits_paginated(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<DepositView> {
+        let Some(ids) = self.deposits_by_account.get(&account_id) else {
+            return vec![];
+        };
+        ids.into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(Self::page_limit(limit) as usize)
+            .filter_map(|id| self.deposits.get(&id))
+            .map(|record| self.view_deposit(record))
+            .collect()
+    }
+
+    /// Deposits across all accounts with `start_ms <= timestamp_ms <= end_ms`.
+    /// `from_index` is a deposit id to resume from; pass the last returned id + 1.
+    pub fn get_deposits_in_range(
+        &self,
+        start_ms: u64,
+        end_ms: u64,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<DepositView> {
+        let limit = Self::page_limit(limit) as usize;
+        let mut result = Vec::new();
+        // Ids are assigned in block order, so timestamps never decrease with id.
+        for id in from_index.unwrap_or(0).max(self.pruned_before_id)..self.next_deposit_id {
+            let Some(record) = self.deposits.get(&id) else { continue };
+            if record.timestamp_ms > end_ms || result.len() >= limit {
+                break;
+            }
+            if record.timestamp_ms >= start_ms {
+                result.push(self.view_deposit(record));
+            }
+        }
+        result
+    }
+
+    /// Number of stored deposits for an account, or of all deposits ever recorded (including
+    /// archived ones) when no account is given.
+    pub fn get_deposit_count(&self, account_id: Option<AccountId>) -> u64 {
+        match account_id {
+            Some(account_id) => self
+                .deposits_by_account
+                .get(&account_id)
+                .map(|ids| ids.len() as u64)
+                .unwrap_or(0),
+            None => self.next_deposit_id,
+        }
+    }
+
+    /// Page through deposits other accounts paid on behalf of `account_id`, oldest first.
+    pub fn get_deposits_on_behalf_of(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<DepositView> {
+        let Some(ids) = self.deposits_by_recipient.get(&account_id) else {
+            return vec![];
+        };
+        ids.into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(Self::page_limit(limit) as usize)
+            .filter_map(|id| self.deposits.get(&id))
+            .map(|record| self.view_deposit(record))
+            .collect()
+    }
+
+    /// Page through deposits credited to a platform beneficiary id, oldest first. Only the most
+    /// recent 1,000 per beneficiary are indexed, and none recorded before the index existed.
+    pub fn get_deposits_for_beneficiary(
+        &self,
+        beneficiary_id: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<DepositView> {
+        let Some(ids) = self.deposits_by_beneficiary.get(&beneficiary_id) else {
+            return vec![];
+        };
+        ids.into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(Self::page_limit(limit) as usize)
+            .filter_map(|id| self.deposits.get(&id))
+            .map(|record| self.view_deposit(record))
             .collect()
     }
 
     /// Retrieve a single deposit record.
     pub fn get_deposit(&self, deposit_id: u64) -> Option<DepositView> {
-        self.deposits.get(&deposit_id).map(DepositRecord::into_view)
+        self.deposits.get(&deposit_id).map(|record| self.view_deposit(record))
     }
 
     /// Payable method for depositing native NEAR.
@@ -246,6 +1206,8 @@ impl DepositContract {
         beneficiary_id: String,
         credits_hint: Option<u64>,
         memo: Option<String>,
+        promo_code: Option<String>,
+        on_behalf_of: Option<AccountId>,
     ) -> DepositView {
         let amount = env::attached_deposit();
         require!(amount.as_yoctonear() > 0, "Attach NEAR to deposit");
@@ -265,25 +1227,47 @@ impl DepositContract {
             "Price data is stale (>1h). Keeper must update price."
         );
 
-        let usd_value = self.usd_value_for(&cfg, amount.as_yoctonear());
-        require!(
-            usd_value >= MIN_DEPOSIT_USD_MICROS,
-            "Minimum deposit is $5 USD"
-        );
+        let mut value = self
+            .price_deposit(&cfg, amount.as_yoctonear())
+            .unwrap_or_else(|reason| env::panic_str(&reason));
+        let promo = self
+            .check_promo(promo_code.as_deref())
+            .unwrap_or_else(|reason| env::panic_str(&reason));
 
         let account_id = env::predecessor_account_id();
+        let recipient = on_behalf_of.as_ref().unwrap_or(&account_id);
+        self.check_denylist(&account_id, recipient)
+            .unwrap_or_else(|reason| env::panic_str(&reason));
+        let block_deposits = self
+            .check_block_limit(&account_id)
+            .unwrap_or_else(|reason| env::panic_str(&reason));
+        self.check_beneficiary_binding(recipient, &beneficiary_id)
+            .unwrap_or_else(|reason| env::panic_str(&reason));
+        self.track_deposit_cap(&account_id, value.usd_value)
+            .unwrap_or_else(|reason| env::panic_str(&reason));
+        self.block_deposits.insert(&account_id, &block_deposits);
+        if let Some((code_hash, promo)) = promo {
+            value.bonus_usd_value = self.redeem_promo(code_hash, promo, &value);
+        }
         let record = self.store_deposit(
-            account_id.clone(),
-            beneficiary_id,
+            account_id,
             NEAR_TOKEN_ID.to_string(),
             amount.as_yoctonear(),
-            usd_value,
-            credits_hint,
-            memo,
+            value,
+            DepositMessage { beneficiary_id, credits_hint, memo, promo_code, on_behalf_of, intent: None },
         );
 
-        // Immediately forward NEAR to the treasury wallet.
-        Promise::new(self.treasury_account_id.clone()).transfer(amount);
+        // Immediately forward NEAR to the treasury wallets.
+        self.split_amount(amount.as_yoctonear())
+            .into_iter()
+            .map(|(receiver, share)| Promise::new(receiver).transfer(NearToken::from_yoctonear(share)))
+            .reduce(|joined, transfer| joined.and(transfer))
+            .unwrap_or_else(|| env::panic_str("Nothing to transfer"))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_FORWARD_CALLBACK)
+                    .on_native_forwarded(record.id),
+            );
 
         record
     }
@@ -291,12 +1275,10 @@ impl DepositContract {
     fn store_deposit(
         &mut self,
         account_id: AccountId,
-        beneficiary_id: String,
         token_id: String,
         amount: u128,
-        usd_value: u128,
-        credits_hint: Option<u64>,
-        memo: Option<String>,
+        value: DepositValue,
+        msg: DepositMessage,
     ) -> DepositView {
         let deposit_id = self.next_deposit_id;
         self.next_deposit_id += 1;
@@ -304,55 +1286,399 @@ impl DepositContract {
         let record = DepositRecord {
             id: deposit_id,
             account_id: account_id.clone(),
-            beneficiary_id,
+            beneficiary_id: msg.beneficiary_id,
             token_id,
             amount: U128(amount),
-            usd_value: U128(usd_value),
-            credits_hint,
-            memo,
+            usd_value: U128(value.usd_value),
+            credits_hint: msg.credits_hint,
+            memo: msg.memo,
             timestamp_ms: env::block_timestamp_ms(),
         };
 
         self.deposits.insert(&deposit_id, &record);
-
-        let mut ids = self.deposits_by_account.get(&account_id).unwrap_or_default();
+        if value.fee_usd_value > 0 {
+            self.deposit_fees.insert(&deposit_id, &value.fee_usd_value);
+        }
+        if value.bonus_usd_value > 0 {
+            self.deposit_bonuses.insert(&deposit_id, &value.bonus_usd_value);
+        }
+        // With a NEP-366 delegate action the user's account is the predecessor (and the FT
+        // sender), so the deposit is attributed to them; the relayer only signs the outer
+        // transaction and shows up as the signer.
+        let signer = env::signer_account_id();
+        if signer != account_id && self.relayers.contains(&signer) {
+            self.deposit_relayers.insert(&deposit_id, &signer);
+        }
+        if let Some(intent) = &msg.intent {
+            self.deposit_intents.insert(&deposit_id, intent);
+        }
+
+        // Credits belong to the account the deposit was paid for.
+        let recipient = msg.on_behalf_of.filter(|a| *a != account_id);
+        if let Some(recipient) = &recipient {
+            self.deposit_recipients.insert(&deposit_id, recipient);
+            let mut ids = self.deposits_by_recipient.get(recipient).unwrap_or_default();
+            ids.push(deposit_id);
+            self.deposits_by_recipient.insert(recipient, &ids);
+        }
+        self.mint_credits_for(recipient.as_ref().unwrap_or(&account_id), deposit_id, value.usd_value - value.fee_usd_value);
+
+        let mut ids = self.deposits_by_account.get(&account_id).unwrap_or_default();
         ids.push(deposit_id);
         self.deposits_by_account.insert(&account_id, &ids);
 
-        env::log_str(&format!(
-            "EVENT_JSON:{{\"standard\":\"dreamweave_deposit\",\"version\":\"1.0.0\",\"event\":\"deposit\",\"data\":[{}]}}",
-            serde_json::to_string(&record).unwrap()
-        ));
+        let mut ids = self.deposits_by_beneficiary.get(&record.beneficiary_id).unwrap_or_default();
+        ids.push(deposit_id);
+        if ids.len() > MAX_BENEFICIARY_DEPOSITS {
+            ids.drain(..ids.len() - MAX_BENEFICIARY_DEPOSITS);
+        }
+        self.deposits_by_beneficiary.insert(&record.beneficiary_id, &ids);
+
+        let view = self.view_deposit(record);
+        DepositRecorded::from(&view).emit();
+        view
+    }
+
+    fn deposit_status(&self, deposit_id: u64) -> DepositStatus {
+        self.deposit_statuses.get(&deposit_id).unwrap_or(DepositStatus::Pending)
+    }
+
+    /// Account the deposit's credits were minted to: its recipient, or else the payer.
+    fn credits_holder(&self, deposit_id: u64, payer_id: &AccountId) -> AccountId {
+        self.deposit_recipients.get(&deposit_id).unwrap_or_else(|| payer_id.clone())
+    }
+
+    fn transition_pending(&mut self, deposit_id: u64, status: DepositStatus) {
+        require!(self.deposits.get(&deposit_id).is_some(), "Deposit not found");
+        require!(
+            self.deposit_status(deposit_id) == DepositStatus::Pending,
+            "Deposit is not pending"
+        );
+        self.deposit_statuses.insert(&deposit_id, &status);
+    }
+
+    fn view_deposit(&self, record: DepositRecord) -> DepositView {
+        let deposit_id = record.id;
+        let mut view = record.into_view();
+        view.status = self.deposit_status(deposit_id);
+        view.credits_granted = self.credits_granted.get(&deposit_id);
+        view.fee_usd_value = U128(self.deposit_fees.get(&deposit_id).unwrap_or(0));
+        view.bonus_usd_value = U128(self.deposit_bonuses.get(&deposit_id).unwrap_or(0));
+        view.on_behalf_of = self.deposit_recipients.get(&deposit_id);
+        view.relayed_by = self.deposit_relayers.get(&deposit_id);
+        view.intent = self.deposit_intents.get(&deposit_id);
+        view
+    }
+
+    /// Validates and stores an FT deposit, returning the rejection reason instead of panicking.
+    fn try_record_ft_deposit(
+        &mut self,
+        sender_id: &AccountId,
+        token_id: &AccountId,
+        amount: u128,
+        msg: &str,
+    ) -> Result<DepositView, String> {
+        // Ensure non-zero deposit and cap inputs to avoid storage blow-up
+        if amount == 0 {
+            return Err("Amount must be > 0".to_string());
+        }
+        let cfg = self
+            .token_configs
+            .get(&token_id.to_string())
+            .ok_or_else(|| "Unsupported token".to_string())?;
+        if !cfg.is_enabled {
+            return Err("Token deposits disabled".to_string());
+        }
+        if cfg.price_usd_micros == 0 {
+            return Err("Token price not configured".to_string());
+        }
+        if env::block_timestamp_ms().saturating_sub(cfg.last_updated) > MAX_PRICE_AGE_MS {
+            return Err("Price data is stale (>1h). Keeper must update price.".to_string());
+        }
+
+        let parsed: DepositMessage =
+            serde_json::from_str(msg).map_err(|_| "Invalid deposit message payload".to_string())?;
+        if parsed.beneficiary_id.len() > MAX_BENEFICIARY_LEN {
+            return Err("beneficiary_id too long".to_string());
+        }
+        if parsed.memo.as_ref().is_some_and(|m| m.len() > MAX_MEMO_LEN) {
+            return Err("memo too long".to_string());
+        }
+        if let Some(intent) = &parsed.intent {
+            if intent.quote_id.is_empty() || intent.original_asset.is_empty() {
+                return Err("Intent settlement needs a quote_id and original_asset".to_string());
+            }
+            if intent.quote_id.len() > MAX_INTENT_FIELD_LEN || intent.original_asset.len() > MAX_INTENT_FIELD_LEN {
+                return Err("Intent settlement field too long".to_string());
+            }
+        }
+
+        let mut value = self.price_deposit(&cfg, amount)?;
+        let promo = self.check_promo(parsed.promo_code.as_deref())?;
+        let recipient = parsed.on_behalf_of.as_ref().unwrap_or(sender_id);
+        self.check_denylist(sender_id, recipient)?;
+        let block_deposits = self.check_block_limit(sender_id)?;
+        self.check_beneficiary_binding(recipient, &parsed.beneficiary_id)?;
+        self.track_deposit_cap(sender_id, value.usd_value)?;
+        self.block_deposits.insert(sender_id, &block_deposits);
+        if let Some((code_hash, promo)) = promo {
+            value.bonus_usd_value = self.redeem_promo(code_hash, promo, &value);
+        }
+
+        Ok(self.store_deposit(sender_id.clone(), token_id.to_string(), amount, value, parsed))
+    }
+
+    /// Values a deposit and applies the token's minimum and fee.
+    fn price_deposit(&self, cfg: &TokenConfig, amount: u128) -> Result<DepositValue, String> {
+        let usd_value = self.usd_value_for(cfg, amount);
+        if usd_value < cfg.min_deposit_usd_micros {
+            return Err(format!("Minimum deposit is ${} USD", format_usd(cfg.min_deposit_usd_micros)));
+        }
+        let fee_usd_value = apply_bps(usd_value, cfg.deposit_fee_bps as u128).expect("Deposit fee overflow");
+        Ok(DepositValue { usd_value, fee_usd_value, bonus_usd_value: 0 })
+    }
+
+    /// Looks up a promo code without redeeming it, so a rejected deposit leaves no trace.
+    fn check_promo(&self, promo_code: Option<&str>) -> Result<Option<([u8; 32], Promo)>, String> {
+        let Some(code) = promo_code else {
+            return Ok(None);
+        };
+        let code_hash = env::sha256_array(code.as_bytes());
+        let promo = self.promos.get(&code_hash).ok_or_else(|| "Unknown promo code".to_string())?;
+        if env::block_timestamp_ms() >= promo.expires_at_ms {
+            return Err("Promo code expired".to_string());
+        }
+        if promo.uses >= promo.max_uses {
+            return Err("Promo code fully redeemed".to_string());
+        }
+        Ok(Some((code_hash, promo)))
+    }
+
+    /// Counts a use of the promo and returns the bonus on the deposit's credited value.
+    fn redeem_promo(&mut self, code_hash: [u8; 32], mut promo: Promo, value: &DepositValue) -> u128 {
+        promo.uses += 1;
+        self.promos.insert(&code_hash, &promo);
+        apply_bps(value.usd_value - value.fee_usd_value, promo.bonus_bps as u128).expect("Promo bonus overflow")
+    }
+
+    fn check_denylist(&self, payer: &AccountId, recipient: &AccountId) -> Result<(), String> {
+        if self.denylist.contains(payer) || self.denylist.contains(recipient) {
+            return Err("Account is denylisted".to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns the account's updated per-block count, to be stored once the deposit is accepted.
+    fn check_block_limit(&self, account_id: &AccountId) -> Result<BlockDeposits, String> {
+        let block_height = env::block_height();
+        let count = self
+            .block_deposits
+            .get(account_id)
+            .filter(|b| b.block_height == block_height)
+            .map_or(0, |b| b.count);
+        if self.max_deposits_per_block.is_some_and(|max| count >= max) {
+            return Err("Too many deposits from this account in one block".to_string());
+        }
+        Ok(BlockDeposits { block_height, count: count + 1 })
+    }
+
+    fn check_beneficiary_binding(&self, account_id: &AccountId, beneficiary_id: &String) -> Result<(), String> {
+        if self.require_bound_beneficiary
+            && self.bound_beneficiaries.get(beneficiary_id).as_ref() != Some(account_id)
+        {
+            return Err("Beneficiary is not bound to this account".to_string());
+        }
+        Ok(())
+    }
+
+    /// Adds a deposit to the account's cap window, rejecting it if the cap would be exceeded.
+    fn track_deposit_cap(&mut self, account_id: &AccountId, usd_value: u128) -> Result<(), String> {
+        let Some(cap) = self.deposit_cap_usd_micros else {
+            return Ok(());
+        };
+        if self.cap_exempt.contains(account_id) {
+            return Ok(());
+        }
 
-        record.into_view()
+        let now = env::block_timestamp_ms();
+        let mut window = self
+            .cap_windows
+            .get(account_id)
+            .filter(|w| now < w.window_start_ms.saturating_add(self.cap_window_ms))
+            .unwrap_or(CapWindow { window_start_ms: now, usd_total: 0 });
+        let new_total = window.usd_total.saturating_add(usd_value);
+        if new_total > cap {
+            return Err(format!(
+                "Deposit cap exceeded: ${} USD per window, ${} USD remaining",
+                format_usd(cap),
+                format_usd(cap.saturating_sub(window.usd_total))
+            ));
+        }
+        window.usd_total = new_total;
+        self.cap_windows.insert(account_id, &window);
+        Ok(())
     }
 
     fn usd_value_for(&self, cfg: &TokenConfig, amount: u128) -> u128 {
         if cfg.price_usd_micros == 0 { return 0; }
         let denominator = 10u128.pow(cfg.decimals as u32);
-        if denominator == 0 { return 0; }
-        // Compute (amount / denom) * price + ((amount % denom) * price) / denom to avoid overflow
-        let whole = amount / denominator;
-        let frac = amount % denominator;
-        let part1 = whole.saturating_mul(cfg.price_usd_micros);
-        let part2 = (frac.saturating_mul(cfg.price_usd_micros)) / denominator;
-        part1.saturating_add(part2)
+        // Saturates rather than panicking; such a value always fails the deposit caps.
+        mul_div(amount, cfg.price_usd_micros, denominator).unwrap_or(u128::MAX)
     }
 
+    fn forward_ft_to_treasury(&self, token_id: AccountId, amount: U128, deposit_id: u64) -> Promise {
+        self.transfer_ft_split(token_id, amount.0, Some(format!("deposit {}", deposit_id)))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_FORWARD_CALLBACK)
+                    .on_ft_forwarded(deposit_id),
+            )
+    }
+
+    /// Divides an amount across the treasury splits. The last split absorbs rounding dust.
+    fn split_amount(&self, amount: u128) -> Vec<(AccountId, u128)> {
+        if self.treasury_splits.is_empty() {
+            return vec![(self.treasury_account_id.clone(), amount)];
+        }
+        let weights: Vec<u16> = self.treasury_splits.iter().map(|split| split.bps).collect();
+        self.treasury_splits
+            .iter()
+            .zip(split_by_bps(amount, &weights))
+            .map(|(split, share)| (split.account_id.clone(), share))
+            .filter(|(_, share)| *share > 0)
+            .collect()
+    }
+
+    /// One `ft_transfer` per treasury split, joined into a single promise.
+    fn transfer_ft_split(&self, token_id: AccountId, amount: u128, memo: Option<String>) -> Promise {
+        self.split_amount(amount)
+            .into_iter()
+            .map(|(receiver, share)| {
+                ext_ft::ext(token_id.clone())
+                    .with_attached_deposit(NearToken::from_yoctonear(1))
+                    .with_static_gas(GAS_FOR_FT_TRANSFER)
+                    .ft_transfer(receiver, U128(share), memo.clone())
+            })
+            .reduce(|joined, transfer| joined.and(transfer))
+            .unwrap_or_else(|| env::panic_str("Nothing to transfer"))
+    }
+
+    fn internal_set_price(&mut self, token_id: &String, price_usd_micros: u128, force: bool) {
+        let mut cfg = self
+            .token_configs
+            .get(token_id)
+            .expect("Token config not found");
+
+        // First price for a token has nothing to compare against.
+        let previous = cfg.price_usd_micros;
+        if !force && previous > 0 && self.max_price_deviation_bps > 0 {
+            let deviation = previous.abs_diff(price_usd_micros);
+            require!(
+                deviation.saturating_mul(BPS_DENOMINATOR)
+                    <= previous.saturating_mul(self.max_price_deviation_bps as u128),
+                format!(
+                    "Price for {} moves more than {} bps from {}; owner must force the update",
+                    token_id, self.max_price_deviation_bps, previous
+                )
+            );
+        }
+
+        cfg.price_usd_micros = price_usd_micros;
+        cfg.last_updated = env::block_timestamp_ms();
+        self.token_configs.insert(token_id, &cfg);
+        PriceUpdated {
+            token_id: token_id.clone(),
+            price_usd_micros: U128(price_usd_micros),
+            previous_price_usd_micros: U128(previous),
+            forced: force,
+        }
+        .emit();
+    }
+
+    /// Only the owner may bypass the price deviation guard.
+    fn assert_force_allowed(&self, force: Option<bool>) -> bool {
+        let force = force.unwrap_or(false);
+        if force {
+            require!(env::predecessor_account_id() == self.owner_id, "Only the owner can force a price update");
+        }
+        force
+    }
+
+    fn page_limit(limit: Option<u64>) -> u64 {
+        limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT)
+    }
+
+    /// Owner or an account holding the admin role.
     fn assert_owner(&self) {
         require!(
-            env::predecessor_account_id() == self.owner_id,
+            self.access.is_authorized(&self.owner_id, Role::Admin, &env::predecessor_account_id()),
             "Only the owner can call this method"
         );
     }
 
-    /// Owner-only: sweep FT balances held by this contract to the treasury.
-    /// Some FT deposits may leave balances in this contract; use this to forward them.
+    fn assert_role(&self, role: Role) {
+        self.access.assert_role(&self.owner_id, role);
+    }
+
+    fn assert_can_manage_role(&self, role: Role) {
+        if role == Role::Admin {
+            require!(env::predecessor_account_id() == self.owner_id, "Only the owner can manage the admin role");
+        } else {
+            self.assert_owner();
+        }
+    }
+
+    fn assert_backend(&self) {
+        require!(
+            self.backend_account_id.as_ref() == Some(&env::predecessor_account_id()),
+            "Only the backend account can call this method"
+        );
+    }
+
+    fn assert_owner_or_keeper(&self) {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.price_keepers.contains(&caller) || self.access.is_authorized(&self.owner_id, Role::Keeper, &caller),
+            "Only the owner or a price keeper can update prices"
+        );
+    }
+
+    /// Owner or treasurer: sweep FT balances held by this contract to the treasury.
+    /// FT deposits are forwarded automatically; use this for deposits whose forward failed.
     pub fn sweep_ft(&mut self, token_id: AccountId, amount: U128) -> Promise {
-        self.assert_owner();
-        ext_ft::ext(token_id.clone())
-            .with_static_gas(GAS_FOR_FT_TRANSFER)
-            .ft_transfer(self.treasury_account_id.clone(), amount, None)
+        self.assert_role(Role::Treasurer);
+        self.transfer_ft_split(token_id, amount.0, None)
+    }
+
+    /// Owner or treasurer: register every treasury wallet on the token contract, then sweep.
+    /// The attached NEAR is split evenly across the `storage_deposit` calls; wallets that are
+    /// already registered get their share refunded to this contract by the token.
+    #[payable]
+    pub fn ensure_storage_then_sweep(&mut self, token_id: AccountId, amount: U128) -> Promise {
+        self.assert_role(Role::Treasurer);
+        let receivers = self.split_amount(amount.0);
+        let storage_share = env::attached_deposit().as_yoctonear() / receivers.len() as u128;
+        require!(storage_share > 0, "Attach NEAR to cover storage registration");
+
+        // The callback runs itself, starts one transfer per wallet and reserves gas for the report.
+        let callback_gas = GAS_FOR_SWEEP_CALLBACK
+            .saturating_add(GAS_FOR_FT_TRANSFER.saturating_mul(receivers.len() as u64))
+            .saturating_add(GAS_FOR_SWEEP_CALLBACK);
+        receivers
+            .into_iter()
+            .map(|(receiver, _)| {
+                ext_ft::ext(token_id.clone())
+                    .with_attached_deposit(NearToken::from_yoctonear(storage_share))
+                    .with_static_gas(GAS_FOR_STORAGE_DEPOSIT)
+                    .storage_deposit(Some(receiver), Some(true))
+            })
+            .reduce(|joined, registration| joined.and(registration))
+            .unwrap()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(callback_gas)
+                    .on_treasury_storage_registered(token_id, amount),
+            )
     }
 }
 
@@ -365,42 +1691,54 @@ impl FungibleTokenReceiver for DepositContract {
         amount: U128,
         msg: String,
     ) -> PromiseOrValue<U128> {
-        // Ensure non-zero deposit and cap inputs to avoid storage blow-up
-        require!(amount.0 > 0, "Amount must be > 0");
+        // Never panic here: a panic can leave the sender's tokens stuck depending on the FT
+        // implementation. Reject by returning the full amount as unused instead.
         let token_id = env::predecessor_account_id();
-        let cfg = self
-            .token_configs
-            .get(&token_id.to_string())
-            .expect("Unsupported token");
-        require!(cfg.is_enabled, "Token deposits disabled");
-        require!(cfg.price_usd_micros > 0, "Token price not configured");
-        require!(
-            env::block_timestamp_ms().saturating_sub(cfg.last_updated) <= MAX_PRICE_AGE_MS,
-            "Price data is stale (>1h). Keeper must update price."
-        );
+        let record = match self.try_record_ft_deposit(&sender_id, &token_id, amount.0, &msg) {
+            Ok(record) => record,
+            Err(reason) => {
+                events::emit(
+                    "deposit_rejected",
+                    serde_json::json!({
+                        "sender_id": sender_id,
+                        "token_id": token_id,
+                        "amount": amount,
+                        "reason": reason,
+                    }),
+                );
+                return PromiseOrValue::Value(amount);
+            }
+        };
 
-        let parsed: DepositMessage = serde_json::from_str(&msg).expect("Invalid deposit message payload");
-        require!(parsed.beneficiary_id.len() <= MAX_BENEFICIARY_LEN, "beneficiary_id too long");
-        if let Some(m) = &parsed.memo { require!(m.len() <= MAX_MEMO_LEN, "memo too long"); }
+        // Forward the tokens to the treasury right away so nothing sits in this contract.
+        // The promise is detached: returning it would make the token contract read its
+        // result as the unused amount and refund the sender.
+        self.forward_ft_to_treasury(token_id, amount, record.id);
+        PromiseOrValue::Value(U128(0))
+    }
+}
 
-        let usd_value = self.usd_value_for(&cfg, amount.0);
-        require!(
-            usd_value >= MIN_DEPOSIT_USD_MICROS,
-            "Minimum deposit is $5 USD"
-        );
+/// True when every promise this callback waited on succeeded (one per treasury split).
+fn all_promises_succeeded() -> bool {
+    (0..env::promise_results_count())
+        .all(|i| matches!(env::promise_result(i), near_sdk::PromiseResult::Successful(_)))
+}
 
-        let _record = self.store_deposit(
-            sender_id.clone(),
-            parsed.beneficiary_id,
-            token_id.to_string(),
-            amount.0,
-            usd_value,
-            parsed.credits_hint,
-            parsed.memo,
-        );
+fn promo_hash(code_hash: Base64VecU8) -> [u8; 32] {
+    code_hash
+        .0
+        .try_into()
+        .unwrap_or_else(|_| env::panic_str("Promo code hash must be 32 bytes"))
+}
 
-        // For FT deposits the tokens remain held in the contract until the owner withdraws them.
-        PromiseOrValue::Value(U128(0))
+/// Formats USD micros as dollars without trailing zeros, e.g. 5 or 2.5.
+fn format_usd(micros: u128) -> String {
+    let dollars = micros / 1_000_000;
+    let cents = micros % 1_000_000;
+    if cents == 0 {
+        dollars.to_string()
+    } else {
+        format!("{}.{:06}", dollars, cents).trim_end_matches('0').to_string()
     }
 }
 
@@ -417,6 +1755,19 @@ pub struct TokenConfigView {
     pub last_updated: u64,
     pub is_enabled: bool,
     pub is_native: bool,
+    #[schemars(with = "String")]
+    pub min_deposit_usd_micros: U128,
+    pub deposit_fee_bps: u16,
+}
+
+/// What one maintenance tick found.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "schemars")]
+pub struct TickReport {
+    pub checked: u64,
+    /// Enabled tokens whose price is unset or older than an hour.
+    pub stale_tokens: Vec<String>,
 }
 
 impl TokenConfigView {
@@ -429,6 +1780,8 @@ impl TokenConfigView {
             last_updated: cfg.last_updated,
             is_enabled: cfg.is_enabled,
             is_native: cfg.is_native,
+            min_deposit_usd_micros: U128(cfg.min_deposit_usd_micros),
+            deposit_fee_bps: cfg.deposit_fee_bps,
         }
     }
 }
@@ -437,23 +1790,179 @@ impl TokenConfigView {
 #[near_sdk::ext_contract(ext_ft)]
 pub trait ExtFungibleToken {
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>);
 }
 
 #[near]
 impl DepositContract {
-    /// Withdraw native NEAR held by the contract to the treasury (owner only).
+    /// Withdraw native NEAR held by the contract to the treasury (owner or treasurer).
     /// Safety mechanism in case forwarding fails.
     pub fn withdraw_native(
         &mut self,
         amount: U128,
         receiver_id: Option<AccountId>,
     ) {
-        self.assert_owner();
+        self.assert_role(Role::Treasurer);
         let receiver = receiver_id.unwrap_or_else(|| self.treasury_account_id.clone());
-        Promise::new(receiver).transfer(NearToken::from_yoctonear(amount.0));
+        Promise::new(receiver.clone()).transfer(NearToken::from_yoctonear(amount.0)).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_FORWARD_CALLBACK)
+                .on_withdraw_complete(NEAR_TOKEN_ID.to_string(), amount, receiver),
+        );
+    }
+
+    /// Return a pending or flagged deposit to the account that paid it (owner, treasury or
+    /// treasurer). Deposits are forwarded to the treasury on arrival, so the treasury must first
+    /// send the refund back to this contract: NEAR to its balance, FT with a plain `ft_transfer`.
+    /// FT refunds require 1 yoctoNEAR for `ft_transfer`; if the tokens are not here the transfer
+    /// fails and the deposit returns to its previous status.
+    #[payable]
+    pub fn refund_deposit(&mut self, deposit_id: u64) -> Promise {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.treasury_account_id || self.access.is_authorized(&self.owner_id, Role::Treasurer, &caller),
+            "Only the owner or treasury can refund deposits"
+        );
+
+        let record = self.deposits.get(&deposit_id).expect("Deposit not found");
+        let previous_status = self.deposit_status(deposit_id);
+        match previous_status {
+            DepositStatus::Pending | DepositStatus::Flagged => {}
+            DepositStatus::Refunded => env::panic_str("Deposit already refunded"),
+            DepositStatus::Credited => env::panic_str("Deposit was already credited"),
+        }
+        self.deposit_statuses.insert(&deposit_id, &DepositStatus::Refunded);
+        let holder = self.credits_holder(deposit_id, &record.account_id);
+        self.burn_deposit_credits(&holder, deposit_id);
+
+        let transfer = if record.token_id == NEAR_TOKEN_ID {
+            require!(
+                env::account_balance().as_yoctonear() >= record.amount.0,
+                "Insufficient contract balance for refund; fund the contract first"
+            );
+            Promise::new(record.account_id.clone()).transfer(NearToken::from_yoctonear(record.amount.0))
+        } else {
+            require!(
+                env::attached_deposit() >= NearToken::from_yoctonear(1),
+                "Attach at least 1 yoctoNEAR to cover security requirements"
+            );
+            let token_id: AccountId = record.token_id.parse().expect("Invalid token account id");
+            ext_ft::ext(token_id)
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .ft_transfer(record.account_id.clone(), record.amount, Some(format!("refund of deposit {}", deposit_id)))
+        };
+
+        DepositRefunded {
+            deposit_id,
+            account_id: record.account_id,
+            beneficiary_id: record.beneficiary_id,
+            token_id: record.token_id,
+            amount: record.amount,
+        }
+        .emit();
+
+        transfer.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_REFUND_CALLBACK)
+                .on_refund_complete(deposit_id, previous_status),
+        )
+    }
+
+    /// Flags the deposit if forwarding its tokens to the treasury failed.
+    /// The tokens stay in this contract and can be moved with `sweep_ft`.
+    #[private]
+    pub fn on_ft_forwarded(&mut self, deposit_id: u64) -> bool {
+        // One result per treasury split; any failure leaves tokens here to sweep.
+        if all_promises_succeeded() {
+            return true;
+        }
+        if self.deposit_status(deposit_id) == DepositStatus::Pending {
+            self.deposit_statuses.insert(&deposit_id, &DepositStatus::Flagged);
+        }
+        events::emit("forward_failed", serde_json::json!({ "deposit_id": deposit_id }));
+        false
+    }
+
+    /// Flags the deposit if forwarding its NEAR to the treasury failed, e.g. to a deleted wallet.
+    /// The NEAR comes back to this contract and can be moved with `withdraw_native`.
+    #[private]
+    pub fn on_native_forwarded(&mut self, deposit_id: u64) -> bool {
+        if all_promises_succeeded() {
+            return true;
+        }
+        if self.deposit_status(deposit_id) == DepositStatus::Pending {
+            self.deposit_statuses.insert(&deposit_id, &DepositStatus::Flagged);
+        }
+        events::emit("forward_failed", serde_json::json!({ "deposit_id": deposit_id, "token_id": NEAR_TOKEN_ID }));
+        false
+    }
+
+    /// Reports a failed `withdraw_native` / `withdraw_ft`; the funds stay in this contract.
+    #[private]
+    pub fn on_withdraw_complete(&mut self, token_id: String, amount: U128, receiver_id: AccountId) -> bool {
+        if near_sdk::is_promise_success() {
+            return true;
+        }
+        events::emit(
+            "withdraw_failed",
+            serde_json::json!({ "token_id": token_id, "amount": amount, "receiver_id": receiver_id }),
+        );
+        false
+    }
+
+    /// Continues `ensure_storage_then_sweep` once the treasury wallets are registered.
+    #[private]
+    pub fn on_treasury_storage_registered(&mut self, token_id: AccountId, amount: U128) -> PromiseOrValue<bool> {
+        if !all_promises_succeeded() {
+            events::emit(
+                "sweep_failed",
+                serde_json::json!({ "token_id": token_id, "amount": amount, "stage": "storage_deposit" }),
+            );
+            return PromiseOrValue::Value(false);
+        }
+        self.transfer_ft_split(token_id.clone(), amount.0, None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_SWEEP_CALLBACK)
+                    .on_sweep_complete(token_id, amount),
+            )
+            .into()
+    }
+
+    /// Reports the outcome of the transfers started by `ensure_storage_then_sweep`.
+    #[private]
+    pub fn on_sweep_complete(&mut self, token_id: AccountId, amount: U128) -> bool {
+        let succeeded = all_promises_succeeded();
+        let (event, data) = if succeeded {
+            ("sweep_completed", serde_json::json!({ "token_id": token_id, "amount": amount }))
+        } else {
+            ("sweep_failed", serde_json::json!({ "token_id": token_id, "amount": amount, "stage": "ft_transfer" }))
+        };
+        events::emit(event, data);
+        succeeded
+    }
+
+    /// Restores the deposit's status from before the refund if the transfer failed, so it can be retried.
+    #[private]
+    pub fn on_refund_complete(&mut self, deposit_id: u64, previous_status: DepositStatus) -> bool {
+        if near_sdk::is_promise_success() {
+            return true;
+        }
+        if let Some(record) = self.deposits.get(&deposit_id) {
+            let holder = self.credits_holder(deposit_id, &record.account_id);
+            self.restore_deposit_credits(&holder, deposit_id);
+        }
+        if previous_status == DepositStatus::Pending {
+            self.deposit_statuses.remove(&deposit_id);
+        } else {
+            self.deposit_statuses.insert(&deposit_id, &previous_status);
+        }
+        env::log_str(&format!("Refund of deposit {} failed; status restored to {:?}", deposit_id, previous_status));
+        false
     }
 
-    /// Withdraw fungible tokens held by the contract to the treasury (owner only).
+    /// Withdraw fungible tokens held by the contract to the treasury (owner or treasurer).
     #[payable]
     pub fn withdraw_ft(
         &mut self,
@@ -462,7 +1971,7 @@ impl DepositContract {
         receiver_id: Option<AccountId>,
         memo: Option<String>,
     ) {
-        self.assert_owner();
+        self.assert_role(Role::Treasurer);
         require!(
             env::attached_deposit() >= NearToken::from_yoctonear(1),
             "Attach at least 1 yoctoNEAR to cover security requirements"
@@ -473,13 +1982,20 @@ impl DepositContract {
         ext_ft::ext(token_id.clone())
             .with_attached_deposit(NearToken::from_yoctonear(1))
             .with_static_gas(GAS_FOR_FT_TRANSFER)
-            .ft_transfer(receiver, amount, memo);
+            .ft_transfer(receiver.clone(), amount, memo)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_FORWARD_CALLBACK)
+                    .on_withdraw_complete(token_id.to_string(), amount, receiver),
+            );
     }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
     mod tests {
     use super::*;
+    use near_contract_standards::fungible_token::core::FungibleTokenCore;
+    use near_contract_standards::fungible_token::metadata::FungibleTokenMetadataProvider;
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::testing_env;
 
@@ -492,6 +2008,17 @@ impl DepositContract {
         testing_env!(builder.build());
     }
 
+    fn assert_ft_rejected(result: PromiseOrValue<U128>, amount: u128, reason: &str) {
+        match result {
+            PromiseOrValue::Value(v) => assert_eq!(v.0, amount, "full amount should be returned"),
+            _ => panic!("Expected Value variant"),
+        }
+        let logs = near_sdk::test_utils::get_logs();
+        let last = logs.last().expect("rejection event");
+        assert!(last.contains("\"event\":\"deposit_rejected\""));
+        assert!(last.contains(reason));
+    }
+
     fn init_contract() -> DepositContract {
         setup_context(0, accounts(0));
         DepositContract::new(accounts(0), accounts(1))
@@ -570,7 +2097,7 @@ impl DepositContract {
         let mut contract = init_contract();
         
         // Update NEAR price
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(5_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(5_000_000), None);
         
         let config = contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap();
         assert_eq!(config.price_usd_micros.0, 5_000_000);
@@ -580,113 +2107,504 @@ impl DepositContract {
     #[should_panic(expected = "Token config not found")]
     fn test_update_nonexistent_token_price_fails() {
         let mut contract = init_contract();
-        contract.update_token_price("nonexistent.token".to_string(), U128(1_000_000));
+        contract.update_token_price("nonexistent.token".to_string(), U128(1_000_000), None);
     }
 
     #[test]
-    #[should_panic(expected = "Only the owner can call this method")]
+    #[should_panic(expected = "Only the owner or a price keeper can update prices")]
     fn test_update_token_price_non_owner_fails() {
         let mut contract = init_contract();
         setup_context(0, accounts(2));
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(5_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(5_000_000), None);
     }
 
     #[test]
-    fn test_list_token_configs() {
+    fn test_keeper_can_update_price() {
         let mut contract = init_contract();
-        
-        contract.upsert_token_config(
-            "usdc.token".to_string(),
-            "USDC".to_string(),
-            6,
-            U128(1_000_000),
-            true,
-            false,
-        );
-        
-        let configs = contract.list_token_configs();
-        assert!(configs.len() >= 2); // NEAR + USDC
-        
-        let has_near = configs.iter().any(|c| c.token_id == NEAR_TOKEN_ID);
-        let has_usdc = configs.iter().any(|c| c.token_id == "usdc.token");
-        assert!(has_near);
-        assert!(has_usdc);
+        contract.add_keeper(accounts(2));
+        assert_eq!(contract.get_price_keepers(), vec![accounts(2)]);
+
+        setup_context(0, accounts(2));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(4_000_000), None);
+        let config = contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap();
+        assert_eq!(config.price_usd_micros.0, 4_000_000);
     }
 
     #[test]
-    fn test_set_treasury() {
+    fn test_tick_reports_stale_prices() {
         let mut contract = init_contract();
-        let new_treasury = accounts(5);
-        
-        contract.set_treasury(new_treasury.clone());
-        assert_eq!(contract.treasury_account_id, new_treasury);
+        contract.grant_role(Role::Keeper, accounts(3));
+        setup_context_at(accounts(0), 1_000);
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(4_000_000), None);
+
+        setup_context_at(accounts(3), 1_000 + MAX_PRICE_AGE_MS);
+        assert_eq!(contract.tick().stale_tokens, Vec::<String>::new());
+        setup_context_at(accounts(3), 1_001 + MAX_PRICE_AGE_MS);
+        let report = contract.tick();
+        assert!(report.stale_tokens.contains(&NEAR_TOKEN_ID.to_string()));
+        assert!(near_sdk::test_utils::get_logs().last().unwrap().contains("\"event\":\"maintenance_tick\""));
     }
 
     #[test]
-    #[should_panic(expected = "Only the owner can call this method")]
-    fn test_set_treasury_non_owner_fails() {
+    #[should_panic(expected = "Only the owner or a keeper can tick")]
+    fn test_tick_requires_keeper() {
         let mut contract = init_contract();
         setup_context(0, accounts(2));
-        contract.set_treasury(accounts(5));
+        contract.tick();
     }
 
-    // ========================================
-    // Native NEAR Deposit Tests
-    // ========================================
-
     #[test]
-    fn test_native_deposit_records() {
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_keeper_cannot_change_token_config() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000)); // $1 per NEAR
+        contract.add_keeper(accounts(2));
 
-        // Attach 6 NEAR (in yocto) to exceed $5 threshold.
-        let six_near = 6u128 * 10u128.pow(24);
-        setup_context(six_near, accounts(2));
-        let receipt = contract.deposit_native("user-123".to_string(), Some(250), None);
+        setup_context(0, accounts(2));
+        contract.upsert_token_config(
+            NEAR_TOKEN_ID.to_string(),
+            "NEAR".to_string(),
+            24,
+            U128(4_000_000),
+            false,
+            true,
+        );
+    }
 
-        assert_eq!(receipt.account_id, accounts(2));
-        assert_eq!(receipt.usd_value.0, 6 * 1_000_000);
-        assert_eq!(receipt.credits_hint, Some(250));
-        assert_eq!(receipt.token_id, NEAR_TOKEN_ID);
-        assert_eq!(receipt.beneficiary_id, "user-123");
-        assert_eq!(receipt.id, 0);
-        // timestamp_ms is 0 in test environment
-        assert_eq!(receipt.timestamp_ms, 0);
+    #[test]
+    #[should_panic(expected = "Only the owner or a price keeper can update prices")]
+    fn test_removed_keeper_cannot_update_price() {
+        let mut contract = init_contract();
+        contract.add_keeper(accounts(2));
+        contract.remove_keeper(accounts(2));
 
-        let deposits = contract.get_deposits_for_account(accounts(2));
-        assert_eq!(deposits.len(), 1);
-        assert_eq!(deposits[0].usd_value.0, receipt.usd_value.0);
+        setup_context(0, accounts(2));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(4_000_000), None);
     }
 
     #[test]
-    fn test_native_deposit_with_memo() {
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_add_keeper_non_owner_fails() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000));
+        setup_context(0, accounts(2));
+        contract.add_keeper(accounts(2));
+    }
 
-        let five_near = 5u128 * 10u128.pow(24);
-        setup_context(five_near, accounts(2));
-        let receipt = contract.deposit_native(
-            "user-456".to_string(),
-            Some(500),
-            Some("Premium subscription".to_string()),
-        );
+    #[test]
+    fn test_batch_price_update() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(0), true, false);
+        contract.add_keeper(accounts(2));
 
-        assert_eq!(receipt.memo, Some("Premium subscription".to_string()));
-        assert_eq!(receipt.usd_value.0, 10_000_000); // 5 NEAR * $2
+        setup_context(0, accounts(2));
+        contract.update_token_prices(
+            vec![
+                (NEAR_TOKEN_ID.to_string(), U128(2_500_000)),
+                ("usdt.token".to_string(), U128(1_000_000)),
+            ],
+            None,
+        );
+        assert_eq!(contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap().price_usd_micros.0, 2_500_000);
+        assert_eq!(contract.get_token_config("usdt.token".to_string()).unwrap().price_usd_micros.0, 1_000_000);
     }
 
     #[test]
-    fn test_multiple_deposits_increment_id() {
+    #[should_panic(expected = "moves more than 2000 bps")]
+    fn test_price_deviation_rejected() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_500_000), None);
+        contract.add_keeper(accounts(2));
 
-        let six_near = 6u128 * 10u128.pow(24);
-        
+        // $2500 instead of $2.50
+        setup_context(0, accounts(2));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_500_000_000), None);
+    }
+
+    #[test]
+    fn test_price_within_deviation_accepted() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_500_000), None);
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(3_000_000), None); // +20%
+        assert_eq!(contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap().price_usd_micros.0, 3_000_000);
+    }
+
+    #[test]
+    fn test_owner_can_force_price() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_500_000), None);
+        contract.update_token_prices(vec![(NEAR_TOKEN_ID.to_string(), U128(10_000_000))], Some(true));
+        assert_eq!(contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap().price_usd_micros.0, 10_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can force a price update")]
+    fn test_keeper_cannot_force_price() {
+        let mut contract = init_contract();
+        contract.add_keeper(accounts(2));
+        setup_context(0, accounts(2));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), Some(true));
+    }
+
+    #[test]
+    fn test_deviation_guard_can_be_disabled() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_500_000), None);
+        contract.set_max_price_deviation(0);
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(25_000_000), None);
+        assert_eq!(contract.get_max_price_deviation(), 0);
+    }
+
+    fn token_metadata(symbol: &str, decimals: u8) -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            spec: "ft-1.0.0".to_string(),
+            name: symbol.to_string(),
+            symbol: symbol.to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals,
+        }
+    }
+
+    fn setup_callback(result: near_sdk::PromiseResult) {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(accounts(0)).current_account_id(accounts(0));
+        testing_env!(
+            builder.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+    }
+
+    #[test]
+    fn test_register_token_stores_fetched_metadata() {
+        let mut contract = init_contract();
+        contract.register_token("usdc.token".parse().unwrap());
+
+        let metadata = token_metadata("USDC", 6);
+        setup_callback(near_sdk::PromiseResult::Successful(serde_json::to_vec(&metadata).unwrap()));
+        let view = contract.on_token_metadata("usdc.token".parse().unwrap(), Ok(metadata));
+        assert_eq!(view.symbol, "USDC");
+        assert_eq!(view.decimals, 6);
+        assert_eq!(view.price_usd_micros.0, 0);
+        assert!(!view.is_enabled);
+
+        // Owner only sets price and enabled flag
+        setup_context(0, accounts(0));
+        contract.update_token_price("usdc.token".to_string(), U128(1_000_000), None);
+        contract.set_token_enabled("usdc.token".to_string(), true);
+        let config = contract.get_token_config("usdc.token".to_string()).unwrap();
+        assert!(config.is_enabled);
+        assert_eq!(config.decimals, 6);
+    }
+
+    #[test]
+    fn test_register_token_refresh_keeps_price() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdc.token".to_string(), "USDX".to_string(), 18, U128(1_000_000), true, false);
+
+        let metadata = token_metadata("USDC", 6);
+        setup_callback(near_sdk::PromiseResult::Successful(serde_json::to_vec(&metadata).unwrap()));
+        let view = contract.on_token_metadata("usdc.token".parse().unwrap(), Ok(metadata));
+        assert_eq!(view.symbol, "USDC");
+        assert_eq!(view.decimals, 6);
+        assert_eq!(view.price_usd_micros.0, 1_000_000);
+        assert!(view.is_enabled);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token decimals out of range")]
+    fn test_register_token_rejects_oversized_decimals() {
+        let mut contract = init_contract();
+        let metadata = token_metadata("BIG", 60);
+        setup_callback(near_sdk::PromiseResult::Successful(serde_json::to_vec(&metadata).unwrap()));
+        contract.on_token_metadata("big.token".parse().unwrap(), Ok(metadata));
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to fetch token metadata")]
+    fn test_register_token_metadata_failure() {
+        let mut contract = init_contract();
+        setup_callback(near_sdk::PromiseResult::Failed);
+        contract.on_token_metadata("usdc.token".parse().unwrap(), Err(PromiseError::Failed));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_register_token_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(2));
+        contract.register_token("usdc.token".parse().unwrap());
+    }
+
+    #[test]
+    fn test_per_token_minimum_deposit() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdc.token".to_string(), "USDC".to_string(), 6, U128(1_000_000), true, false);
+        contract.set_token_limits("usdc.token".to_string(), U128(1_000_000), 0);
+        assert_eq!(contract.get_token_config("usdc.token".to_string()).unwrap().min_deposit_usd_micros.0, 1_000_000);
+
+        // $2 is below the global default but above this stablecoin's $1 floor
+        setup_context(0, "usdc.token".parse().unwrap());
+        let result = contract.ft_on_transfer(accounts(3), U128(2_000_000), r#"{"beneficiary_id":"u"}"#.to_string());
+        match result { PromiseOrValue::Value(v) => assert_eq!(v.0, 0), _ => panic!("Expected Value") }
+        assert_eq!(contract.get_deposits_for_account(accounts(3)).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Minimum deposit is $25 USD")]
+    fn test_raised_minimum_rejects_native_deposit() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_token_limits(NEAR_TOKEN_ID.to_string(), U128(25_000_000), 0);
+
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user".to_string(), None, None, None, None);
+    }
+
+    #[test]
+    fn test_deposit_fee_recorded_and_excluded_from_credits() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_token_limits(NEAR_TOKEN_ID.to_string(), U128(MIN_DEPOSIT_USD_MICROS), 250); // 2.5%
+        contract.set_credits_rate(U128(100));
+
+        setup_context(10 * 10u128.pow(24), accounts(2));
+        let view = contract.deposit_native("user".to_string(), None, None, None, None);
+        assert_eq!(view.usd_value.0, 10_000_000);
+        assert_eq!(view.fee_usd_value.0, 250_000);
+        // $9.75 credited at 100 credits per $1
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 975);
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposit fee cannot exceed 10%")]
+    fn test_deposit_fee_capped() {
+        let mut contract = init_contract();
+        contract.set_token_limits(NEAR_TOKEN_ID.to_string(), U128(MIN_DEPOSIT_USD_MICROS), 1_001);
+    }
+
+    #[test]
+    fn test_format_usd() {
+        assert_eq!(format_usd(5_000_000), "5");
+        assert_eq!(format_usd(2_500_000), "2.5");
+        assert_eq!(format_usd(10_000), "0.01");
+    }
+
+    #[test]
+    fn test_list_token_configs() {
+        let mut contract = init_contract();
+        
+        contract.upsert_token_config(
+            "usdc.token".to_string(),
+            "USDC".to_string(),
+            6,
+            U128(1_000_000),
+            true,
+            false,
+        );
+        
+        let configs = contract.list_token_configs();
+        assert!(configs.len() >= 2); // NEAR + USDC
+        
+        let has_near = configs.iter().any(|c| c.token_id == NEAR_TOKEN_ID);
+        let has_usdc = configs.iter().any(|c| c.token_id == "usdc.token");
+        assert!(has_near);
+        assert!(has_usdc);
+    }
+
+    fn change_treasury(contract: &mut DepositContract, treasury: AccountId) {
+        setup_context(0, accounts(0));
+        contract.propose_treasury(treasury.clone());
+        setup_context(0, treasury);
+        contract.accept_treasury();
+    }
+
+    #[test]
+    fn test_two_step_treasury_change() {
+        let mut contract = init_contract();
+        let new_treasury = accounts(5);
+
+        contract.propose_treasury(new_treasury.clone());
+        assert_eq!(contract.get_treasury(), accounts(1), "treasury changes only on accept");
+        assert_eq!(contract.get_pending_treasury().unwrap().account_id, new_treasury);
+
+        setup_context(0, new_treasury.clone());
+        contract.accept_treasury();
+        assert_eq!(contract.treasury_account_id, new_treasury);
+        assert!(contract.get_pending_treasury().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_propose_treasury_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(2));
+        contract.propose_treasury(accounts(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposed treasury can accept")]
+    fn test_accept_treasury_by_other_account_fails() {
+        let mut contract = init_contract();
+        contract.propose_treasury(accounts(5));
+        setup_context(0, accounts(2));
+        contract.accept_treasury();
+    }
+
+    fn setup_context_at(predecessor: AccountId, timestamp_ms: u64) {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .predecessor_account_id(predecessor)
+            .block_timestamp(timestamp_ms * 1_000_000);
+        testing_env!(builder.build());
+    }
+
+    #[test]
+    #[should_panic(expected = "Treasury timelock has not passed")]
+    fn test_treasury_timelock_blocks_early_accept() {
+        let mut contract = init_contract();
+        contract.set_treasury_timelock(60_000);
+        contract.propose_treasury(accounts(5));
+        assert_eq!(contract.get_pending_treasury().unwrap().accept_after_ms, 60_000);
+
+        setup_context_at(accounts(5), 30_000);
+        contract.accept_treasury();
+    }
+
+    #[test]
+    fn test_treasury_accepted_after_timelock() {
+        let mut contract = init_contract();
+        contract.set_treasury_timelock(60_000);
+        contract.propose_treasury(accounts(5));
+
+        setup_context_at(accounts(5), 60_000);
+        contract.accept_treasury();
+        assert_eq!(contract.get_treasury(), accounts(5));
+    }
+
+    #[test]
+    fn test_treasury_timelock_decrease_is_delayed() {
+        let mut contract = init_contract();
+        contract.set_treasury_timelock(60_000);
+        contract.set_treasury_timelock(0);
+        assert_eq!(contract.get_treasury_timelock(), 60_000);
+        contract.propose_treasury(accounts(5));
+        assert_eq!(contract.get_pending_treasury().unwrap().accept_after_ms, 60_000);
+
+        setup_context_at(accounts(0), 60_000);
+        assert_eq!(contract.get_treasury_timelock(), 0);
+        contract.propose_treasury(accounts(5));
+        assert_eq!(contract.get_pending_treasury().unwrap().accept_after_ms, 60_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "No pending treasury")]
+    fn test_cancel_treasury_proposal() {
+        let mut contract = init_contract();
+        contract.propose_treasury(accounts(5));
+        contract.cancel_treasury_proposal();
+        setup_context(0, accounts(5));
+        contract.accept_treasury();
+    }
+
+    #[test]
+    fn test_two_step_ownership_transfer() {
+        let mut contract = init_contract();
+        contract.propose_owner(accounts(3));
+        assert_eq!(contract.get_owner(), accounts(0));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(3)));
+
+        setup_context(0, accounts(3));
+        contract.accept_ownership();
+        assert_eq!(contract.get_owner(), accounts(3));
+        assert!(contract.get_pending_owner().is_none());
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs.last().unwrap().contains("\"event\":\"ownership_transferred\""));
+
+        // The new owner holds owner rights; the old one no longer does.
+        contract.set_backend_account(Some(accounts(4)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposed owner can accept ownership")]
+    fn test_accept_ownership_without_proposal_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(3));
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_old_owner_loses_rights_after_transfer() {
+        let mut contract = init_contract();
+        contract.propose_owner(accounts(3));
+        setup_context(0, accounts(3));
+        contract.accept_ownership();
+        setup_context(0, accounts(0));
+        contract.propose_owner(accounts(0));
+    }
+
+    // ========================================
+    // Native NEAR Deposit Tests
+    // ========================================
+
+    #[test]
+    fn test_native_deposit_records() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None); // $1 per NEAR
+
+        // Attach 6 NEAR (in yocto) to exceed $5 threshold.
+        let six_near = 6u128 * 10u128.pow(24);
+        setup_context(six_near, accounts(2));
+        let receipt = contract.deposit_native("user-123".to_string(), Some(250), None, None, None);
+
+        assert_eq!(receipt.account_id, accounts(2));
+        assert_eq!(receipt.usd_value.0, 6 * 1_000_000);
+        assert_eq!(receipt.credits_hint, Some(250));
+        assert_eq!(receipt.token_id, NEAR_TOKEN_ID);
+        assert_eq!(receipt.beneficiary_id, "user-123");
+        assert_eq!(receipt.id, 0);
+        // timestamp_ms is 0 in test environment
+        assert_eq!(receipt.timestamp_ms, 0);
+
+        let deposits = contract.get_deposits_for_account(accounts(2));
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(deposits[0].usd_value.0, receipt.usd_value.0);
+    }
+
+    #[test]
+    fn test_native_deposit_with_memo() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000), None);
+
+        let five_near = 5u128 * 10u128.pow(24);
+        setup_context(five_near, accounts(2));
+        let receipt = contract.deposit_native(
+            "user-456".to_string(),
+            Some(500),
+            Some("Premium subscription".to_string()),
+            None,
+            None,
+        );
+
+        assert_eq!(receipt.memo, Some("Premium subscription".to_string()));
+        assert_eq!(receipt.usd_value.0, 10_000_000); // 5 NEAR * $2
+    }
+
+    #[test]
+    fn test_multiple_deposits_increment_id() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+
+        let six_near = 6u128 * 10u128.pow(24);
+        
         setup_context(six_near, accounts(2));
-        let receipt1 = contract.deposit_native("user-1".to_string(), None, None);
+        let receipt1 = contract.deposit_native("user-1".to_string(), None, None, None, None);
         
         setup_context(six_near, accounts(3));
-        let receipt2 = contract.deposit_native("user-2".to_string(), None, None);
+        let receipt2 = contract.deposit_native("user-2".to_string(), None, None, None, None);
         
         assert_eq!(receipt1.id, 0);
         assert_eq!(receipt2.id, 1);
@@ -695,11 +2613,11 @@ impl DepositContract {
     #[test]
     fn test_deposit_retrieval_by_id() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        let receipt = contract.deposit_native("user-789".to_string(), Some(300), None);
+        let receipt = contract.deposit_native("user-789".to_string(), Some(300), None, None, None);
 
         let retrieved = contract.get_deposit(receipt.id);
         assert!(retrieved.is_some());
@@ -719,14 +2637,14 @@ impl DepositContract {
     #[test]
     fn test_deposits_by_account_multiple() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user-a".to_string(), None, None);
-        contract.deposit_native("user-b".to_string(), None, None);
-        contract.deposit_native("user-c".to_string(), None, None);
+        contract.deposit_native("user-a".to_string(), None, None, None, None);
+        contract.deposit_native("user-b".to_string(), None, None, None, None);
+        contract.deposit_native("user-c".to_string(), None, None, None, None);
 
         let deposits = contract.get_deposits_for_account(accounts(2));
         assert_eq!(deposits.len(), 3);
@@ -735,6 +2653,99 @@ impl DepositContract {
         assert_eq!(deposits[2].beneficiary_id, "user-c");
     }
 
+    #[test]
+    fn test_get_deposits_paginated() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+
+        let six_near = 6u128 * 10u128.pow(24);
+        setup_context(six_near, accounts(2));
+        for beneficiary in ["a", "b", "c", "d", "e"] {
+            contract.deposit_native(beneficiary.to_string(), None, None, None, None);
+        }
+
+        let page = contract.get_deposits_paginated(accounts(2), Some(1), Some(2));
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].beneficiary_id, "b");
+        assert_eq!(page[1].beneficiary_id, "c");
+
+        let tail = contract.get_deposits_paginated(accounts(2), Some(4), None);
+        assert_eq!(tail.len(), 1);
+        assert!(contract.get_deposits_paginated(accounts(3), None, None).is_empty());
+
+        assert_eq!(contract.get_deposit_count(Some(accounts(2))), 5);
+        assert_eq!(contract.get_deposit_count(Some(accounts(3))), 0);
+        assert_eq!(contract.get_deposit_count(None), 5);
+    }
+
+    #[test]
+    fn test_get_deposits_for_beneficiary() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+
+        let six_near = 6u128 * 10u128.pow(24);
+        for payer in [accounts(2), accounts(3), accounts(2)] {
+            setup_context(six_near, payer);
+            contract.deposit_native("user-a".to_string(), None, None, None, None);
+        }
+        contract.deposit_native("user-b".to_string(), None, None, None, None);
+
+        let deposits = contract.get_deposits_for_beneficiary("user-a".to_string(), None, None);
+        let payers: Vec<AccountId> = deposits.iter().map(|d| d.account_id.clone()).collect();
+        assert_eq!(payers, vec![accounts(2), accounts(3), accounts(2)]);
+
+        let page = contract.get_deposits_for_beneficiary("user-a".to_string(), Some(1), Some(1));
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, 1);
+        assert_eq!(contract.get_deposits_for_beneficiary("user-b".to_string(), None, None).len(), 1);
+        assert!(contract.get_deposits_for_beneficiary("user-c".to_string(), None, None).is_empty());
+    }
+
+    #[test]
+    fn test_beneficiary_index_keeps_most_recent_deposits() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+
+        for _ in 0..MAX_BENEFICIARY_DEPOSITS + 2 {
+            setup_context(6u128 * 10u128.pow(24), accounts(2));
+            contract.deposit_native("user-a".to_string(), None, None, None, None);
+        }
+
+        let ids = contract.deposits_by_beneficiary.get(&"user-a".to_string()).unwrap();
+        assert_eq!(ids.len(), MAX_BENEFICIARY_DEPOSITS);
+        assert_eq!(ids[0], 2);
+        let first = contract.get_deposits_for_beneficiary("user-a".to_string(), None, Some(1));
+        assert_eq!(first[0].id, 2);
+    }
+
+    #[test]
+    fn test_get_deposits_in_range() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+
+        let six_near = 6u128 * 10u128.pow(24);
+        for (i, ts_ms) in [1_000u64, 2_000, 3_000, 4_000].iter().enumerate() {
+            let mut builder = VMContextBuilder::new();
+            builder
+                .attached_deposit(NearToken::from_yoctonear(six_near))
+                .predecessor_account_id(accounts(2 + (i % 2)))
+                .block_timestamp(ts_ms * 1_000_000);
+            testing_env!(builder.build());
+            contract.deposit_native(format!("user-{}", i), None, None, None, None);
+        }
+
+        let in_range = contract.get_deposits_in_range(2_000, 3_000, None, None);
+        assert_eq!(in_range.iter().map(|d| d.id).collect::<Vec<_>>(), vec![1, 2]);
+
+        // Resume from a cursor with a page size of one
+        let first = contract.get_deposits_in_range(0, u64::MAX, None, Some(1));
+        assert_eq!(first[0].id, 0);
+        let next = contract.get_deposits_in_range(0, u64::MAX, Some(first[0].id + 1), Some(1));
+        assert_eq!(next[0].id, 1);
+
+        assert!(contract.get_deposits_in_range(5_000, 6_000, None, None).is_empty());
+    }
+
     #[test]
     fn test_empty_deposits_for_new_account() {
         let contract = init_contract();
@@ -747,21 +2758,21 @@ impl DepositContract {
     #[should_panic(expected = "Minimum deposit is $5 USD")]
     fn test_native_deposit_below_min_rejected() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000)); // $2 per NEAR
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000), None); // $2 per NEAR
 
         let two_near = 2u128 * 10u128.pow(24);
         setup_context(two_near, accounts(2));
-        let _ = contract.deposit_native("user".to_string(), None, None);
+        let _ = contract.deposit_native("user".to_string(), None, None, None, None);
     }
 
     #[test]
     #[should_panic(expected = "Attach NEAR to deposit")]
     fn test_native_deposit_zero_amount_fails() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         
         setup_context(0, accounts(2));
-        contract.deposit_native("user".to_string(), None, None);
+        contract.deposit_native("user".to_string(), None, None, None, None);
     }
 
     #[test]
@@ -772,7 +2783,7 @@ impl DepositContract {
         
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user".to_string(), None, None);
+        contract.deposit_native("user".to_string(), None, None, None, None);
     }
 
     #[test]
@@ -792,18 +2803,18 @@ impl DepositContract {
         
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user".to_string(), None, None);
+        contract.deposit_native("user".to_string(), None, None, None, None);
     }
 
     #[test]
     fn test_native_deposit_exactly_at_minimum() {
         let mut contract = init_contract();
         // Set price so that deposit equals exactly $5
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         
         let five_near = 5u128 * 10u128.pow(24);
         setup_context(five_near, accounts(2));
-        let receipt = contract.deposit_native("user".to_string(), None, None);
+        let receipt = contract.deposit_native("user".to_string(), None, None, None, None);
         
         assert_eq!(receipt.usd_value.0, MIN_DEPOSIT_USD_MICROS);
     }
@@ -846,6 +2857,9 @@ impl DepositContract {
             beneficiary_id: "user-321".to_string(),
             credits_hint: Some(500),
             memo: Some("stablecoin deposit".to_string()),
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
 
@@ -863,22 +2877,70 @@ impl DepositContract {
     }
 
     #[test]
-        fn test_ft_deposit_minimal_message() {
+    fn test_ft_deposit_records_intent_settlement() {
         let mut contract = init_contract();
-        contract.upsert_token_config(
-            "dai.token".to_string(),
-            "DAI".to_string(),
-            18,
-            U128(1_000_000),
-            true,
-            false,
-        );
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = serde_json::json!({
+            "beneficiary_id": "user-321",
+            "intent": { "solver_id": "solver.near", "quote_id": "q-42", "original_asset": "nep141:wrap.near" }
+        });
+        let result = contract.ft_on_transfer("intents.near".parse().unwrap(), U128(7_000_000), msg.to_string());
+        assert!(matches!(result, PromiseOrValue::Value(U128(0))));
+
+        let deposit = contract.get_deposit(0).unwrap();
+        let intent = deposit.intent.expect("intent settlement");
+        assert_eq!(intent.solver_id, "solver.near".parse::<AccountId>().unwrap());
+        assert_eq!(intent.quote_id, "q-42");
+        assert_eq!(intent.original_asset, "nep141:wrap.near");
+        let logs = near_sdk::test_utils::get_logs();
+        let recorded = logs.iter().find(|log| log.contains("\"event\":\"deposit_recorded\"")).unwrap();
+        assert!(recorded.contains("\"solver_id\":\"solver.near\""));
+        assert!(recorded.contains("\"quote_id\":\"q-42\""));
+
+        // Direct transfers carry no settlement
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = serde_json::json!({ "beneficiary_id": "user-321" });
+        contract.ft_on_transfer(accounts(3), U128(7_000_000), msg.to_string());
+        assert!(contract.get_deposit(1).unwrap().intent.is_none());
+    }
+
+    #[test]
+    fn test_ft_deposit_rejects_incomplete_intent() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = serde_json::json!({
+            "beneficiary_id": "user-321",
+            "intent": { "solver_id": "solver.near", "quote_id": "", "original_asset": "nep141:wrap.near" }
+        });
+        let result = contract.ft_on_transfer("intents.near".parse().unwrap(), U128(7_000_000), msg.to_string());
+        assert_ft_rejected(result, 7_000_000, "Intent settlement needs a quote_id and original_asset");
+        assert!(contract.get_deposit(0).is_none());
+    }
+
+    #[test]
+        fn test_ft_deposit_minimal_message() {
+        let mut contract = init_contract();
+        contract.upsert_token_config(
+            "dai.token".to_string(),
+            "DAI".to_string(),
+            18,
+            U128(1_000_000),
+            true,
+            false,
+        );
 
         setup_context(0, "dai.token".parse().unwrap());
         let msg = serde_json::to_string(&DepositMessage {
             beneficiary_id: "user-999".to_string(),
             credits_hint: None,
             memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
 
@@ -897,8 +2959,7 @@ impl DepositContract {
     }
 
     #[test]
-    #[should_panic(expected = "Amount must be > 0")]
-    fn test_ft_deposit_zero_amount_fails() {
+    fn test_ft_deposit_zero_amount_rejected() {
         let mut contract = init_contract();
         contract.upsert_token_config(
             "usdt.token".to_string(),
@@ -909,30 +2970,40 @@ impl DepositContract {
             false,
         );
         setup_context(0, "usdt.token".parse().unwrap());
-        let msg = serde_json::to_string(&DepositMessage { beneficiary_id: "user".to_string(), credits_hint: None, memo: None }).unwrap();
-        contract.ft_on_transfer(accounts(3), U128(0), msg);
+        let msg = serde_json::to_string(&DepositMessage {
+            beneficiary_id: "user".to_string(),
+            credits_hint: None,
+            memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
+        })
+        .unwrap();
+        let result = contract.ft_on_transfer(accounts(3), U128(0), msg);
+        assert_ft_rejected(result, 0, "Amount must be > 0");
+        assert!(contract.get_deposits_for_account(accounts(3)).is_empty());
     }
 
     #[test]
     #[should_panic(expected = "beneficiary_id too long")]
     fn test_beneficiary_too_long_native() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
         let long = "x".repeat(MAX_BENEFICIARY_LEN + 1);
-        contract.deposit_native(long, None, None);
+        contract.deposit_native(long, None, None, None, None);
     }
 
     #[test]
     #[should_panic(expected = "memo too long")]
     fn test_memo_too_long_native() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
         let memo = "y".repeat(MAX_MEMO_LEN + 1);
-        contract.deposit_native("user".to_string(), None, Some(memo));
+        contract.deposit_native("user".to_string(), None, Some(memo), None, None);
     }
 
     #[test]
@@ -948,7 +3019,15 @@ impl DepositContract {
             false,
         );
         setup_context(0, "big.token".parse().unwrap());
-        let msg = serde_json::to_string(&DepositMessage { beneficiary_id: "user".to_string(), credits_hint: None, memo: None }).unwrap();
+        let msg = serde_json::to_string(&DepositMessage {
+            beneficiary_id: "user".to_string(),
+            credits_hint: None,
+            memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
+        })
+        .unwrap();
         // 10^30 base units (huge but within u128)
         let amount = 1_000_000_000_000_000_000_000_000_000_000u128; // 1e30
         let result = contract.ft_on_transfer(accounts(3), U128(amount), msg);
@@ -960,219 +3039,1287 @@ impl DepositContract {
     }
 
     #[test]
-    #[should_panic(expected = "Only the owner can migrate")]
-    fn test_migrate_requires_owner() {
-        // Write initial state
-        let mut initial = DepositContract::new(accounts(0), accounts(1));
-        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
-        initial.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
-        near_sdk::env::state_write(&initial);
-        // Call migrate as non-owner
-        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(2)).build());
-        let _ = DepositContract::migrate(None);
+    #[should_panic(expected = "Only the owner can migrate")]
+    fn test_migrate_requires_owner() {
+        // Write initial state
+        let mut initial = DepositContract::new(accounts(0), accounts(1));
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        initial.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        near_sdk::env::state_write(&initial);
+        // Call migrate as non-owner
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(2)).build());
+        let _ = DepositContract::migrate(None);
+    }
+
+    #[test]
+    fn test_migrate_updates_treasury() {
+        // initial state with owner=accounts(0), treasury=accounts(1)
+        let mut initial = DepositContract::new(accounts(0), accounts(1));
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        initial.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        near_sdk::env::state_write(&initial);
+        // migrate as owner to set new treasury=accounts(3)
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let updated = DepositContract::migrate(Some(accounts(3)));
+        assert_eq!(updated.treasury_account_id, accounts(3));
+        assert_eq!(updated.owner_id, accounts(0));
+    }
+
+    #[test]
+    fn test_contract_source_metadata_is_stamped() {
+        let metadata: serde_json::Value = serde_json::from_str(CONTRACT_SOURCE_METADATA).unwrap();
+        assert!(metadata["version"].as_str().unwrap().starts_with(env!("CARGO_PKG_VERSION")));
+        assert!(metadata["link"].as_str().unwrap().starts_with(env!("CARGO_PKG_REPOSITORY")));
+        let standards: Vec<&str> =
+            metadata["standards"].as_array().unwrap().iter().map(|s| s["standard"].as_str().unwrap()).collect();
+        for standard in ["nep141", "nep145", "nep148", "nep297", "nep330"] {
+            assert!(standards.contains(&standard), "missing {}", standard);
+        }
+    }
+
+    /// Borsh bytes of the released contract's state, before `state_version` existed.
+    fn released_state() -> Vec<u8> {
+        let mut token_configs = UnorderedMap::new(StorageKey::TokenConfigs);
+        token_configs.insert(
+            &NEAR_TOKEN_ID.to_string(),
+            &ReleasedTokenConfig {
+                symbol: "NEAR".to_string(),
+                decimals: 24,
+                price_usd_micros: 3_000_000,
+                last_updated: 0,
+                is_enabled: true,
+                is_native: true,
+            },
+        );
+        let mut deposits: LookupMap<u64, DepositRecord> = LookupMap::new(StorageKey::Deposits);
+        deposits.insert(
+            &0,
+            &DepositRecord {
+                id: 0,
+                account_id: accounts(2),
+                beneficiary_id: "user-1".to_string(),
+                token_id: NEAR_TOKEN_ID.to_string(),
+                amount: U128(6),
+                usd_value: U128(6_000_000),
+                credits_hint: None,
+                memo: None,
+                timestamp_ms: 0,
+            },
+        );
+        let mut deposits_by_account: LookupMap<AccountId, Vec<u64>> = LookupMap::new(StorageKey::DepositsByAccount);
+        deposits_by_account.insert(&accounts(2), &vec![0u64]);
+        borsh::to_vec(&(accounts(0), accounts(1), 1u64, token_configs, deposits, deposits_by_account)).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_from_released_layout() {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        near_sdk::env::storage_write(b"STATE", &released_state());
+
+        let migrated = DepositContract::migrate(None);
+        assert_eq!(migrated.owner_id, accounts(0));
+        assert_eq!(migrated.treasury_account_id, accounts(1));
+        assert_eq!(migrated.get_state_version(), STATE_VERSION);
+        assert_eq!(migrated.get_deposit_count(None), 1);
+        let deposits = migrated.get_deposits_for_account(accounts(2));
+        assert_eq!(deposits[0].beneficiary_id, "user-1");
+        assert_eq!(deposits[0].status, DepositStatus::Pending);
+
+        let near = migrated.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap();
+        assert_eq!(near.price_usd_micros.0, 3_000_000);
+        assert_eq!(near.min_deposit_usd_micros.0, MIN_DEPOSIT_USD_MICROS);
+        assert_eq!(near.deposit_fee_bps, 0);
+        assert_eq!(migrated.list_token_configs().len(), 1);
+    }
+
+    #[test]
+    fn test_migrate_from_upgrade_batch() {
+        let initial = DepositContract::new(accounts(0), accounts(1));
+        near_sdk::env::state_write(&initial);
+        // The upgrade batch calls migrate from the contract account itself
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(5))
+            .predecessor_account_id(accounts(5))
+            .build());
+        let migrated = DepositContract::migrate(None);
+        assert_eq!(migrated.owner_id, accounts(0));
+        assert_eq!(migrated.treasury_account_id, accounts(1));
+        assert_eq!(migrated.get_state_version(), STATE_VERSION);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported state version 7")]
+    fn test_migrate_rejects_unknown_state_version() {
+        let initial = DepositContract::new(accounts(0), accounts(1));
+        near_sdk::env::state_write(&initial);
+        near_sdk::env::storage_write(groupweave_utils::upgrade::STATE_VERSION_KEY, &7u32.to_le_bytes());
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let _ = DepositContract::migrate(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_upgrade_requires_owner_not_admin() {
+        let mut contract = DepositContract::new(accounts(0), accounts(1));
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        contract.grant_role(Role::Admin, accounts(2));
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(2)).build());
+        let _ = contract.upgrade(b"\0asm".to_vec());
+    }
+
+    #[test]
+    fn test_ft_deposit_unsupported_token_rejected() {
+        let mut contract = init_contract();
+        
+        setup_context(0, "unknown.token".parse().unwrap());
+        let msg = serde_json::to_string(&DepositMessage {
+            beneficiary_id: "user".to_string(),
+            credits_hint: None,
+            memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
+        })
+        .unwrap();
+
+        let result = contract.ft_on_transfer(accounts(3), U128(1_000_000), msg);
+        assert_ft_rejected(result, 1000000, "Unsupported token");
+        assert!(contract.get_deposits_for_account(accounts(3)).is_empty());
+    }
+
+    #[test]
+    fn test_ft_deposit_disabled_token_rejected() {
+        let mut contract = init_contract();
+        contract.upsert_token_config(
+            "usdc.token".to_string(),
+            "USDC".to_string(),
+            6,
+            U128(1_000_000),
+            false, // disabled
+            false,
+        );
+
+        setup_context(0, "usdc.token".parse().unwrap());
+        let msg = serde_json::to_string(&DepositMessage {
+            beneficiary_id: "user".to_string(),
+            credits_hint: None,
+            memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
+        })
+        .unwrap();
+
+        let result = contract.ft_on_transfer(accounts(3), U128(6_000_000), msg);
+        assert_ft_rejected(result, 6000000, "Token deposits disabled");
+        assert!(contract.get_deposits_for_account(accounts(3)).is_empty());
+    }
+
+    #[test]
+    fn test_ft_deposit_zero_price_rejected() {
+        let mut contract = init_contract();
+        contract.upsert_token_config(
+            "test.token".to_string(),
+            "TEST".to_string(),
+            6,
+            U128(0), // no price
+            true,
+            false,
+        );
+
+        setup_context(0, "test.token".parse().unwrap());
+        let msg = serde_json::to_string(&DepositMessage {
+            beneficiary_id: "user".to_string(),
+            credits_hint: None,
+            memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
+        })
+        .unwrap();
+
+        let result = contract.ft_on_transfer(accounts(3), U128(10_000_000), msg);
+        assert_ft_rejected(result, 10000000, "Token price not configured");
+        assert!(contract.get_deposits_for_account(accounts(3)).is_empty());
+    }
+
+    #[test]
+    fn test_ft_deposit_below_minimum_rejected() {
+        let mut contract = init_contract();
+        contract.upsert_token_config(
+            "usdc.token".to_string(),
+            "USDC".to_string(),
+            6,
+            U128(1_000_000),
+            true,
+            false,
+        );
+
+        setup_context(0, "usdc.token".parse().unwrap());
+        let msg = serde_json::to_string(&DepositMessage {
+            beneficiary_id: "user".to_string(),
+            credits_hint: None,
+            memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
+        })
+        .unwrap();
+
+        // Only 3 USDC (below $5 minimum)
+        let result = contract.ft_on_transfer(accounts(3), U128(3_000_000), msg);
+        assert_ft_rejected(result, 3000000, "Minimum deposit is $5 USD");
+        assert!(contract.get_deposits_for_account(accounts(3)).is_empty());
+    }
+
+    #[test]
+    fn test_ft_deposit_invalid_json_rejected() {
+        let mut contract = init_contract();
+        contract.upsert_token_config(
+            "usdc.token".to_string(),
+            "USDC".to_string(),
+            6,
+            U128(1_000_000),
+            true,
+            false,
+        );
+
+        setup_context(0, "usdc.token".parse().unwrap());
+        let invalid_msg = "{invalid json";
+
+        let result = contract.ft_on_transfer(accounts(3), U128(6_000_000), invalid_msg.to_string());
+        assert_ft_rejected(result, 6000000, "Invalid deposit message payload");
+        assert!(contract.get_deposits_for_account(accounts(3)).is_empty());
+    }
+
+    #[test]
+    fn test_ft_deposit_high_precision_token() {
+        let mut contract = init_contract();
+        // Token with 18 decimals
+        contract.upsert_token_config(
+            "weth.token".to_string(),
+            "WETH".to_string(),
+            18,
+            U128(3_000_000_000), // $3000 per token
+            true,
+            false,
+        );
+
+        setup_context(0, "weth.token".parse().unwrap());
+        let msg = serde_json::to_string(&DepositMessage {
+            beneficiary_id: "whale".to_string(),
+            credits_hint: Some(10000),
+            memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
+        })
+        .unwrap();
+
+        // 0.01 WETH = 10^16
+        let amount = 10u128.pow(16);
+        let result = contract.ft_on_transfer(accounts(5), U128(amount), msg);
+        match result {
+            PromiseOrValue::Value(v) => assert_eq!(v.0, 0),
+            _ => panic!("Expected Value variant"),
+        }
+
+        let deposits = contract.get_deposits_for_account(accounts(5));
+        assert_eq!(deposits.len(), 1);
+        // 0.01 WETH * $3000 = $30
+        assert_eq!(deposits[0].usd_value.0, 30_000_000);
+    }
+
+    #[test]
+    fn test_ft_deposit_forwards_to_treasury() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = r#"{"beneficiary_id":"user-1"}"#.to_string();
+        contract.ft_on_transfer(accounts(3), U128(7_000_000), msg);
+
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let forward = receipts
+            .iter()
+            .find(|r| r.receiver_id.as_str() == "usdt.token")
+            .expect("ft_transfer to the token contract");
+        let has_transfer = forward.actions.iter().any(|a| match a {
+            near_sdk::mock::MockAction::FunctionCallWeight { method_name, args, .. } => {
+                let args = String::from_utf8(args.clone()).unwrap();
+                method_name == b"ft_transfer" && args.contains(accounts(1).as_str()) && args.contains("7000000")
+            }
+            _ => false,
+        });
+        assert!(has_transfer);
+    }
+
+    #[test]
+    fn test_failed_ft_forward_flags_deposit() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+        setup_context(0, "usdt.token".parse().unwrap());
+        contract.ft_on_transfer(accounts(3), U128(7_000_000), r#"{"beneficiary_id":"user-1"}"#.to_string());
+
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(accounts(0)).current_account_id(accounts(0));
+        testing_env!(
+            builder.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_ft_forwarded(0));
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Flagged);
+    }
+
+    #[test]
+    fn test_failed_native_forward_flags_deposit() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-123".to_string(), None, None, None, None);
+
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(accounts(0)).current_account_id(accounts(0));
+        testing_env!(
+            builder.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(vec![]), near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_native_forwarded(0));
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Flagged);
+    }
+
+    // ========================================
+    // Withdrawal Tests
+    // ========================================
+
+    #[test]
+    fn test_withdraw_ft_requires_owner() {
+        let mut contract = init_contract();
+        
+        // Owner can call (won't panic, but promise won't execute in test)
+        setup_context(1, accounts(0));
+        contract.withdraw_ft(
+            "usdc.token".parse().unwrap(),
+            U128(1_000_000),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the treasurer role")]
+    fn test_withdraw_ft_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(1, accounts(2)); // Not owner
+        
+        contract.withdraw_ft(
+            "usdc.token".parse().unwrap(),
+            U128(1_000_000),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Attach at least 1 yoctoNEAR")]
+    fn test_withdraw_ft_requires_yoctonear() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(0)); // No attached deposit
+        
+        contract.withdraw_ft(
+            "usdc.token".parse().unwrap(),
+            U128(1_000_000),
+            None,
+            None,
+        );
+    }
+
+    // ========================================
+    // Treasury Split Tests
+    // ========================================
+
+    fn ops_and_community_splits() -> Vec<TreasurySplit> {
+        vec![
+            TreasurySplit { account_id: accounts(1), bps: 9_000 },
+            TreasurySplit { account_id: accounts(5), bps: 1_000 },
+        ]
+    }
+
+    fn transfers_to(receiver: &AccountId) -> Vec<u128> {
+        near_sdk::test_utils::get_created_receipts()
+            .into_iter()
+            .filter(|r| &r.receiver_id == receiver)
+            .flat_map(|r| r.actions)
+            .filter_map(|a| match a {
+                near_sdk::mock::MockAction::Transfer { deposit, .. } => Some(deposit.as_yoctonear()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_native_deposit_split_across_treasuries() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_treasury_splits(ops_and_community_splits());
+        assert_eq!(contract.get_treasury_splits().len(), 2);
+
+        setup_context(10 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user".to_string(), None, None, None, None);
+
+        assert_eq!(transfers_to(&accounts(1)), vec![9 * 10u128.pow(24)]);
+        assert_eq!(transfers_to(&accounts(5)), vec![10u128.pow(24)]);
+    }
+
+    #[test]
+    fn test_split_amount_assigns_dust_to_last_split() {
+        let mut contract = init_contract();
+        contract.set_treasury_splits(vec![
+            TreasurySplit { account_id: accounts(1), bps: 3_333 },
+            TreasurySplit { account_id: accounts(5), bps: 6_667 },
+        ]);
+        let shares = contract.split_amount(10_001);
+        assert_eq!(shares[0].1 + shares[1].1, 10_001);
+        assert_eq!(shares[0].1, 3_333);
+    }
+
+    #[test]
+    #[should_panic(expected = "Treasury splits must total 10000 bps")]
+    fn test_treasury_splits_must_total_full_amount() {
+        let mut contract = init_contract();
+        contract.set_treasury_splits(vec![TreasurySplit { account_id: accounts(1), bps: 9_000 }]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_set_treasury_splits_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(2));
+        contract.set_treasury_splits(ops_and_community_splits());
+    }
+
+    #[test]
+    fn test_ensure_storage_then_sweep_registers_each_split() {
+        let mut contract = init_contract();
+        contract.set_treasury_splits(ops_and_community_splits());
+
+        setup_context(2 * 1_250_000_000_000_000_000_000, accounts(0));
+        contract.ensure_storage_then_sweep("usdt.token".parse().unwrap(), U128(1_000));
+
+        let registered: Vec<String> = near_sdk::test_utils::get_created_receipts()
+            .iter()
+            .filter(|r| r.receiver_id.as_str() == "usdt.token")
+            .flat_map(|r| r.actions.clone())
+            .filter_map(|a| match a {
+                near_sdk::mock::MockAction::FunctionCallWeight { method_name, args, .. }
+                    if method_name == b"storage_deposit" =>
+                {
+                    Some(String::from_utf8(args).unwrap())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(registered.len(), 2);
+        assert!(registered[0].contains(accounts(1).as_str()));
+        assert!(registered[1].contains(accounts(5).as_str()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attach NEAR to cover storage registration")]
+    fn test_ensure_storage_then_sweep_requires_deposit() {
+        let mut contract = init_contract();
+        contract.ensure_storage_then_sweep("usdt.token".parse().unwrap(), U128(1_000));
+    }
+
+    #[test]
+    fn test_failed_storage_registration_emits_event() {
+        let mut contract = init_contract();
+        setup_callback(near_sdk::PromiseResult::Failed);
+        let result = contract.on_treasury_storage_registered("usdt.token".parse().unwrap(), U128(1_000));
+        assert!(matches!(result, PromiseOrValue::Value(false)));
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].contains("\"event\":\"sweep_failed\""));
+        assert!(logs[0].contains("\"stage\":\"storage_deposit\""));
+    }
+
+    #[test]
+    fn test_failed_sweep_transfer_emits_event() {
+        let mut contract = init_contract();
+        setup_callback(near_sdk::PromiseResult::Failed);
+        assert!(!contract.on_sweep_complete("usdt.token".parse().unwrap(), U128(1_000)));
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].contains("\"stage\":\"ft_transfer\""));
+    }
+
+    // ========================================
+    // Beneficiary Binding Tests
+    // ========================================
+
+    fn backend_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn backend_public_key() -> PublicKey {
+        let mut bytes = vec![0u8]; // ed25519 curve prefix
+        bytes.extend(backend_key().verifying_key().to_bytes());
+        PublicKey::try_from(bytes).unwrap()
+    }
+
+    fn sign_binding(user_id: &str, account_id: &AccountId, nonce: [u8; 32]) -> Base64VecU8 {
+        use ed25519_dalek::Signer;
+        let payload = Nep413Payload {
+            message: format!("bind_beneficiary:{}:{}", user_id, account_id),
+            nonce,
+            recipient: env::current_account_id().to_string(),
+            callback_url: None,
+        };
+        let mut bytes = borsh::to_vec(&NEP413_TAG).unwrap();
+        bytes.extend(borsh::to_vec(&payload).unwrap());
+        let hash = env::sha256_array(&bytes);
+        Base64VecU8(backend_key().sign(&hash).to_bytes().to_vec())
+    }
+
+    fn contract_with_binding_signer() -> DepositContract {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_beneficiary_signer(Some(backend_public_key()));
+        contract
+    }
+
+    #[test]
+    fn test_bind_beneficiary_with_backend_signature() {
+        let mut contract = contract_with_binding_signer();
+        contract.set_require_bound_beneficiary(true);
+
+        setup_context(0, accounts(2));
+        let signature = sign_binding("user-42", &accounts(2), [1u8; 32]);
+        contract.bind_beneficiary("user-42".to_string(), Base64VecU8(vec![1u8; 32]), signature);
+        assert_eq!(contract.get_bound_account("user-42".to_string()), Some(accounts(2)));
+
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-42".to_string(), None, None, None, None);
+        assert_eq!(contract.get_deposit_count(Some(accounts(2))), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid beneficiary signature")]
+    fn test_bind_beneficiary_signed_for_other_account_fails() {
+        let mut contract = contract_with_binding_signer();
+        setup_context(0, accounts(3));
+        let signature = sign_binding("user-42", &accounts(2), [1u8; 32]);
+        contract.bind_beneficiary("user-42".to_string(), Base64VecU8(vec![1u8; 32]), signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Binding nonce already used")]
+    fn test_bind_beneficiary_replay_fails() {
+        let mut contract = contract_with_binding_signer();
+        setup_context(0, accounts(2));
+        let signature = sign_binding("user-42", &accounts(2), [1u8; 32]);
+        contract.bind_beneficiary("user-42".to_string(), Base64VecU8(vec![1u8; 32]), signature.clone());
+        contract.bind_beneficiary("user-42".to_string(), Base64VecU8(vec![1u8; 32]), signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Beneficiary is not bound to this account")]
+    fn test_unbound_beneficiary_rejected_when_required() {
+        let mut contract = contract_with_binding_signer();
+        contract.set_require_bound_beneficiary(true);
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-42".to_string(), None, None, None, None);
+    }
+
+    #[test]
+    fn test_unbound_ft_beneficiary_refunded_when_required() {
+        let mut contract = contract_with_binding_signer();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+        contract.set_require_bound_beneficiary(true);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let result = contract.ft_on_transfer(accounts(3), U128(7_000_000), r#"{"beneficiary_id":"user-42"}"#.to_string());
+        assert_ft_rejected(result, 7_000_000, "Beneficiary is not bound to this account");
+    }
+
+    // ========================================
+    // Promo Code Tests
+    // ========================================
+
+    fn contract_with_promo(code: &str, bonus_bps: u16, expires_at_ms: u64, max_uses: u32) -> DepositContract {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        let code_hash = env::sha256(code.as_bytes());
+        contract.set_promo(Base64VecU8(code_hash), bonus_bps, expires_at_ms, max_uses);
+        contract
+    }
+
+    #[test]
+    fn test_promo_bonus_recorded_on_native_deposit() {
+        let mut contract = contract_with_promo("LAUNCH20", 2_000, 1_000, 10);
+        contract.set_token_limits(NEAR_TOKEN_ID.to_string(), U128(MIN_DEPOSIT_USD_MICROS), 500); // 5%
+
+        setup_context(10 * 10u128.pow(24), accounts(2));
+        let view = contract.deposit_native("user".to_string(), None, None, Some("LAUNCH20".to_string()), None);
+        // 20% of the $9.50 credited after the fee
+        assert_eq!(view.bonus_usd_value.0, 1_900_000);
+        assert_eq!(contract.get_deposit(view.id).unwrap().bonus_usd_value.0, 1_900_000);
+        assert_eq!(contract.get_promo("LAUNCH20".to_string()).unwrap().uses, 1);
+    }
+
+    #[test]
+    fn test_promo_bonus_recorded_on_ft_deposit() {
+        let mut contract = contract_with_promo("LAUNCH20", 2_000, 1_000, 10);
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = r#"{"beneficiary_id":"user","promo_code":"LAUNCH20"}"#.to_string();
+        contract.ft_on_transfer(accounts(3), U128(10_000_000), msg);
+        let view = contract.get_deposit(0).unwrap();
+        assert_eq!(view.bonus_usd_value.0, 2_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Promo code expired")]
+    fn test_expired_promo_rejected() {
+        let mut contract = contract_with_promo("OLD", 1_000, 0, 10);
+        setup_context(10 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user".to_string(), None, None, Some("OLD".to_string()), None);
+    }
+
+    #[test]
+    fn test_promo_max_uses_enforced() {
+        let mut contract = contract_with_promo("ONCE", 1_000, 1_000, 1);
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = r#"{"beneficiary_id":"user","promo_code":"ONCE"}"#.to_string();
+        contract.ft_on_transfer(accounts(3), U128(10_000_000), msg.clone());
+        let result = contract.ft_on_transfer(accounts(3), U128(10_000_000), msg);
+        assert_ft_rejected(result, 10_000_000, "Promo code fully redeemed");
+    }
+
+    #[test]
+    fn test_unknown_promo_rejected_without_recording() {
+        let mut contract = contract_with_promo("LAUNCH20", 2_000, 1_000, 10);
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = r#"{"beneficiary_id":"user","promo_code":"NOPE"}"#.to_string();
+        let result = contract.ft_on_transfer(accounts(3), U128(10_000_000), msg);
+        assert_ft_rejected(result, 10_000_000, "Unknown promo code");
+        assert_eq!(contract.get_deposit_count(None), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_set_promo_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(2));
+        contract.set_promo(Base64VecU8(vec![0u8; 32]), 1_000, 1_000, 1);
+    }
+
+    // ========================================
+    // On-Behalf-Of Tests
+    // ========================================
+
+    #[test]
+    fn test_native_deposit_on_behalf_of_other_account() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_credits_rate(U128(100));
+
+        setup_context(10 * 10u128.pow(24), accounts(2));
+        let view = contract.deposit_native("gift".to_string(), None, None, None, Some(accounts(3)));
+        assert_eq!(view.account_id, accounts(2));
+        assert_eq!(view.on_behalf_of, Some(accounts(3)));
+
+        // Credits go to the recipient; the deposit stays listed under the payer.
+        assert_eq!(contract.ft_balance_of(accounts(3)).0, 1_000);
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 0);
+        assert_eq!(contract.get_deposit_count(Some(accounts(2))), 1);
+        let received = contract.get_deposits_on_behalf_of(accounts(3), None, None);
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].id, view.id);
+    }
+
+    #[test]
+    fn test_ft_deposit_on_behalf_of_other_account() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = format!(r#"{{"beneficiary_id":"team","on_behalf_of":"{}"}}"#, accounts(4));
+        contract.ft_on_transfer(accounts(3), U128(10_000_000), msg);
+        let view = contract.get_deposit(0).unwrap();
+        assert_eq!(view.account_id, accounts(3));
+        assert_eq!(view.on_behalf_of, Some(accounts(4)));
+        assert_eq!(contract.get_deposits_on_behalf_of(accounts(4), None, None).len(), 1);
+    }
+
+    #[test]
+    fn test_deposit_on_behalf_of_self_not_indexed() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        let view = contract.deposit_native("user".to_string(), None, None, None, Some(accounts(2)));
+        assert_eq!(view.on_behalf_of, None);
+        assert!(contract.get_deposits_on_behalf_of(accounts(2), None, None).is_empty());
+    }
+
+    // ========================================
+    // Rate Limit & Denylist Tests
+    // ========================================
+
+    fn deposit_near_in_block(contract: &mut DepositContract, account: AccountId, block_height: u64) -> DepositView {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .attached_deposit(NearToken::from_near(6))
+            .predecessor_account_id(account)
+            .block_height(block_height);
+        testing_env!(builder.build());
+        contract.deposit_native("user".to_string(), None, None, None, None)
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many deposits from this account in one block")]
+    fn test_per_block_deposit_limit() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_max_deposits_per_block(Some(2));
+
+        deposit_near_in_block(&mut contract, accounts(2), 10);
+        deposit_near_in_block(&mut contract, accounts(2), 10);
+        deposit_near_in_block(&mut contract, accounts(2), 10);
+    }
+
+    #[test]
+    fn test_per_block_limit_resets_next_block() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_max_deposits_per_block(Some(1));
+
+        deposit_near_in_block(&mut contract, accounts(2), 10);
+        deposit_near_in_block(&mut contract, accounts(3), 10);
+        deposit_near_in_block(&mut contract, accounts(2), 11);
+        assert_eq!(contract.get_deposit_count(None), 3);
+    }
+
+    #[test]
+    fn test_ft_per_block_limit_refunds() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+        contract.set_max_deposits_per_block(Some(1));
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = r#"{"beneficiary_id":"user"}"#.to_string();
+        contract.ft_on_transfer(accounts(3), U128(7_000_000), msg.clone());
+        let result = contract.ft_on_transfer(accounts(3), U128(7_000_000), msg);
+        assert_ft_rejected(result, 7_000_000, "Too many deposits from this account in one block");
+    }
+
+    #[test]
+    #[should_panic(expected = "Account is denylisted")]
+    fn test_denylisted_account_cannot_deposit() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.add_to_denylist(accounts(2));
+        assert_eq!(contract.get_denylist(), vec![accounts(2)]);
+
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user".to_string(), None, None, None, None);
+    }
+
+    #[test]
+    fn test_denylisted_recipient_ft_deposit_refunded() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+        contract.add_to_denylist(accounts(4));
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = format!(r#"{{"beneficiary_id":"user","on_behalf_of":"{}"}}"#, accounts(4));
+        let result = contract.ft_on_transfer(accounts(3), U128(7_000_000), msg);
+        assert_ft_rejected(result, 7_000_000, "Account is denylisted");
+    }
+
+    #[test]
+    fn test_removed_from_denylist_can_deposit() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.add_to_denylist(accounts(2));
+        contract.remove_from_denylist(accounts(2));
+
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user".to_string(), None, None, None, None);
+        assert_eq!(contract.get_deposit_count(Some(accounts(2))), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the guardian role")]
+    fn test_add_to_denylist_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(2));
+        contract.add_to_denylist(accounts(3));
+    }
+
+    // ========================================
+    // Role Tests
+    // ========================================
+
+    #[test]
+    fn test_roles_delegate_narrow_powers() {
+        let mut contract = init_contract();
+        contract.grant_role(Role::Guardian, accounts(2));
+        contract.grant_role(Role::Keeper, accounts(3));
+        assert_eq!(contract.get_roles(accounts(2)), vec![Role::Guardian]);
+
+        setup_context(0, accounts(2));
+        contract.add_to_denylist(accounts(4));
+        contract.set_token_enabled(NEAR_TOKEN_ID.to_string(), false);
+        assert_eq!(contract.get_denylist(), vec![accounts(4)]);
+
+        setup_context(0, accounts(3));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(5_000_000), None);
+        assert_eq!(contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap().price_usd_micros, U128(5_000_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the treasurer role")]
+    fn test_guardian_cannot_withdraw() {
+        let mut contract = init_contract();
+        contract.grant_role(Role::Guardian, accounts(2));
+        setup_context(0, accounts(2));
+        contract.withdraw_native(U128(1), None);
+    }
+
+    #[test]
+    fn test_admin_role_passes_owner_checks() {
+        let mut contract = init_contract();
+        contract.grant_role(Role::Admin, accounts(2));
+        setup_context(0, accounts(2));
+        contract.set_max_deposits_per_block(Some(3));
+        contract.grant_role(Role::Treasurer, accounts(3));
+        assert!(contract.has_role(Role::Treasurer, accounts(3)));
+        contract.revoke_role(Role::Treasurer, accounts(3));
+        assert!(!contract.has_role(Role::Treasurer, accounts(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can manage the admin role")]
+    fn test_admin_cannot_grant_admin() {
+        let mut contract = init_contract();
+        contract.grant_role(Role::Admin, accounts(2));
+        setup_context(0, accounts(2));
+        contract.grant_role(Role::Admin, accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_admin_cannot_transfer_ownership() {
+        let mut contract = init_contract();
+        contract.grant_role(Role::Admin, accounts(2));
+        setup_context(0, accounts(2));
+        contract.propose_owner(accounts(2));
+    }
+
+    // ========================================
+    // Relayer Tests
+    // ========================================
+
+    fn relayed_ft_context(token: &str, relayer: AccountId) {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .predecessor_account_id(token.parse().unwrap())
+            .signer_account_id(relayer);
+        testing_env!(builder.build());
+    }
+
+    #[test]
+    fn test_relayed_ft_deposit_attributed_to_user() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdc.token".to_string(), "USDC".to_string(), 6, U128(1_000_000), true, false);
+        contract.add_relayer(accounts(5));
+        assert_eq!(contract.get_relayers(), vec![accounts(5)]);
+
+        // accounts(3) signed the delegate action; accounts(5) relayed it.
+        relayed_ft_context("usdc.token", accounts(5));
+        contract.ft_on_transfer(accounts(3), U128(10_000_000), r#"{"beneficiary_id":"new-user"}"#.to_string());
+        let view = contract.get_deposit(0).unwrap();
+        assert_eq!(view.account_id, accounts(3));
+        assert_eq!(view.relayed_by, Some(accounts(5)));
+        assert_eq!(contract.get_deposit_count(Some(accounts(3))), 1);
+    }
+
+    #[test]
+    fn test_unregistered_relayer_not_tagged() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdc.token".to_string(), "USDC".to_string(), 6, U128(1_000_000), true, false);
+
+        relayed_ft_context("usdc.token", accounts(5));
+        contract.ft_on_transfer(accounts(3), U128(10_000_000), r#"{"beneficiary_id":"new-user"}"#.to_string());
+        let view = contract.get_deposit(0).unwrap();
+        assert_eq!(view.account_id, accounts(3));
+        assert_eq!(view.relayed_by, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_add_relayer_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(2));
+        contract.add_relayer(accounts(5));
+    }
+
+    // ========================================
+    // Deposit Cap Tests
+    // ========================================
+
+    fn deposit_near_at(contract: &mut DepositContract, account: AccountId, near: u128, ts_ms: u64) -> DepositView {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .attached_deposit(NearToken::from_near(near))
+            .predecessor_account_id(account)
+            .block_timestamp(ts_ms * 1_000_000);
+        testing_env!(builder.build());
+        contract.deposit_native("user".to_string(), None, None, None, None)
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposit cap exceeded: $10 USD per window, $4 USD remaining")]
+    fn test_deposit_cap_enforced_within_window() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_deposit_cap(Some(U128(10_000_000)), None);
+
+        deposit_near_at(&mut contract, accounts(2), 6, 1_000);
+        assert_eq!(contract.get_cap_usage(accounts(2)).0, 6_000_000);
+        deposit_near_at(&mut contract, accounts(2), 6, 2_000);
+    }
+
+    #[test]
+    fn test_deposit_cap_resets_after_window() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_deposit_cap(Some(U128(10_000_000)), Some(60_000));
+
+        deposit_near_at(&mut contract, accounts(2), 6, 1_000);
+        // Keep the price fresh for the second deposit
+        setup_context(0, accounts(0));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        deposit_near_at(&mut contract, accounts(2), 6, 61_000);
+        assert_eq!(contract.get_deposit_count(Some(accounts(2))), 2);
+    }
+
+    #[test]
+    fn test_cap_exempt_account_not_limited() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_deposit_cap(Some(U128(10_000_000)), None);
+        contract.add_cap_exemption(accounts(2));
+
+        deposit_near_at(&mut contract, accounts(2), 6, 1_000);
+        deposit_near_at(&mut contract, accounts(2), 6, 2_000);
+        assert_eq!(contract.get_deposit_count(Some(accounts(2))), 2);
+    }
+
+    #[test]
+    fn test_ft_deposit_over_cap_refunded() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+        contract.set_deposit_cap(Some(U128(10_000_000)), None);
+
+        setup_context(0, "usdt.token".parse().unwrap());
+        let result = contract.ft_on_transfer(accounts(3), U128(11_000_000), r#"{"beneficiary_id":"u"}"#.to_string());
+        assert_ft_rejected(result, 11_000_000, "Deposit cap exceeded");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_set_deposit_cap_non_owner_fails() {
+        let mut contract = init_contract();
+        setup_context(0, accounts(2));
+        contract.set_deposit_cap(Some(U128(1)), None);
+    }
+
+    // ========================================
+    // Refund Tests
+    // ========================================
+
+    fn contract_with_native_deposit() -> DepositContract {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-1".to_string(), None, None, None, None);
+        contract
+    }
+
+    #[test]
+    fn test_refund_native_deposit_marks_refunded() {
+        let mut contract = contract_with_native_deposit();
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Pending);
+
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Refunded);
+    }
+
+    #[test]
+    fn test_treasury_can_refund_deposit() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(1));
+        contract.refund_deposit(0);
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Refunded);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or treasury can refund deposits")]
+    fn test_refund_by_other_account_fails() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(2));
+        contract.refund_deposit(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposit already refunded")]
+    fn test_refund_twice_fails() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+        contract.refund_deposit(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attach at least 1 yoctoNEAR")]
+    fn test_refund_ft_deposit_requires_yoctonear() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+        setup_context(0, "usdt.token".parse().unwrap());
+        let msg = r#"{"beneficiary_id":"user-1"}"#.to_string();
+        contract.ft_on_transfer(accounts(3), U128(7_000_000), msg);
+
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+    }
+
+    #[test]
+    fn test_failed_refund_resets_status() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(accounts(0)).current_account_id(accounts(0));
+        testing_env!(
+            builder.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_refund_complete(0, DepositStatus::Pending));
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Pending);
+    }
+
+    #[test]
+    fn test_failed_refund_restores_flagged_status() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
+        setup_context(0, accounts(4));
+        contract.flag_deposit(0);
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+
+        setup_callback(near_sdk::PromiseResult::Failed);
+        assert!(!contract.on_refund_complete(0, DepositStatus::Flagged));
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Flagged);
     }
 
     #[test]
-    fn test_migrate_updates_treasury() {
-        // initial state with owner=accounts(0), treasury=accounts(1)
-        let mut initial = DepositContract::new(accounts(0), accounts(1));
-        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
-        initial.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
-        near_sdk::env::state_write(&initial);
-        // migrate as owner to set new treasury=accounts(3)
-        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
-        let updated = DepositContract::migrate(Some(accounts(3)));
-        assert_eq!(updated.treasury_account_id, accounts(3));
-        assert_eq!(updated.owner_id, accounts(0));
+    #[should_panic(expected = "Deposit was already credited")]
+    fn test_credited_deposit_cannot_be_refunded() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
+        setup_context(0, accounts(4));
+        contract.mark_deposit_credited(0, 600);
+
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
     }
 
+    // ========================================
+    // Reconciliation Status Tests
+    // ========================================
+
     #[test]
-    #[should_panic(expected = "Unsupported token")]
-    fn test_ft_deposit_unsupported_token_fails() {
-        let mut contract = init_contract();
-        
-        setup_context(0, "unknown.token".parse().unwrap());
-        let msg = serde_json::to_string(&DepositMessage {
-            beneficiary_id: "user".to_string(),
-            credits_hint: None,
-            memo: None,
-        })
-        .unwrap();
+    fn test_backend_marks_deposit_credited() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
 
-        contract.ft_on_transfer(accounts(3), U128(1_000_000), msg);
+        setup_context(0, accounts(4));
+        contract.mark_deposit_credited(0, 600);
+
+        let deposit = contract.get_deposit(0).unwrap();
+        assert_eq!(deposit.status, DepositStatus::Credited);
+        assert_eq!(deposit.credits_granted, Some(600));
     }
 
     #[test]
-    #[should_panic(expected = "Token deposits disabled")]
-    fn test_ft_deposit_disabled_token_fails() {
-        let mut contract = init_contract();
-        contract.upsert_token_config(
-            "usdc.token".to_string(),
-            "USDC".to_string(),
-            6,
-            U128(1_000_000),
-            false, // disabled
-            false,
-        );
+    #[should_panic(expected = "Only the backend account can call this method")]
+    fn test_mark_credited_requires_backend() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.mark_deposit_credited(0, 600);
+    }
 
-        setup_context(0, "usdc.token".parse().unwrap());
-        let msg = serde_json::to_string(&DepositMessage {
-            beneficiary_id: "user".to_string(),
-            credits_hint: None,
-            memo: None,
-        })
-        .unwrap();
+    #[test]
+    #[should_panic(expected = "Deposit is not pending")]
+    fn test_cannot_credit_refunded_deposit() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
+        contract.refund_deposit(0);
 
-        contract.ft_on_transfer(accounts(3), U128(6_000_000), msg);
+        setup_context(0, accounts(4));
+        contract.mark_deposit_credited(0, 600);
     }
 
     #[test]
-    #[should_panic(expected = "Token price not configured")]
-    fn test_ft_deposit_zero_price_fails() {
-        let mut contract = init_contract();
-        contract.upsert_token_config(
-            "test.token".to_string(),
-            "TEST".to_string(),
-            6,
-            U128(0), // no price
-            true,
-            false,
-        );
+    fn test_flagged_deposit_can_be_refunded() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
 
-        setup_context(0, "test.token".parse().unwrap());
-        let msg = serde_json::to_string(&DepositMessage {
-            beneficiary_id: "user".to_string(),
-            credits_hint: None,
-            memo: None,
-        })
-        .unwrap();
+        setup_context(0, accounts(4));
+        contract.flag_deposit(0);
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Flagged);
 
-        contract.ft_on_transfer(accounts(3), U128(10_000_000), msg);
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Refunded);
     }
 
-    #[test]
-    #[should_panic(expected = "Minimum deposit is $5 USD")]
-    fn test_ft_deposit_below_minimum_fails() {
-        let mut contract = init_contract();
-        contract.upsert_token_config(
-            "usdc.token".to_string(),
-            "USDC".to_string(),
-            6,
-            U128(1_000_000),
-            true,
-            false,
-        );
+    // ========================================
+    // Credits Ledger Tests
+    // ========================================
 
-        setup_context(0, "usdc.token".parse().unwrap());
-        let msg = serde_json::to_string(&DepositMessage {
-            beneficiary_id: "user".to_string(),
-            credits_hint: None,
-            memo: None,
-        })
-        .unwrap();
+    fn contract_with_credits(spender: AccountId) -> DepositContract {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_credits_rate(U128(100));
+        contract.add_credit_spender(spender);
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-1".to_string(), None, None, None, None);
+        contract
+    }
 
-        // Only 3 USDC (below $5 minimum)
-        contract.ft_on_transfer(accounts(3), U128(3_000_000), msg);
+    #[test]
+    fn test_spender_spends_credits_by_usd_cost() {
+        let mut contract = contract_with_credits(accounts(5));
+        assert_eq!(contract.get_credit_spenders(), vec![accounts(5)]);
+
+        // $0.015 at 100 credits per $1 is 1.5 credits, rounded up.
+        setup_context(0, accounts(5));
+        let spent = contract.spend_credits(accounts(2), U128(15_000), Some("session s1".to_string()));
+        assert_eq!(spent.0, 2);
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 598);
     }
 
     #[test]
-    #[should_panic(expected = "Invalid deposit message payload")]
-    fn test_ft_deposit_invalid_json_fails() {
-        let mut contract = init_contract();
-        contract.upsert_token_config(
-            "usdc.token".to_string(),
-            "USDC".to_string(),
-            6,
-            U128(1_000_000),
-            true,
-            false,
-        );
+    #[should_panic(expected = "Only a credit spender can call this method")]
+    fn test_spend_credits_requires_spender() {
+        let mut contract = contract_with_credits(accounts(5));
+        setup_context(0, accounts(4));
+        contract.spend_credits(accounts(2), U128(15_000), None);
+    }
 
-        setup_context(0, "usdc.token".parse().unwrap());
-        let invalid_msg = "{invalid json";
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn test_spend_credits_beyond_balance_fails() {
+        let mut contract = contract_with_credits(accounts(5));
+        setup_context(0, accounts(5));
+        contract.spend_credits(accounts(2), U128(7_000_000), None);
+    }
 
-        contract.ft_on_transfer(accounts(3), U128(6_000_000), invalid_msg.to_string());
+    #[test]
+    fn test_no_credits_minted_without_rate() {
+        let contract = contract_with_native_deposit();
+        assert_eq!(contract.ft_total_supply().0, 0);
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 0);
     }
 
     #[test]
-    fn test_ft_deposit_high_precision_token() {
+    fn test_deposit_mints_credits_at_rate() {
         let mut contract = init_contract();
-        // Token with 18 decimals
-        contract.upsert_token_config(
-            "weth.token".to_string(),
-            "WETH".to_string(),
-            18,
-            U128(3_000_000_000), // $3000 per token
-            true,
-            false,
-        );
-
-        setup_context(0, "weth.token".parse().unwrap());
-        let msg = serde_json::to_string(&DepositMessage {
-            beneficiary_id: "whale".to_string(),
-            credits_hint: Some(10000),
-            memo: None,
-        })
-        .unwrap();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_credits_rate(U128(100)); // 100 credits per $1
 
-        // 0.01 WETH = 10^16
-        let amount = 10u128.pow(16);
-        let result = contract.ft_on_transfer(accounts(5), U128(amount), msg);
-        match result {
-            PromiseOrValue::Value(v) => assert_eq!(v.0, 0),
-            _ => panic!("Expected Value variant"),
-        }
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-1".to_string(), None, None, None, None);
 
-        let deposits = contract.get_deposits_for_account(accounts(5));
-        assert_eq!(deposits.len(), 1);
-        // 0.01 WETH * $3000 = $30
-        assert_eq!(deposits[0].usd_value.0, 30_000_000);
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 600);
+        assert_eq!(contract.ft_total_supply().0, 600);
+        assert_eq!(contract.ft_metadata().symbol, "DWC");
     }
 
-    // ========================================
-    // Withdrawal Tests
-    // ========================================
+    #[test]
+    fn test_backend_burns_spent_credits() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        contract.set_credits_rate(U128(100));
+        contract.set_backend_account(Some(accounts(4)));
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-1".to_string(), None, None, None, None);
+
+        setup_context(0, accounts(4));
+        contract.burn_credits(accounts(2), U128(250), Some("image generation".to_string()));
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 350);
+        assert_eq!(contract.ft_total_supply().0, 350);
+    }
 
     #[test]
-    fn test_withdraw_ft_requires_owner() {
+    #[should_panic(expected = "Only the backend account can call this method")]
+    fn test_burn_credits_requires_backend() {
         let mut contract = init_contract();
-        
-        // Owner can call (won't panic, but promise won't execute in test)
-        setup_context(1, accounts(0));
-        contract.withdraw_ft(
-            "usdc.token".parse().unwrap(),
-            U128(1_000_000),
-            None,
-            None,
-        );
+        contract.burn_credits(accounts(2), U128(1), None);
     }
 
     #[test]
     #[should_panic(expected = "Only the owner can call this method")]
-    fn test_withdraw_ft_non_owner_fails() {
+    fn test_set_credits_rate_non_owner_fails() {
         let mut contract = init_contract();
-        setup_context(1, accounts(2)); // Not owner
-        
-        contract.withdraw_ft(
-            "usdc.token".parse().unwrap(),
-            U128(1_000_000),
-            None,
-            None,
-        );
+        setup_context(0, accounts(2));
+        contract.set_credits_rate(U128(100));
     }
 
     #[test]
-    #[should_panic(expected = "Attach at least 1 yoctoNEAR")]
-    fn test_withdraw_ft_requires_yoctonear() {
-        let mut contract = init_contract();
-        setup_context(0, accounts(0)); // No attached deposit
-        
-        contract.withdraw_ft(
-            "usdc.token".parse().unwrap(),
-            U128(1_000_000),
-            None,
-            None,
-        );
+    fn test_refund_burns_minted_credits() {
+        let mut contract = contract_with_credits(accounts(5));
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 0);
+        assert_eq!(contract.ft_total_supply().0, 0);
+
+        setup_callback(near_sdk::PromiseResult::Failed);
+        contract.on_refund_complete(0, DepositStatus::Pending);
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, 600);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credits minted for this deposit were already spent or transferred")]
+    fn test_refund_after_credits_spent_fails() {
+        let mut contract = contract_with_credits(accounts(5));
+        setup_context(0, accounts(5));
+        contract.spend_credits(accounts(2), U128(15_000), None);
+
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
     }
 
     // ========================================
@@ -1182,12 +4329,12 @@ impl DepositContract {
     #[test]
     fn test_large_deposit_amount() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(5_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(5_000_000), None);
 
         // 1 million NEAR
         let large_amount = 1_000_000u128 * 10u128.pow(24);
         setup_context(large_amount, accounts(2));
-        let receipt = contract.deposit_native("whale".to_string(), Some(1_000_000), None);
+        let receipt = contract.deposit_native("whale".to_string(), Some(1_000_000), None, None, None);
 
         // $5M USD value
         assert_eq!(receipt.usd_value.0, 5_000_000_000_000);
@@ -1197,10 +4344,10 @@ impl DepositContract {
     fn test_token_config_update_changes_price() {
         let mut contract = init_contract();
         
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000), None);
         let config1 = contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap();
         
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(3_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(3_000_000), Some(true)); // +50% needs an owner override
         let config2 = contract.get_token_config(NEAR_TOKEN_ID.to_string()).unwrap();
         
         assert_ne!(config1.price_usd_micros.0, config2.price_usd_micros.0);
@@ -1212,7 +4359,7 @@ impl DepositContract {
     #[test]
     fn test_beneficiary_id_formats() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         
@@ -1226,7 +4373,7 @@ impl DepositContract {
 
         for (i, beneficiary) in test_ids.iter().enumerate() {
             setup_context(six_near, accounts(i as usize + 2));
-            let receipt = contract.deposit_native(beneficiary.to_string(), None, None);
+            let receipt = contract.deposit_native(beneficiary.to_string(), None, None, None, None);
             assert_eq!(receipt.beneficiary_id, *beneficiary);
         }
     }
@@ -1248,6 +4395,9 @@ impl DepositContract {
             beneficiary_id: "nft-buyer".to_string(),
             credits_hint: None,
             memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
 
@@ -1265,14 +4415,14 @@ impl DepositContract {
     #[test]
     fn test_concurrent_deposits_different_accounts() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         
         // Simulate deposits from multiple accounts (only use available test accounts)
         for i in 0..3 {
             setup_context(six_near, accounts(i + 2));
-            contract.deposit_native(format!("user-{}", i), None, None);
+            contract.deposit_native(format!("user-{}", i), None, None, None, None);
         }
 
         // Check each account has exactly one deposit
@@ -1289,13 +4439,13 @@ impl DepositContract {
     #[test]
     fn test_deposit_id_sequential() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         
         for i in 0..5 {
             setup_context(six_near, accounts(2));
-            let receipt = contract.deposit_native(format!("user-{}", i), None, None);
+            let receipt = contract.deposit_native(format!("user-{}", i), None, None, None, None);
             assert_eq!(receipt.id, i as u64);
         }
     }
@@ -1303,15 +4453,15 @@ impl DepositContract {
     #[test]
     fn test_multiple_deposits_same_beneficiary() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
         
         // Same beneficiary, multiple deposits
-        contract.deposit_native("user-123".to_string(), Some(100), None);
-        contract.deposit_native("user-123".to_string(), Some(200), None);
-        contract.deposit_native("user-123".to_string(), Some(300), None);
+        contract.deposit_native("user-123".to_string(), Some(100), None, None, None);
+        contract.deposit_native("user-123".to_string(), Some(200), None, None, None);
+        contract.deposit_native("user-123".to_string(), Some(300), None, None, None);
 
         let deposits = contract.get_deposits_for_account(accounts(2));
         assert_eq!(deposits.len(), 3);
@@ -1323,15 +4473,15 @@ impl DepositContract {
     #[test]
     fn test_deposit_preserves_account_isolation() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user-a".to_string(), None, None);
+        contract.deposit_native("user-a".to_string(), None, None, None, None);
         
         setup_context(six_near, accounts(3));
-        contract.deposit_native("user-b".to_string(), None, None);
+        contract.deposit_native("user-b".to_string(), None, None, None, None);
 
         // Verify isolation
         let deposits_a = contract.get_deposits_for_account(accounts(2));
@@ -1343,6 +4493,198 @@ impl DepositContract {
         assert_eq!(deposits_b[0].beneficiary_id, "user-b");
     }
 
+    // ========================================
+    // Event Tests
+    // ========================================
+
+    fn last_event() -> serde_json::Value {
+        let logs = near_sdk::test_utils::get_logs();
+        let line = logs.last().expect("event log");
+        serde_json::from_str(line.strip_prefix("EVENT_JSON:").expect("NEP-297 prefix")).unwrap()
+    }
+
+    #[test]
+    fn test_deposit_recorded_event_schema() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        setup_context(6 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user-7".to_string(), Some(600), None, None, None);
+
+        let event = last_event();
+        assert_eq!(event["standard"], "dreamweave_deposit");
+        assert_eq!(event["version"], "2.0.0");
+        assert_eq!(event["event"], "deposit_recorded");
+        let data = &event["data"][0];
+        assert_eq!(data["deposit_id"], 0);
+        assert_eq!(data["beneficiary_id"], "user-7");
+        assert_eq!(data["account_id"], accounts(2).as_str());
+        assert_eq!(data["usd_value"], "6000000");
+        assert_eq!(data["fee_usd_value"], "0");
+    }
+
+    #[test]
+    fn test_price_updated_event() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000), None);
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_100_000), None);
+
+        let event = last_event();
+        assert_eq!(event["event"], "price_updated");
+        assert_eq!(event["data"][0]["token_id"], "NEAR");
+        assert_eq!(event["data"][0]["price_usd_micros"], "2100000");
+        assert_eq!(event["data"][0]["previous_price_usd_micros"], "2000000");
+        assert_eq!(event["data"][0]["forced"], false);
+    }
+
+    #[test]
+    fn test_token_config_updated_event() {
+        let mut contract = init_contract();
+        contract.upsert_token_config("usdt.token".to_string(), "USDT".to_string(), 6, U128(1_000_000), true, false);
+        contract.set_token_limits("usdt.token".to_string(), U128(10_000_000), 100);
+
+        let event = last_event();
+        assert_eq!(event["event"], "token_config_updated");
+        assert_eq!(event["data"][0]["token_id"], "usdt.token");
+        assert_eq!(event["data"][0]["min_deposit_usd_micros"], "10000000");
+        assert_eq!(event["data"][0]["deposit_fee_bps"], 100);
+    }
+
+    #[test]
+    fn test_deposit_credited_event() {
+        let mut contract = contract_with_native_deposit();
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
+        setup_context(0, accounts(4));
+        contract.mark_deposit_credited(0, 600);
+
+        let event = last_event();
+        assert_eq!(event["event"], "deposit_credited");
+        assert_eq!(event["data"][0]["deposit_id"], 0);
+        assert_eq!(event["data"][0]["credits_granted"], 600);
+    }
+
+    // ========================================
+    // Archival Tests
+    // ========================================
+
+    fn contract_with_three_deposits() -> DepositContract {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        deposit_near_at(&mut contract, accounts(2), 6, 1_000);
+        deposit_near_at(&mut contract, accounts(3), 6, 2_000);
+        deposit_near_at(&mut contract, accounts(2), 6, 3_000);
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
+        setup_context(0, accounts(4));
+        for deposit_id in 0..3 {
+            contract.mark_deposit_credited(deposit_id, 600);
+        }
+        setup_context(0, accounts(0));
+        contract
+    }
+
+    #[test]
+    fn test_prune_deposits_archives_old_records() {
+        let mut contract = contract_with_three_deposits();
+        let leaves: Vec<String> = (0..2)
+            .map(|id| serde_json::to_string(&DepositRecorded::from(&contract.get_deposit(id).unwrap())).unwrap())
+            .collect();
+
+        let result = contract.prune_deposits(2_500, 10);
+        assert_eq!(result.pruned, 2);
+        assert_eq!(result.pruned_before_id, 2);
+        assert!(contract.get_deposit(0).is_none());
+        assert!(contract.get_deposit(2).is_some());
+        assert_eq!(contract.get_deposit_count(Some(accounts(2))), 1);
+        assert_eq!(contract.get_deposit_count(Some(accounts(3))), 0);
+        assert_eq!(contract.get_deposit_count(None), 3);
+        let remaining: Vec<u64> =
+            contract.get_deposits_for_beneficiary("user".to_string(), None, None).iter().map(|d| d.id).collect();
+        assert_eq!(remaining, vec![2]);
+
+        let mut expected = [0u8; 32];
+        for leaf in leaves {
+            let mut preimage = expected.to_vec();
+            preimage.extend(env::sha256_array(leaf.as_bytes()));
+            expected = env::sha256_array(&preimage);
+        }
+        let commitments = contract.get_archive_commitments(None, None);
+        assert_eq!(commitments.len(), 1);
+        assert_eq!(commitments[0].period_start_ms, 0);
+        assert_eq!(commitments[0].count, 2);
+        assert_eq!((commitments[0].first_deposit_id, commitments[0].last_deposit_id), (0, 1));
+        assert_eq!(commitments[0].hash, expected.into());
+    }
+
+    #[test]
+    fn test_prune_refunded_bonus_deposit_matches_recorded_event() {
+        let mut contract = contract_with_promo("LAUNCH20", 2_000, 1_000, 10);
+        setup_context(10 * 10u128.pow(24), accounts(2));
+        contract.deposit_native("user".to_string(), None, None, Some("LAUNCH20".to_string()), None);
+        let logs = near_sdk::test_utils::get_logs();
+        let event = logs.iter().find(|l| l.contains("\"event\":\"deposit_recorded\"")).expect("recorded event");
+        let start = event.find("\"data\":[").unwrap() + "\"data\":[".len();
+        let leaf = event[start..event.len() - "]}".len()].to_string();
+
+        setup_context(0, accounts(0));
+        contract.refund_deposit(0);
+        setup_callback(near_sdk::PromiseResult::Successful(vec![]));
+        assert!(contract.on_refund_complete(0, DepositStatus::Pending));
+        assert_eq!(contract.get_deposit(0).unwrap().status, DepositStatus::Refunded);
+
+        assert_eq!(contract.prune_deposits(u64::MAX, 10).pruned, 1);
+        let mut preimage = [0u8; 32].to_vec();
+        preimage.extend(env::sha256_array(leaf.as_bytes()));
+        let commitments = contract.get_archive_commitments(None, None);
+        assert_eq!(commitments[0].hash, env::sha256_array(&preimage).into());
+    }
+
+    #[test]
+    fn test_prune_deposits_respects_limit() {
+        let mut contract = contract_with_three_deposits();
+        assert_eq!(contract.prune_deposits(u64::MAX, 1).pruned, 1);
+        assert_eq!(contract.prune_deposits(u64::MAX, 5).pruned, 2);
+        assert_eq!(contract.prune_deposits(u64::MAX, 5).pruned, 0);
+        assert_eq!(contract.get_archive_commitments(None, None)[0].count, 3);
+    }
+
+    #[test]
+    fn test_range_view_skips_pruned_deposits() {
+        let mut contract = contract_with_three_deposits();
+        contract.prune_deposits(2_500, 10);
+        let ids: Vec<u64> = contract.get_deposits_in_range(0, u64::MAX, None, None).iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn test_prune_deposits_stops_at_unsettled_deposit() {
+        let mut contract = init_contract();
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
+        deposit_near_at(&mut contract, accounts(2), 6, 1_000);
+        deposit_near_at(&mut contract, accounts(3), 6, 2_000);
+        setup_context(0, accounts(0));
+        contract.set_backend_account(Some(accounts(4)));
+        setup_context(0, accounts(4));
+        contract.flag_deposit(0);
+        contract.mark_deposit_credited(1, 600);
+        setup_context(0, accounts(0));
+
+        let result = contract.prune_deposits(u64::MAX, 10);
+        assert_eq!(result.pruned, 0);
+        assert_eq!(result.pruned_before_id, 0);
+
+        contract.refund_deposit(0);
+        assert_eq!(contract.prune_deposits(u64::MAX, 10).pruned, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_prune_deposits_non_owner_fails() {
+        let mut contract = contract_with_three_deposits();
+        setup_context(0, accounts(2));
+        contract.prune_deposits(2_500, 10);
+    }
+
     // ========================================
     // Security & Access Control Tests
     // ========================================
@@ -1352,7 +4694,7 @@ impl DepositContract {
         let mut contract = init_contract();
         let original_treasury = contract.treasury_account_id.clone();
         
-        contract.set_treasury(accounts(4));
+        change_treasury(&mut contract, accounts(4));
         assert_ne!(contract.treasury_account_id, original_treasury);
         assert_eq!(contract.treasury_account_id, accounts(4));
     }
@@ -1464,7 +4806,6 @@ impl DepositContract {
     // ========================================
 
     #[test]
-    #[should_panic(expected = "memo too long")]
     fn test_ft_deposit_with_very_long_memo() {
         let mut contract = init_contract();
         contract.upsert_token_config(
@@ -1482,10 +4823,15 @@ impl DepositContract {
             beneficiary_id: "user".to_string(),
             credits_hint: None,
             memo: Some(long_memo.clone()),
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
 
-        let _ = contract.ft_on_transfer(accounts(3), U128(6_000_000), msg);
+        let result = contract.ft_on_transfer(accounts(3), U128(6_000_000), msg);
+        assert_ft_rejected(result, 6000000, "memo too long");
+        assert!(contract.get_deposits_for_account(accounts(3)).is_empty());
     }
 
     #[test]
@@ -1506,6 +4852,9 @@ impl DepositContract {
             beneficiary_id: special_id.to_string(),
             credits_hint: None,
             memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
 
@@ -1536,6 +4885,9 @@ impl DepositContract {
             beneficiary_id: "user".to_string(),
             credits_hint: Some(u64::MAX),
             memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
 
@@ -1557,12 +4909,12 @@ impl DepositContract {
     fn test_native_deposit_exactly_one_yoctonear_above_minimum() {
         let mut contract = init_contract();
         // Set price such that minimum is achievable
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         
         // $5.000001 - but due to integer division, might round to $5.000000
         let amount = (5u128 * 10u128.pow(24)) + 1;
         setup_context(amount, accounts(2));
-        let receipt = contract.deposit_native("user".to_string(), None, None);
+        let receipt = contract.deposit_native("user".to_string(), None, None, None, None);
         
         // Accept that due to rounding, it might equal the minimum
         assert!(receipt.usd_value.0 >= MIN_DEPOSIT_USD_MICROS);
@@ -1572,22 +4924,22 @@ impl DepositContract {
     #[should_panic(expected = "Minimum deposit is $5 USD")]
     fn test_native_deposit_one_yoctonear_below_minimum() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         
         // Just under $5
         let amount = (5u128 * 10u128.pow(24)) - 1;
         setup_context(amount, accounts(2));
-        contract.deposit_native("user".to_string(), None, None);
+        contract.deposit_native("user".to_string(), None, None, None, None);
     }
 
     #[test]
     fn test_native_deposit_with_empty_string_beneficiary() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        let receipt = contract.deposit_native("".to_string(), None, None);
+        let receipt = contract.deposit_native("".to_string(), None, None, None, None);
         
         assert_eq!(receipt.beneficiary_id, "");
     }
@@ -1595,7 +4947,7 @@ impl DepositContract {
     #[test]
     fn test_native_deposit_memo_with_unicode() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
@@ -1604,6 +4956,8 @@ impl DepositContract {
             "user".to_string(),
             None,
             Some(unicode_memo.to_string()),
+            None,
+            None,
         );
         
         assert_eq!(receipt.memo, Some(unicode_memo.to_string()));
@@ -1719,50 +5073,50 @@ impl DepositContract {
     #[test]
     fn test_native_deposit_exact_storage_cost() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        let _receipt = contract.deposit_native("user-123".to_string(), Some(250), None);
+        let _receipt = contract.deposit_native("user-123".to_string(), Some(250), None, None, None);
     }
 
     #[test]
     fn test_storage_usage_increases_with_deposit_count() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user-1".to_string(), None, None);
+        contract.deposit_native("user-1".to_string(), None, None, None, None);
         
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user-2".to_string(), None, None);
+        contract.deposit_native("user-2".to_string(), None, None, None, None);
         
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user-3".to_string(), None, None);
+        contract.deposit_native("user-3".to_string(), None, None, None, None);
     }
 
     #[test]
     fn test_treasury_change_with_pending_deposits() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user-1".to_string(), None, None);
+        contract.deposit_native("user-1".to_string(), None, None, None, None);
         
         let original_treasury = contract.treasury_account_id.clone();
         
         setup_context(0, accounts(0));
         let new_treasury = accounts(5);
-        contract.set_treasury(new_treasury.clone());
+        change_treasury(&mut contract, new_treasury.clone());
         
         assert_ne!(contract.treasury_account_id, original_treasury);
         assert_eq!(contract.treasury_account_id, new_treasury);
         
         setup_context(six_near, accounts(3));
-        contract.deposit_native("user-2".to_string(), None, None);
+        contract.deposit_native("user-2".to_string(), None, None, None, None);
     }
 
     #[test]
@@ -1773,7 +5127,7 @@ impl DepositContract {
         
         for treasury in treasuries {
             setup_context(0, accounts(0));
-            contract.set_treasury(treasury.clone());
+            change_treasury(&mut contract, treasury.clone());
             assert_eq!(contract.treasury_account_id, treasury);
         }
     }
@@ -1781,14 +5135,14 @@ impl DepositContract {
     #[test]
     fn test_deposit_id_never_reuses() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         let mut seen_ids = std::collections::HashSet::new();
         
         for i in 0..10 {
             setup_context(six_near, accounts(2));
-            let receipt = contract.deposit_native(format!("user-{}", i), None, None);
+            let receipt = contract.deposit_native(format!("user-{}", i), None, None, None, None);
             assert!(!seen_ids.contains(&receipt.id), "Deposit ID should be unique");
             seen_ids.insert(receipt.id);
         }
@@ -1893,14 +5247,14 @@ impl DepositContract {
     #[test]
     fn test_get_deposits_preserves_order() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         
         let beneficiaries = vec!["first", "second", "third", "fourth", "fifth"];
         for beneficiary in &beneficiaries {
             setup_context(six_near, accounts(2));
-            contract.deposit_native(beneficiary.to_string(), None, None);
+            contract.deposit_native(beneficiary.to_string(), None, None, None, None);
         }
         
         let deposits = contract.get_deposits_for_account(accounts(2));
@@ -1915,11 +5269,11 @@ impl DepositContract {
     #[test]
     fn test_get_deposit_by_id_boundary_values() {
         let mut contract = init_contract();
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
 
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        let receipt = contract.deposit_native("user".to_string(), None, None);
+        let receipt = contract.deposit_native("user".to_string(), None, None, None, None);
         
         let retrieved = contract.get_deposit(receipt.id);
         assert!(retrieved.is_some());
@@ -1996,7 +5350,7 @@ impl DepositContract {
         let mut contract = init_contract();
         
         // 1. Owner sets price
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(3_500_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(3_500_000), None);
         
         // 2. User makes deposit
         let ten_near = 10u128 * 10u128.pow(24);
@@ -2005,6 +5359,8 @@ impl DepositContract {
             "user@example.com".to_string(),
             Some(1000),
             Some("Monthly subscription".to_string()),
+            None,
+            None,
         );
         
         // 3. Verify deposit recorded
@@ -2040,6 +5396,9 @@ impl DepositContract {
             beneficiary_id: "0x123abc".to_string(),
             credits_hint: Some(500),
             memo: Some("Premium plan".to_string()),
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
         
@@ -2063,7 +5422,7 @@ impl DepositContract {
         let mut contract = init_contract();
         
         // Setup
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(1_000_000), None);
         contract.upsert_token_config(
             "usdc.token".to_string(),
             "USDC".to_string(),
@@ -2076,7 +5435,7 @@ impl DepositContract {
         // Native deposit
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        contract.deposit_native("user-1".to_string(), None, None);
+        contract.deposit_native("user-1".to_string(), None, None, None, None);
         
         // FT deposit from same account
         setup_context(0, "usdc.token".parse().unwrap());
@@ -2084,6 +5443,9 @@ impl DepositContract {
             beneficiary_id: "user-2".to_string(),
             credits_hint: None,
             memo: None,
+            promo_code: None,
+            on_behalf_of: None,
+            intent: None,
         })
         .unwrap();
         contract.ft_on_transfer(accounts(2), U128(10_000_000), msg);
@@ -2100,17 +5462,17 @@ impl DepositContract {
         let mut contract = init_contract();
         
         // First price
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(2_000_000), None);
         let six_near = 6u128 * 10u128.pow(24);
         setup_context(six_near, accounts(2));
-        let receipt1 = contract.deposit_native("user-1".to_string(), None, None);
+        let receipt1 = contract.deposit_native("user-1".to_string(), None, None, None, None);
         
         // Update price (must be called as owner)
         setup_context(0, accounts(0));
-        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(3_000_000));
+        contract.update_token_price(NEAR_TOKEN_ID.to_string(), U128(3_000_000), Some(true)); // +50% needs an owner override
         
         setup_context(six_near, accounts(3));
-        let receipt2 = contract.deposit_native("user-2".to_string(), None, None);
+        let receipt2 = contract.deposit_native("user-2".to_string(), None, None, None, None);
         
         // Different USD values
         assert_eq!(receipt1.usd_value.0, 12_000_000); // 6 * $2
@@ -0,0 +1,629 @@
+//! Commission escrow – milestone payments for 1:1 creator work.
+//!
+//! Besides open bounties, communities commission specific creators:
+//! - The client opens a commission for a named creator with the price split into milestones and
+//!   attaches the total plus storage. Until the creator accepts, either side can cancel and the
+//!   client is refunded in full.
+//! - Once accepted, the creator submits each milestone with a deliverable hash. The client
+//!   approves it or disputes it within the review period; a submitted milestone that is neither
+//!   can be released to the creator by anyone after the review period.
+//! - Milestones still unsubmitted at the delivery deadline can be reclaimed by the client.
+//! - Disputes go to the same arbiters as the bounty market (its owner, admins and resolvers).
+//!   An arbiter proposes a split in basis points; the escrow confirms them with a cross-contract
+//!   `is_arbiter` read on the bounty market before paying out.
+
+use groupweave_events::commission::{CommissionCreated, MilestoneDisputed, MilestoneSettled};
+use groupweave_events::Nep297Event;
+use groupweave_utils::{apply_bps, BPS_DENOMINATOR};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, ext_contract, near, require, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseError,
+};
+
+/// Most milestones one commission can have.
+pub const MAX_MILESTONES: usize = 10;
+/// Time the client has to approve or dispute a submitted milestone before it can be released.
+pub const DEFAULT_REVIEW_PERIOD_NS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+/// Maximum number of commissions returned by one page of `get_commissions`.
+pub const MAX_COMMISSION_PAGE: u64 = 100;
+
+const FULL_SHARE_BPS: u16 = BPS_DENOMINATOR as u16;
+const NS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const MIN_MILESTONE_AMOUNT: NearToken = NearToken::from_millinear(100);
+const MAX_DELIVERY_DAYS: u64 = 365;
+const GAS_FOR_ARBITER_CHECK: Gas = Gas::from_tgas(5);
+const GAS_FOR_RESOLVE_CALLBACK: Gas = Gas::from_tgas(15);
+
+// Bounty market, whose owner, admins and resolvers arbitrate commission disputes
+#[ext_contract(ext_arbiters)]
+pub trait ArbiterSet {
+    fn is_arbiter(&self, account_id: AccountId) -> bool;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum CommissionStatus {
+    /// Funded, waiting for the creator to accept.
+    Proposed,
+    Active,
+    /// Every milestone has been paid out or refunded.
+    Completed,
+    Cancelled,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum MilestoneStatus {
+    Pending,
+    Submitted,
+    Disputed,
+    /// Paid to the creator, on approval or after the review period.
+    Released,
+    /// Returned to the client, unsubmitted at the delivery deadline.
+    Refunded,
+    /// Split by an arbiter; `creator_bps` went to the creator and the rest to the client.
+    Resolved { creator_bps: u16 },
+}
+
+impl MilestoneStatus {
+    fn is_settled(self) -> bool {
+        matches!(self, Self::Released | Self::Refunded | Self::Resolved { .. })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MilestoneInput {
+    pub description: String,
+    pub amount: NearToken,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Milestone {
+    pub description: String,
+    pub amount: NearToken,
+    pub status: MilestoneStatus,
+    pub deliverable_hash: Option<String>,
+    pub submitted_at_ns: Option<u64>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Commission {
+    pub client: AccountId,
+    pub creator: AccountId,
+    pub brief: String,
+    pub milestones: Vec<Milestone>,
+    pub status: CommissionStatus,
+    pub created_at_ns: u64,
+    pub delivery_period_ns: u64, // counted from acceptance
+    pub delivery_deadline_ns: Option<u64>, // set on acceptance
+    pub review_period_ns: u64, // the contract's review period when the commission was opened
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommissionView {
+    pub id: u64,
+    #[serde(flatten)]
+    pub commission: Commission,
+}
+
+#[near(contract_state, contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
+#[derive(PanicOnDefault)]
+pub struct CommissionEscrow {
+    owner_id: AccountId,
+    /// Bounty market whose arbiters settle disputes.
+    arbiter_contract_id: AccountId,
+    commissions: LookupMap<u64, Commission>,
+    next_commission_id: u64,
+    review_period_ns: u64,
+}
+
+#[near]
+impl CommissionEscrow {
+    #[init]
+    pub fn new(owner_id: AccountId, arbiter_contract_id: AccountId) -> Self {
+        Self {
+            owner_id,
+            arbiter_contract_id,
+            commissions: LookupMap::new(b"c"),
+            next_commission_id: 1,
+            review_period_ns: DEFAULT_REVIEW_PERIOD_NS,
+        }
+    }
+
+    // Commission lifecycle
+    /// Open a commission for `creator`. Attach the sum of the milestone amounts plus storage;
+    /// the excess is refunded.
+    #[payable]
+    pub fn create_commission(
+        &mut self,
+        creator: AccountId,
+        brief: String,
+        milestones: Vec<MilestoneInput>,
+        delivery_days: u64,
+    ) -> u64 {
+        let client = env::predecessor_account_id();
+        require!(client != creator, "Cannot commission yourself");
+        require!(!brief.trim().is_empty(), "Brief cannot be empty");
+        require!(brief.len() <= 1000, "Brief too long (max 1000 characters)");
+        require!(!milestones.is_empty(), "At least one milestone is required");
+        require!(milestones.len() <= MAX_MILESTONES, format!("At most {} milestones", MAX_MILESTONES));
+        require!((1..=MAX_DELIVERY_DAYS).contains(&delivery_days), "Delivery must be 1 to 365 days");
+
+        let mut total = NearToken::from_yoctonear(0);
+        for milestone in &milestones {
+            require!(!milestone.description.trim().is_empty(), "Milestone description cannot be empty");
+            require!(milestone.description.len() <= 500, "Milestone description too long (max 500 characters)");
+            require!(milestone.amount >= MIN_MILESTONE_AMOUNT, "Each milestone must be at least 0.1 NEAR");
+            total = total.checked_add(milestone.amount).unwrap_or_else(|| env::panic_str("Total overflow"));
+        }
+
+        let initial_storage = env::storage_usage();
+        let commission_id = self.next_commission_id;
+        let milestone_count = milestones.len();
+        let commission = Commission {
+            client: client.clone(),
+            creator: creator.clone(),
+            brief,
+            milestones: milestones
+                .into_iter()
+                .map(|input| Milestone {
+                    description: input.description,
+                    amount: input.amount,
+                    status: MilestoneStatus::Pending,
+                    deliverable_hash: None,
+                    submitted_at_ns: None,
+                })
+                .collect(),
+            status: CommissionStatus::Proposed,
+            created_at_ns: env::block_timestamp(),
+            delivery_period_ns: delivery_days * NS_PER_DAY,
+            delivery_deadline_ns: None,
+            review_period_ns: self.review_period_ns,
+        };
+        self.commissions.insert(&commission_id, &commission);
+        self.next_commission_id += 1;
+
+        let storage_cost = env::storage_byte_cost().saturating_mul((env::storage_usage() - initial_storage).into());
+        let required = total.saturating_add(storage_cost);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= required,
+            format!("Attach at least {} yoctoNEAR (milestones plus storage)", required.as_yoctonear())
+        );
+        let refund = deposit.saturating_sub(required);
+        if refund > NearToken::from_yoctonear(0) {
+            Promise::new(client.clone()).transfer(refund);
+        }
+
+        CommissionCreated {
+            commission_id,
+            client,
+            creator,
+            total: U128(total.as_yoctonear()),
+            milestones: milestone_count,
+        }
+        .emit();
+        commission_id
+    }
+
+    /// Accept a proposed commission (creator only); the delivery deadline starts now.
+    pub fn accept_commission(&mut self, commission_id: u64) {
+        let mut commission = self.expect_commission(commission_id);
+        require!(env::predecessor_account_id() == commission.creator, "Only the creator can accept");
+        require!(commission.status == CommissionStatus::Proposed, "Commission is not awaiting acceptance");
+
+        commission.status = CommissionStatus::Active;
+        commission.delivery_deadline_ns = Some(env::block_timestamp() + commission.delivery_period_ns);
+        self.commissions.insert(&commission_id, &commission);
+        emit_event("commission_accepted", near_sdk::serde_json::json!({ "commission_id": commission_id }));
+    }
+
+    /// Withdraw (client) or decline (creator) a commission that has not been accepted; the client
+    /// gets the escrowed total back.
+    pub fn cancel_commission(&mut self, commission_id: u64) {
+        let mut commission = self.expect_commission(commission_id);
+        let caller = env::predecessor_account_id();
+        require!(caller == commission.client || caller == commission.creator, "Only the client or creator can cancel");
+        require!(commission.status == CommissionStatus::Proposed, "Only a proposed commission can be cancelled");
+
+        let total =
+            commission.milestones.iter().fold(NearToken::from_yoctonear(0), |sum, m| sum.saturating_add(m.amount));
+        for milestone in &mut commission.milestones {
+            milestone.status = MilestoneStatus::Refunded;
+        }
+        commission.status = CommissionStatus::Cancelled;
+        self.commissions.insert(&commission_id, &commission);
+        Promise::new(commission.client).transfer(total);
+        emit_event(
+            "commission_cancelled",
+            near_sdk::serde_json::json!({ "commission_id": commission_id, "cancelled_by": caller }),
+        );
+    }
+
+    // Milestones
+    /// Submit a milestone for review (creator only, before the delivery deadline).
+    pub fn submit_milestone(&mut self, commission_id: u64, milestone_index: usize, deliverable_hash: String) {
+        let mut commission = self.expect_active(commission_id);
+        require!(env::predecessor_account_id() == commission.creator, "Only the creator can submit");
+        require!(!deliverable_hash.trim().is_empty(), "Deliverable hash cannot be empty");
+        let now = env::block_timestamp();
+        require!(now < commission.delivery_deadline_ns.unwrap_or(0), "Delivery deadline has passed");
+
+        let milestone = Self::milestone_mut(&mut commission, milestone_index);
+        require!(milestone.status == MilestoneStatus::Pending, "Milestone was already submitted");
+        milestone.status = MilestoneStatus::Submitted;
+        milestone.deliverable_hash = Some(deliverable_hash.clone());
+        milestone.submitted_at_ns = Some(now);
+        self.commissions.insert(&commission_id, &commission);
+        emit_event(
+            "milestone_submitted",
+            near_sdk::serde_json::json!({
+                "commission_id": commission_id,
+                "milestone_index": milestone_index,
+                "deliverable_hash": deliverable_hash,
+            }),
+        );
+    }
+
+    /// Pay a submitted milestone to the creator (client only). Also ends a dispute the client raised.
+    pub fn approve_milestone(&mut self, commission_id: u64, milestone_index: usize) {
+        let mut commission = self.expect_active(commission_id);
+        require!(env::predecessor_account_id() == commission.client, "Only the client can approve");
+        let status = Self::milestone_mut(&mut commission, milestone_index).status;
+        require!(
+            matches!(status, MilestoneStatus::Submitted | MilestoneStatus::Disputed),
+            "Milestone is not awaiting approval"
+        );
+        let client = env::predecessor_account_id();
+        self.settle(commission_id, commission, milestone_index, MilestoneStatus::Released, FULL_SHARE_BPS, client);
+    }
+
+    /// Release a submitted milestone the client neither approved nor disputed within the review
+    /// period (anyone can call).
+    pub fn release_milestone(&mut self, commission_id: u64, milestone_index: usize) {
+        let mut commission = self.expect_active(commission_id);
+        let review_period_ns = commission.review_period_ns;
+        let milestone = Self::milestone_mut(&mut commission, milestone_index);
+        require!(milestone.status == MilestoneStatus::Submitted, "Milestone is not awaiting approval");
+        let review_ends_at = milestone.submitted_at_ns.unwrap_or(0).saturating_add(review_period_ns);
+        require!(env::block_timestamp() >= review_ends_at, "Review period has not ended");
+        let caller = env::predecessor_account_id();
+        self.settle(commission_id, commission, milestone_index, MilestoneStatus::Released, FULL_SHARE_BPS, caller);
+    }
+
+    /// Dispute a submitted milestone within the review period (client only). An arbiter then
+    /// splits it with `resolve_dispute`, unless the client approves it after all.
+    pub fn dispute_milestone(&mut self, commission_id: u64, milestone_index: usize, reason_hash: String) {
+        let mut commission = self.expect_active(commission_id);
+        require!(env::predecessor_account_id() == commission.client, "Only the client can dispute");
+        let review_period_ns = commission.review_period_ns;
+        let milestone = Self::milestone_mut(&mut commission, milestone_index);
+        require!(milestone.status == MilestoneStatus::Submitted, "Milestone is not awaiting approval");
+        let review_ends_at = milestone.submitted_at_ns.unwrap_or(0).saturating_add(review_period_ns);
+        require!(env::block_timestamp() < review_ends_at, "Review period has ended");
+
+        milestone.status = MilestoneStatus::Disputed;
+        self.commissions.insert(&commission_id, &commission);
+        MilestoneDisputed { commission_id, milestone_index, reason_hash }.emit();
+    }
+
+    /// Refund the milestones still unsubmitted at the delivery deadline (client only).
+    pub fn reclaim_unsubmitted(&mut self, commission_id: u64) -> U128 {
+        let mut commission = self.expect_active(commission_id);
+        require!(env::predecessor_account_id() == commission.client, "Only the client can reclaim");
+        require!(
+            env::block_timestamp() >= commission.delivery_deadline_ns.unwrap_or(u64::MAX),
+            "Delivery deadline has not passed"
+        );
+
+        let pending: Vec<usize> = (0..commission.milestones.len())
+            .filter(|index| commission.milestones[*index].status == MilestoneStatus::Pending)
+            .collect();
+        require!(!pending.is_empty(), "No unsubmitted milestones");
+        let client = commission.client.clone();
+        let mut refunded = 0u128;
+        for index in pending {
+            refunded += commission.milestones[index].amount.as_yoctonear();
+            commission = self.settle(commission_id, commission, index, MilestoneStatus::Refunded, 0, client.clone());
+        }
+        U128(refunded)
+    }
+
+    // Disputes
+    /// Split a disputed milestone: `creator_bps` of it to the creator, the rest to the client.
+    /// The caller must be an arbiter of the bounty market, which is checked before paying out.
+    pub fn resolve_dispute(&mut self, commission_id: u64, milestone_index: usize, creator_bps: u16) -> Promise {
+        require!(u128::from(creator_bps) <= BPS_DENOMINATOR, "Creator share cannot exceed 10000 bps");
+        let mut commission = self.expect_active(commission_id);
+        let status = Self::milestone_mut(&mut commission, milestone_index).status;
+        require!(status == MilestoneStatus::Disputed, "Milestone is not disputed");
+
+        let arbiter = env::predecessor_account_id();
+        ext_arbiters::ext(self.arbiter_contract_id.clone())
+            .with_static_gas(GAS_FOR_ARBITER_CHECK)
+            .is_arbiter(arbiter.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_CALLBACK)
+                    .on_arbiter_checked(arbiter, commission_id, milestone_index, creator_bps),
+            )
+    }
+
+    #[private]
+    pub fn on_arbiter_checked(
+        &mut self,
+        arbiter: AccountId,
+        commission_id: u64,
+        milestone_index: usize,
+        creator_bps: u16,
+        #[callback_result] is_arbiter: Result<bool, PromiseError>,
+    ) {
+        require!(matches!(is_arbiter, Ok(true)), "Only a bounty market arbiter can resolve disputes");
+        let mut commission = self.expect_active(commission_id);
+        // The client may have approved the milestone while the check ran
+        let status = Self::milestone_mut(&mut commission, milestone_index).status;
+        require!(status == MilestoneStatus::Disputed, "Milestone is not disputed");
+        emit_event(
+            "dispute_resolved",
+            near_sdk::serde_json::json!({
+                "commission_id": commission_id,
+                "milestone_index": milestone_index,
+                "arbiter": arbiter,
+                "creator_bps": creator_bps,
+            }),
+        );
+        let status = MilestoneStatus::Resolved { creator_bps };
+        self.settle(commission_id, commission, milestone_index, status, creator_bps, arbiter);
+    }
+
+    // Configuration
+    pub fn set_arbiter_contract(&mut self, arbiter_contract_id: AccountId) {
+        self.assert_owner();
+        self.arbiter_contract_id = arbiter_contract_id;
+    }
+
+    /// Review period for commissions opened from now on.
+    pub fn set_review_period(&mut self, review_period_ns: u64) {
+        self.assert_owner();
+        require!(review_period_ns >= NS_PER_DAY, "Review period must be at least one day");
+        self.review_period_ns = review_period_ns;
+    }
+
+    // Views
+    pub fn get_commission(&self, commission_id: u64) -> Option<Commission> {
+        self.commissions.get(&commission_id)
+    }
+
+    /// Commissions by id, starting at `from_id` (default 1).
+    pub fn get_commissions(&self, from_id: Option<u64>, limit: Option<u64>) -> Vec<CommissionView> {
+        let from_id = from_id.unwrap_or(1).max(1);
+        let limit = limit.unwrap_or(MAX_COMMISSION_PAGE).min(MAX_COMMISSION_PAGE);
+        (from_id..self.next_commission_id)
+            .take(limit as usize)
+            .filter_map(|id| self.commissions.get(&id).map(|commission| CommissionView { id, commission }))
+            .collect()
+    }
+
+    pub fn get_arbiter_contract(&self) -> AccountId {
+        self.arbiter_contract_id.clone()
+    }
+
+    pub fn get_review_period(&self) -> u64 {
+        self.review_period_ns
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+}
+
+impl CommissionEscrow {
+    fn assert_owner(&self) {
+        require!(env::predecessor_account_id() == self.owner_id, "Only the owner can call this method");
+    }
+
+    fn expect_commission(&self, commission_id: u64) -> Commission {
+        self.commissions.get(&commission_id).unwrap_or_else(|| env::panic_str("Commission not found"))
+    }
+
+    fn expect_active(&self, commission_id: u64) -> Commission {
+        let commission = self.expect_commission(commission_id);
+        require!(commission.status == CommissionStatus::Active, "Commission is not active");
+        commission
+    }
+
+    fn milestone_mut(commission: &mut Commission, milestone_index: usize) -> &mut Milestone {
+        commission.milestones.get_mut(milestone_index).unwrap_or_else(|| env::panic_str("Milestone not found"))
+    }
+
+    /// Pay out a milestone, `creator_bps` of it to the creator and the rest to the client, and
+    /// complete the commission once every milestone is settled.
+    fn settle(
+        &mut self,
+        commission_id: u64,
+        mut commission: Commission,
+        milestone_index: usize,
+        status: MilestoneStatus,
+        creator_bps: u16,
+        settled_by: AccountId,
+    ) -> Commission {
+        let milestone = Self::milestone_mut(&mut commission, milestone_index);
+        let amount = milestone.amount.as_yoctonear();
+        milestone.status = status;
+        let creator_amount = apply_bps(amount, creator_bps.into()).unwrap_or_else(|| env::panic_str("Split overflow"));
+        let client_amount = amount - creator_amount;
+
+        if commission.milestones.iter().all(|milestone| milestone.status.is_settled()) {
+            commission.status = CommissionStatus::Completed;
+        }
+        self.commissions.insert(&commission_id, &commission);
+
+        if creator_amount > 0 {
+            Promise::new(commission.creator.clone()).transfer(NearToken::from_yoctonear(creator_amount));
+        }
+        if client_amount > 0 {
+            Promise::new(commission.client.clone()).transfer(NearToken::from_yoctonear(client_amount));
+        }
+        MilestoneSettled {
+            commission_id,
+            milestone_index,
+            creator_amount: U128(creator_amount),
+            client_amount: U128(client_amount),
+            settled_by,
+        }
+        .emit();
+        commission
+    }
+}
+
+fn emit_event(event: &str, data: near_sdk::serde_json::Value) {
+    groupweave_events::commission::emit(event, data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const DAY: u64 = NS_PER_DAY;
+
+    // accounts(0) owns the escrow, accounts(1) is the client, accounts(2) the creator
+    fn set_context(predecessor: AccountId, deposit: NearToken, timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(5))
+            .predecessor_account_id(predecessor)
+            .attached_deposit(deposit)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    fn milestones(amounts: &[u128]) -> Vec<MilestoneInput> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(index, near)| MilestoneInput {
+                description: format!("Milestone {}", index),
+                amount: NearToken::from_near(*near),
+            })
+            .collect()
+    }
+
+    fn accepted_commission(amounts: &[u128]) -> (CommissionEscrow, u64) {
+        set_context(accounts(0), NearToken::from_near(0), 0);
+        let mut escrow = CommissionEscrow::new(accounts(0), "market.testnet".parse().unwrap());
+        let total: u128 = amounts.iter().sum();
+        set_context(accounts(1), NearToken::from_near(total + 1), 0);
+        let id = escrow.create_commission(accounts(2), "Character sheet".into(), milestones(amounts), 30);
+        set_context(accounts(2), NearToken::from_near(0), 0);
+        escrow.accept_commission(id);
+        (escrow, id)
+    }
+
+    fn status(escrow: &CommissionEscrow, id: u64, index: usize) -> MilestoneStatus {
+        escrow.get_commission(id).unwrap().milestones[index].status
+    }
+
+    #[test]
+    fn milestones_are_approved_or_released_after_review() {
+        let (mut escrow, id) = accepted_commission(&[2, 3]);
+        escrow.submit_milestone(id, 0, "hash-0".into());
+        escrow.submit_milestone(id, 1, "hash-1".into());
+
+        set_context(accounts(1), NearToken::from_near(0), DAY);
+        escrow.approve_milestone(id, 0);
+        assert_eq!(status(&escrow, id, 0), MilestoneStatus::Released);
+        assert_eq!(escrow.get_commission(id).unwrap().status, CommissionStatus::Active);
+
+        // The client stays silent on the second one; anyone releases it after the review period
+        set_context(accounts(3), NearToken::from_near(0), DEFAULT_REVIEW_PERIOD_NS);
+        escrow.release_milestone(id, 1);
+        assert_eq!(status(&escrow, id, 1), MilestoneStatus::Released);
+        assert_eq!(escrow.get_commission(id).unwrap().status, CommissionStatus::Completed);
+    }
+
+    #[test]
+    #[should_panic(expected = "Review period has not ended")]
+    fn release_waits_for_the_review_period() {
+        let (mut escrow, id) = accepted_commission(&[2]);
+        escrow.submit_milestone(id, 0, "hash-0".into());
+        set_context(accounts(3), NearToken::from_near(0), DEFAULT_REVIEW_PERIOD_NS - 1);
+        escrow.release_milestone(id, 0);
+    }
+
+    #[test]
+    fn cancel_before_acceptance_refunds_the_client() {
+        set_context(accounts(0), NearToken::from_near(0), 0);
+        let mut escrow = CommissionEscrow::new(accounts(0), "market.testnet".parse().unwrap());
+        set_context(accounts(1), NearToken::from_near(4), 0);
+        let id = escrow.create_commission(accounts(2), "Logo".into(), milestones(&[1, 2]), 10);
+
+        // The creator declines
+        set_context(accounts(2), NearToken::from_near(0), 0);
+        escrow.cancel_commission(id);
+        let commission = escrow.get_commission(id).unwrap();
+        assert_eq!(commission.status, CommissionStatus::Cancelled);
+        assert!(commission.milestones.iter().all(|m| m.status == MilestoneStatus::Refunded));
+    }
+
+    #[test]
+    #[should_panic(expected = "(milestones plus storage)")]
+    fn create_requires_the_full_total() {
+        set_context(accounts(0), NearToken::from_near(0), 0);
+        let mut escrow = CommissionEscrow::new(accounts(0), "market.testnet".parse().unwrap());
+        set_context(accounts(1), NearToken::from_near(3), 0);
+        escrow.create_commission(accounts(2), "Logo".into(), milestones(&[1, 2]), 10);
+    }
+
+    #[test]
+    fn arbiter_splits_a_disputed_milestone() {
+        let (mut escrow, id) = accepted_commission(&[10]);
+        escrow.submit_milestone(id, 0, "hash-0".into());
+        set_context(accounts(1), NearToken::from_near(0), DAY);
+        escrow.dispute_milestone(id, 0, "reason".into());
+        assert_eq!(status(&escrow, id, 0), MilestoneStatus::Disputed);
+
+        set_context(accounts(3), NearToken::from_near(0), DAY);
+        escrow.resolve_dispute(id, 0, 7_000);
+
+        // The bounty market confirmed accounts(3) as an arbiter
+        set_context(accounts(5), NearToken::from_near(0), DAY);
+        escrow.on_arbiter_checked(accounts(3), id, 0, 7_000, Ok(true));
+        assert_eq!(status(&escrow, id, 0), MilestoneStatus::Resolved { creator_bps: 7_000 });
+        assert_eq!(escrow.get_commission(id).unwrap().status, CommissionStatus::Completed);
+        let settled = get_logs().into_iter().find(|log| log.contains("milestone_settled")).unwrap();
+        assert!(settled.contains(&format!("\"creator_amount\":\"{}\"", NearToken::from_near(7).as_yoctonear())));
+        assert!(settled.contains(&format!("\"client_amount\":\"{}\"", NearToken::from_near(3).as_yoctonear())));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only a bounty market arbiter can resolve disputes")]
+    fn non_arbiter_cannot_resolve() {
+        let (mut escrow, id) = accepted_commission(&[10]);
+        escrow.submit_milestone(id, 0, "hash-0".into());
+        set_context(accounts(1), NearToken::from_near(0), DAY);
+        escrow.dispute_milestone(id, 0, "reason".into());
+
+        set_context(accounts(5), NearToken::from_near(0), DAY);
+        escrow.on_arbiter_checked(accounts(2), id, 0, 10_000, Ok(false));
+    }
+
+    #[test]
+    fn client_reclaims_unsubmitted_milestones_after_the_deadline() {
+        let (mut escrow, id) = accepted_commission(&[2, 3]);
+        escrow.submit_milestone(id, 0, "hash-0".into());
+
+        set_context(accounts(1), NearToken::from_near(0), 30 * DAY);
+        let refunded = escrow.reclaim_unsubmitted(id);
+        assert_eq!(refunded, U128(NearToken::from_near(3).as_yoctonear()));
+        assert_eq!(status(&escrow, id, 0), MilestoneStatus::Submitted);
+        assert_eq!(status(&escrow, id, 1), MilestoneStatus::Refunded);
+    }
+}
@@ -0,0 +1,79 @@
+//! Events of the content bounty market.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "groupweave_bounty";
+pub const VERSION: &str = "1.0.0";
+
+/// Emit a bounty event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyCreated {
+    pub bounty_id: u64,
+    pub creator: AccountId,
+    pub base_prize: U128,
+    pub ends_at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContentSubmitted {
+    pub bounty_id: u64,
+    pub submission_index: u64,
+    pub creator: AccountId,
+    pub creation_id: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionStaked {
+    pub bounty_id: u64,
+    pub submission_index: u64,
+    pub account_id: AccountId,
+    pub amount: U128, // this stake, not the account's running total
+}
+
+/// A bounty was settled. `winning_submission` is `None` when nobody staked or a single
+/// participant was refunded.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyClosed {
+    pub bounty_id: u64,
+    pub winning_submission: Option<u64>,
+    pub total_staked: U128,
+}
+
+/// A participant was paid out, in NEAR or (`as_credits`) as platform credits.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WinningsClaimed {
+    pub bounty_id: u64,
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub as_credits: bool,
+}
+
+/// A settled bounty's records were deleted; the creator got `storage_refund` back for the freed storage.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyCleanedUp {
+    pub bounty_id: u64,
+    pub storage_freed: u64,
+    pub storage_refund: U128,
+    pub unclaimed_stakes: u64,
+}
+
+nep297_events! {
+    BountyCreated => "bounty_created",
+    ContentSubmitted => "content_submitted",
+    SubmissionStaked => "submission_staked",
+    BountyClosed => "bounty_closed",
+    WinningsClaimed => "winnings_claimed",
+    BountyCleanedUp => "bounty_cleaned_up",
+}
@@ -0,0 +1,30 @@
+//! Events of the shade classifier agent contract.
+
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "shade_classifier";
+pub const VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClassificationLogged {
+    pub session_id: String,
+    pub label: String,
+    pub confidence_bps: u32,
+    pub model: String,
+}
+
+/// A human review accepted or overrode the agent's label.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClassificationReviewed {
+    pub session_id: String,
+    pub reviewer: AccountId,
+    pub final_label: String,
+}
+
+nep297_events! {
+    ClassificationLogged => "classification_logged",
+    ClassificationReviewed => "classification_reviewed",
+}
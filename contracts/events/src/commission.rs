@@ -0,0 +1,48 @@
+//! Events of the commission escrow contract: commission and milestone lifecycle.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "groupweave_commission";
+pub const VERSION: &str = "1.0.0";
+
+/// Emit a commission event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommissionCreated {
+    pub commission_id: u64,
+    pub client: AccountId,
+    pub creator: AccountId,
+    pub total: U128,
+    pub milestones: usize,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MilestoneDisputed {
+    pub commission_id: u64,
+    pub milestone_index: usize,
+    pub reason_hash: String,
+}
+
+/// Escrowed funds of a milestone were paid out: approved, auto-released, refunded or split by an arbiter.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MilestoneSettled {
+    pub commission_id: u64,
+    pub milestone_index: usize,
+    pub creator_amount: U128,
+    pub client_amount: U128,
+    pub settled_by: AccountId,
+}
+
+nep297_events! {
+    CommissionCreated => "commission_created",
+    MilestoneDisputed => "milestone_disputed",
+    MilestoneSettled => "milestone_settled",
+}
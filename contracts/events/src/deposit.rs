@@ -0,0 +1,88 @@
+//! Events of the deposits contract. The lifecycle events have dedicated payload structs with
+//! stable field names, so reshaping stored records never changes what the indexer and webhook
+//! service receive.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "dreamweave_deposit";
+pub const VERSION: &str = "2.0.0";
+
+/// Emit a deposits event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+/// A deposit was accepted and stored. `beneficiary_id` is top-level so it can be indexed.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositRecorded {
+    pub deposit_id: u64,
+    pub account_id: AccountId,
+    pub beneficiary_id: String,
+    pub token_id: String,
+    pub amount: U128,
+    pub usd_value: U128,
+    pub fee_usd_value: U128,
+    pub bonus_usd_value: U128,
+    pub credits_hint: Option<u64>,
+    pub memo: Option<String>,
+    pub on_behalf_of: Option<AccountId>,
+    pub relayed_by: Option<AccountId>,
+    /// NEAR Intents settlement, set only for solver-settled deposits.
+    pub solver_id: Option<AccountId>,
+    pub quote_id: Option<String>,
+    pub original_asset: Option<String>,
+    pub timestamp_ms: u64,
+}
+
+/// A deposit was returned to its payer.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositRefunded {
+    pub deposit_id: u64,
+    pub account_id: AccountId,
+    pub beneficiary_id: String,
+    pub token_id: String,
+    pub amount: U128,
+}
+
+/// The backend credited a deposit on the platform.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositCredited {
+    pub deposit_id: u64,
+    pub credits_granted: u64,
+}
+
+/// A token was added or its configuration changed (price changes emit `price_updated`).
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenConfigUpdated {
+    pub token_id: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub is_enabled: bool,
+    pub is_native: bool,
+    pub min_deposit_usd_micros: U128,
+    pub deposit_fee_bps: u16,
+}
+
+/// A token's USD price was set.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PriceUpdated {
+    pub token_id: String,
+    pub price_usd_micros: U128,
+    pub previous_price_usd_micros: U128,
+    pub forced: bool,
+}
+
+nep297_events! {
+    DepositRecorded => "deposit_recorded",
+    DepositRefunded => "deposit_refunded",
+    DepositCredited => "deposit_credited",
+    TokenConfigUpdated => "token_config_updated",
+    PriceUpdated => "price_updated",
+}
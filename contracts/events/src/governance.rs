@@ -0,0 +1,52 @@
+//! Events of the governance contract: proposal lifecycle and council changes.
+
+use near_sdk::json_types::U64;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "groupweave_governance";
+pub const VERSION: &str = "1.0.0";
+
+/// Emit a governance event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalCreated {
+    pub proposal_id: u64,
+    pub proposer: AccountId,
+    pub description: String,
+    pub voting_ends_at_ns: U64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalVoted {
+    pub proposal_id: u64,
+    pub voter: AccountId,
+    pub approve: bool,
+}
+
+/// The proposal reached the approval threshold and can run once the timelock passes.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalApproved {
+    pub proposal_id: u64,
+    pub executable_at_ns: U64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalExecuted {
+    pub proposal_id: u64,
+    pub success: bool,
+}
+
+nep297_events! {
+    ProposalCreated => "proposal_created",
+    ProposalVoted => "proposal_voted",
+    ProposalApproved => "proposal_approved",
+    ProposalExecuted => "proposal_executed",
+}
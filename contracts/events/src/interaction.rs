@@ -0,0 +1,41 @@
+//! Events of the shade curation agent contract: agent logs and the state around them.
+
+use near_sdk::json_types::U64;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "shade_curation";
+pub const VERSION: &str = "1.0.0";
+
+/// Emit a curation event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+/// A `log_interactions` call stored `count` new logs.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InteractionsLogged {
+    pub agent_account_id: AccountId,
+    pub count: usize,
+    pub duplicates: usize, // ignored re-sends
+    pub total_cost_microusd: U64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AgentAdded {
+    pub agent_account_id: AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AgentRemoved {
+    pub agent_account_id: AccountId,
+}
+
+nep297_events! {
+    InteractionsLogged => "interactions_logged",
+    AgentAdded => "agent_added",
+    AgentRemoved => "agent_removed",
+}
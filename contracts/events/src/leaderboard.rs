@@ -0,0 +1,28 @@
+//! Events of the leaderboard contract: settlements folded into the rankings.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "groupweave_leaderboard";
+pub const VERSION: &str = "1.0.0";
+
+/// Emit a leaderboard event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+/// A reporter contract pushed a settled market.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SettlementRecorded {
+    pub reporter_id: AccountId,
+    pub market_id: u64,
+    pub winner: AccountId,
+    pub volume: U128,
+    pub backers: usize,
+}
+
+nep297_events! {
+    SettlementRecorded => "settlement_recorded",
+}
@@ -0,0 +1,239 @@
+//! Shared NEP-297 event schema for the groupweave contracts.
+//!
+//! Every contract logs its events through this crate, so indexers parse one envelope:
+//! `EVENT_JSON:{"standard":...,"version":...,"event":...,"data":[...]}`. Each contract keeps its
+//! own `standard` (one module per contract family below). Events with a stable payload have a
+//! struct implementing [`Nep297Event`]; the rest are emitted with the module's `emit` helper.
+
+use near_sdk::env;
+use near_sdk::serde::Serialize;
+
+/// Implement [`Nep297Event`] for payload structs using the enclosing module's
+/// `STANDARD` and `VERSION`.
+macro_rules! nep297_events {
+    ($($ty:ident => $event:literal),* $(,)?) => {
+        $(
+            impl $crate::Nep297Event for $ty {
+                const STANDARD: &'static str = STANDARD;
+                const VERSION: &'static str = VERSION;
+                const EVENT: &'static str = $event;
+            }
+        )*
+    };
+}
+
+pub mod bounty;
+pub mod classification;
+pub mod commission;
+pub mod deposit;
+pub mod governance;
+pub mod interaction;
+pub mod leaderboard;
+pub mod reputation;
+pub mod stream;
+
+/// The NEP-297 envelope. `data` always holds a single entry.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventLog<'a, T: Serialize> {
+    pub standard: &'a str,
+    pub version: &'a str,
+    pub event: &'a str,
+    pub data: [T; 1],
+}
+
+/// A typed event payload.
+pub trait Nep297Event: Serialize + Sized {
+    const STANDARD: &'static str;
+    const VERSION: &'static str;
+    const EVENT: &'static str;
+
+    fn emit(self) {
+        emit(Self::STANDARD, Self::VERSION, Self::EVENT, self)
+    }
+}
+
+/// The full `EVENT_JSON:` log line for a single-entry event.
+pub fn log_line<T: Serialize>(standard: &str, version: &str, event: &str, data: T) -> String {
+    let log = EventLog { standard, version, event, data: [data] };
+    format!("EVENT_JSON:{}", near_sdk::serde_json::to_string(&log).unwrap())
+}
+
+/// Emit a single-entry NEP-297 event.
+pub fn emit<T: Serialize>(standard: &str, version: &str, event: &str, data: T) {
+    env::log_str(&log_line(standard, version, event, data));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::json_types::U128;
+    use near_sdk::serde_json::{self, json, Value};
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::testing_env;
+    use near_sdk::test_utils::VMContextBuilder;
+
+    fn parse(line: &str) -> Value {
+        serde_json::from_str(line.strip_prefix("EVENT_JSON:").expect("NEP-297 prefix")).unwrap()
+    }
+
+    fn emitted<T: Nep297Event>(event: T) -> Value {
+        testing_env!(VMContextBuilder::new().build());
+        event.emit();
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        parse(&logs[0])
+    }
+
+    #[test]
+    fn envelope_wraps_data_in_single_entry_array() {
+        let event = parse(&log_line("std", "1.2.3", "thing_happened", json!({ "id": 7 })));
+        assert_eq!(event, json!({ "standard": "std", "version": "1.2.3", "event": "thing_happened", "data": [{ "id": 7 }] }));
+    }
+
+    #[test]
+    fn module_emit_uses_module_standard() {
+        testing_env!(VMContextBuilder::new().build());
+        interaction::emit("agent_added", json!({ "agent_account_id": "agent.testnet" }));
+        let event = parse(&get_logs()[0]);
+        assert_eq!(event["standard"], interaction::STANDARD);
+        assert_eq!(event["version"], interaction::VERSION);
+        assert_eq!(event["event"], "agent_added");
+    }
+
+    #[test]
+    fn deposit_recorded_schema() {
+        let event = emitted(deposit::DepositRecorded {
+            deposit_id: 3,
+            account_id: "payer.testnet".parse().unwrap(),
+            beneficiary_id: "user-1".into(),
+            token_id: "near".into(),
+            amount: U128(5),
+            usd_value: U128(6_000_000),
+            fee_usd_value: U128(0),
+            bonus_usd_value: U128(0),
+            credits_hint: None,
+            memo: None,
+            on_behalf_of: None,
+            relayed_by: None,
+            solver_id: None,
+            quote_id: None,
+            original_asset: None,
+            timestamp_ms: 1,
+        });
+        assert_eq!(event["standard"], "dreamweave_deposit");
+        assert_eq!(event["version"], "2.0.0");
+        assert_eq!(event["event"], "deposit_recorded");
+        assert_eq!(event["data"][0]["usd_value"], "6000000");
+        assert_eq!(event["data"][0]["beneficiary_id"], "user-1");
+    }
+
+    #[test]
+    fn bounty_events_schema() {
+        let event = emitted(bounty::SubmissionStaked {
+            bounty_id: 1,
+            submission_index: 0,
+            account_id: "backer.testnet".parse().unwrap(),
+            amount: U128(10),
+        });
+        assert_eq!(event["standard"], "groupweave_bounty");
+        assert_eq!(event["event"], "submission_staked");
+        assert_eq!(event["data"][0], json!({ "bounty_id": 1, "submission_index": 0, "account_id": "backer.testnet", "amount": "10" }));
+
+        let event = emitted(bounty::BountyClosed { bounty_id: 1, winning_submission: None, total_staked: U128(0) });
+        assert_eq!(event["event"], "bounty_closed");
+        assert_eq!(event["data"][0]["winning_submission"], Value::Null);
+    }
+
+    #[test]
+    fn interaction_events_schema() {
+        let event = emitted(interaction::InteractionsLogged {
+            agent_account_id: "agent.testnet".parse().unwrap(),
+            count: 2,
+            duplicates: 1,
+            total_cost_microusd: 30.into(),
+        });
+        assert_eq!(event["standard"], "shade_curation");
+        assert_eq!(event["version"], "1.0.0");
+        assert_eq!(event["event"], "interactions_logged");
+        assert_eq!(event["data"][0]["total_cost_microusd"], "30");
+
+        let event = emitted(classification::ClassificationReviewed {
+            session_id: "s1".into(),
+            reviewer: "owner.testnet".parse().unwrap(),
+            final_label: "cat".into(),
+        });
+        assert_eq!(event["standard"], "shade_classifier");
+        assert_eq!(event["event"], "classification_reviewed");
+    }
+
+    #[test]
+    fn governance_events_schema() {
+        let event = emitted(governance::ProposalApproved { proposal_id: 4, executable_at_ns: 86_400.into() });
+        assert_eq!(event["standard"], "groupweave_governance");
+        assert_eq!(event["version"], "1.0.0");
+        assert_eq!(event["event"], "proposal_approved");
+        assert_eq!(event["data"][0], json!({ "proposal_id": 4, "executable_at_ns": "86400" }));
+    }
+
+    #[test]
+    fn commission_events_schema() {
+        let event = emitted(commission::MilestoneSettled {
+            commission_id: 2,
+            milestone_index: 1,
+            creator_amount: U128(70),
+            client_amount: U128(30),
+            settled_by: "arbiter.testnet".parse().unwrap(),
+        });
+        assert_eq!(event["standard"], "groupweave_commission");
+        assert_eq!(event["event"], "milestone_settled");
+        assert_eq!(event["data"][0]["creator_amount"], "70");
+        assert_eq!(event["data"][0]["milestone_index"], 1);
+    }
+
+    #[test]
+    fn stream_events_schema() {
+        let event = emitted(stream::StreamCancelled {
+            stream_id: 3,
+            cancelled_by: "sender.testnet".parse().unwrap(),
+            recipient_amount: U128(25),
+            sender_amount: U128(75),
+        });
+        assert_eq!(event["standard"], "groupweave_stream");
+        assert_eq!(event["event"], "stream_cancelled");
+        assert_eq!(
+            event["data"][0],
+            json!({ "stream_id": 3, "cancelled_by": "sender.testnet", "recipient_amount": "25", "sender_amount": "75" })
+        );
+    }
+
+    #[test]
+    fn leaderboard_events_schema() {
+        let event = emitted(leaderboard::SettlementRecorded {
+            reporter_id: "market.testnet".parse().unwrap(),
+            market_id: 7,
+            winner: "creator.testnet".parse().unwrap(),
+            volume: U128(1_000),
+            backers: 2,
+        });
+        assert_eq!(event["standard"], "groupweave_leaderboard");
+        assert_eq!(event["event"], "settlement_recorded");
+        assert_eq!(event["data"][0]["volume"], "1000");
+        assert_eq!(event["data"][0]["backers"], 2);
+    }
+
+    #[test]
+    fn reputation_events_schema() {
+        let event = emitted(reputation::ReputationRecorded {
+            reporter_id: "market.testnet".parse().unwrap(),
+            signal: "win".into(),
+            account_ids: vec!["creator.testnet".parse().unwrap()],
+        });
+        assert_eq!(event["standard"], "groupweave_reputation");
+        assert_eq!(event["event"], "reputation_recorded");
+        assert_eq!(
+            event["data"][0],
+            json!({ "reporter_id": "market.testnet", "signal": "win", "account_ids": ["creator.testnet"] })
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Events of the reputation contract: signals recorded against accounts.
+
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "groupweave_reputation";
+pub const VERSION: &str = "1.0.0";
+
+/// Emit a reputation event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+/// A reporter contract recorded `signal` for each of `account_ids`.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReputationRecorded {
+    pub reporter_id: AccountId,
+    pub signal: String,
+    pub account_ids: Vec<AccountId>,
+}
+
+nep297_events! {
+    ReputationRecorded => "reputation_recorded",
+}
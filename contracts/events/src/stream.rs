@@ -0,0 +1,48 @@
+//! Events of the payment streams contract: stream creation, withdrawals and cancellations.
+
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+pub const STANDARD: &str = "groupweave_stream";
+pub const VERSION: &str = "1.0.0";
+
+/// Emit a stream event without a dedicated payload struct.
+pub fn emit<T: Serialize>(event: &str, data: T) {
+    crate::emit(STANDARD, VERSION, event, data)
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamCreated {
+    pub stream_id: u64,
+    pub sender: AccountId,
+    pub recipient: AccountId,
+    pub total: U128,
+    pub cliff_ends_at_ns: U64,
+    pub ends_at_ns: U64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamWithdrawn {
+    pub stream_id: u64,
+    pub recipient: AccountId,
+    pub amount: U128,
+}
+
+/// A stream was stopped early: the recipient keeps what had vested, the sender gets the rest back.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamCancelled {
+    pub stream_id: u64,
+    pub cancelled_by: AccountId,
+    pub recipient_amount: U128,
+    pub sender_amount: U128,
+}
+
+nep297_events! {
+    StreamCreated => "stream_created",
+    StreamWithdrawn => "stream_withdrawn",
+    StreamCancelled => "stream_cancelled",
+}
@@ -0,0 +1,513 @@
+//! Governance – council-run parameter control for the groupweave contracts.
+//!
+//! Platform parameters (fees, shares, budgets, treasury accounts) are owner-only on each
+//! contract. Making this contract the owner puts those calls behind a vote instead of a
+//! single hot key:
+//! - A council member proposes an action: a function call on another contract (e.g.
+//!   `update_platform_fee_rate` on the bounty market), or a change to the council or policy.
+//! - Council members vote. Once approvals reach the policy threshold the proposal is approved,
+//!   and it becomes executable after the policy timelock; anyone can then call `execute`.
+//! - Function calls and NEAR transfers run as promises from this account. A failed call leaves
+//!   the proposal `Failed` and it can be executed again.
+//!
+//! Contracts are handed over with their two-step ownership transfer: the current owner
+//! proposes this account, then a governance proposal calls `accept_ownership` (or
+//! `accept_owner` on the curation agent).
+
+use groupweave_events::governance::{ProposalApproved, ProposalCreated, ProposalExecuted, ProposalVoted};
+use groupweave_events::Nep297Event;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedSet};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, is_promise_success, near, require, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseOrValue,
+};
+
+/// Largest council; votes are tallied per member.
+pub const MAX_COUNCIL_SIZE: u64 = 50;
+
+/// Maximum number of proposals returned by one page of `get_proposals`.
+pub const MAX_PROPOSAL_PAGE: u64 = 100;
+
+/// Gas a proposal may attach to its function call; the rest covers the callback.
+const MAX_ACTION_GAS: Gas = Gas::from_tgas(200);
+const GAS_FOR_EXECUTE_CALLBACK: Gas = Gas::from_tgas(10);
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Policy {
+    /// Approvals needed to pass a proposal.
+    pub threshold: u64,
+    /// How long a proposal stays open for votes.
+    pub voting_period_ns: u64,
+    /// Delay between approval and execution, so affected users can react.
+    pub timelock_ns: u64,
+}
+
+/// What a proposal does when executed.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalAction {
+    /// Call `method_name` on `receiver_id` from this account, attaching `deposit` from its balance.
+    FunctionCall { receiver_id: AccountId, method_name: String, args: Base64VecU8, deposit: U128, gas: U64 },
+    /// Send NEAR from this account, e.g. platform fees the bounty market paid to its owner.
+    Transfer { receiver_id: AccountId, amount: U128 },
+    AddMember { member_id: AccountId },
+    RemoveMember { member_id: AccountId },
+    SetPolicy { policy: Policy },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum ProposalStatus {
+    InProgress,
+    Approved,
+    Rejected,
+    Cancelled,
+    /// A function call is in flight; its callback settles the status.
+    Executing,
+    Executed,
+    /// The function call failed; the proposal can be executed again.
+    Failed,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub proposer: AccountId,
+    pub description: String,
+    pub action: ProposalAction,
+    pub status: ProposalStatus,
+    pub approvals: u64,
+    pub rejections: u64,
+    pub submitted_at_ns: u64,
+    pub voting_ends_at_ns: u64,
+    /// Set on approval: submission of the last approving vote plus the timelock.
+    pub executable_at_ns: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalView {
+    pub proposal_id: u64,
+    #[serde(flatten)]
+    pub proposal: Proposal,
+}
+
+#[near(contract_state, contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
+#[derive(PanicOnDefault)]
+pub struct GovernanceContract {
+    council: UnorderedSet<AccountId>,
+    policy: Policy,
+    proposals: LookupMap<u64, Proposal>,
+    votes: LookupMap<(u64, AccountId), bool>,
+    next_proposal_id: u64,
+}
+
+#[near]
+impl GovernanceContract {
+    #[init]
+    pub fn new(council: Vec<AccountId>, policy: Policy) -> Self {
+        let mut this = Self {
+            council: UnorderedSet::new(b"c".to_vec()),
+            policy: policy.clone(),
+            proposals: LookupMap::new(b"p".to_vec()),
+            votes: LookupMap::new(b"v".to_vec()),
+            next_proposal_id: 0,
+        };
+        for member in council {
+            this.council.insert(&member);
+        }
+        require!(this.council.len() <= MAX_COUNCIL_SIZE, "Council is too large");
+        this.assert_valid_policy(&policy);
+        this
+    }
+
+    // Proposals
+    /// Open a proposal for a council vote (council only). Returns its id.
+    pub fn propose(&mut self, description: String, action: ProposalAction) -> u64 {
+        let proposer = self.assert_council();
+        require!(description.len() <= 1000, "Description too long (max 1000 characters)");
+        if let ProposalAction::FunctionCall { gas, .. } = &action {
+            require!(Gas::from_gas(gas.0) <= MAX_ACTION_GAS, "Function call gas exceeds 200 Tgas");
+        }
+
+        let proposal_id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+        let now = env::block_timestamp();
+        let proposal = Proposal {
+            proposer: proposer.clone(),
+            description: description.clone(),
+            action,
+            status: ProposalStatus::InProgress,
+            approvals: 0,
+            rejections: 0,
+            submitted_at_ns: now,
+            voting_ends_at_ns: now.saturating_add(self.policy.voting_period_ns),
+            executable_at_ns: None,
+        };
+        self.proposals.insert(&proposal_id, &proposal);
+        ProposalCreated { proposal_id, proposer, description, voting_ends_at_ns: U64(proposal.voting_ends_at_ns) }
+            .emit();
+        proposal_id
+    }
+
+    /// Vote on an open proposal (council only, one vote per member). Reaching the threshold
+    /// approves it; enough rejections that the threshold is out of reach reject it.
+    pub fn vote(&mut self, proposal_id: u64, approve: bool) {
+        let voter = self.assert_council();
+        let mut proposal = self.proposals.get(&proposal_id).expect("Proposal not found");
+        require!(proposal.status == ProposalStatus::InProgress, "Proposal is not open for voting");
+        require!(env::block_timestamp() < proposal.voting_ends_at_ns, "Voting period has ended");
+        require!(self.votes.insert(&(proposal_id, voter.clone()), &approve).is_none(), "Already voted");
+
+        if approve {
+            proposal.approvals += 1;
+        } else {
+            proposal.rejections += 1;
+        }
+        ProposalVoted { proposal_id, voter, approve }.emit();
+
+        if proposal.approvals >= self.policy.threshold {
+            let executable_at_ns = env::block_timestamp().saturating_add(self.policy.timelock_ns);
+            proposal.status = ProposalStatus::Approved;
+            proposal.executable_at_ns = Some(executable_at_ns);
+            ProposalApproved { proposal_id, executable_at_ns: U64(executable_at_ns) }.emit();
+        } else if proposal.rejections > self.council.len().saturating_sub(self.policy.threshold) {
+            proposal.status = ProposalStatus::Rejected;
+            groupweave_events::governance::emit("proposal_rejected", serde_json::json!({ "proposal_id": proposal_id }));
+        }
+        self.proposals.insert(&proposal_id, &proposal);
+    }
+
+    /// Withdraw an open proposal (proposer only).
+    pub fn cancel_proposal(&mut self, proposal_id: u64) {
+        let mut proposal = self.proposals.get(&proposal_id).expect("Proposal not found");
+        require!(env::predecessor_account_id() == proposal.proposer, "Only the proposer can cancel");
+        require!(proposal.status == ProposalStatus::InProgress, "Proposal is not open for voting");
+        proposal.status = ProposalStatus::Cancelled;
+        self.proposals.insert(&proposal_id, &proposal);
+        groupweave_events::governance::emit("proposal_cancelled", serde_json::json!({ "proposal_id": proposal_id }));
+    }
+
+    /// Run an approved proposal once its timelock has passed. Callable by anyone.
+    pub fn execute(&mut self, proposal_id: u64) -> PromiseOrValue<bool> {
+        let mut proposal = self.proposals.get(&proposal_id).expect("Proposal not found");
+        require!(
+            matches!(proposal.status, ProposalStatus::Approved | ProposalStatus::Failed),
+            "Proposal is not approved"
+        );
+        let executable_at_ns = proposal.executable_at_ns.expect("approved proposals have an execution time");
+        require!(env::block_timestamp() >= executable_at_ns, "Timelock has not expired");
+
+        match proposal.action.clone() {
+            ProposalAction::FunctionCall { receiver_id, method_name, args, deposit, gas } => {
+                proposal.status = ProposalStatus::Executing;
+                self.proposals.insert(&proposal_id, &proposal);
+                let promise = Promise::new(receiver_id)
+                    .function_call(method_name, args.0, NearToken::from_yoctonear(deposit.0), Gas::from_gas(gas.0))
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_EXECUTE_CALLBACK)
+                            .on_proposal_executed(proposal_id),
+                    );
+                PromiseOrValue::Promise(promise)
+            }
+            ProposalAction::Transfer { receiver_id, amount } => {
+                proposal.status = ProposalStatus::Executing;
+                self.proposals.insert(&proposal_id, &proposal);
+                let promise = Promise::new(receiver_id).transfer(NearToken::from_yoctonear(amount.0)).then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_EXECUTE_CALLBACK)
+                        .on_proposal_executed(proposal_id),
+                );
+                PromiseOrValue::Promise(promise)
+            }
+            action => {
+                self.apply_action(action);
+                proposal.status = ProposalStatus::Executed;
+                self.proposals.insert(&proposal_id, &proposal);
+                ProposalExecuted { proposal_id, success: true }.emit();
+                PromiseOrValue::Value(true)
+            }
+        }
+    }
+
+    #[private]
+    pub fn on_proposal_executed(&mut self, proposal_id: u64) -> bool {
+        let success = is_promise_success();
+        let mut proposal = self.proposals.get(&proposal_id).expect("Proposal not found");
+        proposal.status = if success { ProposalStatus::Executed } else { ProposalStatus::Failed };
+        self.proposals.insert(&proposal_id, &proposal);
+        ProposalExecuted { proposal_id, success }.emit();
+        success
+    }
+
+    // Views
+    pub fn get_proposal(&self, proposal_id: u64) -> Option<ProposalView> {
+        self.proposals.get(&proposal_id).map(|proposal| ProposalView { proposal_id, proposal })
+    }
+
+    /// Proposals in id order, at most [`MAX_PROPOSAL_PAGE`] per call.
+    pub fn get_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<ProposalView> {
+        let from = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(MAX_PROPOSAL_PAGE).min(MAX_PROPOSAL_PAGE);
+        (from..self.next_proposal_id.min(from.saturating_add(limit)))
+            .filter_map(|proposal_id| self.get_proposal(proposal_id))
+            .collect()
+    }
+
+    pub fn get_vote(&self, proposal_id: u64, account_id: AccountId) -> Option<bool> {
+        self.votes.get(&(proposal_id, account_id))
+    }
+
+    pub fn get_council(&self) -> Vec<AccountId> {
+        self.council.to_vec()
+    }
+
+    pub fn get_policy(&self) -> Policy {
+        self.policy.clone()
+    }
+
+    // Internal
+    fn apply_action(&mut self, action: ProposalAction) {
+        match action {
+            ProposalAction::AddMember { member_id } => {
+                require!(self.council.len() < MAX_COUNCIL_SIZE, "Council is too large");
+                self.council.insert(&member_id);
+                groupweave_events::governance::emit("member_added", serde_json::json!({ "member_id": member_id }));
+            }
+            ProposalAction::RemoveMember { member_id } => {
+                require!(self.council.remove(&member_id), "Not a council member");
+                self.assert_valid_policy(&self.policy);
+                groupweave_events::governance::emit("member_removed", serde_json::json!({ "member_id": member_id }));
+            }
+            ProposalAction::SetPolicy { policy } => {
+                self.assert_valid_policy(&policy);
+                groupweave_events::governance::emit("policy_updated", serde_json::json!(policy));
+                self.policy = policy;
+            }
+            ProposalAction::FunctionCall { .. } | ProposalAction::Transfer { .. } => {
+                unreachable!("calls and transfers execute as promises")
+            }
+        }
+    }
+
+    fn assert_council(&self) -> AccountId {
+        let caller = env::predecessor_account_id();
+        require!(self.council.contains(&caller), "Only council members can do this");
+        caller
+    }
+
+    fn assert_valid_policy(&self, policy: &Policy) {
+        require!(policy.threshold > 0, "Threshold must be at least 1");
+        require!(policy.threshold <= self.council.len(), "Threshold exceeds council size");
+        require!(policy.voting_period_ns > 0, "Voting period must be positive");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const DAY_NS: u64 = 86_400_000_000_000;
+
+    fn policy(threshold: u64) -> Policy {
+        Policy { threshold, voting_period_ns: 7 * DAY_NS, timelock_ns: DAY_NS }
+    }
+
+    fn set_context(predecessor: AccountId, timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id("dao.testnet".parse().unwrap())
+            .predecessor_account_id(predecessor)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    fn setup() -> GovernanceContract {
+        set_context(accounts(0), 0);
+        GovernanceContract::new(vec![accounts(0), accounts(1), accounts(2)], policy(2))
+    }
+
+    fn fee_action() -> ProposalAction {
+        ProposalAction::FunctionCall {
+            receiver_id: "bounty.testnet".parse().unwrap(),
+            method_name: "update_platform_fee_rate".into(),
+            args: Base64VecU8(br#"{"new_rate":300}"#.to_vec()),
+            deposit: U128(0),
+            gas: U64(Gas::from_tgas(20).as_gas()),
+        }
+    }
+
+    fn approve(contract: &mut GovernanceContract, proposal_id: u64) {
+        for voter in [accounts(0), accounts(1)] {
+            set_context(voter, DAY_NS / 2);
+            contract.vote(proposal_id, true);
+        }
+    }
+
+    fn status(contract: &GovernanceContract, proposal_id: u64) -> ProposalStatus {
+        contract.get_proposal(proposal_id).unwrap().proposal.status
+    }
+
+    #[test]
+    fn threshold_approval_starts_timelock() {
+        let mut contract = setup();
+        let id = contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+        approve(&mut contract, id);
+
+        let view = contract.get_proposal(id).unwrap();
+        assert_eq!(view.proposal.status, ProposalStatus::Approved);
+        assert_eq!(view.proposal.approvals, 2);
+        assert_eq!(view.proposal.executable_at_ns, Some(DAY_NS / 2 + DAY_NS));
+        assert!(get_logs().iter().any(|log| log.contains("\"event\":\"proposal_approved\"")));
+        assert_eq!(contract.get_vote(id, accounts(1)), Some(true));
+    }
+
+    #[test]
+    #[should_panic(expected = "Timelock has not expired")]
+    fn execute_waits_for_timelock() {
+        let mut contract = setup();
+        let id = contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+        approve(&mut contract, id);
+        set_context(accounts(3), DAY_NS);
+        contract.execute(id);
+    }
+
+    #[test]
+    fn function_call_settles_on_callback() {
+        let mut contract = setup();
+        let id = contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+        approve(&mut contract, id);
+
+        set_context(accounts(3), 2 * DAY_NS);
+        assert!(matches!(contract.execute(id), PromiseOrValue::Promise(_)));
+        assert_eq!(status(&contract, id), ProposalStatus::Executing);
+
+        let callback_context = VMContextBuilder::new()
+            .current_account_id("dao.testnet".parse().unwrap())
+            .predecessor_account_id("dao.testnet".parse().unwrap())
+            .build();
+        testing_env!(
+            callback_context.clone(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed],
+        );
+        assert!(!contract.on_proposal_executed(id));
+        assert_eq!(status(&contract, id), ProposalStatus::Failed);
+
+        // A failed call can be retried
+        set_context(accounts(3), 3 * DAY_NS);
+        contract.execute(id);
+        testing_env!(
+            callback_context,
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(vec![])],
+        );
+        assert!(contract.on_proposal_executed(id));
+        assert_eq!(status(&contract, id), ProposalStatus::Executed);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal is not approved")]
+    fn executed_proposal_cannot_run_twice() {
+        let mut contract = setup();
+        let id = contract.propose("Add a member".into(), ProposalAction::AddMember { member_id: accounts(3) });
+        approve(&mut contract, id);
+        set_context(accounts(3), 2 * DAY_NS);
+        contract.execute(id);
+        contract.execute(id);
+    }
+
+    #[test]
+    fn council_changes_apply_locally() {
+        let mut contract = setup();
+        let id = contract.propose("Add a member".into(), ProposalAction::AddMember { member_id: accounts(3) });
+        approve(&mut contract, id);
+        set_context(accounts(4), 2 * DAY_NS);
+        assert!(matches!(contract.execute(id), PromiseOrValue::Value(true)));
+        assert!(contract.get_council().contains(&accounts(3)));
+
+        set_context(accounts(3), 2 * DAY_NS);
+        let id = contract.propose("Require three approvals".into(), ProposalAction::SetPolicy { policy: policy(3) });
+        for voter in [accounts(0), accounts(3)] {
+            set_context(voter, 2 * DAY_NS);
+            contract.vote(id, true);
+        }
+        set_context(accounts(4), 4 * DAY_NS);
+        contract.execute(id);
+        assert_eq!(contract.get_policy().threshold, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold exceeds council size")]
+    fn removing_members_cannot_strand_the_threshold() {
+        set_context(accounts(0), 0);
+        let mut contract = GovernanceContract::new(vec![accounts(0), accounts(1)], policy(2));
+        let id = contract.propose("Remove a member".into(), ProposalAction::RemoveMember { member_id: accounts(1) });
+        for voter in [accounts(0), accounts(1)] {
+            set_context(voter, 0);
+            contract.vote(id, true);
+        }
+        set_context(accounts(0), 2 * DAY_NS);
+        contract.execute(id);
+    }
+
+    #[test]
+    fn rejection_when_threshold_unreachable() {
+        let mut contract = setup();
+        let id = contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+        set_context(accounts(1), 1);
+        contract.vote(id, false);
+        assert_eq!(status(&contract, id), ProposalStatus::InProgress);
+        set_context(accounts(2), 2);
+        contract.vote(id, false);
+        assert_eq!(status(&contract, id), ProposalStatus::Rejected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already voted")]
+    fn members_vote_once() {
+        let mut contract = setup();
+        let id = contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+        contract.vote(id, false);
+        contract.vote(id, true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only council members can do this")]
+    fn outsiders_cannot_propose() {
+        let mut contract = setup();
+        set_context(accounts(4), 0);
+        contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+    }
+
+    #[test]
+    #[should_panic(expected = "Voting period has ended")]
+    fn votes_close_after_voting_period() {
+        let mut contract = setup();
+        let id = contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+        set_context(accounts(1), 7 * DAY_NS);
+        contract.vote(id, true);
+    }
+
+    #[test]
+    fn proposer_can_cancel_and_proposals_page() {
+        let mut contract = setup();
+        let first = contract.propose("Lower the bounty fee to 3%".into(), fee_action());
+        let second = contract.propose("Add a member".into(), ProposalAction::AddMember { member_id: accounts(3) });
+        contract.cancel_proposal(first);
+        assert_eq!(status(&contract, first), ProposalStatus::Cancelled);
+
+        let page = contract.get_proposals(Some(1), Some(10));
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].proposal_id, second);
+    }
+}
@@ -0,0 +1,6 @@
+//! End-to-end sandbox tests for the groupweave contracts live in `tests/`; this crate has no
+//! library code. The tests compile and deploy the real contracts (`content-bounty-market`,
+//! `deposits` and the `mock-ft` token) with near-workspaces, so cross-contract transfers, gas
+//! and failed-promise callbacks run as they do on chain.
+//!
+//! Run with `cargo test -p groupweave-integration-tests` (needs `cargo-near` and the sandbox).
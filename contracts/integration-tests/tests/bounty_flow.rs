@@ -0,0 +1,366 @@
+mod common;
+
+use common::{
+    deploy_bounty_market, deploy_content_nft, deploy_deposits, deploy_leaderboard, deploy_reputation, set_near_price,
+    TestResult, NEAR_PRICE_USD_MICROS, ONE_DAY_OF_BLOCKS,
+};
+use near_sdk::NearToken;
+use near_workspaces::network::Sandbox;
+use near_workspaces::{Account, Contract, Worker};
+use serde_json::{json, Value};
+
+/// Accounts of a closed bounty where submission 0 won: `winner` backed it, `loser` backed submission 1.
+struct ClosedBounty {
+    bounty_id: u64,
+    winner: Account,
+    loser: Account,
+}
+
+/// Create → submit → stake → close with two submissions and one backer on each.
+async fn run_bounty_to_close(
+    sandbox: &Worker<Sandbox>,
+    market: &Contract,
+) -> Result<ClosedBounty, Box<dyn std::error::Error>> {
+    let creator = sandbox.dev_create_account().await?;
+    let created = creator
+        .call(market.id(), "create_content_bounty")
+        .args_json(json!({
+            "title": "Best short film",
+            "description": "Sandbox lifecycle bounty",
+            "requirements": "Submit a creation",
+            "base_prize": NearToken::from_near(1).as_yoctonear().to_string(),
+            "max_stake_per_user": NearToken::from_near(5).as_yoctonear().to_string(),
+            "duration_days": 1
+        }))
+        .deposit(NearToken::from_near(2))
+        .transact()
+        .await?;
+    assert!(created.is_success(), "bounty creation failed: {:?}", created);
+    let bounty_id: u64 = created.json()?;
+
+    for index in 0..2 {
+        let author = sandbox.dev_create_account().await?;
+        let submitted = author
+            .call(market.id(), "submit_content")
+            .args_json(json!({
+                "bounty_id": bounty_id,
+                "creation_id": format!("creation-{}", index),
+                "title": format!("Submission {}", index),
+                "thumbnail_url": format!("https://example.com/{}.png", index)
+            }))
+            .transact()
+            .await?;
+        assert!(submitted.is_success(), "submission failed: {:?}", submitted);
+    }
+
+    let winner = sandbox.dev_create_account().await?;
+    let loser = sandbox.dev_create_account().await?;
+    for (backer, submission_index, stake) in [(&winner, 0, 3), (&loser, 1, 2)] {
+        let staked = backer
+            .call(market.id(), "stake_on_submission")
+            .args_json(json!({ "bounty_id": bounty_id, "submission_index": submission_index }))
+            .deposit(NearToken::from_near(stake))
+            .transact()
+            .await?;
+        assert!(staked.is_success(), "stake failed: {:?}", staked);
+    }
+
+    sandbox.fast_forward(ONE_DAY_OF_BLOCKS).await?;
+
+    let closed = creator
+        .call(market.id(), "close_bounty")
+        .args_json(json!({ "bounty_id": bounty_id }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(closed.is_success(), "close failed: {:?}", closed);
+
+    let bounty: Value = market.view("get_bounty").args_json(json!({ "bounty_id": bounty_id })).await?.json()?;
+    assert_eq!(bounty["is_closed"], json!(true));
+    assert_eq!(bounty["winning_submission"], json!(0));
+
+    Ok(ClosedBounty { bounty_id, winner, loser })
+}
+
+async fn participant_stake(
+    market: &Contract,
+    account: &Account,
+    bounty_id: u64,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    Ok(market
+        .view("get_participant_stake")
+        .args_json(json!({ "account": account.id(), "bounty_id": bounty_id }))
+        .await?
+        .json()?)
+}
+
+#[tokio::test]
+async fn test_create_stake_close_claim() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+    let ClosedBounty { bounty_id, winner, loser } = run_bounty_to_close(&sandbox, &market).await?;
+
+    let before = winner.view_account().await?.balance;
+    let claimed = winner
+        .call(market.id(), "claim_bounty_winnings")
+        .args_json(json!({ "bounty_id": bounty_id }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(claimed.is_success(), "winning claim failed: {:?}", claimed);
+    assert!(winner.view_account().await?.balance > before, "winning backer was not paid");
+    assert!(participant_stake(&market, &winner, bounty_id).await?.is_null());
+
+    // A second claim finds no stake
+    let again = winner
+        .call(market.id(), "claim_bounty_winnings")
+        .args_json(json!({ "bounty_id": bounty_id }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(again.is_failure(), "double claim should fail");
+
+    // The losing backer's stake is forfeit: the claim succeeds but pays nothing back
+    let before = loser.view_account().await?.balance;
+    let forfeited = loser
+        .call(market.id(), "claim_bounty_winnings")
+        .args_json(json!({ "bounty_id": bounty_id }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(forfeited.is_success(), "forfeit claim failed: {:?}", forfeited);
+    assert!(loser.view_account().await?.balance <= before);
+    assert!(participant_stake(&market, &loser, bounty_id).await?.is_null());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_as_credits_deposits_into_credits_contract() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+    let treasury = sandbox.dev_create_account().await?;
+    let deposits = deploy_deposits(&sandbox, treasury.id()).await?;
+    set_near_price(&deposits, NEAR_PRICE_USD_MICROS).await?;
+
+    let configured = market
+        .call("set_credits_contract")
+        .args_json(json!({ "credits_contract_id": deposits.id() }))
+        .transact()
+        .await?;
+    assert!(configured.is_success(), "set_credits_contract failed: {:?}", configured);
+
+    let ClosedBounty { bounty_id, winner, .. } = run_bounty_to_close(&sandbox, &market).await?;
+
+    let treasury_before = treasury.view_account().await?.balance;
+    let claimed = winner
+        .call(market.id(), "claim_as_credits")
+        .args_json(json!({ "bounty_id": bounty_id, "beneficiary_id": "user-uuid-1" }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(claimed.is_success(), "claim_as_credits failed: {:?}", claimed);
+    assert!(participant_stake(&market, &winner, bounty_id).await?.is_null());
+
+    // The bounty market is the payer, paying on behalf of the winner; the platform user is the beneficiary
+    let records: Vec<Value> = deposits
+        .view("get_deposits_for_account")
+        .args_json(json!({ "account_id": market.id() }))
+        .await?
+        .json()?;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["beneficiary_id"], json!("user-uuid-1"));
+    assert_eq!(records[0]["on_behalf_of"], json!(winner.id()));
+    assert_eq!(records[0]["token_id"], json!("NEAR"));
+    assert!(treasury.view_account().await?.balance > treasury_before, "deposit was not forwarded");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_as_credits_failure_restores_stake() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+    let treasury = sandbox.dev_create_account().await?;
+    // No NEAR price: deposit_native panics, so on_claim_as_credits sees a failed promise
+    let deposits = deploy_deposits(&sandbox, treasury.id()).await?;
+
+    let configured = market
+        .call("set_credits_contract")
+        .args_json(json!({ "credits_contract_id": deposits.id() }))
+        .transact()
+        .await?;
+    assert!(configured.is_success(), "set_credits_contract failed: {:?}", configured);
+
+    let ClosedBounty { bounty_id, winner, .. } = run_bounty_to_close(&sandbox, &market).await?;
+
+    let claimed = winner
+        .call(market.id(), "claim_as_credits")
+        .args_json(json!({ "bounty_id": bounty_id, "beneficiary_id": "user-uuid-1" }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(claimed.is_success(), "claim_as_credits transaction failed: {:?}", claimed);
+    assert!(
+        claimed.logs().iter().any(|log| log.starts_with("CLAIM_AS_CREDITS_FAILED")),
+        "failure callback did not run"
+    );
+
+    let restored = participant_stake(&market, &winner, bounty_id).await?;
+    assert_eq!(restored["submission_index"], json!(0));
+
+    let records: Vec<Value> = deposits
+        .view("get_deposits_for_account")
+        .args_json(json!({ "account_id": market.id() }))
+        .await?
+        .json()?;
+    assert!(records.is_empty());
+
+    // The restored stake can still be claimed as NEAR
+    let before = winner.view_account().await?.balance;
+    let fallback = winner
+        .call(market.id(), "claim_bounty_winnings")
+        .args_json(json!({ "bounty_id": bounty_id }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(fallback.is_success(), "fallback claim failed: {:?}", fallback);
+    assert!(winner.view_account().await?.balance > before);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_close_mints_winning_creation_as_nft() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+    let nft = deploy_content_nft(&sandbox, market.id()).await?;
+
+    let configured = market
+        .call("set_nft_contract")
+        .args_json(json!({ "nft_contract_id": nft.id() }))
+        .transact()
+        .await?;
+    assert!(configured.is_success(), "set_nft_contract failed: {:?}", configured);
+
+    let ClosedBounty { bounty_id, winner, .. } = run_bounty_to_close(&sandbox, &market).await?;
+
+    let token_id: Option<String> =
+        market.view("get_bounty_nft").args_json(json!({ "bounty_id": bounty_id })).await?.json()?;
+    assert_eq!(token_id.as_deref(), Some("creation-0"));
+
+    let bounty: Value = market.view("get_bounty").args_json(json!({ "bounty_id": bounty_id })).await?.json()?;
+    let token: Value = nft.view("nft_token").args_json(json!({ "token_id": "creation-0" })).await?.json()?;
+    assert_eq!(token["owner_id"], bounty["submissions"][0]["creator"]);
+
+    // Creator 5%, the only winning backer 3%, the platform (market owner) 2% of a resale
+    let payout: Value = nft
+        .view("nft_payout")
+        .args_json(json!({ "token_id": "creation-0", "balance": "10000", "max_len_payout": 10 }))
+        .await?
+        .json()?;
+    assert_eq!(payout["payout"][winner.id().as_str()], json!("300"));
+    assert_eq!(payout["payout"][market.id().as_str()], json!("200"));
+
+    Ok(())
+}
+
+/// Creates a 1 NEAR bounty and returns it, or `None` if the reputation check removed it.
+async fn create_one_near_bounty(
+    market: &Contract,
+    creator: &Account,
+) -> Result<Option<Value>, Box<dyn std::error::Error>> {
+    let created = creator
+        .call(market.id(), "create_content_bounty")
+        .args_json(json!({
+            "title": "Gated bounty",
+            "description": "Needs a track record",
+            "requirements": "Submit a creation",
+            "base_prize": NearToken::from_near(1).as_yoctonear().to_string(),
+            "max_stake_per_user": NearToken::from_near(5).as_yoctonear().to_string(),
+            "duration_days": 1
+        }))
+        .deposit(NearToken::from_near(2))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(created.is_success(), "bounty creation failed: {:?}", created);
+    let bounty_id: u64 = created.json()?;
+    Ok(market.view("get_bounty").args_json(json!({ "bounty_id": bounty_id })).await?.json()?)
+}
+
+#[tokio::test]
+async fn test_settlement_builds_reputation_that_gates_bounties() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+    let reputation = deploy_reputation(&sandbox, market.id()).await?;
+
+    let configured = market
+        .call("set_reputation_contract")
+        .args_json(json!({ "reputation_contract_id": reputation.id() }))
+        .transact()
+        .await?;
+    assert!(configured.is_success(), "set_reputation_contract failed: {:?}", configured);
+
+    let ClosedBounty { bounty_id, winner, loser } = run_bounty_to_close(&sandbox, &market).await?;
+
+    let bounty: Value = market.view("get_bounty").args_json(json!({ "bounty_id": bounty_id })).await?.json()?;
+    let author: Value = reputation
+        .view("get_reputation")
+        .args_json(json!({ "account_id": bounty["submissions"][0]["creator"] }))
+        .await?
+        .json()?;
+    assert_eq!((author["wins"].clone(), author["score"].clone()), (json!(1), json!(10)));
+    let backer: Value =
+        reputation.view("get_reputation").args_json(json!({ "account_id": winner.id() })).await?.json()?;
+    assert_eq!((backer["successful_stakes"].clone(), backer["score"].clone()), (json!(1), json!(2)));
+
+    // From now on a 1 NEAR bounty is high-value and needs a score of 2 to create
+    let gated = market
+        .call("set_reputation_gate")
+        .args_json(json!({
+            "gate": {
+                "high_value_prize": NearToken::from_near(1).as_yoctonear().to_string(),
+                "min_creator_score": 2,
+                "min_submitter_score": 0
+            }
+        }))
+        .transact()
+        .await?;
+    assert!(gated.is_success(), "set_reputation_gate failed: {:?}", gated);
+
+    let accepted = create_one_near_bounty(&market, &winner).await?.expect("bounty should exist");
+    assert_eq!(accepted["is_active"], json!(true));
+    let rejected = create_one_near_bounty(&market, &loser).await?;
+    assert!(rejected.is_none(), "low-reputation bounty should be removed: {:?}", rejected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_settlement_is_pushed_to_the_leaderboard() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+    let leaderboard = deploy_leaderboard(&sandbox, market.id()).await?;
+
+    let configured = market
+        .call("set_leaderboard_contract")
+        .args_json(json!({ "leaderboard_contract_id": leaderboard.id() }))
+        .transact()
+        .await?;
+    assert!(configured.is_success(), "set_leaderboard_contract failed: {:?}", configured);
+
+    let ClosedBounty { bounty_id, winner, .. } = run_bounty_to_close(&sandbox, &market).await?;
+
+    let bounty: Value = market.view("get_bounty").args_json(json!({ "bounty_id": bounty_id })).await?.json()?;
+    let creators: Value = leaderboard.view("get_top_creators").args_json(json!({})).await?.json()?;
+    assert_eq!(creators[0]["account_id"], bounty["submissions"][0]["creator"]);
+    let backers: Value = leaderboard.view("get_top_backers").args_json(json!({})).await?.json()?;
+    assert_eq!(backers[0]["account_id"], json!(winner.id()));
+    let markets: Value = leaderboard.view("get_biggest_markets").args_json(json!({})).await?.json()?;
+    assert_eq!(markets[0]["market_id"], json!(bounty_id));
+    let totals: Value = leaderboard.view("get_totals").args_json(json!({})).await?.json()?;
+    assert_eq!(totals["markets_settled"], json!(1));
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{deploy_bounty_market, deploy_commission_escrow, TestResult};
+use near_sdk::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_disputed_milestone_is_split_by_a_bounty_market_resolver() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+    let escrow = deploy_commission_escrow(&sandbox, market.id()).await?;
+    let client = sandbox.dev_create_account().await?;
+    let creator = sandbox.dev_create_account().await?;
+    let arbiter = sandbox.dev_create_account().await?;
+
+    let created = client
+        .call(escrow.id(), "create_commission")
+        .args_json(json!({
+            "creator": creator.id(),
+            "brief": "Portrait of the guild founder",
+            "milestones": [{ "description": "Final render", "amount": NearToken::from_near(4) }],
+            "delivery_days": 14
+        }))
+        .deposit(NearToken::from_near(5))
+        .transact()
+        .await?;
+    assert!(created.is_success(), "create_commission failed: {:?}", created);
+    let commission_id: u64 = created.json()?;
+
+    let milestone = |key: &str, value: &str| json!({ "commission_id": commission_id, "milestone_index": 0, key: value });
+    for (account, method, args) in [
+        (&creator, "accept_commission", json!({ "commission_id": commission_id })),
+        (&creator, "submit_milestone", milestone("deliverable_hash", "ipfs://render")),
+        (&client, "dispute_milestone", milestone("reason_hash", "ipfs://complaint")),
+    ] {
+        let outcome = account.call(escrow.id(), method).args_json(args).transact().await?;
+        assert!(outcome.is_success(), "{} failed: {:?}", method, outcome);
+    }
+
+    let resolve = json!({ "commission_id": commission_id, "milestone_index": 0, "creator_bps": 5000 });
+    let rejected = arbiter.call(escrow.id(), "resolve_dispute").args_json(resolve.clone()).max_gas().transact().await?;
+    assert!(rejected.is_failure(), "a non-resolver should not settle disputes");
+
+    let granted = market
+        .call("grant_role")
+        .args_json(json!({ "role": "resolver", "account_id": arbiter.id() }))
+        .transact()
+        .await?;
+    assert!(granted.is_success(), "grant_role failed: {:?}", granted);
+
+    let creator_before = creator.view_account().await?.balance;
+    let resolved = arbiter.call(escrow.id(), "resolve_dispute").args_json(resolve).max_gas().transact().await?;
+    assert!(resolved.is_success(), "resolve_dispute failed: {:?}", resolved);
+
+    let commission: Value =
+        escrow.view("get_commission").args_json(json!({ "commission_id": commission_id })).await?.json()?;
+    assert_eq!(commission["status"], json!("completed"));
+    assert_eq!(commission["milestones"][0]["status"], json!({ "resolved": { "creator_bps": 5000 } }));
+    let creator_after = creator.view_account().await?.balance;
+    assert_eq!(creator_after.as_yoctonear() - creator_before.as_yoctonear(), NearToken::from_near(2).as_yoctonear());
+
+    Ok(())
+}
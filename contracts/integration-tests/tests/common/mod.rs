@@ -0,0 +1,166 @@
+//! Deploy helpers shared by the cross-contract sandbox tests.
+//!
+//! Each helper compiles the contract from its workspace directory, deploys it to a fresh
+//! dev account and runs its init call. Contracts own themselves, so owner-only calls are
+//! made with `contract.call(...)`.
+#![allow(dead_code)]
+
+use near_sdk::NearToken;
+use near_workspaces::network::Sandbox;
+use near_workspaces::{AccountId, Contract, Worker};
+use serde_json::json;
+
+pub type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+/// One NEAR = $100, so bounty payouts clear the deposits contract's $5 minimum.
+pub const NEAR_PRICE_USD_MICROS: u128 = 100_000_000;
+
+/// Blocks to fast-forward past a one-day bounty (the sandbox advances about a second per block).
+pub const ONE_DAY_OF_BLOCKS: u64 = 100_000;
+
+pub async fn deploy_bounty_market(sandbox: &Worker<Sandbox>) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("../content-bounty-market").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract
+        .call("new")
+        .args_json(json!({
+            "reward_rate": 100u128,
+            "min_stake_amount": NearToken::from_near(1).as_yoctonear().to_string(),
+            "max_stake_amount": NearToken::from_near(10_000).as_yoctonear().to_string(),
+        }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "bounty market init failed: {:?}", outcome);
+    Ok(contract)
+}
+
+/// Deploys the content NFT contract with `minter` (normally the bounty market) registered.
+pub async fn deploy_content_nft(
+    sandbox: &Worker<Sandbox>,
+    minter: &AccountId,
+) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("../content-nft").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract.call("new_default_meta").args_json(json!({ "owner_id": contract.id() })).transact().await?;
+    assert!(outcome.is_success(), "content NFT init failed: {:?}", outcome);
+    let outcome = contract.call("add_minter").args_json(json!({ "account_id": minter })).transact().await?;
+    assert!(outcome.is_success(), "add_minter failed: {:?}", outcome);
+    Ok(contract)
+}
+
+/// Deploys the reputation contract with `reporter` (normally the bounty market) registered.
+pub async fn deploy_reputation(
+    sandbox: &Worker<Sandbox>,
+    reporter: &AccountId,
+) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("../reputation").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract.call("new").args_json(json!({ "owner_id": contract.id() })).transact().await?;
+    assert!(outcome.is_success(), "reputation init failed: {:?}", outcome);
+    let outcome = contract.call("add_reporter").args_json(json!({ "account_id": reporter })).transact().await?;
+    assert!(outcome.is_success(), "add_reporter failed: {:?}", outcome);
+    Ok(contract)
+}
+
+/// Deploys the leaderboard contract with `reporter` (normally the bounty market) registered.
+pub async fn deploy_leaderboard(
+    sandbox: &Worker<Sandbox>,
+    reporter: &AccountId,
+) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("../leaderboard").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract.call("new").args_json(json!({ "owner_id": contract.id() })).transact().await?;
+    assert!(outcome.is_success(), "leaderboard init failed: {:?}", outcome);
+    let outcome = contract.call("add_reporter").args_json(json!({ "account_id": reporter })).transact().await?;
+    assert!(outcome.is_success(), "add_reporter failed: {:?}", outcome);
+    Ok(contract)
+}
+
+/// Deploys the commission escrow with disputes arbitrated by `arbiter_contract` (normally the bounty market).
+pub async fn deploy_commission_escrow(
+    sandbox: &Worker<Sandbox>,
+    arbiter_contract: &AccountId,
+) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("../escrow").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract
+        .call("new")
+        .args_json(json!({ "owner_id": contract.id(), "arbiter_contract_id": arbiter_contract }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "commission escrow init failed: {:?}", outcome);
+    Ok(contract)
+}
+
+/// Deploys the deposits contract forwarding to `treasury`. The NEAR price is left unset.
+pub async fn deploy_deposits(
+    sandbox: &Worker<Sandbox>,
+    treasury: &AccountId,
+) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("../deposits").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract
+        .call("new")
+        .args_json(json!({
+            "owner_id": contract.id(),
+            "treasury_account_id": treasury,
+        }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "deposits init failed: {:?}", outcome);
+    Ok(contract)
+}
+
+pub async fn set_near_price(deposits: &Contract, price_usd_micros: u128) -> TestResult {
+    let outcome = deposits
+        .call("update_token_price")
+        .args_json(json!({
+            "token_id": "NEAR",
+            "price_usd_micros": price_usd_micros.to_string(),
+        }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "price update failed: {:?}", outcome);
+    Ok(())
+}
+
+/// Deploys the mock NEP-141 token with the whole supply on the token account itself.
+pub async fn deploy_mock_ft(
+    sandbox: &Worker<Sandbox>,
+    total_supply: u128,
+    decimals: u8,
+) -> Result<Contract, Box<dyn std::error::Error>> {
+    let wasm = near_workspaces::compile_project("./mock-ft").await?;
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    let outcome = contract
+        .call("new")
+        .args_json(json!({
+            "owner_id": contract.id(),
+            "total_supply": total_supply.to_string(),
+            "decimals": decimals,
+        }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "mock ft init failed: {:?}", outcome);
+    Ok(contract)
+}
+
+pub async fn register_ft_account(ft: &Contract, account_id: &AccountId) -> TestResult {
+    let outcome = ft
+        .call("storage_deposit")
+        .args_json(json!({ "account_id": account_id }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "storage_deposit failed: {:?}", outcome);
+    Ok(())
+}
+
+pub async fn ft_balance_of(ft: &Contract, account_id: &AccountId) -> Result<u128, Box<dyn std::error::Error>> {
+    let balance: String = ft
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": account_id }))
+        .await?
+        .json()?;
+    Ok(balance.parse()?)
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use common::{
+    deploy_deposits, deploy_mock_ft, ft_balance_of, register_ft_account, set_near_price, TestResult,
+    NEAR_PRICE_USD_MICROS,
+};
+use near_sdk::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_native_deposit_forwards_to_treasury() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let treasury = sandbox.dev_create_account().await?;
+    let deposits = deploy_deposits(&sandbox, treasury.id()).await?;
+    set_near_price(&deposits, NEAR_PRICE_USD_MICROS).await?;
+    let user = sandbox.dev_create_account().await?;
+
+    let treasury_before = treasury.view_account().await?.balance;
+    let outcome = user
+        .call(deposits.id(), "deposit_native")
+        .args_json(json!({ "beneficiary_id": "user-uuid-1", "credits_hint": 100 }))
+        .deposit(NearToken::from_near(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "deposit_native failed: {:?}", outcome);
+
+    let record: Value = outcome.json()?;
+    assert_eq!(record["beneficiary_id"], json!("user-uuid-1"));
+    assert_eq!(record["usd_value"], json!(NEAR_PRICE_USD_MICROS.to_string()));
+    assert_eq!(record["status"], json!("pending"));
+
+    let treasury_after = treasury.view_account().await?.balance;
+    assert_eq!(treasury_after.saturating_sub(treasury_before), NearToken::from_near(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ft_deposit_failed_forward_is_flagged_then_swept() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let treasury = sandbox.dev_create_account().await?;
+    let deposits = deploy_deposits(&sandbox, treasury.id()).await?;
+    let user = sandbox.dev_create_account().await?;
+
+    // 6-decimal stablecoin at $1
+    let ft = deploy_mock_ft(&sandbox, 1_000_000_000_000, 6).await?;
+    let configured = deposits
+        .call("upsert_token_config")
+        .args_json(json!({
+            "token_id": ft.id(),
+            "symbol": "MOCK",
+            "decimals": 6,
+            "price_usd_micros": "1000000",
+            "is_enabled": true,
+            "is_native": false
+        }))
+        .transact()
+        .await?;
+    assert!(configured.is_success(), "upsert_token_config failed: {:?}", configured);
+
+    // The treasury is deliberately not registered on the token, so the forward fails
+    register_ft_account(&ft, user.id()).await?;
+    register_ft_account(&ft, deposits.id()).await?;
+    let amount: u128 = 10_000_000;
+    let funded = ft
+        .call("ft_transfer")
+        .args_json(json!({ "receiver_id": user.id(), "amount": amount.to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(funded.is_success(), "funding transfer failed: {:?}", funded);
+
+    let deposited = user
+        .call(ft.id(), "ft_transfer_call")
+        .args_json(json!({
+            "receiver_id": deposits.id(),
+            "amount": amount.to_string(),
+            "msg": json!({ "beneficiary_id": "user-uuid-2" }).to_string()
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(deposited.is_success(), "ft_transfer_call failed: {:?}", deposited);
+
+    // The deposit is recorded and kept: tokens stay on the deposits contract, not refunded
+    assert_eq!(ft_balance_of(&ft, user.id()).await?, 0);
+    assert_eq!(ft_balance_of(&ft, deposits.id()).await?, amount);
+
+    let records: Vec<Value> = deposits
+        .view("get_deposits_for_account")
+        .args_json(json!({ "account_id": user.id() }))
+        .await?
+        .json()?;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["beneficiary_id"], json!("user-uuid-2"));
+    assert_eq!(records[0]["status"], json!("flagged"));
+
+    // Registering the treasury and sweeping moves the stranded tokens
+    let swept = deposits
+        .call("ensure_storage_then_sweep")
+        .args_json(json!({ "token_id": ft.id(), "amount": amount.to_string() }))
+        .deposit(NearToken::from_millinear(10))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(swept.is_success(), "ensure_storage_then_sweep failed: {:?}", swept);
+    assert!(swept.logs().iter().any(|log| log.contains("sweep_completed")));
+
+    assert_eq!(ft_balance_of(&ft, treasury.id()).await?, amount);
+    assert_eq!(ft_balance_of(&ft, deposits.id()).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ft_deposit_for_unknown_token_is_refunded() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let treasury = sandbox.dev_create_account().await?;
+    let deposits = deploy_deposits(&sandbox, treasury.id()).await?;
+    let user = sandbox.dev_create_account().await?;
+
+    // Token never configured on the deposits contract
+    let ft = deploy_mock_ft(&sandbox, 1_000_000_000_000, 6).await?;
+    register_ft_account(&ft, user.id()).await?;
+    register_ft_account(&ft, deposits.id()).await?;
+    let amount: u128 = 10_000_000;
+    let funded = ft
+        .call("ft_transfer")
+        .args_json(json!({ "receiver_id": user.id(), "amount": amount.to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(funded.is_success(), "funding transfer failed: {:?}", funded);
+
+    let deposited = user
+        .call(ft.id(), "ft_transfer_call")
+        .args_json(json!({
+            "receiver_id": deposits.id(),
+            "amount": amount.to_string(),
+            "msg": json!({ "beneficiary_id": "user-uuid-3" }).to_string()
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(deposited.is_success(), "ft_transfer_call failed: {:?}", deposited);
+    assert!(deposited.logs().iter().any(|log| log.contains("deposit_rejected")));
+
+    // ft_on_transfer returned the full amount unused, so ft_resolve_transfer refunds it
+    assert_eq!(ft_balance_of(&ft, user.id()).await?, amount);
+    let records: Vec<Value> = deposits
+        .view("get_deposits_for_account")
+        .args_json(json!({ "account_id": user.id() }))
+        .await?
+        .json()?;
+    assert!(records.is_empty());
+
+    Ok(())
+}
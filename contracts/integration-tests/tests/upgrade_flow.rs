@@ -0,0 +1,62 @@
+mod common;
+
+use common::{deploy_bounty_market, deploy_deposits, TestResult};
+use near_sdk::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_bounty_market_upgrade_keeps_state() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let market = deploy_bounty_market(&sandbox).await?;
+
+    let creator = sandbox.dev_create_account().await?;
+    let created = creator
+        .call(market.id(), "create_content_bounty")
+        .args_json(json!({
+            "title": "Upgrade survivor",
+            "description": "Must outlive the redeploy",
+            "requirements": "None",
+            "base_prize": NearToken::from_near(1).as_yoctonear().to_string(),
+            "max_stake_per_user": NearToken::from_near(5).as_yoctonear().to_string(),
+            "duration_days": 1
+        }))
+        .deposit(NearToken::from_near(2))
+        .transact()
+        .await?;
+    assert!(created.is_success(), "bounty creation failed: {:?}", created);
+    let bounty_id: u64 = created.json()?;
+
+    // Only the owner (here the contract itself) may upgrade
+    let wasm = near_workspaces::compile_project("../content-bounty-market").await?;
+    let rejected = creator.call(market.id(), "upgrade").args_borsh(wasm.clone()).max_gas().transact().await?;
+    assert!(rejected.is_failure(), "non-owner upgrade should fail");
+
+    let upgraded = market.call("upgrade").args_borsh(wasm).max_gas().transact().await?;
+    assert!(upgraded.is_success(), "upgrade failed: {:?}", upgraded);
+    assert!(upgraded.logs().iter().any(|log| log.contains("State version is current")), "migrate did not run");
+
+    let bounty: Value = market.view("get_bounty").args_json(json!({ "bounty_id": bounty_id })).await?.json()?;
+    assert_eq!(bounty["title"], json!("Upgrade survivor"));
+    let version: u32 = market.view("get_state_version").await?.json()?;
+    assert_eq!(version, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deposits_upgrade_keeps_state() -> TestResult {
+    let sandbox = near_workspaces::sandbox().await?;
+    let treasury = sandbox.dev_create_account().await?;
+    let deposits = deploy_deposits(&sandbox, treasury.id()).await?;
+
+    let wasm = near_workspaces::compile_project("../deposits").await?;
+    let upgraded = deposits.call("upgrade").args_borsh(wasm).max_gas().transact().await?;
+    assert!(upgraded.is_success(), "upgrade failed: {:?}", upgraded);
+
+    let treasury_after: String = deposits.view("get_treasury").await?.json()?;
+    assert_eq!(treasury_after, treasury.id().to_string());
+    let version: u32 = deposits.view("get_state_version").await?.json()?;
+    assert_eq!(version, 1);
+
+    Ok(())
+}
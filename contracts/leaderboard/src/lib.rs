@@ -0,0 +1,340 @@
+//! Leaderboard – platform-wide rankings without an indexer.
+//!
+//! Reporter contracts push each settled market with `record_settlement`. The contract keeps:
+//! - per-account totals: wins and earnings of creators, winning stakes of backers,
+//! - bounded top-N lists (default 10) of creators by earnings, backers by stake on winners and
+//!   markets by volume, re-ranked on every report,
+//! - platform totals across all reporters.
+//!
+//! A market is counted once per reporter; a repeated report is rejected. Records are paid from
+//! this contract's balance, so keep it funded for storage.
+
+use groupweave_events::leaderboard::SettlementRecorded;
+use groupweave_events::Nep297Event;
+use groupweave_utils::SettlementReport;
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedSet};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near, require, AccountId, PanicOnDefault};
+
+/// Length of each top-N list unless the owner changes it.
+pub const DEFAULT_TOP_N: u32 = 10;
+/// Longest top-N list the owner can configure.
+pub const MAX_TOP_N: u32 = 50;
+/// Most backers one report can carry; matches the bounty market's participant cap.
+pub const MAX_REPORT_BACKERS: usize = 150;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CreatorStats {
+    pub wins: u64,
+    pub earned: U128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BackerStats {
+    pub winning_stakes: u64,
+    /// Total staked on winning entries.
+    pub backed: U128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Totals {
+    pub markets_settled: u64,
+    pub volume: U128,
+    pub creator_earnings: U128,
+    pub winning_stakes: U128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RankedAccount {
+    pub account_id: AccountId,
+    pub value: U128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RankedMarket {
+    pub reporter_id: AccountId,
+    pub market_id: u64,
+    pub title: String,
+    pub winner: AccountId,
+    pub volume: U128,
+}
+
+#[near(contract_state, contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
+#[derive(PanicOnDefault)]
+pub struct Leaderboard {
+    owner_id: AccountId,
+    /// Contracts allowed to push settlements, e.g. the bounty market.
+    reporters: UnorderedSet<AccountId>,
+    /// (reporter, market id) pairs already counted.
+    recorded: LookupSet<(AccountId, u64)>,
+    creators: LookupMap<AccountId, CreatorStats>,
+    backers: LookupMap<AccountId, BackerStats>,
+    top_creators: Vec<RankedAccount>,
+    top_backers: Vec<RankedAccount>,
+    biggest_markets: Vec<RankedMarket>,
+    totals: Totals,
+    top_n: u32,
+}
+
+#[near]
+impl Leaderboard {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self {
+            owner_id,
+            reporters: UnorderedSet::new(b"r"),
+            recorded: LookupSet::new(b"s"),
+            creators: LookupMap::new(b"c"),
+            backers: LookupMap::new(b"b"),
+            top_creators: Vec::new(),
+            top_backers: Vec::new(),
+            biggest_markets: Vec::new(),
+            totals: Totals::default(),
+            top_n: DEFAULT_TOP_N,
+        }
+    }
+
+    // Recording
+    /// Fold a settled market into the totals and rankings (reporters only).
+    pub fn record_settlement(&mut self, report: SettlementReport) {
+        let reporter_id = env::predecessor_account_id();
+        require!(self.reporters.contains(&reporter_id), "Only a reporter can record settlements");
+        require!(report.backers.len() <= MAX_REPORT_BACKERS, "Too many backers in one report");
+        require!(
+            self.recorded.insert(&(reporter_id.clone(), report.market_id)),
+            "Settlement was already recorded"
+        );
+        let top_n = self.top_n as usize;
+
+        let mut creator = self.creators.get(&report.winner).unwrap_or_default();
+        creator.wins += 1;
+        creator.earned = U128(creator.earned.0.saturating_add(report.winner_earned.0));
+        self.creators.insert(&report.winner, &creator);
+        rank_account(&mut self.top_creators, &report.winner, creator.earned, top_n);
+
+        let mut backed_total = 0u128;
+        for stake in &report.backers {
+            let mut backer = self.backers.get(&stake.account_id).unwrap_or_default();
+            backer.winning_stakes += 1;
+            backer.backed = U128(backer.backed.0.saturating_add(stake.amount.0));
+            self.backers.insert(&stake.account_id, &backer);
+            rank_account(&mut self.top_backers, &stake.account_id, backer.backed, top_n);
+            backed_total = backed_total.saturating_add(stake.amount.0);
+        }
+
+        let market = RankedMarket {
+            reporter_id: reporter_id.clone(),
+            market_id: report.market_id,
+            title: report.title,
+            winner: report.winner.clone(),
+            volume: report.volume,
+        };
+        let position = self.biggest_markets.partition_point(|ranked| ranked.volume.0 >= market.volume.0);
+        if position < top_n {
+            self.biggest_markets.insert(position, market);
+            self.biggest_markets.truncate(top_n);
+        }
+
+        let totals = &mut self.totals;
+        totals.markets_settled += 1;
+        totals.volume = U128(totals.volume.0.saturating_add(report.volume.0));
+        totals.creator_earnings = U128(totals.creator_earnings.0.saturating_add(report.winner_earned.0));
+        totals.winning_stakes = U128(totals.winning_stakes.0.saturating_add(backed_total));
+
+        SettlementRecorded {
+            reporter_id,
+            market_id: report.market_id,
+            winner: report.winner,
+            volume: report.volume,
+            backers: report.backers.len(),
+        }
+        .emit();
+    }
+
+    // Configuration
+    pub fn add_reporter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.reporters.insert(&account_id);
+    }
+
+    pub fn remove_reporter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.reporters.remove(&account_id);
+    }
+
+    /// Resize the top-N lists. Shrinking drops the tail; growing fills up as new reports come in.
+    pub fn set_top_n(&mut self, top_n: u32) {
+        self.assert_owner();
+        require!((1..=MAX_TOP_N).contains(&top_n), format!("Top N must be between 1 and {}", MAX_TOP_N));
+        self.top_n = top_n;
+        let top_n = top_n as usize;
+        self.top_creators.truncate(top_n);
+        self.top_backers.truncate(top_n);
+        self.biggest_markets.truncate(top_n);
+    }
+
+    // Views
+    /// Creators by total earnings, highest first.
+    pub fn get_top_creators(&self, limit: Option<u32>) -> Vec<RankedAccount> {
+        self.top_creators.iter().take(self.view_limit(limit)).cloned().collect()
+    }
+
+    /// Backers by total stake on winning entries, highest first.
+    pub fn get_top_backers(&self, limit: Option<u32>) -> Vec<RankedAccount> {
+        self.top_backers.iter().take(self.view_limit(limit)).cloned().collect()
+    }
+
+    /// Settled markets by volume, highest first.
+    pub fn get_biggest_markets(&self, limit: Option<u32>) -> Vec<RankedMarket> {
+        self.biggest_markets.iter().take(self.view_limit(limit)).cloned().collect()
+    }
+
+    pub fn get_totals(&self) -> Totals {
+        self.totals.clone()
+    }
+
+    pub fn get_creator_stats(&self, account_id: AccountId) -> CreatorStats {
+        self.creators.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_backer_stats(&self, account_id: AccountId) -> BackerStats {
+        self.backers.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_top_n(&self) -> u32 {
+        self.top_n
+    }
+
+    pub fn get_reporters(&self) -> Vec<AccountId> {
+        self.reporters.to_vec()
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+}
+
+impl Leaderboard {
+    fn assert_owner(&self) {
+        require!(env::predecessor_account_id() == self.owner_id, "Only the owner can call this method");
+    }
+
+    fn view_limit(&self, limit: Option<u32>) -> usize {
+        limit.unwrap_or(self.top_n).min(self.top_n) as usize
+    }
+}
+
+/// Move `account_id` to its place for its new (never smaller) `value`, keeping at most `top_n`
+/// entries. Ties keep the account that got there first ahead.
+fn rank_account(list: &mut Vec<RankedAccount>, account_id: &AccountId, value: U128, top_n: usize) {
+    list.retain(|ranked| &ranked.account_id != account_id);
+    let position = list.partition_point(|ranked| ranked.value.0 >= value.0);
+    if position < top_n {
+        list.insert(position, RankedAccount { account_id: account_id.clone(), value });
+        list.truncate(top_n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use groupweave_utils::BackerStake;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn set_predecessor(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(account_id).build());
+    }
+
+    // accounts(0) owns the leaderboard, accounts(1) reports
+    fn leaderboard_with_reporter() -> Leaderboard {
+        set_predecessor(accounts(0));
+        let mut contract = Leaderboard::new(accounts(0));
+        contract.add_reporter(accounts(1));
+        set_predecessor(accounts(1));
+        contract
+    }
+
+    fn report(market_id: u64, winner: AccountId, earned: u128, backers: &[(AccountId, u128)]) -> SettlementReport {
+        let backers: Vec<BackerStake> = backers
+            .iter()
+            .map(|(account_id, amount)| BackerStake { account_id: account_id.clone(), amount: U128(*amount) })
+            .collect();
+        let staked: u128 = backers.iter().map(|stake| stake.amount.0).sum();
+        SettlementReport {
+            market_id,
+            title: format!("Bounty {}", market_id),
+            winner,
+            winner_earned: U128(earned),
+            volume: U128(earned + staked),
+            backers,
+        }
+    }
+
+    #[test]
+    fn settlements_accumulate_into_rankings_and_totals() {
+        let mut contract = leaderboard_with_reporter();
+        contract.record_settlement(report(1, accounts(2), 50, &[(accounts(4), 10)]));
+        contract.record_settlement(report(2, accounts(3), 80, &[(accounts(4), 5), (accounts(5), 30)]));
+        contract.record_settlement(report(3, accounts(2), 40, &[]));
+
+        let creators = contract.get_top_creators(None);
+        assert_eq!(creators[0], RankedAccount { account_id: accounts(2), value: U128(90) });
+        assert_eq!(creators[1].account_id, accounts(3));
+        assert_eq!(contract.get_creator_stats(accounts(2)), CreatorStats { wins: 2, earned: U128(90) });
+
+        let backers = contract.get_top_backers(None);
+        assert_eq!((backers[0].account_id.clone(), backers[0].value), (accounts(5), U128(30)));
+        assert_eq!(contract.get_backer_stats(accounts(4)).winning_stakes, 2);
+
+        let markets = contract.get_biggest_markets(Some(1));
+        assert_eq!((markets.len(), markets[0].market_id), (1, 2));
+        let totals = contract.get_totals();
+        assert_eq!((totals.markets_settled, totals.volume, totals.winning_stakes), (3, U128(215), U128(45)));
+        assert!(get_logs().last().unwrap().contains("\"event\":\"settlement_recorded\""));
+    }
+
+    #[test]
+    fn top_lists_are_bounded() {
+        let mut contract = leaderboard_with_reporter();
+        set_predecessor(accounts(0));
+        contract.set_top_n(2);
+        set_predecessor(accounts(1));
+        for (market_id, earned) in [(1, 10), (2, 30), (3, 20)] {
+            let winner: AccountId = format!("creator{}.testnet", market_id).parse().unwrap();
+            contract.record_settlement(report(market_id, winner, earned, &[]));
+        }
+
+        let values: Vec<u128> = contract.get_top_creators(None).iter().map(|ranked| ranked.value.0).collect();
+        assert_eq!(values, vec![30, 20]);
+        let markets: Vec<u64> = contract.get_biggest_markets(None).iter().map(|ranked| ranked.market_id).collect();
+        assert_eq!(markets, vec![2, 3]);
+
+        // A creator that fell off re-enters once their total climbs back
+        contract.record_settlement(report(4, "creator1.testnet".parse().unwrap(), 25, &[]));
+        assert_eq!(contract.get_top_creators(None)[0].account_id.as_str(), "creator1.testnet");
+    }
+
+    #[test]
+    #[should_panic(expected = "Settlement was already recorded")]
+    fn settlements_count_once() {
+        let mut contract = leaderboard_with_reporter();
+        contract.record_settlement(report(1, accounts(2), 50, &[]));
+        contract.record_settlement(report(1, accounts(2), 50, &[]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only a reporter can record settlements")]
+    fn non_reporter_cannot_record() {
+        let mut contract = leaderboard_with_reporter();
+        set_predecessor(accounts(2));
+        contract.record_settlement(report(1, accounts(2), 50, &[]));
+    }
+}
@@ -0,0 +1,228 @@
+//! Reputation – per-account track record shared by the groupweave contracts.
+//!
+//! Sybil resistance used to live entirely off-chain. This contract keeps an on-chain count of
+//! what each account has done across the platform:
+//! - wins: bounties the account won as creator (reported by the bounty market),
+//! - successful stakes: winning submissions the account backed (bounty market),
+//! - disputes: flags the account raised that were dismissed (curation agent),
+//! - flags: interactions of the account that a curator flagged and the flag was upheld (curation agent).
+//!
+//! Only registered reporter contracts can record signals. The counts fold into a single score
+//! with owner-set weights; other contracts read it with `get_score` to gate high-value actions,
+//! e.g. the bounty market requires a minimum score to create or submit to large bounties.
+//!
+//! Records are paid from this contract's balance, so keep it funded for storage.
+
+use groupweave_events::reputation::ReputationRecorded;
+use groupweave_events::Nep297Event;
+use groupweave_utils::ReputationSignal;
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedSet};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near, require, AccountId, PanicOnDefault};
+
+/// Most accounts one `record` call can update; covers every backer of a full bounty.
+pub const MAX_RECORD_BATCH: usize = 150;
+
+/// Counts of each signal for one account.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReputationRecord {
+    pub wins: u64,
+    pub successful_stakes: u64,
+    pub disputes: u64,
+    pub flags: u64,
+    pub updated_at_ns: u64,
+}
+
+/// Score points per signal; disputes and flags are subtracted.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreWeights {
+    pub win: u64,
+    pub successful_stake: u64,
+    pub dispute: u64,
+    pub flag: u64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self { win: 10, successful_stake: 2, dispute: 5, flag: 20 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReputationView {
+    pub account_id: AccountId,
+    #[serde(flatten)]
+    pub record: ReputationRecord,
+    pub score: u64,
+}
+
+#[near(contract_state, contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
+#[derive(PanicOnDefault)]
+pub struct ReputationContract {
+    owner_id: AccountId,
+    /// Contracts allowed to record signals, e.g. the bounty market and the curation agent.
+    reporters: UnorderedSet<AccountId>,
+    records: LookupMap<AccountId, ReputationRecord>,
+    weights: ScoreWeights,
+}
+
+#[near]
+impl ReputationContract {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self {
+            owner_id,
+            reporters: UnorderedSet::new(b"r"),
+            records: LookupMap::new(b"a"),
+            weights: ScoreWeights::default(),
+        }
+    }
+
+    // Recording
+    /// Count `signal` once for each of `account_ids` (reporters only).
+    pub fn record(&mut self, account_ids: Vec<AccountId>, signal: ReputationSignal) {
+        let reporter_id = env::predecessor_account_id();
+        require!(self.reporters.contains(&reporter_id), "Only a reporter can record reputation");
+        require!(account_ids.len() <= MAX_RECORD_BATCH, "Too many accounts in one record");
+
+        let now = env::block_timestamp();
+        for account_id in &account_ids {
+            let mut record = self.records.get(account_id).unwrap_or_default();
+            let count = match signal {
+                ReputationSignal::Win => &mut record.wins,
+                ReputationSignal::SuccessfulStake => &mut record.successful_stakes,
+                ReputationSignal::Dispute => &mut record.disputes,
+                ReputationSignal::Flag => &mut record.flags,
+            };
+            *count += 1;
+            record.updated_at_ns = now;
+            self.records.insert(account_id, &record);
+        }
+
+        ReputationRecorded { reporter_id, signal: signal.to_string(), account_ids }.emit();
+    }
+
+    // Configuration
+    pub fn add_reporter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.reporters.insert(&account_id);
+    }
+
+    pub fn remove_reporter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.reporters.remove(&account_id);
+    }
+
+    pub fn set_weights(&mut self, weights: ScoreWeights) {
+        self.assert_owner();
+        self.weights = weights;
+    }
+
+    // Views
+    pub fn get_reputation(&self, account_id: AccountId) -> ReputationView {
+        let record = self.records.get(&account_id).unwrap_or_default();
+        let score = self.score_of(&record);
+        ReputationView { account_id, record, score }
+    }
+
+    /// The account's score: weighted wins and successful stakes, minus disputes and flags, floored at 0.
+    pub fn get_score(&self, account_id: AccountId) -> u64 {
+        self.score_of(&self.records.get(&account_id).unwrap_or_default())
+    }
+
+    pub fn get_weights(&self) -> ScoreWeights {
+        self.weights.clone()
+    }
+
+    pub fn get_reporters(&self) -> Vec<AccountId> {
+        self.reporters.to_vec()
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+}
+
+impl ReputationContract {
+    fn assert_owner(&self) {
+        require!(env::predecessor_account_id() == self.owner_id, "Only the owner can call this method");
+    }
+
+    fn score_of(&self, record: &ReputationRecord) -> u64 {
+        let weights = &self.weights;
+        let earned = record
+            .wins
+            .saturating_mul(weights.win)
+            .saturating_add(record.successful_stakes.saturating_mul(weights.successful_stake));
+        let lost = record
+            .disputes
+            .saturating_mul(weights.dispute)
+            .saturating_add(record.flags.saturating_mul(weights.flag));
+        earned.saturating_sub(lost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn set_predecessor(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(account_id).build());
+    }
+
+    fn contract_with_reporter() -> ReputationContract {
+        set_predecessor(accounts(0));
+        let mut contract = ReputationContract::new(accounts(0));
+        contract.add_reporter(accounts(1));
+        set_predecessor(accounts(1));
+        contract
+    }
+
+    #[test]
+    fn reporter_records_signals_per_account() {
+        let mut contract = contract_with_reporter();
+        contract.record(vec![accounts(2)], ReputationSignal::Win);
+        contract.record(vec![accounts(2), accounts(3)], ReputationSignal::SuccessfulStake);
+        contract.record(vec![accounts(3)], ReputationSignal::Flag);
+
+        let view = contract.get_reputation(accounts(2));
+        assert_eq!((view.record.wins, view.record.successful_stakes), (1, 1));
+        assert_eq!(view.score, 12);
+        assert_eq!(contract.get_score(accounts(3)), 0, "score is floored at zero");
+        assert_eq!(contract.get_reputation(accounts(3)).record.flags, 1);
+        assert!(get_logs().last().unwrap().contains("\"event\":\"reputation_recorded\""));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only a reporter can record reputation")]
+    fn non_reporter_cannot_record() {
+        let mut contract = contract_with_reporter();
+        set_predecessor(accounts(2));
+        contract.record(vec![accounts(2)], ReputationSignal::Win);
+    }
+
+    #[test]
+    fn weights_change_the_score() {
+        let mut contract = contract_with_reporter();
+        contract.record(vec![accounts(2)], ReputationSignal::Win);
+        contract.record(vec![accounts(2)], ReputationSignal::Dispute);
+        assert_eq!(contract.get_score(accounts(2)), 5);
+
+        set_predecessor(accounts(0));
+        contract.set_weights(ScoreWeights { win: 10, successful_stake: 2, dispute: 0, flag: 20 });
+        assert_eq!(contract.get_score(accounts(2)), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn only_owner_adds_reporters() {
+        let mut contract = contract_with_reporter();
+        contract.add_reporter(accounts(2));
+    }
+}
@@ -1,6 +1,8 @@
 //! Shade Classifier Agent – user-owned, exclusive per user, logs classification events
 //! with optional human-in-the-loop review.
 
+use groupweave_events::classification::{ClassificationLogged, ClassificationReviewed};
+use groupweave_events::Nep297Event;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::store::UnorderedMap;
 use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault, Promise};
@@ -22,7 +24,7 @@ pub struct ClassifyLog {
     pub reviewed_at_ns: Option<u64>,
 }
 
-#[near_bindgen]
+#[near_bindgen(contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
 #[derive(BorshSerialize, BorshDeserialize, PanicOnDefault)]
 pub struct Contract {
     pub owner_id: AccountId,
@@ -60,9 +62,9 @@ impl Contract {
             session_id: session_id.clone(),
             image_hash,
             prompt_hash,
-            label,
+            label: label.clone(),
             confidence_bps,
-            model,
+            model: model.clone(),
             created_at_ns: env::block_timestamp(),
             reviewed: false,
             final_label: None,
@@ -70,6 +72,7 @@ impl Contract {
             reviewed_at_ns: None,
         };
         self.logs.insert(session_id.clone(), log);
+        ClassificationLogged { session_id, label, confidence_bps, model }.emit();
 
         let after = env::storage_usage();
         if after > before {
@@ -87,10 +90,11 @@ impl Contract {
         self.assert_owner();
         let mut log = self.logs.get(&session_id).expect("session not found").clone();
         log.reviewed = true;
-        log.final_label = Some(final_label);
+        log.final_label = Some(final_label.clone());
         log.reviewer = Some(env::predecessor_account_id().to_string());
         log.reviewed_at_ns = Some(env::block_timestamp());
-        self.logs.insert(session_id, log);
+        self.logs.insert(session_id.clone(), log);
+        ClassificationReviewed { session_id, reviewer: env::predecessor_account_id(), final_label }.emit();
     }
 
     // Views
@@ -139,6 +143,7 @@ mod tests {
         c.log_classification("s1".into(), "ihash".into(), "phash".into(), "cat".into(), 8123, "gpt-4o".into());
         set_predecessor("owner.testnet");
         c.record_review("s1".into(), "cat".to_string());
+        assert!(near_sdk::test_utils::get_logs()[0].contains("\"event\":\"classification_reviewed\""));
         let log = c.get_classification("s1".into()).unwrap();
         assert!(log.reviewed);
         assert_eq!(log.final_label.unwrap(), "cat");
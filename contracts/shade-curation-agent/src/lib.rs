@@ -3,23 +3,101 @@
 //!
 //! This contract intentionally keeps on-chain state minimal. The private data
 //! and LLM remain inside the Shade agent (TEE on Phala Cloud). The contract:
-//! - Stores owner and the single allowed `agent_account_id` (the Shade agent's NEAR account).
-//! - Stores dataset metadata (hash/uri) and a small allowlist of community IDs.
-//! - Allows the agent to log interaction digests for audit and cost accounting.
+//! - Stores owner and the set of allowed agent accounts (the Shade agents' NEAR accounts).
+//!   The owner can delegate upkeep to roles (guardian, treasurer, keeper, resolver, admin)
+//!   from `groupweave-utils`. Ownership moves in two steps, and with a timelock set, agent changes
+//!   (`set_agent_account`, `add_agent`, new enclave keys), `set_dataset_meta` and code approved
+//!   for `upgrade` are queued so communities can see changes before they apply.
+//! - Stores the communities the agents serve, each with its own dataset metadata (hash/uri),
+//!   versioned guardrails, budget and enabled flag. Dataset updates are appended to a
+//!   per-community history rather than overwriting the previous hash. Prepaid communities fund a NEAR
+//!   balance that each logged query draws down at the keeper-set NEAR/USD price.
+//! - Records a TEE attestation per agent, including the enclave's ed25519 key; only agents
+//!   with an unexpired attestation may log, and each log must be signed by that key.
+//! - Allows the agent to log interaction digests for audit and cost accounting. A log can
+//!   name the account that made the query; its cost is then deducted from that account's
+//!   platform credits on the deposits contract, and the log is marked unpaid if that fails.
+//! - Keeps a registry of which NEAR accounts may query each community's dataset; those
+//!   members can also rate logged answers for a quality signal.
+//! - Lets community curators dispute logged interactions; disputed costs are reported
+//!   separately so billing can exclude them. With a reputation contract set, an upheld flag
+//!   counts against the billed account and a dismissed one against the curator who raised it.
+//! - Accepts Merkle roots over batches of session digests, so only high-value
+//!   interactions need a full on-chain log.
+//!
+//! Batch trees hash leaves as `sha256(0x00 || digest)` and inner nodes as
+//! `sha256(0x01 || min(a, b) || max(a, b))`; sorting each pair means a proof is just
+//! the list of sibling hashes from the leaf up.
+
+// `log_interaction` keeps its flat argument list (also mirrored in the generated `ext` API).
+#![allow(clippy::too_many_arguments)]
 
+use groupweave_events::interaction::{AgentAdded, AgentRemoved, InteractionsLogged};
+use groupweave_events::Nep297Event;
+use groupweave_utils::upgrade::{deploy_and_migrate, record_state_version, stored_state_version};
+use groupweave_utils::{AccessControl, ReputationSignal, Role};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::UnorderedMap;
-use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault, Promise};
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U128};
+use near_sdk::{
+    env, ext_contract, near_bindgen, AccountId, CryptoHash, CurveType, Gas, NearToken, PanicOnDefault, Promise,
+    PromiseError, PublicKey,
+};
+use near_sdk::serde_json;
 use serde::{Deserialize, Serialize};
 
+/// Upper bound on concurrently authorized agents (e.g. blue/green TEE instances).
+pub const MAX_AGENTS: u64 = 8;
+
+/// Maximum number of logs returned by one page of a log query.
+pub const MAX_LOG_PAGE: u64 = 100;
+
+/// Maximum number of interactions accepted by one `log_interactions` call.
+pub const MAX_INTERACTION_BATCH: usize = 50;
+
+/// Longest accepted Merkle inclusion proof (trees of up to 2^32 leaves).
+pub const MAX_PROOF_LEN: usize = 32;
+
+/// Maximum number of communities returned by one page of `get_communities`.
+pub const MAX_COMMUNITY_PAGE: u64 = 100;
+
+const NS_PER_DAY: u64 = 86_400_000_000_000;
+
+/// Communities one maintenance `tick` inspects.
+pub const MAX_TICK_COMMUNITIES: u64 = 20;
+
+/// Longest day range one `get_usage` call covers.
+pub const MAX_USAGE_DAYS: u64 = 366;
+
+/// How long after a log is created members may rate it.
+pub const RATING_WINDOW_NS: u64 = 7 * NS_PER_DAY;
+pub const MAX_RATING_SCORE: u8 = 5;
+const YOCTO_PER_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+/// Most billed interactions one `log_interactions` call may carry; each one schedules a
+/// cross-contract credit deduction.
+pub const MAX_BILLED_PER_BATCH: usize = 10;
+
+const GAS_FOR_SPEND_CREDITS: Gas = Gas::from_tgas(10);
+const GAS_FOR_CREDITS_CALLBACK: Gas = Gas::from_tgas(5);
+const GAS_FOR_REPUTATION_RECORD: Gas = Gas::from_tgas(10);
+
+/// Layout version of the contract state; bump it once per release that changes fields, and migrate
+/// from the layout that release replaces.
+const STATE_VERSION: u32 = 1;
+
+pub use groupweave_events::interaction::{STANDARD as EVENT_STANDARD, VERSION as EVENT_VERSION};
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct DatasetMeta {
     pub dataset_hash: String, // e.g., SHA256 of a tarball or manifest
     pub dataset_uri: String,  // off-chain reference (ipfs://, https://, etc.)
+    pub version: u32,         // 1 for a community's first dataset
     pub updated_at_ns: u64,
 }
 
+/// A logged interaction, assembled from its stored `LogEntry` and `LogDetails`.
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct InteractionLog {
@@ -27,18 +105,420 @@ pub struct InteractionLog {
     pub query_hash: String,       // hash of user query (not plaintext)
     pub answer_hash: String,      // hash of final answer (not plaintext)
     pub cost_microusd: u64,       // approx cost in micro-USD for accounting
-    pub community_id: Option<String>,
+    pub community_id: String,
+    pub guardrails_version: u32,  // community guardrails in force when logged (0 = none)
+    pub dataset_version: u32,     // community dataset version the answer was based on
+    pub billed_account_id: Option<AccountId>, // account whose credits pay for the query
     pub created_at_ns: u64,
 }
 
-#[near_bindgen]
+/// Stored form of a log, in the layout of the released contract. Data recorded per log since
+/// then lives in `log_details` under the same session_id.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LogEntry {
+    pub session_id: String,
+    pub query_hash: String,
+    pub answer_hash: String,
+    pub cost_microusd: u64,
+    pub community_id: Option<String>, // always set on new logs
+    pub created_at_ns: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct LogDetails {
+    pub guardrails_version: u32,
+    pub dataset_version: u32,
+    pub billed_account_id: Option<AccountId>,
+}
+
+/// Settlement of a billed log's credit deduction.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PaymentStatus {
+    /// The deduction was sent and has not resolved yet.
+    Pending,
+    Paid,
+    /// The credits contract rejected the deduction (e.g. insufficient credits).
+    Unpaid,
+}
+
+#[ext_contract(ext_credits)]
+#[allow(dead_code)]
+trait CreditsLedger {
+    fn spend_credits(&mut self, account_id: AccountId, usd_micros: U128, memo: Option<String>) -> U128;
+}
+
+#[ext_contract(ext_reputation)]
+#[allow(dead_code)]
+trait ReputationLedger {
+    fn record(&mut self, account_ids: Vec<AccountId>, signal: ReputationSignal);
+}
+
+/// What to do when an agent logs a session_id that is already on record.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DuplicatePolicy {
+    /// Panic, naming the existing record's hash.
+    Reject,
+    /// Leave the existing record untouched and return its hash.
+    Ignore,
+}
+
+/// An account's entitlement to query a community's dataset.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccessGrant {
+    pub expires_at_ns: Option<u64>, // None = until revoked
+    pub granted_at_ns: u64,
+}
+
+/// Logged queries and cost for a community on one UTC day.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DailyUsage {
+    pub day: u64, // days since the Unix epoch
+    pub query_count: u64,
+    pub total_cost_microusd: u64,
+    pub disputed_cost_microusd: u64, // part of the total under an open or upheld flag
+}
+
+/// Usage over a day range, for invoicing.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UsageReport {
+    pub community_id: String,
+    pub query_count: u64,
+    pub total_cost_microusd: u64,
+    pub disputed_cost_microusd: u64, // bill total_cost_microusd - disputed_cost_microusd
+    pub days: Vec<DailyUsage>, // days with usage only
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum FlagStatus {
+    Open,
+    /// The dispute stands; the cost stays excluded from billing.
+    Upheld,
+    /// The dispute was rejected; the cost is billable again.
+    Dismissed,
+}
+
+/// A curator's dispute of a logged interaction.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InteractionFlag {
+    pub flagged_by: AccountId,
+    pub reason_hash: String,
+    pub flagged_at_ns: u64,
+    pub status: FlagStatus,
+    pub resolved_by: Option<AccountId>,
+    pub resolved_at_ns: Option<u64>,
+}
+
+/// A stored log together with its dispute state.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InteractionLogView {
+    #[serde(flatten)]
+    pub log: InteractionLog,
+    pub flag: Option<InteractionFlag>,
+    pub payment: Option<PaymentStatus>, // None for logs that bill no account
+}
+
+/// An owner change waiting out the timelock.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PendingChange {
+    AgentAccount { agent_account_id: AccountId },
+    AddAgent { agent_account_id: AccountId },
+    /// A first attestation or a new enclave key; renewing the current key is not queued.
+    AgentAttestation {
+        agent_account_id: AccountId,
+        attestation_hash: String,
+        measurement: String,
+        expires_at_ns: u64,
+        enclave_public_key: PublicKey,
+    },
+    DatasetMeta { community_id: String, dataset_hash: String, dataset_uri: String },
+    /// Lowering the timelock is itself timelocked.
+    Timelock { timelock_ns: u64 },
+    /// Approves code with this sha256 for `upgrade`.
+    Upgrade { code_hash: Base58CryptoHash },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QueuedChange {
+    pub change: PendingChange,
+    pub executable_at_ns: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QueuedChangeView {
+    pub change_id: u64,
+    #[serde(flatten)]
+    pub queued: QueuedChange,
+}
+
+/// Result of logging one interaction.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LogOutcome {
+    pub session_id: String,
+    pub inserted: bool, // false if the session was already logged
+    pub record_hash: Base58CryptoHash, // sha256 of the borsh-serialized InteractionLog
+}
+
+/// One interaction submitted through `log_interactions`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InteractionInput {
+    pub session_id: String,
+    pub query_hash: String,
+    pub answer_hash: String,
+    pub cost_microusd: u64,
+    pub community_id: String,
+    pub signature: Base64VecU8, // enclave signature over `log_digest`
+    #[serde(default)]
+    pub billed_account_id: Option<AccountId>,
+}
+
+/// Policy the agent runs under for a community. Every change is stored as a new version.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Guardrails {
+    pub max_cost_per_query_microusd: Option<u64>,
+    pub blocked_topics_hash: String, // hash of the blocked-topics list
+    pub system_prompt_hash: String,  // hash of the system prompt
+    pub version: u32,
+    pub updated_at_ns: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AgentAttestation {
+    pub attestation_hash: String, // hash of the TEE quote/report verified off-chain
+    pub measurement: String,      // enclave build measurement (e.g. compose hash / MRTD)
+    pub enclave_public_key: PublicKey, // ed25519 key generated inside the enclave; signs logs
+    pub expires_at_ns: u64,
+    pub registered_at_ns: u64,
+}
+
+/// Spending caps for a community's logged costs; `None` leaves a period uncapped.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Budget {
+    pub daily_cap_microusd: Option<u64>,
+    pub monthly_cap_microusd: Option<u64>, // UTC calendar month
+}
+
+/// What one maintenance tick did.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TickReport {
+    pub scanned: u64,
+    pub budgets_reset: Vec<String>, // communities whose day or month spend rolled over
+}
+
+/// Cost logged so far in the current UTC day and month.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct BudgetSpend {
+    pub day: u64,   // days since the Unix epoch
+    pub month: u64, // year * 12 + month index
+    pub day_spent_microusd: u64,
+    pub month_spent_microusd: u64,
+}
+
+/// A community member's rating of a logged answer.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InteractionRating {
+    pub rater_id: AccountId,
+    pub score: u8, // 1..=MAX_RATING_SCORE
+    pub comment_hash: Option<String>,
+    pub rated_at_ns: u64,
+}
+
+/// Ratings aggregated per community.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QualityStats {
+    pub rating_count: u64,
+    pub score_sum: u64,
+    pub score_counts: [u64; MAX_RATING_SCORE as usize], // index 0 = score 1
+}
+
+/// Merkle root an agent posted over a batch of session digests.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LogBatch {
+    pub root_hash: Base58CryptoHash,
+    pub community_id: String,
+    pub count: u64, // number of leaves in the tree
+    pub period_start_ns: u64,
+    pub period_end_ns: u64,
+    pub agent_account_id: AccountId,
+    pub committed_at_ns: u64,
+}
+
+/// A community the agents serve and the dataset they curate for it.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Community {
+    pub dataset: DatasetMeta,
+    pub budget: Budget,
+    pub guardrails_version: u32, // latest guardrails version (0 = none set)
+    pub enabled: bool,           // disabled communities reject new logs
+    pub prepaid: bool,           // queries are paid from the community's funded balance
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommunityView {
+    pub community_id: String,
+    pub community: Community,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RemainingBudget {
+    pub daily_microusd: Option<u64>,
+    pub monthly_microusd: Option<u64>,
+}
+
+#[near_bindgen(contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
 #[derive(BorshSerialize, BorshDeserialize, PanicOnDefault)]
 pub struct Contract {
     pub owner_id: AccountId,
-    pub agent_account_id: AccountId,
-    pub dataset: DatasetMeta,
-    pub community_id: String, // exclusive community assignment
-    pub logs: UnorderedMap<String, InteractionLog>, // keyed by session_id
+    pub agents: UnorderedSet<AccountId>, // accounts allowed to log interactions
+    pub attestations: LookupMap<AccountId, AgentAttestation>, // keyed by agent account
+    pub communities: UnorderedMap<String, Community>, // keyed by community_id
+    pub logs: UnorderedMap<String, LogEntry>, // keyed by session_id
+    pub budget_spend: LookupMap<String, BudgetSpend>, // keyed by community_id
+    pub log_batches: Vector<LogBatch>,
+    pub log_batch_roots: LookupMap<CryptoHash, u64>, // root -> index in log_batches
+    pub guardrails: LookupMap<(String, u32), Guardrails>, // (community_id, version)
+    pub balances: LookupMap<String, u128>, // prepaid yoctoNEAR per community_id
+    pub collected_yocto: u128,             // deducted from balances, withdrawable by the owner
+    pub price_keeper: Option<AccountId>,   // may update the NEAR/USD price besides the owner
+    pub near_price_usd_micros: u64,        // micro-USD per NEAR (0 = not set)
+    pub access_grants: LookupMap<(String, AccountId), AccessGrant>, // (community_id, member)
+    pub ratings: LookupMap<String, InteractionRating>, // keyed by session_id
+    pub quality_stats: LookupMap<String, QualityStats>, // keyed by community_id
+    pub duplicate_policy: DuplicatePolicy,
+    pub daily_usage: LookupMap<(String, u64), DailyUsage>, // (community_id, day)
+    pub dataset_history: LookupMap<(String, u32), DatasetMeta>, // (community_id, version)
+    pub dataset_versions: LookupMap<String, u32>, // latest version per community_id, kept on removal
+    pub curators: LookupSet<(String, AccountId)>, // (community_id, curator) allowed to flag logs
+    pub flags: LookupMap<String, InteractionFlag>, // keyed by session_id
+    pub pending_owner_id: Option<AccountId>,
+    pub change_timelock_ns: u64, // delay for agent/dataset changes (0 = immediate)
+    pub queued_changes: UnorderedMap<u64, QueuedChange>, // keyed by change_id
+    pub next_change_id: u64,
+    pub credits_contract_id: Option<AccountId>, // deposits contract that holds platform credits
+    pub payments: LookupMap<String, PaymentStatus>, // keyed by session_id, billed logs only
+    pub access: AccessControl,                      // roles delegated by the owner
+    pub reputation_contract_id: Option<AccountId>,  // receives flag outcomes as reputation signals
+    pub tick_cursor: u64,                           // index into communities where the next tick resumes
+    pub log_details: LookupMap<String, LogDetails>, // keyed by session_id
+    pub released_community_id: Option<String>, // community of released logs that named none
+    pub approved_code_hash: Option<CryptoHash>, // code `upgrade` may deploy while a timelock is set
+    state_version: LazyOption<u32>, // stored under its own key so `migrate` reads it first
+}
+
+/// State layout of the released single-community contract, before `state_version` existed.
+/// `migrate` converts it; every field added since starts empty or at its default.
+#[derive(BorshDeserialize)]
+struct ReleasedContract {
+    owner_id: AccountId,
+    agent_account_id: AccountId,
+    dataset: ReleasedDatasetMeta,
+    community_id: String,
+    logs: UnorderedMap<String, LogEntry>,
+}
+
+#[derive(BorshDeserialize)]
+struct ReleasedDatasetMeta {
+    dataset_hash: String,
+    dataset_uri: String,
+    updated_at_ns: u64,
+}
+
+impl ReleasedContract {
+    fn into_current(self) -> Contract {
+        let mut agents = UnorderedSet::new(b"a".to_vec());
+        agents.insert(&self.agent_account_id);
+        let mut this = Contract::empty(self.owner_id, agents, self.logs);
+
+        // The released dataset becomes version 1 of the community's history.
+        let dataset = DatasetMeta {
+            dataset_hash: self.dataset.dataset_hash,
+            dataset_uri: self.dataset.dataset_uri,
+            version: 1,
+            updated_at_ns: self.dataset.updated_at_ns,
+        };
+        this.dataset_history.insert(&(self.community_id.clone(), 1), &dataset);
+        this.dataset_versions.insert(&self.community_id, &1);
+        this.communities.insert(&self.community_id, &Community::new(dataset));
+        this.released_community_id = Some(self.community_id);
+        this
+    }
+}
+
+impl Contract {
+    /// Fresh state around the given agents and log map; `new` and the migration add the first community.
+    fn empty(owner_id: AccountId, agents: UnorderedSet<AccountId>, logs: UnorderedMap<String, LogEntry>) -> Self {
+        Self {
+            owner_id,
+            agents,
+            attestations: LookupMap::new(b"t".to_vec()),
+            communities: UnorderedMap::new(b"c".to_vec()),
+            logs,
+            budget_spend: LookupMap::new(b"s".to_vec()),
+            log_batches: Vector::new(b"m".to_vec()),
+            log_batch_roots: LookupMap::new(b"r".to_vec()),
+            guardrails: LookupMap::new(b"g".to_vec()),
+            balances: LookupMap::new(b"p".to_vec()),
+            collected_yocto: 0,
+            price_keeper: None,
+            near_price_usd_micros: 0,
+            access_grants: LookupMap::new(b"e".to_vec()),
+            ratings: LookupMap::new(b"q".to_vec()),
+            quality_stats: LookupMap::new(b"k".to_vec()),
+            duplicate_policy: DuplicatePolicy::Reject,
+            daily_usage: LookupMap::new(b"u".to_vec()),
+            dataset_history: LookupMap::new(b"d".to_vec()),
+            dataset_versions: LookupMap::new(b"v".to_vec()),
+            curators: LookupSet::new(b"o".to_vec()),
+            flags: LookupMap::new(b"f".to_vec()),
+            pending_owner_id: None,
+            change_timelock_ns: 0,
+            queued_changes: UnorderedMap::new(b"x".to_vec()),
+            next_change_id: 0,
+            credits_contract_id: None,
+            payments: LookupMap::new(b"y".to_vec()),
+            access: AccessControl::new(b"w".to_vec()),
+            reputation_contract_id: None,
+            tick_cursor: 0,
+            log_details: LookupMap::new(b"n".to_vec()),
+            released_community_id: None,
+            approved_code_hash: None,
+            state_version: record_state_version(STATE_VERSION),
+        }
+    }
+}
+
+impl Community {
+    fn new(dataset: DatasetMeta) -> Self {
+        Self {
+            dataset,
+            budget: Budget::default(),
+            guardrails_version: 0,
+            enabled: true,
+            prepaid: false,
+        }
+    }
 }
 
 #[near_bindgen]
@@ -53,169 +533,2471 @@ impl Contract {
     ) -> Self {
         assert!(!env::state_exists(), "Already initialized");
 
-        let dataset = DatasetMeta {
-            dataset_hash,
-            dataset_uri,
-            updated_at_ns: env::block_timestamp(),
+        let mut agents = UnorderedSet::new(b"a".to_vec());
+        agents.insert(&agent_account_id);
+
+        let mut this = Self::empty(owner_id, agents, UnorderedMap::new(b"l".to_vec()));
+        let dataset = this.push_dataset_version(&community_id, dataset_hash, dataset_uri);
+        this.communities.insert(&community_id, &Community::new(dataset));
+        this
+    }
+
+    /// Migrate the stored state to this code's layout (owner, or the contract itself in the
+    /// `upgrade` batch). The stored state version picks the layout; unversioned state is the
+    /// released contract's.
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let bytes = env::storage_read(b"STATE").expect("no existing state to migrate");
+        let state = match stored_state_version() {
+            Some(STATE_VERSION) => Contract::try_from_slice(&bytes).ok(),
+            Some(version) => env::panic_str(&format!("unsupported state version {}", version)),
+            None => ReleasedContract::try_from_slice(&bytes).ok().map(ReleasedContract::into_current),
         };
+        let state = state.unwrap_or_else(|| env::panic_str("cannot deserialize the contract state"));
+        let caller = env::predecessor_account_id();
+        assert!(caller == state.owner_id || caller == env::current_account_id(), "owner only");
+        state
+    }
+
+    /// Approve code for `upgrade` by its sha256 (owner only). With a timelock set the approval is
+    /// queued and its id returned, so communities see the new code before it can be deployed.
+    pub fn propose_upgrade(&mut self, code_hash: Base58CryptoHash) -> Option<u64> {
+        assert_eq!(env::predecessor_account_id(), self.owner_id, "owner only");
+        self.queue_or_apply(PendingChange::Upgrade { code_hash })
+    }
+
+    /// Deploy new code and run its `migrate` in one batch (owner only, e.g. the governance contract).
+    /// `code` is the wasm module, borsh-serialized as the call arguments. With a timelock set, its
+    /// hash must have been approved through `propose_upgrade`.
+    pub fn upgrade(&mut self, #[serializer(borsh)] code: Vec<u8>) -> Promise {
+        assert_eq!(env::predecessor_account_id(), self.owner_id, "owner only");
+        let approved = self.approved_code_hash.take();
+        if self.change_timelock_ns > 0 {
+            assert!(approved == Some(env::sha256_array(&code)), "code hash not approved");
+        }
+        emit_event("contract_upgrade", serde_json::json!({ "code_len": code.len(), "state_version": STATE_VERSION }));
+        deploy_and_migrate(code, b"{}".to_vec())
+    }
+
+    pub fn get_state_version(&self) -> u32 { self.state_version.get().unwrap_or(0) }
+
+    // Ownership
+    /// First step of an ownership transfer; `new_owner_id` must call `accept_owner`.
+    pub fn propose_owner(&mut self, new_owner_id: AccountId) {
+        assert_eq!(env::predecessor_account_id(), self.owner_id, "owner only");
+        self.pending_owner_id = Some(new_owner_id.clone());
+        emit_event(
+            "owner_proposed",
+            serde_json::json!({ "owner_id": self.owner_id, "pending_owner_id": new_owner_id }),
+        );
+    }
+
+    pub fn accept_owner(&mut self) {
+        let caller = env::predecessor_account_id();
+        assert!(self.pending_owner_id.as_ref() == Some(&caller), "pending owner only");
+        let previous = std::mem::replace(&mut self.owner_id, caller);
+        self.pending_owner_id = None;
+        emit_event(
+            "owner_transferred",
+            serde_json::json!({ "previous_owner_id": previous, "owner_id": self.owner_id }),
+        );
+    }
+
+    // Roles
+    /// Grant a role (owner or admin; only the owner grants admin).
+    pub fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_can_manage_role(role);
+        if self.access.grant(role, &account_id) {
+            emit_event("role_granted", serde_json::json!({ "role": role, "account_id": account_id }));
+        }
+    }
 
-        Self { owner_id, agent_account_id, dataset, community_id, logs: UnorderedMap::new(b"l".to_vec()) }
+    pub fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_can_manage_role(role);
+        if self.access.revoke(role, &account_id) {
+            emit_event("role_revoked", serde_json::json!({ "role": role, "account_id": account_id }));
+        }
     }
 
+    pub fn has_role(&self, role: Role, account_id: AccountId) -> bool { self.access.has_role(role, &account_id) }
+
+    pub fn get_roles(&self, account_id: AccountId) -> Vec<Role> { self.access.roles_of(&account_id) }
+
     // Owner-only config
-    pub fn set_agent_account(&mut self, agent_account_id: AccountId) {
+    /// Delay before agent and dataset changes take effect. Raising it applies
+    /// immediately; lowering it is queued behind the current timelock.
+    pub fn set_change_timelock(&mut self, timelock_ns: u64) -> Option<u64> {
         self.assert_owner();
-        self.agent_account_id = agent_account_id;
+        if timelock_ns >= self.change_timelock_ns {
+            self.apply_change(PendingChange::Timelock { timelock_ns });
+            None
+        } else {
+            Some(self.queue_change(PendingChange::Timelock { timelock_ns }))
+        }
     }
 
-    pub fn set_dataset_meta(&mut self, dataset_hash: String, dataset_uri: String) {
+    /// Replace every authorized agent with a single account. With a timelock set the change
+    /// is queued and its id returned.
+    pub fn set_agent_account(&mut self, agent_account_id: AccountId) -> Option<u64> {
         self.assert_owner();
-        self.dataset = DatasetMeta { dataset_hash, dataset_uri, updated_at_ns: env::block_timestamp() };
+        self.queue_or_apply(PendingChange::AgentAccount { agent_account_id })
     }
 
-    pub fn set_community(&mut self, community_id: String) {
-        self.assert_owner();
-        self.community_id = community_id;
+    /// Apply a queued change once its timelock has passed. Callable by anyone.
+    pub fn execute_change(&mut self, change_id: u64) {
+        let queued = self.queued_changes.get(&change_id).expect("unknown change");
+        assert!(env::block_timestamp() >= queued.executable_at_ns, "timelock not expired");
+        self.queued_changes.remove(&change_id);
+        self.apply_change(queued.change);
+        emit_event("change_executed", serde_json::json!({ "change_id": change_id }));
     }
 
-    // Agent-only logging
-    pub fn log_interaction(
-        &mut self,
-        session_id: String,
-        query_hash: String,
-        answer_hash: String,
-        cost_microusd: u64,
-        community_id: Option<String>,
-    ) {
-        self.assert_agent();
+    pub fn cancel_change(&mut self, change_id: u64) {
+        self.assert_owner();
+        assert!(self.queued_changes.remove(&change_id).is_some(), "unknown change");
+        emit_event("change_cancelled", serde_json::json!({ "change_id": change_id }));
+    }
 
-        if let Some(cid) = &community_id {
-            assert!(cid == &self.community_id, "community mismatch");
+    fn queue_or_apply(&mut self, change: PendingChange) -> Option<u64> {
+        if self.change_timelock_ns == 0 {
+            self.apply_change(change);
+            None
+        } else {
+            Some(self.queue_change(change))
         }
+    }
 
-        let before = env::storage_usage();
+    fn queue_change(&mut self, change: PendingChange) -> u64 {
+        let change_id = self.next_change_id;
+        self.next_change_id += 1;
+        let queued = QueuedChange { change, executable_at_ns: env::block_timestamp() + self.change_timelock_ns };
+        self.queued_changes.insert(&change_id, &queued);
+        emit_event(
+            "change_queued",
+            serde_json::json!({
+                "change_id": change_id,
+                "change": queued.change,
+                "executable_at_ns": queued.executable_at_ns.to_string(),
+            }),
+        );
+        change_id
+    }
 
-        let log = InteractionLog {
-            session_id: session_id.clone(),
-            query_hash,
-            answer_hash,
-            cost_microusd,
-            community_id,
-            created_at_ns: env::block_timestamp(),
-        };
-        self.logs.insert(&session_id, &log);
-
-        // Storage cost handling: require attached deposit >= delta * cost, refund extra
-        let after = env::storage_usage();
-        if after > before {
-            let delta = u128::from(after - before);
-            let required: u128 = delta * env::storage_byte_cost().as_yoctonear();
-            let deposit: u128 = env::attached_deposit().as_yoctonear();
-            assert!(deposit >= required, "insufficient deposit for storage");
-            let refund = deposit - required;
-            if refund > 0 {
-                Promise::new(env::predecessor_account_id()).transfer(near_sdk::NearToken::from_yoctonear(refund));
+    fn apply_change(&mut self, change: PendingChange) {
+        match change {
+            PendingChange::AgentAccount { agent_account_id } => self.apply_agent_account(agent_account_id),
+            PendingChange::AddAgent { agent_account_id } => self.apply_add_agent(agent_account_id),
+            PendingChange::AgentAttestation {
+                agent_account_id,
+                attestation_hash,
+                measurement,
+                expires_at_ns,
+                enclave_public_key,
+            } => self.apply_agent_attestation(
+                agent_account_id,
+                attestation_hash,
+                measurement,
+                expires_at_ns,
+                enclave_public_key,
+            ),
+            PendingChange::DatasetMeta { community_id, dataset_hash, dataset_uri } => {
+                self.apply_dataset_meta(community_id, dataset_hash, dataset_uri)
             }
+            PendingChange::Timelock { timelock_ns } => self.change_timelock_ns = timelock_ns,
+            PendingChange::Upgrade { code_hash } => self.approved_code_hash = Some(code_hash.into()),
         }
     }
 
-    // Views
-    pub fn get_dataset_meta(&self) -> DatasetMeta { self.dataset.clone() }
+    fn apply_agent_account(&mut self, agent_account_id: AccountId) {
+        for agent in self.agents.to_vec() {
+            if agent != agent_account_id {
+                self.agents.remove(&agent);
+                self.attestations.remove(&agent);
+                AgentRemoved { agent_account_id: agent }.emit();
+            }
+        }
+        if self.agents.insert(&agent_account_id) {
+            AgentAdded { agent_account_id }.emit();
+        }
+    }
 
-    pub fn get_community_id(&self) -> String { self.community_id.clone() }
+    /// Authorize an additional agent, e.g. a new TEE instance during rotation. With a timelock
+    /// set the change is queued and its id returned.
+    pub fn add_agent(&mut self, agent_account_id: AccountId) -> Option<u64> {
+        self.assert_owner();
+        if self.agents.contains(&agent_account_id) {
+            return None;
+        }
+        assert!(self.agents.len() < MAX_AGENTS, "too many agents");
+        self.queue_or_apply(PendingChange::AddAgent { agent_account_id })
+    }
 
-    pub fn get_interaction(&self, session_id: String) -> Option<InteractionLog> { self.logs.get(&session_id) }
+    fn apply_add_agent(&mut self, agent_account_id: AccountId) {
+        if self.agents.contains(&agent_account_id) {
+            return;
+        }
+        assert!(self.agents.len() < MAX_AGENTS, "too many agents");
+        self.agents.insert(&agent_account_id);
+        AgentAdded { agent_account_id }.emit();
+    }
 
-    // Internal guards
-    fn assert_owner(&self) {
-        assert_eq!(env::predecessor_account_id(), self.owner_id, "owner only");
+    pub fn remove_agent(&mut self, agent_account_id: AccountId) {
+        self.access.assert_role(&self.owner_id, Role::Guardian);
+        assert!(self.agents.remove(&agent_account_id), "unknown agent");
+        self.attestations.remove(&agent_account_id);
+        AgentRemoved { agent_account_id }.emit();
     }
 
-    fn assert_agent(&self) {
-        assert_eq!(env::predecessor_account_id(), self.agent_account_id, "agent only");
+    /// Record the TEE attestation for an agent after verifying its quote off-chain.
+    /// Re-registering replaces the previous record, e.g. after an enclave rebuild. With a timelock
+    /// set, an attestation that introduces a signing key is queued and its id returned; renewing
+    /// the current key applies immediately.
+    pub fn register_agent_attestation(
+        &mut self,
+        agent_account_id: AccountId,
+        attestation_hash: String,
+        measurement: String,
+        expires_at_ns: u64,
+        enclave_public_key: PublicKey,
+    ) -> Option<u64> {
+        self.assert_owner();
+        assert!(self.agents.contains(&agent_account_id), "unknown agent");
+        assert!(enclave_public_key.curve_type() == CurveType::ED25519, "enclave key must be ed25519");
+        assert!(expires_at_ns > env::block_timestamp(), "attestation already expired");
+        let change = PendingChange::AgentAttestation {
+            agent_account_id: agent_account_id.clone(),
+            attestation_hash,
+            measurement,
+            expires_at_ns,
+            enclave_public_key: enclave_public_key.clone(),
+        };
+        let current_key = self.attestations.get(&agent_account_id).map(|a| a.enclave_public_key);
+        if current_key == Some(enclave_public_key) {
+            self.apply_change(change);
+            None
+        } else {
+            self.queue_or_apply(change)
+        }
     }
-}
 
-// Unit tests (basic)
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::testing_env;
+    fn apply_agent_attestation(
+        &mut self,
+        agent_account_id: AccountId,
+        attestation_hash: String,
+        measurement: String,
+        expires_at_ns: u64,
+        enclave_public_key: PublicKey,
+    ) {
+        assert!(self.agents.contains(&agent_account_id), "unknown agent");
+        assert!(expires_at_ns > env::block_timestamp(), "attestation already expired");
+        let attestation = AgentAttestation {
+            attestation_hash,
+            measurement,
+            enclave_public_key,
+            expires_at_ns,
+            registered_at_ns: env::block_timestamp(),
+        };
+        self.attestations.insert(&agent_account_id, &attestation);
+        emit_event(
+            "agent_attested",
+            serde_json::json!({
+                "agent_account_id": agent_account_id,
+                "attestation_hash": attestation.attestation_hash,
+                "measurement": attestation.measurement,
+                "enclave_public_key": attestation.enclave_public_key,
+                "expires_at_ns": attestation.expires_at_ns.to_string(),
+            }),
+        );
+    }
 
-    fn set_predecessor(predecessor: &str) {
-        let mut builder = VMContextBuilder::new();
-        builder.predecessor_account_id(predecessor.parse().unwrap());
-        testing_env!(builder.build());
+    /// Start serving a new community; it is enabled with no budget caps.
+    pub fn add_community(&mut self, community_id: String, dataset_hash: String, dataset_uri: String) {
+        self.assert_owner();
+        assert!(self.communities.get(&community_id).is_none(), "community exists");
+        let dataset = self.push_dataset_version(&community_id, dataset_hash, dataset_uri);
+        self.communities.insert(&community_id, &Community::new(dataset));
+        emit_event("community_added", serde_json::json!({ "community_id": community_id }));
     }
 
-    fn set_actor_with_deposit(predecessor: &str, deposit: u128) {
-        let mut builder = VMContextBuilder::new();
-        builder.predecessor_account_id(predecessor.parse().unwrap());
-        builder.attached_deposit(near_sdk::NearToken::from_yoctonear(deposit));
-        testing_env!(builder.build());
+    /// Stop serving a community. Its logs and dataset history are kept.
+    pub fn remove_community(&mut self, community_id: String) {
+        self.assert_owner();
+        assert!(self.communities.remove(&community_id).is_some(), "unknown community");
+        self.budget_spend.remove(&community_id);
+        emit_event("community_removed", serde_json::json!({ "community_id": community_id }));
     }
 
-    #[test]
-    fn init_and_views() {
-        set_predecessor("owner.testnet");
-        let c = Contract::new(
-            "owner.testnet".parse().unwrap(),
-            "agent.testnet".parse().unwrap(),
-            "hash".into(),
-            "uri".into(),
-            "dw".into(),
-        );
-        let ds = c.get_dataset_meta();
-        assert_eq!(ds.dataset_hash, "hash");
+    pub fn set_community_enabled(&mut self, community_id: String, enabled: bool) {
+        self.access.assert_role(&self.owner_id, Role::Guardian);
+        let mut community = self.expect_community(&community_id);
+        community.enabled = enabled;
+        self.communities.insert(&community_id, &community);
+        emit_event("community_updated", serde_json::json!({ "community_id": community_id, "enabled": enabled }));
     }
 
-    #[test]
-    fn owner_sets_agent_and_allowlist() {
-        set_predecessor("owner.testnet");
-        let mut c = Contract::new(
-            "owner.testnet".parse().unwrap(),
-            "agent.testnet".parse().unwrap(),
-            "h".into(),
-            "u".into(),
-            "dw".into(),
+    /// Entitle an account to query a community's dataset (and rate its answers) until
+    /// `expires_at_ns`, or until revoked if omitted. Re-granting replaces the expiry.
+    pub fn grant_access(&mut self, account_id: AccountId, community_id: String, expires_at_ns: Option<u64>) {
+        self.assert_owner();
+        self.expect_community(&community_id);
+        if let Some(expiry) = expires_at_ns {
+            assert!(expiry > env::block_timestamp(), "expiry in the past");
+        }
+        let grant = AccessGrant { expires_at_ns, granted_at_ns: env::block_timestamp() };
+        self.access_grants.insert(&(community_id.clone(), account_id.clone()), &grant);
+        emit_event(
+            "access_granted",
+            serde_json::json!({
+                "account_id": account_id,
+                "community_id": community_id,
+                "expires_at_ns": expires_at_ns.map(|v| v.to_string()),
+            }),
         );
-        c.set_agent_account("agent2.testnet".parse().unwrap());
-        c.set_community("dw-community".into());
-        assert_eq!(c.get_community_id(), "dw-community");
     }
 
-    #[test]
-    fn agent_logs_interaction() {
-        // init
-        set_predecessor("owner.testnet");
-        let mut c = Contract::new(
-            "owner.testnet".parse().unwrap(),
-            "agent.testnet".parse().unwrap(),
-            "h".into(),
-            "u".into(),
-            "dw".into(),
+    pub fn revoke_access(&mut self, account_id: AccountId, community_id: String) {
+        self.assert_owner();
+        assert!(self.access_grants.remove(&(community_id.clone(), account_id.clone())).is_some(), "no access grant");
+        emit_event("access_revoked", serde_json::json!({ "account_id": account_id, "community_id": community_id }));
+    }
+
+    /// Allow an account to dispute logs for a community.
+    pub fn add_curator(&mut self, community_id: String, account_id: AccountId) {
+        self.assert_owner();
+        self.expect_community(&community_id);
+        self.curators.insert(&(community_id, account_id));
+    }
+
+    pub fn remove_curator(&mut self, community_id: String, account_id: AccountId) {
+        self.assert_owner();
+        self.curators.remove(&(community_id, account_id));
+    }
+
+    /// Choose whether re-sent logs for a known session_id panic or are ignored.
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) {
+        self.assert_owner();
+        self.duplicate_policy = policy;
+    }
+
+    /// Bill a community's queries against its prepaid balance instead of invoicing.
+    pub fn set_community_prepaid(&mut self, community_id: String, prepaid: bool) {
+        self.assert_owner();
+        let mut community = self.expect_community(&community_id);
+        community.prepaid = prepaid;
+        self.communities.insert(&community_id, &community);
+        emit_event("community_updated", serde_json::json!({ "community_id": community_id, "prepaid": prepaid }));
+    }
+
+    /// Contract that flag outcomes are recorded on. This contract must be one of its reporters.
+    pub fn set_reputation_contract(&mut self, reputation_contract_id: Option<AccountId>) {
+        self.assert_owner();
+        self.reputation_contract_id = reputation_contract_id;
+    }
+
+    /// Contract whose `spend_credits` is called for billed logs. This contract must be one of
+    /// its credit spenders.
+    pub fn set_credits_contract(&mut self, credits_contract_id: Option<AccountId>) {
+        self.assert_owner();
+        self.credits_contract_id = credits_contract_id;
+    }
+
+    pub fn set_price_keeper(&mut self, price_keeper: Option<AccountId>) {
+        self.assert_owner();
+        self.price_keeper = price_keeper;
+    }
+
+    /// Set the NEAR/USD price used to convert query costs (owner or price keeper).
+    pub fn set_near_price(&mut self, near_price_usd_micros: u64) {
+        let caller = env::predecessor_account_id();
+        assert!(
+            self.price_keeper.as_ref() == Some(&caller)
+                || self.access.is_authorized(&self.owner_id, Role::Keeper, &caller),
+            "owner or price keeper only"
+        );
+        assert!(near_price_usd_micros > 0, "price must be positive");
+        self.near_price_usd_micros = near_price_usd_micros;
+        emit_event(
+            "near_price_updated",
+            serde_json::json!({ "near_price_usd_micros": near_price_usd_micros.to_string() }),
+        );
+    }
+
+    /// Maintenance for keepers and cron agents such as a Croncat task (owner or keeper role).
+    /// Rolls over the budget spend of up to `MAX_TICK_COMMUNITIES` communities whose day or month
+    /// has ended, resuming where the previous tick stopped, so budget resets show up as events
+    /// even for communities that log nothing.
+    pub fn tick(&mut self) -> TickReport {
+        self.access.assert_role(&self.owner_id, Role::Keeper);
+        let community_ids = self.communities.keys_as_vector();
+        let count = community_ids.len();
+        let mut cursor = self.tick_cursor;
+        let mut report = TickReport { scanned: 0, budgets_reset: Vec::new() };
+        while report.scanned < MAX_TICK_COMMUNITIES.min(count) {
+            if cursor >= count {
+                cursor = 0;
+            }
+            let community_id = community_ids.get(cursor).expect("community index in range");
+            if let Some(spend) = self.budget_spend.get(&community_id) {
+                let current = self.current_spend(&community_id);
+                if current.day != spend.day || current.month != spend.month {
+                    self.budget_spend.insert(&community_id, &current);
+                    report.budgets_reset.push(community_id);
+                }
+            }
+            report.scanned += 1;
+            cursor += 1;
+        }
+        self.tick_cursor = cursor;
+        emit_event("maintenance_tick", serde_json::json!(report));
+        report
+    }
+
+    /// Move NEAR deducted from community balances to `receiver_id`.
+    pub fn withdraw_collected(&mut self, receiver_id: AccountId, amount: U128) -> Promise {
+        self.access.assert_role(&self.owner_id, Role::Treasurer);
+        assert!(amount.0 <= self.collected_yocto, "amount exceeds collected balance");
+        self.collected_yocto -= amount.0;
+        Promise::new(receiver_id).transfer(NearToken::from_yoctonear(amount.0))
+    }
+
+    /// Prepay NEAR for a community's agent usage. Anyone can fund any community.
+    #[payable]
+    pub fn fund_community(&mut self, community_id: String) -> U128 {
+        let amount = env::attached_deposit().as_yoctonear();
+        assert!(amount > 0, "attach NEAR to fund");
+        self.expect_community(&community_id);
+        let balance = self.balances.get(&community_id).unwrap_or(0) + amount;
+        self.balances.insert(&community_id, &balance);
+        emit_event(
+            "community_funded",
+            serde_json::json!({
+                "community_id": community_id,
+                "account_id": env::predecessor_account_id(),
+                "amount": U128(amount),
+                "balance": U128(balance),
+            }),
+        );
+        U128(balance)
+    }
+
+    /// Publish a new dataset version for a community; earlier versions stay in its history.
+    /// With a timelock set the change is queued and its id returned.
+    pub fn set_dataset_meta(&mut self, community_id: String, dataset_hash: String, dataset_uri: String) -> Option<u64> {
+        self.assert_owner();
+        self.expect_community(&community_id);
+        self.queue_or_apply(PendingChange::DatasetMeta { community_id, dataset_hash, dataset_uri })
+    }
+
+    fn apply_dataset_meta(&mut self, community_id: String, dataset_hash: String, dataset_uri: String) {
+        let mut community = self.expect_community(&community_id);
+        community.dataset = self.push_dataset_version(&community_id, dataset_hash, dataset_uri);
+        self.communities.insert(&community_id, &community);
+        emit_event(
+            "dataset_updated",
+            serde_json::json!({
+                "community_id": community_id,
+                "version": community.dataset.version,
+                "dataset_hash": community.dataset.dataset_hash,
+                "dataset_uri": community.dataset.dataset_uri,
+            }),
+        );
+    }
+
+    /// Publish a new guardrails version for a community. Earlier versions stay readable so
+    /// logs can be tied to the policy they ran under.
+    pub fn set_guardrails(
+        &mut self,
+        community_id: String,
+        max_cost_per_query_microusd: Option<u64>,
+        blocked_topics_hash: String,
+        system_prompt_hash: String,
+    ) -> u32 {
+        self.assert_owner();
+        let mut community = self.expect_community(&community_id);
+        community.guardrails_version += 1;
+        let guardrails = Guardrails {
+            max_cost_per_query_microusd,
+            blocked_topics_hash,
+            system_prompt_hash,
+            version: community.guardrails_version,
+            updated_at_ns: env::block_timestamp(),
+        };
+        self.guardrails.insert(&(community_id.clone(), guardrails.version), &guardrails);
+        self.communities.insert(&community_id, &community);
+        emit_event(
+            "guardrails_updated",
+            serde_json::json!({ "community_id": community_id, "version": guardrails.version }),
+        );
+        guardrails.version
+    }
+
+    /// Cap the cost the agents may log for a community per UTC day and month.
+    pub fn set_budget(
+        &mut self,
+        community_id: String,
+        daily_cap_microusd: Option<u64>,
+        monthly_cap_microusd: Option<u64>,
+    ) {
+        self.assert_owner();
+        let mut community = self.expect_community(&community_id);
+        community.budget = Budget { daily_cap_microusd, monthly_cap_microusd };
+        self.communities.insert(&community_id, &community);
+        emit_event(
+            "budget_updated",
+            serde_json::json!({
+                "community_id": community_id,
+                "daily_cap_microusd": daily_cap_microusd.map(|v| v.to_string()),
+                "monthly_cap_microusd": monthly_cap_microusd.map(|v| v.to_string()),
+            }),
+        );
+    }
+
+    // Agent-only logging
+    /// Attach enough NEAR to cover the new log's storage; the excess is refunded.
+    #[payable]
+    pub fn log_interaction(
+        &mut self,
+        session_id: String,
+        query_hash: String,
+        answer_hash: String,
+        cost_microusd: u64,
+        community_id: String,
+        signature: Base64VecU8,
+        billed_account_id: Option<AccountId>,
+    ) -> LogOutcome {
+        let attestation = self.assert_agent();
+        let before = env::storage_usage();
+        let outcome = self.record_interaction(
+            &attestation,
+            InteractionInput {
+                session_id,
+                query_hash,
+                answer_hash,
+                cost_microusd,
+                community_id,
+                signature,
+                billed_account_id,
+            },
+        );
+        charge_storage(before);
+        outcome
+    }
+
+    /// Log several interactions in one transaction. The attached deposit must cover the storage
+    /// of the whole batch; any invalid entry rejects the batch.
+    #[payable]
+    pub fn log_interactions(&mut self, interactions: Vec<InteractionInput>) -> Vec<LogOutcome> {
+        let attestation = self.assert_agent();
+        assert!(!interactions.is_empty(), "empty batch");
+        assert!(interactions.len() <= MAX_INTERACTION_BATCH, "batch too large");
+        assert!(
+            interactions.iter().filter(|i| i.billed_account_id.is_some()).count() <= MAX_BILLED_PER_BATCH,
+            "too many billed interactions in batch"
+        );
+
+        let before = env::storage_usage();
+        let count = interactions.len();
+        let mut total_cost_microusd: u64 = 0;
+        let mut outcomes = Vec::with_capacity(count);
+        for input in interactions {
+            let cost_microusd = input.cost_microusd;
+            let outcome = self.record_interaction(&attestation, input);
+            if outcome.inserted {
+                total_cost_microusd = total_cost_microusd.checked_add(cost_microusd).expect("cost overflow");
+            }
+            outcomes.push(outcome);
+        }
+        charge_storage(before);
+
+        InteractionsLogged {
+            agent_account_id: env::predecessor_account_id(),
+            count: outcomes.iter().filter(|o| o.inserted).count(),
+            duplicates: outcomes.iter().filter(|o| !o.inserted).count(),
+            total_cost_microusd: total_cost_microusd.into(),
+        }
+        .emit();
+        outcomes
+    }
+
+    /// Resolves the credit deduction scheduled for a billed log.
+    #[private]
+    pub fn on_credits_spent(
+        &mut self,
+        session_id: String,
+        #[callback_result] result: Result<U128, PromiseError>,
+    ) -> bool {
+        let paid = result.is_ok();
+        let status = if paid { PaymentStatus::Paid } else { PaymentStatus::Unpaid };
+        self.payments.insert(&session_id, &status);
+        emit_event("payment_settled", serde_json::json!({ "session_id": session_id, "status": status }));
+        paid
+    }
+
+    /// Rate a logged answer. Callable once per session by a member of its community, within
+    /// `RATING_WINDOW_NS` of the log being created.
+    pub fn rate_interaction(&mut self, session_id: String, score: u8, comment_hash: Option<String>) {
+        let log = self.get_log(&session_id).expect("unknown session");
+        let rater_id = env::predecessor_account_id();
+        assert!(self.has_access(rater_id.clone(), log.community_id.clone()), "community members only");
+        assert!((1..=MAX_RATING_SCORE).contains(&score), "score out of range");
+        assert!(env::block_timestamp() <= log.created_at_ns + RATING_WINDOW_NS, "rating window closed");
+        assert!(self.ratings.get(&session_id).is_none(), "already rated");
+
+        let rating = InteractionRating { rater_id, score, comment_hash, rated_at_ns: env::block_timestamp() };
+        self.ratings.insert(&session_id, &rating);
+
+        let mut stats = self.quality_stats.get(&log.community_id).unwrap_or_default();
+        stats.rating_count += 1;
+        stats.score_sum += u64::from(score);
+        stats.score_counts[usize::from(score - 1)] += 1;
+        self.quality_stats.insert(&log.community_id, &stats);
+        emit_event(
+            "interaction_rated",
+            serde_json::json!({ "session_id": session_id, "community_id": log.community_id, "score": score }),
+        );
+    }
+
+    /// Dispute a logged interaction (curators of its community only). Each log can be flagged
+    /// once; its cost counts as disputed until the flag is dismissed.
+    pub fn flag_interaction(&mut self, session_id: String, reason_hash: String) {
+        let log = self.get_log(&session_id).expect("unknown session");
+        let flagged_by = env::predecessor_account_id();
+        assert!(self.curators.contains(&(log.community_id.clone(), flagged_by.clone())), "curators only");
+        assert!(self.flags.get(&session_id).is_none(), "already flagged");
+
+        let flag = InteractionFlag {
+            flagged_by,
+            reason_hash,
+            flagged_at_ns: env::block_timestamp(),
+            status: FlagStatus::Open,
+            resolved_by: None,
+            resolved_at_ns: None,
+        };
+        self.flags.insert(&session_id, &flag);
+        self.adjust_disputed_cost(&log, true);
+        emit_event(
+            "interaction_flagged",
+            serde_json::json!({
+                "session_id": session_id,
+                "community_id": log.community_id,
+                "flagged_by": flag.flagged_by,
+                "reason_hash": flag.reason_hash,
+            }),
+        );
+    }
+
+    /// Settle an open flag (owner or agent). Upholding keeps the cost out of billing.
+    pub fn resolve_flag(&mut self, session_id: String, upheld: bool) {
+        let caller = env::predecessor_account_id();
+        assert!(
+            self.agents.contains(&caller) || self.access.is_authorized(&self.owner_id, Role::Resolver, &caller),
+            "owner, resolver or agent only"
+        );
+        let mut flag = self.flags.get(&session_id).expect("not flagged");
+        assert!(flag.status == FlagStatus::Open, "flag already resolved");
+
+        flag.status = if upheld { FlagStatus::Upheld } else { FlagStatus::Dismissed };
+        flag.resolved_by = Some(caller);
+        flag.resolved_at_ns = Some(env::block_timestamp());
+        self.flags.insert(&session_id, &flag);
+        let log = self.get_log(&session_id).expect("unknown session");
+        if upheld {
+            if let Some(account_id) = log.billed_account_id {
+                self.report_reputation(account_id, ReputationSignal::Flag);
+            }
+        } else {
+            self.adjust_disputed_cost(&log, false);
+            self.report_reputation(flag.flagged_by.clone(), ReputationSignal::Dispute);
+        }
+        emit_event("flag_resolved", serde_json::json!({ "session_id": session_id, "status": flag.status }));
+    }
+
+    /// Commit a Merkle root over the session digests of a batch of interactions that are
+    /// not logged individually. Each root can be committed once.
+    #[payable]
+    pub fn log_batch_commitment(
+        &mut self,
+        community_id: String,
+        root_hash: Base58CryptoHash,
+        count: u64,
+        period_start_ns: u64,
+        period_end_ns: u64,
+    ) -> u64 {
+        self.assert_agent();
+        let community = self.expect_community(&community_id);
+        assert!(community.enabled, "community disabled");
+        assert!(count > 0, "empty batch");
+        assert!(period_start_ns <= period_end_ns, "invalid period");
+        let root: CryptoHash = root_hash.into();
+        assert!(self.log_batch_roots.get(&root).is_none(), "batch already committed");
+
+        let before = env::storage_usage();
+        let batch = LogBatch {
+            root_hash,
+            community_id,
+            count,
+            period_start_ns,
+            period_end_ns,
+            agent_account_id: env::predecessor_account_id(),
+            committed_at_ns: env::block_timestamp(),
+        };
+        let index = self.log_batches.len();
+        self.log_batches.push(&batch);
+        self.log_batch_roots.insert(&root, &index);
+        emit_event(
+            "log_batch_committed",
+            serde_json::json!({
+                "index": index,
+                "root_hash": batch.root_hash,
+                "community_id": batch.community_id,
+                "count": count.to_string(),
+            }),
+        );
+
+        charge_storage(before);
+        index
+    }
+
+    // Views
+    pub fn get_dataset_meta(&self, community_id: String) -> Option<DatasetMeta> {
+        self.communities.get(&community_id).map(|c| c.dataset)
+    }
+
+    pub fn get_community(&self, community_id: String) -> Option<Community> { self.communities.get(&community_id) }
+
+    /// A community's dataset versions, oldest first, starting at `from_version` (default 1).
+    pub fn get_dataset_history(
+        &self,
+        community_id: String,
+        from_version: Option<u32>,
+        limit: Option<u64>,
+    ) -> Vec<DatasetMeta> {
+        let latest = self.dataset_versions.get(&community_id).unwrap_or(0);
+        let start = from_version.unwrap_or(1).max(1);
+        let limit = limit.unwrap_or(MAX_LOG_PAGE).min(MAX_LOG_PAGE) as usize;
+        (start..=latest)
+            .take(limit)
+            .filter_map(|version| self.dataset_history.get(&(community_id.clone(), version)))
+            .collect()
+    }
+
+    /// Guardrails for a community: the given version, or the latest if `version` is omitted.
+    pub fn get_community_balance(&self, community_id: String) -> U128 {
+        U128(self.balances.get(&community_id).unwrap_or(0))
+    }
+
+    pub fn get_collected(&self) -> U128 { U128(self.collected_yocto) }
+
+    pub fn get_near_price(&self) -> u64 { self.near_price_usd_micros }
+
+    pub fn get_guardrails(&self, community_id: String, version: Option<u32>) -> Option<Guardrails> {
+        let version = match version {
+            Some(v) => v,
+            None => self.communities.get(&community_id)?.guardrails_version,
+        };
+        self.guardrails.get(&(community_id, version))
+    }
+
+    pub fn get_communities(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<CommunityView> {
+        let start = from_index.unwrap_or(0) as usize;
+        let limit = limit.unwrap_or(MAX_COMMUNITY_PAGE).min(MAX_COMMUNITY_PAGE) as usize;
+        self.communities
+            .iter()
+            .skip(start)
+            .take(limit)
+            .map(|(community_id, community)| CommunityView { community_id, community })
+            .collect()
+    }
+
+    pub fn get_owner(&self) -> AccountId { self.owner_id.clone() }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> { self.pending_owner_id.clone() }
+
+    pub fn get_change_timelock(&self) -> u64 { self.change_timelock_ns }
+
+    pub fn get_queued_changes(&self) -> Vec<QueuedChangeView> {
+        self.queued_changes.iter().map(|(change_id, queued)| QueuedChangeView { change_id, queued }).collect()
+    }
+
+    pub fn get_agents(&self) -> Vec<AccountId> { self.agents.to_vec() }
+
+    pub fn is_agent(&self, account_id: AccountId) -> bool { self.agents.contains(&account_id) }
+
+    pub fn get_agent_attestation(&self, agent_account_id: AccountId) -> Option<AgentAttestation> {
+        self.attestations.get(&agent_account_id)
+    }
+
+    pub fn get_interaction(&self, session_id: String) -> Option<InteractionLogView> {
+        self.get_log(&session_id).map(|log| self.view_log(log))
+    }
+
+    pub fn get_interaction_flag(&self, session_id: String) -> Option<InteractionFlag> { self.flags.get(&session_id) }
+
+    pub fn get_payment_status(&self, session_id: String) -> Option<PaymentStatus> { self.payments.get(&session_id) }
+
+    pub fn get_credits_contract(&self) -> Option<AccountId> { self.credits_contract_id.clone() }
+
+    pub fn get_reputation_contract(&self) -> Option<AccountId> { self.reputation_contract_id.clone() }
+
+    pub fn is_curator(&self, community_id: String, account_id: AccountId) -> bool {
+        self.curators.contains(&(community_id, account_id))
+    }
+
+    pub fn get_log_batch_count(&self) -> u64 { self.log_batches.len() }
+
+    pub fn get_log_batches(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<LogBatch> {
+        let start = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(MAX_LOG_PAGE).min(MAX_LOG_PAGE);
+        (start..self.log_batches.len()).take(limit as usize).filter_map(|i| self.log_batches.get(i)).collect()
+    }
+
+    /// True if `leaf` (a session digest) is in the committed batch with this `root`.
+    /// `proof` lists the sibling hashes from the leaf up to the root.
+    pub fn verify_log_inclusion(
+        &self,
+        root: Base58CryptoHash,
+        proof: Vec<Base58CryptoHash>,
+        leaf: Base58CryptoHash,
+    ) -> bool {
+        let root: CryptoHash = root.into();
+        if proof.len() > MAX_PROOF_LEN || self.log_batch_roots.get(&root).is_none() {
+            return false;
+        }
+        let proof: Vec<CryptoHash> = proof.into_iter().map(Into::into).collect();
+        merkle_root_from_proof(&leaf.into(), &proof) == root
+    }
+
+    pub fn get_budget(&self, community_id: String) -> Option<Budget> {
+        self.communities.get(&community_id).map(|c| c.budget)
+    }
+
+    /// Cost that can still be logged for a community this day and month; `None` means uncapped.
+    pub fn get_remaining_budget(&self, community_id: String) -> RemainingBudget {
+        let budget = self.get_budget(community_id.clone()).unwrap_or_default();
+        let spend = self.current_spend(&community_id);
+        RemainingBudget {
+            daily_microusd: budget.daily_cap_microusd.map(|cap| cap.saturating_sub(spend.day_spent_microusd)),
+            monthly_microusd: budget.monthly_cap_microusd.map(|cap| cap.saturating_sub(spend.month_spent_microusd)),
+        }
+    }
+
+    pub fn get_interaction_rating(&self, session_id: String) -> Option<InteractionRating> {
+        self.ratings.get(&session_id)
+    }
+
+    pub fn get_community_quality_stats(&self, community_id: String) -> QualityStats {
+        self.quality_stats.get(&community_id).unwrap_or_default()
+    }
+
+    /// Whether `account_id` currently holds an unexpired grant for `community_id`.
+    pub fn has_access(&self, account_id: AccountId, community_id: String) -> bool {
+        self.access_grants
+            .get(&(community_id, account_id))
+            .is_some_and(|grant| grant.expires_at_ns.is_none_or(|expiry| expiry > env::block_timestamp()))
+    }
+
+    pub fn get_access_grant(&self, account_id: AccountId, community_id: String) -> Option<AccessGrant> {
+        self.access_grants.get(&(community_id, account_id))
+    }
+
+    /// Usage for a community over UTC days `[start_day, end_day]` (days since the Unix epoch).
+    pub fn get_usage(&self, community_id: String, start_day: u64, end_day: u64) -> UsageReport {
+        assert!(start_day <= end_day, "invalid day range");
+        assert!(end_day - start_day < MAX_USAGE_DAYS, "day range too long");
+        let days: Vec<DailyUsage> =
+            (start_day..=end_day).filter_map(|day| self.daily_usage.get(&(community_id.clone(), day))).collect();
+        UsageReport {
+            query_count: days.iter().map(|d| d.query_count).sum(),
+            total_cost_microusd: days.iter().map(|d| d.total_cost_microusd).sum(),
+            disputed_cost_microusd: days.iter().map(|d| d.disputed_cost_microusd).sum(),
+            community_id,
+            days,
+        }
+    }
+
+    pub fn get_duplicate_policy(&self) -> DuplicatePolicy { self.duplicate_policy }
+
+    /// Hash of the stored log, for reconciling re-sent transactions.
+    pub fn get_interaction_hash(&self, session_id: String) -> Option<Base58CryptoHash> {
+        self.get_log(&session_id).map(|log| record_hash(&log).into())
+    }
+
+    pub fn get_log_count(&self) -> u64 { self.logs.len() }
+
+    /// Logs in insertion order, starting at `from_index`.
+    pub fn get_logs(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<InteractionLogView> {
+        let logs = self.logs.values_as_vector();
+        let start = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(MAX_LOG_PAGE).min(MAX_LOG_PAGE);
+        (start..logs.len())
+            .take(limit as usize)
+            .filter_map(|i| logs.get(i))
+            .map(|entry| self.view_log(self.load_log(entry)))
+            .collect()
+    }
+
+    /// Logs created in `[start_ns, end_ns)`, oldest first. Logs are appended in block order,
+    /// so the first match is found by binary search; page by passing the last
+    /// `created_at_ns` + 1 as the next `start_ns`.
+    pub fn get_logs_by_time_range(&self, start_ns: u64, end_ns: u64, limit: Option<u64>) -> Vec<InteractionLogView> {
+        let logs = self.logs.values_as_vector();
+        let limit = limit.unwrap_or(MAX_LOG_PAGE).min(MAX_LOG_PAGE) as usize;
+        let (mut lo, mut hi) = (0, logs.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if logs.get(mid).unwrap().created_at_ns < start_ns {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        (lo..logs.len())
+            .map(|i| logs.get(i).unwrap())
+            .take_while(|entry| entry.created_at_ns < end_ns)
+            .take(limit)
+            .map(|entry| self.view_log(self.load_log(entry)))
+            .collect()
+    }
+
+    fn get_log(&self, session_id: &String) -> Option<InteractionLog> {
+        self.logs.get(session_id).map(|entry| self.load_log(entry))
+    }
+
+    fn load_log(&self, entry: LogEntry) -> InteractionLog {
+        let details = self.log_details.get(&entry.session_id).unwrap_or_default();
+        InteractionLog {
+            session_id: entry.session_id,
+            query_hash: entry.query_hash,
+            answer_hash: entry.answer_hash,
+            cost_microusd: entry.cost_microusd,
+            community_id: entry.community_id.or_else(|| self.released_community_id.clone()).unwrap_or_default(),
+            guardrails_version: details.guardrails_version,
+            dataset_version: details.dataset_version,
+            billed_account_id: details.billed_account_id,
+            created_at_ns: entry.created_at_ns,
+        }
+    }
+
+    fn store_log(&mut self, log: &InteractionLog) {
+        let entry = LogEntry {
+            session_id: log.session_id.clone(),
+            query_hash: log.query_hash.clone(),
+            answer_hash: log.answer_hash.clone(),
+            cost_microusd: log.cost_microusd,
+            community_id: Some(log.community_id.clone()),
+            created_at_ns: log.created_at_ns,
+        };
+        let details = LogDetails {
+            guardrails_version: log.guardrails_version,
+            dataset_version: log.dataset_version,
+            billed_account_id: log.billed_account_id.clone(),
+        };
+        self.logs.insert(&log.session_id, &entry);
+        self.log_details.insert(&log.session_id, &details);
+    }
+
+    fn view_log(&self, log: InteractionLog) -> InteractionLogView {
+        let flag = self.flags.get(&log.session_id);
+        let payment = self.payments.get(&log.session_id);
+        InteractionLogView { log, flag, payment }
+    }
+
+    // Budget accounting
+    /// Spend for the current day/month, with elapsed periods reset to zero.
+    fn current_spend(&self, community_id: &String) -> BudgetSpend {
+        let day = env::block_timestamp() / NS_PER_DAY;
+        let month = month_index(day);
+        let mut spend = self.budget_spend.get(community_id).unwrap_or_default();
+        if spend.day != day {
+            spend.day = day;
+            spend.day_spent_microusd = 0;
+        }
+        if spend.month != month {
+            spend.month = month;
+            spend.month_spent_microusd = 0;
+        }
+        spend
+    }
+
+    fn charge_budget(&mut self, community_id: &String, budget: &Budget, cost_microusd: u64) {
+        let mut spend = self.current_spend(community_id);
+        spend.day_spent_microusd = spend.day_spent_microusd.checked_add(cost_microusd).expect("cost overflow");
+        spend.month_spent_microusd = spend.month_spent_microusd.checked_add(cost_microusd).expect("cost overflow");
+        if let Some(cap) = budget.daily_cap_microusd {
+            assert!(spend.day_spent_microusd <= cap, "daily budget exceeded");
+        }
+        if let Some(cap) = budget.monthly_cap_microusd {
+            assert!(spend.month_spent_microusd <= cap, "monthly budget exceeded");
+        }
+        self.budget_spend.insert(community_id, &spend);
+    }
+
+    /// Validate one signed interaction, charge its cost and store it. Storage is paid by the caller.
+    fn record_interaction(&mut self, attestation: &AgentAttestation, input: InteractionInput) -> LogOutcome {
+        let InteractionInput {
+            session_id,
+            query_hash,
+            answer_hash,
+            cost_microusd,
+            community_id,
+            signature,
+            billed_account_id,
+        } = input;
+        let digest = log_digest(
+            &session_id,
+            &query_hash,
+            &answer_hash,
+            cost_microusd,
+            &community_id,
+            billed_account_id.as_ref(),
+        );
+        let signature: [u8; 64] =
+            signature.0.try_into().unwrap_or_else(|_| env::panic_str("signature must be 64 bytes"));
+        let public_key: [u8; 32] = attestation.enclave_public_key.as_bytes()[1..].try_into().unwrap();
+        assert!(env::ed25519_verify(&signature, &digest, &public_key), "invalid log signature");
+
+        if let Some(existing) = self.get_log(&session_id) {
+            let record_hash = Base58CryptoHash::from(record_hash(&existing));
+            if self.duplicate_policy == DuplicatePolicy::Reject {
+                env::panic_str(&format!(
+                    "duplicate session {}: existing record {}",
+                    session_id,
+                    String::from(&record_hash)
+                ));
+            }
+            return LogOutcome { session_id, inserted: false, record_hash };
+        }
+
+        let community = self.expect_community(&community_id);
+        assert!(community.enabled, "community disabled");
+        if let Some(guardrails) = self.guardrails.get(&(community_id.clone(), community.guardrails_version)) {
+            if let Some(max_cost) = guardrails.max_cost_per_query_microusd {
+                assert!(cost_microusd <= max_cost, "cost exceeds guardrails");
+            }
+        }
+
+        self.charge_budget(&community_id, &community.budget, cost_microusd);
+        if community.prepaid {
+            self.charge_balance(&community_id, cost_microusd);
+        }
+        self.record_usage(&community_id, cost_microusd);
+
+        let log = InteractionLog {
+            session_id: session_id.clone(),
+            query_hash,
+            answer_hash,
+            cost_microusd,
+            community_id,
+            guardrails_version: community.guardrails_version,
+            dataset_version: community.dataset.version,
+            billed_account_id,
+            created_at_ns: env::block_timestamp(),
+        };
+        self.store_log(&log);
+        if let Some(account_id) = &log.billed_account_id {
+            if cost_microusd > 0 {
+                self.bill_credits(&session_id, account_id, cost_microusd);
+            }
+        }
+        LogOutcome { session_id, inserted: true, record_hash: record_hash(&log).into() }
+    }
+
+    /// Ask the credits contract to deduct a billed log's cost; `on_credits_spent` records the
+    /// outcome. The log itself stands either way.
+    fn bill_credits(&mut self, session_id: &String, account_id: &AccountId, cost_microusd: u64) {
+        let credits_contract_id = self.credits_contract_id.clone().expect("credits contract not set");
+        self.payments.insert(session_id, &PaymentStatus::Pending);
+        ext_credits::ext(credits_contract_id)
+            .with_static_gas(GAS_FOR_SPEND_CREDITS)
+            .spend_credits(account_id.clone(), U128(u128::from(cost_microusd)), Some(session_id.clone()))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CREDITS_CALLBACK)
+                    .on_credits_spent(session_id.clone()),
+            );
+    }
+
+    fn record_usage(&mut self, community_id: &str, cost_microusd: u64) {
+        let day = env::block_timestamp() / NS_PER_DAY;
+        let key = (community_id.to_string(), day);
+        let mut usage = self.daily_usage.get(&key).unwrap_or(DailyUsage { day, ..Default::default() });
+        usage.query_count += 1;
+        usage.total_cost_microusd = usage.total_cost_microusd.checked_add(cost_microusd).expect("cost overflow");
+        self.daily_usage.insert(&key, &usage);
+    }
+
+    /// Record a flag outcome on the reputation contract, if one is set. Fire-and-forget: a failed
+    /// record does not undo the resolution.
+    fn report_reputation(&self, account_id: AccountId, signal: ReputationSignal) {
+        let Some(reputation_contract_id) = self.reputation_contract_id.clone() else {
+            return;
+        };
+        ext_reputation::ext(reputation_contract_id)
+            .with_static_gas(GAS_FOR_REPUTATION_RECORD)
+            .record(vec![account_id.clone()], signal);
+        emit_event("reputation_reported", serde_json::json!({ "account_id": account_id, "signal": signal }));
+    }
+
+    /// Move a log's cost into (or back out of) the disputed total for the day it was logged.
+    fn adjust_disputed_cost(&mut self, log: &InteractionLog, disputed: bool) {
+        let key = (log.community_id.clone(), log.created_at_ns / NS_PER_DAY);
+        let mut usage = self.daily_usage.get(&key).expect("missing usage roll-up");
+        usage.disputed_cost_microusd = if disputed {
+            usage.disputed_cost_microusd + log.cost_microusd
+        } else {
+            usage.disputed_cost_microusd - log.cost_microusd
+        };
+        self.daily_usage.insert(&key, &usage);
+    }
+
+    fn charge_balance(&mut self, community_id: &String, cost_microusd: u64) {
+        assert!(self.near_price_usd_micros > 0, "NEAR price not set");
+        // Round up so a non-zero cost is never free.
+        let cost_yocto = (u128::from(cost_microusd) * YOCTO_PER_NEAR).div_ceil(u128::from(self.near_price_usd_micros));
+        let balance = self.balances.get(community_id).unwrap_or(0);
+        assert!(balance >= cost_yocto, "community balance exhausted");
+        self.balances.insert(community_id, &(balance - cost_yocto));
+        self.collected_yocto += cost_yocto;
+    }
+
+    fn push_dataset_version(
+        &mut self,
+        community_id: &String,
+        dataset_hash: String,
+        dataset_uri: String,
+    ) -> DatasetMeta {
+        let version = self.dataset_versions.get(community_id).unwrap_or(0) + 1;
+        let dataset = DatasetMeta { dataset_hash, dataset_uri, version, updated_at_ns: env::block_timestamp() };
+        self.dataset_history.insert(&(community_id.clone(), version), &dataset);
+        self.dataset_versions.insert(community_id, &version);
+        dataset
+    }
+
+    fn expect_community(&self, community_id: &String) -> Community {
+        self.communities.get(community_id).expect("unknown community")
+    }
+
+    // Internal guards
+    /// Owner or an admin role holder.
+    fn assert_owner(&self) {
+        assert!(
+            self.access.is_authorized(&self.owner_id, Role::Admin, &env::predecessor_account_id()),
+            "owner only"
+        );
+    }
+
+    fn assert_can_manage_role(&self, role: Role) {
+        if role == Role::Admin {
+            assert_eq!(env::predecessor_account_id(), self.owner_id, "only the owner manages admins");
+        } else {
+            self.assert_owner();
+        }
+    }
+
+    /// Returns the caller's attestation, which must be unexpired.
+    fn assert_agent(&self) -> AgentAttestation {
+        let agent = env::predecessor_account_id();
+        assert!(self.agents.contains(&agent), "agent only");
+        let attestation = self.attestations.get(&agent).expect("agent not attested");
+        assert!(attestation.expires_at_ns > env::block_timestamp(), "agent attestation expired");
+        attestation
+    }
+}
+
+/// What the enclave signs for each log:
+/// `sha256(borsh((session_id, query_hash, answer_hash, cost_microusd, community_id, billed_account_id)))`.
+pub fn log_digest(
+    session_id: &str,
+    query_hash: &str,
+    answer_hash: &str,
+    cost_microusd: u64,
+    community_id: &str,
+    billed_account_id: Option<&AccountId>,
+) -> CryptoHash {
+    let bytes =
+        borsh::to_vec(&(session_id, query_hash, answer_hash, cost_microusd, community_id, billed_account_id)).unwrap();
+    env::sha256_array(&bytes)
+}
+
+/// sha256 of the borsh-serialized log.
+fn record_hash(log: &InteractionLog) -> CryptoHash {
+    env::sha256_array(&borsh::to_vec(log).unwrap())
+}
+
+/// Require the attached deposit to cover storage added since `before` and refund the rest
+/// to the caller. If nothing was added (e.g. an ignored duplicate) the whole deposit is
+/// refunded. Returns the refunded amount.
+fn charge_storage(before: u64) -> u128 {
+    let added = u128::from(env::storage_usage().saturating_sub(before));
+    let required = added.checked_mul(env::storage_byte_cost().as_yoctonear()).expect("storage cost overflow");
+    let deposit = env::attached_deposit().as_yoctonear();
+    assert!(
+        deposit >= required,
+        "insufficient deposit for storage: attached {} yoctoNEAR, required {}",
+        deposit,
+        required
+    );
+    let refund = deposit - required;
+    if refund > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(refund));
+    }
+    refund
+}
+
+fn merkle_leaf(digest: &CryptoHash) -> CryptoHash {
+    let mut data = Vec::with_capacity(33);
+    data.push(0u8);
+    data.extend_from_slice(digest);
+    env::sha256_array(&data)
+}
+
+fn merkle_node(a: &CryptoHash, b: &CryptoHash) -> CryptoHash {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Vec::with_capacity(65);
+    data.push(1u8);
+    data.extend_from_slice(lo);
+    data.extend_from_slice(hi);
+    env::sha256_array(&data)
+}
+
+fn merkle_root_from_proof(digest: &CryptoHash, proof: &[CryptoHash]) -> CryptoHash {
+    proof.iter().fold(merkle_leaf(digest), |node, sibling| merkle_node(&node, sibling))
+}
+
+/// `year * 12 + month` for a day count since the Unix epoch (proleptic Gregorian, UTC).
+fn month_index(days_since_epoch: u64) -> u64 {
+    // Howard Hinnant's civil_from_days, restricted to dates after 1970.
+    let z = days_since_epoch + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 2 } else { mp - 10 }; // 0 = January
+    let year = yoe + era * 400 + u64::from(month < 2);
+    year * 12 + month
+}
+
+/// Emit a NEP-297 event without a dedicated payload struct in `groupweave_events::interaction`.
+fn emit_event(event: &str, data: serde_json::Value) {
+    groupweave_events::interaction::emit(event, data);
+}
+
+// Unit tests (basic)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn set_predecessor(predecessor: &str) {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(predecessor.parse().unwrap());
+        testing_env!(builder.build());
+    }
+
+    fn set_actor_with_deposit(predecessor: &str, deposit: u128) {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(predecessor.parse().unwrap());
+        builder.attached_deposit(near_sdk::NearToken::from_yoctonear(deposit));
+        testing_env!(builder.build());
+    }
+
+    fn enclave_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[9u8; 32])
+    }
+
+    fn enclave_public_key() -> PublicKey {
+        let mut bytes = vec![0u8]; // ed25519 curve prefix
+        bytes.extend(enclave_key().verifying_key().to_bytes());
+        PublicKey::try_from(bytes).unwrap()
+    }
+
+    fn sign_log(session_id: &str, cost_microusd: u64, community_id: &str) -> Base64VecU8 {
+        use ed25519_dalek::Signer;
+        let digest = log_digest(session_id, "q", "a", cost_microusd, community_id, None);
+        Base64VecU8(enclave_key().sign(&digest).to_bytes().to_vec())
+    }
+
+    fn attest(c: &mut Contract, agent: &str) {
+        set_predecessor("owner.testnet");
+        c.register_agent_attestation(
+            agent.parse().unwrap(),
+            "quote".into(),
+            "mrtd".into(),
+            u64::MAX,
+            enclave_public_key(),
+        );
+    }
+
+    #[test]
+    fn init_and_views() {
+        set_predecessor("owner.testnet");
+        let c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "hash".into(),
+            "uri".into(),
+            "dw".into(),
+        );
+        let ds = c.get_dataset_meta("dw".into()).unwrap();
+        assert_eq!(ds.dataset_hash, "hash");
+        assert!(c.get_community("dw".into()).unwrap().enabled);
+    }
+
+    #[test]
+    fn owner_sets_agent_and_allowlist() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        c.set_agent_account("agent2.testnet".parse().unwrap());
+        assert_eq!(c.get_agents(), vec!["agent2.testnet".parse::<AccountId>().unwrap()]);
+        c.add_community("dw-community".into(), "h2".into(), "u2".into());
+        let ids: Vec<String> = c.get_communities(None, None).into_iter().map(|v| v.community_id).collect();
+        assert_eq!(ids, vec!["dw", "dw-community"]);
+        c.remove_community("dw".into());
+        assert_eq!(c.get_communities(None, None).len(), 1);
+    }
+
+    #[test]
+    fn multiple_agents_can_log() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        c.add_agent("agent2.testnet".parse().unwrap());
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].starts_with("EVENT_JSON:"));
+        assert!(logs[0].contains("\"event\":\"agent_added\""));
+        assert_eq!(c.get_agents().len(), 2);
+        attest(&mut c, "agent.testnet");
+        attest(&mut c, "agent2.testnet");
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s1", 1, "dw"), None);
+        set_actor_with_deposit("agent2.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s2".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s2", 1, "dw"), None);
+        assert!(c.get_interaction("s2".into()).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "agent only")]
+    fn removed_agent_cannot_log() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        c.add_agent("agent2.testnet".parse().unwrap());
+        attest(&mut c, "agent.testnet");
+        c.remove_agent("agent.testnet".parse().unwrap());
+        assert!(!c.is_agent("agent.testnet".parse().unwrap()));
+        assert!(c.get_agent_attestation("agent.testnet".parse().unwrap()).is_none());
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s1", 1, "dw"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many agents")]
+    fn agent_set_is_bounded() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        for i in 0..MAX_AGENTS {
+            c.add_agent(format!("agent{}.testnet", i).parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn agent_logs_interaction() {
+        // init
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+
+        attest(&mut c, "agent.testnet");
+
+        // agent call
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000); // 0.01 NEAR
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1234, "dw".into(), sign_log("s1", 1234, "dw"), None);
+        let l = c.get_interaction("s1".into()).unwrap();
+        assert_eq!(l.log.session_id, "s1");
+    }
+
+    #[test]
+    fn stored_log_keeps_released_layout() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        attest(&mut c, "agent.testnet");
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1234, "dw".into(), sign_log("s1", 1234, "dw"), None);
+
+        // session_id, query_hash, answer_hash, cost_microusd, community_id, created_at_ns
+        type ReleasedLog = (String, String, String, u64, Option<String>, u64);
+        let bytes = borsh::to_vec(&c.logs.get(&"s1".to_string()).unwrap()).unwrap();
+        let released: ReleasedLog = borsh::from_slice(&bytes).unwrap();
+        assert_eq!(released.4.as_deref(), Some("dw"));
+        assert_eq!(c.get_interaction("s1".into()).unwrap().log.dataset_version, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown community")]
+    fn agent_logs_with_mismatched_community_panics() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        attest(&mut c, "agent.testnet");
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000); // 0.01 NEAR
+        c.log_interaction("s2".into(), "q".into(), "a".into(), 0, "other".into(), sign_log("s2", 0, "other"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "agent not attested")]
+    fn unattested_agent_cannot_log() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s1", 1, "dw"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "agent attestation expired")]
+    fn expired_attestation_rejected() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        c.register_agent_attestation(
+            "agent.testnet".parse().unwrap(),
+            "quote".into(),
+            "mrtd".into(),
+            1_000,
+            enclave_public_key(),
+        );
+        let a = c.get_agent_attestation("agent.testnet".parse().unwrap()).unwrap();
+        assert_eq!(a.measurement, "mrtd");
+
+        let mut builder = VMContextBuilder::new();
+        builder
+            .predecessor_account_id("agent.testnet".parse().unwrap())
+            .attached_deposit(near_sdk::NearToken::from_millinear(10))
+            .block_timestamp(1_000);
+        testing_env!(builder.build());
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s1", 1, "dw"), None);
+    }
+
+    fn log_at(c: &mut Contract, session_id: &str, timestamp_ns: u64) {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .predecessor_account_id("agent.testnet".parse().unwrap())
+            .attached_deposit(near_sdk::NearToken::from_millinear(10))
+            .block_timestamp(timestamp_ns);
+        testing_env!(builder.build());
+        c.log_interaction(
+            session_id.into(),
+            "q".into(),
+            "a".into(),
+            1,
+            "dw".into(),
+            sign_log(session_id, 1, "dw"),
+            None,
+        );
+    }
+
+    #[test]
+    fn paginated_and_time_ranged_log_queries() {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        attest(&mut c, "agent.testnet");
+        for (i, ts) in [100, 200, 200, 300, 400].iter().enumerate() {
+            log_at(&mut c, &format!("s{}", i), *ts);
+        }
+        assert_eq!(c.get_log_count(), 5);
+
+        let page: Vec<String> = c.get_logs(Some(1), Some(2)).into_iter().map(|l| l.log.session_id).collect();
+        assert_eq!(page, vec!["s1", "s2"]);
+        assert!(c.get_logs(Some(5), None).is_empty());
+
+        let range: Vec<String> =
+            c.get_logs_by_time_range(200, 400, None).into_iter().map(|l| l.log.session_id).collect();
+        assert_eq!(range, vec!["s1", "s2", "s3"]);
+        assert_eq!(c.get_logs_by_time_range(150, 1_000, Some(2)).len(), 2);
+        assert!(c.get_logs_by_time_range(500, 1_000, None).is_empty());
+    }
+
+    fn log_cost_at(c: &mut Contract, session_id: &str, cost_microusd: u64, timestamp_ns: u64) {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .predecessor_account_id("agent.testnet".parse().unwrap())
+            .attached_deposit(near_sdk::NearToken::from_millinear(10))
+            .block_timestamp(timestamp_ns);
+        testing_env!(builder.build());
+        let signature = sign_log(session_id, cost_microusd, "dw");
+        c.log_interaction(session_id.into(), "q".into(), "a".into(), cost_microusd, "dw".into(), signature, None);
+    }
+
+    fn contract_with_budget(daily: Option<u64>, monthly: Option<u64>) -> Contract {
+        set_predecessor("owner.testnet");
+        let mut c = Contract::new(
+            "owner.testnet".parse().unwrap(),
+            "agent.testnet".parse().unwrap(),
+            "h".into(),
+            "u".into(),
+            "dw".into(),
+        );
+        attest(&mut c, "agent.testnet");
+        set_predecessor("owner.testnet");
+        c.set_budget("dw".into(), daily, monthly);
+        c
+    }
+
+    #[test]
+    fn month_index_handles_calendar_boundaries() {
+        assert_eq!(month_index(0), 1970 * 12); // 1970-01-01
+        assert_eq!(month_index(30), 1970 * 12); // 1970-01-31
+        assert_eq!(month_index(31), 1970 * 12 + 1); // 1970-02-01
+        assert_eq!(month_index(19_782), 2024 * 12 + 1); // 2024-02-29
+        assert_eq!(month_index(19_783), 2024 * 12 + 2); // 2024-03-01
+    }
+
+    #[test]
+    fn daily_budget_resets_next_day() {
+        let mut c = contract_with_budget(Some(1_000), None);
+        log_cost_at(&mut c, "s1", 600, 0);
+        assert_eq!(c.get_remaining_budget("dw".into()).daily_microusd, Some(400));
+        assert_eq!(c.get_remaining_budget("dw".into()).monthly_microusd, None);
+
+        log_cost_at(&mut c, "s2", 900, NS_PER_DAY);
+        assert_eq!(c.get_remaining_budget("dw".into()).daily_microusd, Some(100));
+    }
+
+    #[test]
+    fn tick_resets_elapsed_budgets() {
+        let mut c = contract_with_budget(Some(1_000), None);
+        c.grant_role(Role::Keeper, "keeper.testnet".parse().unwrap());
+        log_cost_at(&mut c, "s1", 600, 0);
+
+        set_time("keeper.testnet", NS_PER_DAY - 1);
+        assert!(c.tick().budgets_reset.is_empty());
+        set_time("keeper.testnet", NS_PER_DAY);
+        assert_eq!(c.tick(), TickReport { scanned: 1, budgets_reset: vec!["dw".to_string()] });
+        assert!(near_sdk::test_utils::get_logs().last().unwrap().contains("\"event\":\"maintenance_tick\""));
+        assert!(c.tick().budgets_reset.is_empty(), "an elapsed budget is reset once");
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the keeper role")]
+    fn tick_requires_keeper_role() {
+        let mut c = owner_contract();
+        set_predecessor("agent.testnet");
+        c.tick();
+    }
+
+    #[test]
+    #[should_panic(expected = "daily budget exceeded")]
+    fn daily_budget_rejects_overspend() {
+        let mut c = contract_with_budget(Some(1_000), None);
+        log_cost_at(&mut c, "s1", 600, 0);
+        log_cost_at(&mut c, "s2", 401, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "monthly budget exceeded")]
+    fn monthly_budget_spans_days() {
+        let mut c = contract_with_budget(Some(1_000), Some(1_500));
+        log_cost_at(&mut c, "s1", 1_000, 0);
+        log_cost_at(&mut c, "s2", 501, NS_PER_DAY);
+    }
+
+    #[test]
+    fn communities_have_separate_datasets_and_budgets() {
+        let mut c = contract_with_budget(Some(1_000), None);
+        set_predecessor("owner.testnet");
+        c.add_community("other".into(), "h2".into(), "u2".into());
+        c.set_dataset_meta("other".into(), "h3".into(), "u3".into());
+        assert_eq!(c.get_dataset_meta("other".into()).unwrap().dataset_hash, "h3");
+        assert_eq!(c.get_dataset_meta("dw".into()).unwrap().dataset_hash, "h");
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction(
+            "s1".into(),
+            "q".into(),
+            "a".into(),
+            5_000,
+            "other".into(),
+            sign_log("s1", 5_000, "other"),
+            None,
+        );
+        assert_eq!(c.get_interaction("s1".into()).unwrap().log.community_id, "other");
+        assert_eq!(c.get_remaining_budget("dw".into()).daily_microusd, Some(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "community disabled")]
+    fn disabled_community_rejects_logs() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_community_enabled("dw".into(), false);
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s1", 1, "dw"), None);
+    }
+
+    fn digest(n: u8) -> CryptoHash { env::sha256_array(&[n]) }
+
+    #[test]
+    fn batch_commitment_verifies_inclusion() {
+        let mut c = contract_with_budget(None, None);
+        // Four-leaf tree: root = node(node(l0, l1), node(l2, l3)).
+        let leaves: Vec<CryptoHash> = (0..4).map(|i| merkle_leaf(&digest(i))).collect();
+        let left = merkle_node(&leaves[0], &leaves[1]);
+        let right = merkle_node(&leaves[2], &leaves[3]);
+        let root = merkle_node(&left, &right);
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        let index = c.log_batch_commitment("dw".into(), root.into(), 4, 0, 100);
+        assert_eq!(index, 0);
+        assert_eq!(c.get_log_batches(None, None)[0].count, 4);
+
+        let proof = vec![leaves[3].into(), left.into()];
+        assert!(c.verify_log_inclusion(root.into(), proof.clone(), digest(2).into()));
+        assert!(!c.verify_log_inclusion(root.into(), proof.clone(), digest(1).into()));
+        assert!(!c.verify_log_inclusion(right.into(), proof, digest(2).into()));
+    }
+
+    #[test]
+    #[should_panic(expected = "batch already committed")]
+    fn batch_root_committed_once() {
+        let mut c = contract_with_budget(None, None);
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_batch_commitment("dw".into(), digest(0).into(), 1, 0, 100);
+        c.log_batch_commitment("dw".into(), digest(0).into(), 1, 0, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "agent only")]
+    fn batch_commitment_is_agent_only() {
+        let mut c = contract_with_budget(None, None);
+        set_actor_with_deposit("owner.testnet", 10_000_000_000_000_000_000_000);
+        c.log_batch_commitment("dw".into(), digest(0).into(), 1, 0, 100);
+    }
+
+    #[test]
+    fn guardrails_are_versioned_and_stamped_on_logs() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        assert!(c.get_guardrails("dw".into(), None).is_none());
+        assert_eq!(c.set_guardrails("dw".into(), Some(100), "topics1".into(), "prompt1".into()), 1);
+        assert_eq!(c.set_guardrails("dw".into(), Some(500), "topics2".into(), "prompt2".into()), 2);
+
+        assert_eq!(c.get_guardrails("dw".into(), None).unwrap().system_prompt_hash, "prompt2");
+        assert_eq!(c.get_guardrails("dw".into(), Some(1)).unwrap().system_prompt_hash, "prompt1");
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 200, "dw".into(), sign_log("s1", 200, "dw"), None);
+        assert_eq!(c.get_interaction("s1".into()).unwrap().log.guardrails_version, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "cost exceeds guardrails")]
+    fn guardrails_cap_cost_per_query() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_guardrails("dw".into(), Some(100), "t".into(), "p".into());
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 101, "dw".into(), sign_log("s1", 101, "dw"), None);
+    }
+
+    fn prepaid_contract(near_price_usd_micros: u64) -> Contract {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_community_prepaid("dw".into(), true);
+        c.set_near_price(near_price_usd_micros);
+        c
+    }
+
+    #[test]
+    fn prepaid_queries_draw_down_balance() {
+        let mut c = prepaid_contract(4_000_000); // $4 per NEAR
+        set_actor_with_deposit("alice.testnet", YOCTO_PER_NEAR);
+        assert_eq!(c.fund_community("dw".into()).0, YOCTO_PER_NEAR);
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        // $1
+        c.log_interaction(
+            "s1".into(),
+            "q".into(),
+            "a".into(),
+            1_000_000,
+            "dw".into(),
+            sign_log("s1", 1_000_000, "dw"),
+            None,
         );
+        assert_eq!(c.get_community_balance("dw".into()).0, YOCTO_PER_NEAR * 3 / 4);
+        assert_eq!(c.get_collected().0, YOCTO_PER_NEAR / 4);
+    }
 
-        // agent call
-        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000); // 0.01 NEAR
-        c.log_interaction("s1".into(), "q".into(), "a".into(), 1234, Some("dw".into()));
-        let l = c.get_interaction("s1".into()).unwrap();
-        assert_eq!(l.session_id, "s1");
+    #[test]
+    #[should_panic(expected = "community balance exhausted")]
+    fn prepaid_query_rejected_when_exhausted() {
+        let mut c = prepaid_contract(4_000_000);
+        set_actor_with_deposit("alice.testnet", YOCTO_PER_NEAR);
+        c.fund_community("dw".into());
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction(
+            "s1".into(),
+            "q".into(),
+            "a".into(),
+            4_000_001,
+            "dw".into(),
+            sign_log("s1", 4_000_001, "dw"),
+            None,
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn agent_logs_with_mismatched_community_panics() {
+    fn price_keeper_sets_price() {
+        let mut c = prepaid_contract(4_000_000);
+        c.set_price_keeper(Some("keeper.testnet".parse().unwrap()));
+        set_predecessor("keeper.testnet");
+        c.set_near_price(5_000_000);
+        assert_eq!(c.get_near_price(), 5_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "owner or price keeper only")]
+    fn stranger_cannot_set_price() {
+        let mut c = prepaid_contract(4_000_000);
+        set_predecessor("alice.testnet");
+        c.set_near_price(1);
+    }
+
+    fn contract_with_rated_log() -> Contract {
+        let mut c = contract_with_budget(None, None);
         set_predecessor("owner.testnet");
-        let mut c = Contract::new(
+        c.grant_access("alice.testnet".parse().unwrap(), "dw".into(), None);
+        log_at(&mut c, "s1", 1_000);
+        log_at(&mut c, "s2", 1_000);
+        c
+    }
+
+    #[test]
+    fn members_rate_interactions() {
+        let mut c = contract_with_rated_log();
+        set_predecessor("alice.testnet");
+        c.rate_interaction("s1".into(), 5, Some("comment".into()));
+        c.rate_interaction("s2".into(), 2, None);
+
+        assert_eq!(c.get_interaction_rating("s1".into()).unwrap().score, 5);
+        let stats = c.get_community_quality_stats("dw".into());
+        assert_eq!(stats.rating_count, 2);
+        assert_eq!(stats.score_sum, 7);
+        assert_eq!(stats.score_counts, [0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "community members only")]
+    fn non_member_cannot_rate() {
+        let mut c = contract_with_rated_log();
+        set_predecessor("bob.testnet");
+        c.rate_interaction("s1".into(), 5, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "already rated")]
+    fn session_rated_once() {
+        let mut c = contract_with_rated_log();
+        set_predecessor("alice.testnet");
+        c.rate_interaction("s1".into(), 5, None);
+        c.rate_interaction("s1".into(), 1, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "rating window closed")]
+    fn rating_window_closes() {
+        let mut c = contract_with_rated_log();
+        let mut builder = VMContextBuilder::new();
+        builder
+            .predecessor_account_id("alice.testnet".parse().unwrap())
+            .block_timestamp(1_001 + RATING_WINDOW_NS);
+        testing_env!(builder.build());
+        c.rate_interaction("s1".into(), 5, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid log signature")]
+    fn tampered_log_rejected() {
+        let mut c = contract_with_budget(None, None);
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        // Signed for a cost of 1, submitted with a cost of 1_000.
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1_000, "dw".into(), sign_log("s1", 1, "dw"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid log signature")]
+    fn log_signed_by_other_key_rejected() {
+        use ed25519_dalek::Signer;
+        let mut c = contract_with_budget(None, None);
+        let other = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let signature = other.sign(&log_digest("s1", "q", "a", 1, "dw", None)).to_bytes().to_vec();
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), Base64VecU8(signature), None);
+    }
+
+    fn input(session_id: &str, cost_microusd: u64) -> InteractionInput {
+        InteractionInput {
+            session_id: session_id.into(),
+            query_hash: "q".into(),
+            answer_hash: "a".into(),
+            cost_microusd,
+            community_id: "dw".into(),
+            signature: sign_log(session_id, cost_microusd, "dw"),
+            billed_account_id: None,
+        }
+    }
+
+    #[test]
+    fn batch_logs_interactions_with_one_event() {
+        let mut c = contract_with_budget(Some(100), None);
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interactions(vec![input("s1", 10), input("s2", 20), input("s3", 30)]);
+
+        assert_eq!(c.get_log_count(), 3);
+        assert_eq!(c.get_remaining_budget("dw".into()).daily_microusd, Some(40));
+        let logs = near_sdk::test_utils::get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].contains("\"event\":\"interactions_logged\""));
+        assert!(logs[0].contains("\"total_cost_microusd\":\"60\""));
+    }
+
+    #[test]
+    #[should_panic(expected = "batch too large")]
+    fn batch_size_is_bounded() {
+        let mut c = contract_with_budget(None, None);
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        let batch = (0..=MAX_INTERACTION_BATCH).map(|i| input(&format!("s{}", i), 1)).collect();
+        c.log_interactions(batch);
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient deposit for storage")]
+    fn batch_storage_checked_once_for_whole_batch() {
+        let mut c = contract_with_budget(None, None);
+        set_actor_with_deposit("agent.testnet", 1_000_000_000_000_000_000); // 0.001 NEAR
+        c.log_interactions((0..20).map(|i| input(&format!("s{}", i), 1)).collect());
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate session s1: existing record")]
+    fn duplicate_session_rejected_by_default() {
+        let mut c = contract_with_budget(None, None);
+        log_at(&mut c, "s1", 1_000);
+        log_at(&mut c, "s1", 2_000);
+    }
+
+    #[test]
+    fn duplicate_session_ignored_returns_existing_hash() {
+        let mut c = contract_with_budget(Some(100), None);
+        set_predecessor("owner.testnet");
+        c.set_duplicate_policy(DuplicatePolicy::Ignore);
+
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        let first = c.log_interaction(
+            "s1".into(),
+            "q".into(),
+            "a".into(),
+            10,
+            "dw".into(),
+            sign_log("s1", 10, "dw"),
+            None,
+        );
+        assert!(first.inserted);
+        let outcomes = c.log_interactions(vec![input("s1", 10), input("s2", 10)]);
+        assert!(!outcomes[0].inserted);
+        assert_eq!(outcomes[0].record_hash, first.record_hash);
+        assert!(outcomes[1].inserted);
+
+        // The duplicate was not charged again.
+        assert_eq!(c.get_remaining_budget("dw".into()).daily_microusd, Some(80));
+        assert_eq!(c.get_interaction_hash("s1".into()), Some(first.record_hash));
+    }
+
+    #[test]
+    fn usage_rolls_up_per_day() {
+        let mut c = contract_with_budget(None, None);
+        log_cost_at(&mut c, "s1", 10, 0);
+        log_cost_at(&mut c, "s2", 20, NS_PER_DAY - 1);
+        log_cost_at(&mut c, "s3", 5, 2 * NS_PER_DAY);
+
+        let report = c.get_usage("dw".into(), 0, 2);
+        assert_eq!(report.query_count, 3);
+        assert_eq!(report.total_cost_microusd, 35);
+        assert_eq!(report.days.len(), 2);
+        assert_eq!((report.days[0].day, report.days[0].query_count), (0, 2));
+        assert_eq!((report.days[1].day, report.days[1].total_cost_microusd), (2, 5));
+
+        assert_eq!(c.get_usage("dw".into(), 1, 1).query_count, 0);
+        assert_eq!(c.get_usage("other".into(), 0, 2).query_count, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "day range too long")]
+    fn usage_range_is_bounded() {
+        let c = contract_with_budget(None, None);
+        c.get_usage("dw".into(), 0, MAX_USAGE_DAYS);
+    }
+
+    #[test]
+    fn access_grants_expire_and_revoke() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.grant_access("alice.testnet".parse().unwrap(), "dw".into(), Some(1_000));
+        c.grant_access("bob.testnet".parse().unwrap(), "dw".into(), None);
+        assert!(c.has_access("alice.testnet".parse().unwrap(), "dw".into()));
+        assert!(!c.has_access("alice.testnet".parse().unwrap(), "other".into()));
+
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id("owner.testnet".parse().unwrap()).block_timestamp(1_000);
+        testing_env!(builder.build());
+        assert!(!c.has_access("alice.testnet".parse().unwrap(), "dw".into()));
+        assert!(c.has_access("bob.testnet".parse().unwrap(), "dw".into()));
+
+        c.revoke_access("bob.testnet".parse().unwrap(), "dw".into());
+        assert!(!c.has_access("bob.testnet".parse().unwrap(), "dw".into()));
+    }
+
+    #[test]
+    #[should_panic(expected = "owner only")]
+    fn grant_access_is_owner_only() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("alice.testnet");
+        c.grant_access("alice.testnet".parse().unwrap(), "dw".into(), None);
+    }
+
+    #[test]
+    fn dataset_updates_are_versioned() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_dataset_meta("dw".into(), "h2".into(), "u2".into());
+        c.set_dataset_meta("dw".into(), "h3".into(), "u3".into());
+        assert_eq!(c.get_dataset_meta("dw".into()).unwrap().version, 3);
+
+        let hashes: Vec<String> =
+            c.get_dataset_history("dw".into(), None, None).into_iter().map(|d| d.dataset_hash).collect();
+        assert_eq!(hashes, vec!["h", "h2", "h3"]);
+        let page = c.get_dataset_history("dw".into(), Some(2), Some(1));
+        assert_eq!((page.len(), page[0].version), (1, 2));
+
+        log_at(&mut c, "s1", 1_000);
+        assert_eq!(c.get_interaction("s1".into()).unwrap().log.dataset_version, 3);
+    }
+
+    #[test]
+    fn readded_community_continues_dataset_versions() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.remove_community("dw".into());
+        c.add_community("dw".into(), "h2".into(), "u2".into());
+        assert_eq!(c.get_dataset_meta("dw".into()).unwrap().version, 2);
+        assert_eq!(c.get_dataset_history("dw".into(), None, None).len(), 2);
+    }
+
+    /// NEAR transferred back by the last call.
+    fn refunded() -> u128 {
+        near_sdk::test_utils::get_created_receipts()
+            .into_iter()
+            .flat_map(|r| r.actions)
+            .filter_map(|a| match a {
+                near_sdk::mock::MockAction::Transfer { deposit, .. } => Some(deposit.as_yoctonear()),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Storage cost of a steady-state log (after the day's usage and budget entries exist).
+    fn log_storage_cost(c: &mut Contract) -> u128 {
+        log_at(c, "s0", 1_000);
+        let deposit = 10_000_000_000_000_000_000_000;
+        set_actor_with_deposit("agent.testnet", deposit);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s1", 1, "dw"), None);
+        deposit - refunded()
+    }
+
+    #[test]
+    fn exact_storage_deposit_not_refunded() {
+        let mut c = contract_with_budget(None, None);
+        let required = log_storage_cost(&mut c);
+        assert!(required > 0);
+
+        set_actor_with_deposit("agent.testnet", required);
+        c.log_interaction("s2".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s2", 1, "dw"), None);
+        assert_eq!(refunded(), 0);
+    }
+
+    #[test]
+    fn excess_storage_deposit_refunded() {
+        let mut c = contract_with_budget(None, None);
+        let required = log_storage_cost(&mut c);
+
+        set_actor_with_deposit("agent.testnet", required + 12_345);
+        c.log_interaction("s2".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s2", 1, "dw"), None);
+        assert_eq!(refunded(), 12_345);
+    }
+
+    #[test]
+    fn deposit_refunded_in_full_when_nothing_stored() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_duplicate_policy(DuplicatePolicy::Ignore);
+        log_at(&mut c, "s1", 1_000);
+
+        set_actor_with_deposit("agent.testnet", 5_000);
+        let outcome = c.log_interaction(
+            "s1".into(),
+            "q".into(),
+            "a".into(),
+            1,
+            "dw".into(),
+            sign_log("s1", 1, "dw"),
+            None,
+        );
+        assert!(!outcome.inserted);
+        assert_eq!(refunded(), 5_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient deposit for storage")]
+    fn insufficient_storage_deposit_rejected() {
+        let mut c = contract_with_budget(None, None);
+        let required = log_storage_cost(&mut c);
+
+        set_actor_with_deposit("agent.testnet", required - 1);
+        c.log_interaction("s2".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s2", 1, "dw"), None);
+    }
+
+    fn contract_with_flagged_log() -> Contract {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.add_curator("dw".into(), "mod.testnet".parse().unwrap());
+        log_cost_at(&mut c, "s1", 40, 0);
+        log_cost_at(&mut c, "s2", 60, 0);
+        set_predecessor("mod.testnet");
+        c.flag_interaction("s1".into(), "reason".into());
+        c
+    }
+
+    #[test]
+    fn flagged_cost_is_reported_as_disputed() {
+        let mut c = contract_with_flagged_log();
+        let view = c.get_interaction("s1".into()).unwrap();
+        assert_eq!(view.flag.unwrap().status, FlagStatus::Open);
+        assert_eq!(c.get_usage("dw".into(), 0, 0).disputed_cost_microusd, 40);
+
+        set_predecessor("agent.testnet");
+        c.resolve_flag("s1".into(), true);
+        assert_eq!(c.get_interaction_flag("s1".into()).unwrap().status, FlagStatus::Upheld);
+        let report = c.get_usage("dw".into(), 0, 0);
+        assert_eq!((report.total_cost_microusd, report.disputed_cost_microusd), (100, 40));
+    }
+
+    #[test]
+    fn dismissed_flag_makes_cost_billable_again() {
+        let mut c = contract_with_flagged_log();
+        set_predecessor("owner.testnet");
+        c.resolve_flag("s1".into(), false);
+        assert_eq!(c.get_usage("dw".into(), 0, 0).disputed_cost_microusd, 0);
+        assert!(c.get_logs(None, None)[0].flag.is_some());
+    }
+
+    #[test]
+    fn dismissed_flag_counts_against_the_curator() {
+        let mut c = contract_with_flagged_log();
+        set_predecessor("owner.testnet");
+        c.set_reputation_contract(Some("reputation.testnet".parse().unwrap()));
+        c.resolve_flag("s1".into(), false);
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs.iter().any(|l| l.contains("\"event\":\"reputation_reported\"")
+            && l.contains("\"account_id\":\"mod.testnet\"")
+            && l.contains("\"signal\":\"dispute\"")));
+    }
+
+    #[test]
+    #[should_panic(expected = "curators only")]
+    fn non_curator_cannot_flag() {
+        let mut c = contract_with_flagged_log();
+        set_predecessor("alice.testnet");
+        c.flag_interaction("s2".into(), "reason".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "flag already resolved")]
+    fn flag_resolved_once() {
+        let mut c = contract_with_flagged_log();
+        set_predecessor("owner.testnet");
+        c.resolve_flag("s1".into(), true);
+        c.resolve_flag("s1".into(), false);
+    }
+
+    #[test]
+    fn ownership_transfer_takes_two_steps() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.propose_owner("dao.testnet".parse().unwrap());
+        assert_eq!(c.get_owner().as_str(), "owner.testnet");
+
+        set_predecessor("dao.testnet");
+        c.accept_owner();
+        assert_eq!(c.get_owner().as_str(), "dao.testnet");
+        assert!(c.get_pending_owner().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "pending owner only")]
+    fn only_pending_owner_accepts() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.propose_owner("dao.testnet".parse().unwrap());
+        set_predecessor("alice.testnet");
+        c.accept_owner();
+    }
+
+    fn set_time(predecessor: &str, timestamp_ns: u64) {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(predecessor.parse().unwrap()).block_timestamp(timestamp_ns);
+        testing_env!(builder.build());
+    }
+
+    #[test]
+    fn timelocked_changes_apply_after_delay() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_change_timelock(NS_PER_DAY);
+
+        let agent_change = c.set_agent_account("agent2.testnet".parse().unwrap()).unwrap();
+        let dataset_change = c.set_dataset_meta("dw".into(), "h2".into(), "u2".into()).unwrap();
+        assert_eq!(c.get_queued_changes().len(), 2);
+        assert_eq!(c.get_agents(), vec!["agent.testnet".parse::<AccountId>().unwrap()]);
+        assert_eq!(c.get_dataset_meta("dw".into()).unwrap().dataset_hash, "h");
+
+        set_time("anyone.testnet", NS_PER_DAY);
+        c.execute_change(agent_change);
+        c.execute_change(dataset_change);
+        assert_eq!(c.get_agents(), vec!["agent2.testnet".parse::<AccountId>().unwrap()]);
+        assert_eq!(c.get_dataset_meta("dw".into()).unwrap().dataset_hash, "h2");
+        assert!(c.get_queued_changes().is_empty());
+    }
+
+    #[test]
+    fn new_agents_and_enclave_keys_are_timelocked() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_change_timelock(NS_PER_DAY);
+
+        let agent_change = c.add_agent("agent2.testnet".parse().unwrap()).unwrap();
+        assert!(!c.is_agent("agent2.testnet".parse().unwrap()));
+
+        // Renewing the current key is not a new identity
+        let agent: AccountId = "agent.testnet".parse().unwrap();
+        let renewal = c.register_agent_attestation(agent, "q2".into(), "mrtd".into(), u64::MAX, enclave_public_key());
+        assert!(renewal.is_none());
+        assert_eq!(c.get_agent_attestation("agent.testnet".parse().unwrap()).unwrap().attestation_hash, "q2");
+
+        set_time("anyone.testnet", NS_PER_DAY);
+        c.execute_change(agent_change);
+        assert!(c.is_agent("agent2.testnet".parse().unwrap()));
+
+        set_time("owner.testnet", NS_PER_DAY);
+        let agent2: AccountId = "agent2.testnet".parse().unwrap();
+        let key_change = c
+            .register_agent_attestation(agent2, "q".into(), "mrtd".into(), u64::MAX, enclave_public_key())
+            .unwrap();
+        assert!(c.get_agent_attestation("agent2.testnet".parse().unwrap()).is_none());
+        set_time("anyone.testnet", 2 * NS_PER_DAY);
+        c.execute_change(key_change);
+        assert!(c.get_agent_attestation("agent2.testnet".parse().unwrap()).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "timelock not expired")]
+    fn queued_change_waits_for_timelock() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_change_timelock(NS_PER_DAY);
+        let change = c.set_agent_account("agent2.testnet".parse().unwrap()).unwrap();
+        set_time("owner.testnet", NS_PER_DAY - 1);
+        c.execute_change(change);
+    }
+
+    #[test]
+    fn lowering_timelock_is_queued() {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_change_timelock(NS_PER_DAY);
+        let change = c.set_change_timelock(0).unwrap();
+        assert_eq!(c.get_change_timelock(), NS_PER_DAY);
+
+        c.cancel_change(change);
+        assert!(c.get_queued_changes().is_empty());
+        assert_eq!(c.get_change_timelock(), NS_PER_DAY);
+    }
+
+    fn billed_input(session_id: &str, cost_microusd: u64, account: &str) -> InteractionInput {
+        use ed25519_dalek::Signer;
+        let account: AccountId = account.parse().unwrap();
+        let digest = log_digest(session_id, "q", "a", cost_microusd, "dw", Some(&account));
+        InteractionInput {
+            signature: Base64VecU8(enclave_key().sign(&digest).to_bytes().to_vec()),
+            billed_account_id: Some(account),
+            ..input(session_id, cost_microusd)
+        }
+    }
+
+    fn contract_with_credits() -> Contract {
+        let mut c = contract_with_budget(None, None);
+        set_predecessor("owner.testnet");
+        c.set_credits_contract(Some("credits.testnet".parse().unwrap()));
+        c
+    }
+
+    #[test]
+    fn billed_log_schedules_credit_deduction() {
+        let mut c = contract_with_credits();
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interactions(vec![billed_input("s1", 250, "user.testnet")]);
+
+        let view = c.get_interaction("s1".into()).unwrap();
+        assert_eq!(view.log.billed_account_id, Some("user.testnet".parse().unwrap()));
+        assert_eq!(view.payment, Some(PaymentStatus::Pending));
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert!(receipts.iter().any(|r| r.receiver_id.as_str() == "credits.testnet"));
+    }
+
+    #[test]
+    fn unbilled_log_has_no_payment() {
+        let mut c = contract_with_credits();
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interaction("s1".into(), "q".into(), "a".into(), 1, "dw".into(), sign_log("s1", 1, "dw"), None);
+        assert!(c.get_interaction("s1".into()).unwrap().payment.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid log signature")]
+    fn billed_account_is_covered_by_signature() {
+        let mut c = contract_with_credits();
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        let mut forged = billed_input("s1", 250, "user.testnet");
+        forged.billed_account_id = Some("victim.testnet".parse().unwrap());
+        c.log_interactions(vec![forged]);
+    }
+
+    #[test]
+    #[should_panic(expected = "credits contract not set")]
+    fn billed_log_requires_credits_contract() {
+        let mut c = contract_with_budget(None, None);
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interactions(vec![billed_input("s1", 250, "user.testnet")]);
+    }
+
+    #[test]
+    fn failed_deduction_marks_log_unpaid() {
+        let mut c = contract_with_credits();
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        c.log_interactions(vec![billed_input("s1", 250, "user.testnet"), billed_input("s2", 250, "user.testnet")]);
+
+        assert!(c.on_credits_spent("s1".into(), Ok(U128(250))));
+        assert!(!c.on_credits_spent("s2".into(), Err(PromiseError::Failed)));
+        assert_eq!(c.get_payment_status("s1".into()), Some(PaymentStatus::Paid));
+        assert_eq!(c.get_payment_status("s2".into()), Some(PaymentStatus::Unpaid));
+        assert!(c.get_interaction("s2".into()).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "too many billed interactions in batch")]
+    fn batch_caps_billed_interactions() {
+        let mut c = contract_with_credits();
+        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000);
+        let batch = (0..=MAX_BILLED_PER_BATCH).map(|i| billed_input(&format!("s{}", i), 1, "user.testnet")).collect();
+        c.log_interactions(batch);
+    }
+
+    fn owner_contract() -> Contract {
+        set_predecessor("owner.testnet");
+        Contract::new(
             "owner.testnet".parse().unwrap(),
             "agent.testnet".parse().unwrap(),
             "h".into(),
             "u".into(),
             "dw".into(),
-        );
-        set_actor_with_deposit("agent.testnet", 10_000_000_000_000_000_000_000); // 0.01 NEAR
-        c.log_interaction("s2".into(), "q".into(), "a".into(), 0, Some("other".into()));
+        )
+    }
+
+    #[test]
+    fn delegated_roles_cover_their_methods() {
+        let mut c = owner_contract();
+        c.grant_role(Role::Guardian, "guardian.testnet".parse().unwrap());
+        c.grant_role(Role::Keeper, "keeper.testnet".parse().unwrap());
+        assert_eq!(c.get_roles("guardian.testnet".parse().unwrap()), vec![Role::Guardian]);
+
+        set_predecessor("guardian.testnet");
+        c.set_community_enabled("dw".into(), false);
+        assert!(!c.get_community("dw".into()).unwrap().enabled);
+
+        set_predecessor("keeper.testnet");
+        c.set_near_price(3_000_000);
+
+        set_predecessor("owner.testnet");
+        c.revoke_role(Role::Guardian, "guardian.testnet".parse().unwrap());
+        assert!(!c.has_role(Role::Guardian, "guardian.testnet".parse().unwrap()));
+    }
+
+    #[test]
+    #[should_panic(expected = "only the owner manages admins")]
+    fn admin_cannot_grant_admin() {
+        let mut c = owner_contract();
+        c.grant_role(Role::Admin, "admin.testnet".parse().unwrap());
+        set_predecessor("admin.testnet");
+        c.add_community("dw-2".into(), "h2".into(), "u2".into());
+        c.grant_role(Role::Admin, "other.testnet".parse().unwrap());
+    }
+
+    /// Borsh bytes of the released single-community contract's state, with one log that named no community.
+    fn released_state() -> Vec<u8> {
+        let mut logs: UnorderedMap<String, LogEntry> = UnorderedMap::new(b"l".to_vec());
+        let entry = LogEntry {
+            session_id: "s1".into(),
+            query_hash: "q".into(),
+            answer_hash: "a".into(),
+            cost_microusd: 1234,
+            community_id: None,
+            created_at_ns: 5,
+        };
+        logs.insert(&"s1".to_string(), &entry);
+        let owner: AccountId = "owner.testnet".parse().unwrap();
+        let agent: AccountId = "agent.testnet".parse().unwrap();
+        let dataset = ("h".to_string(), "u".to_string(), 7u64);
+        borsh::to_vec(&(owner, agent, dataset, "dw".to_string(), logs)).unwrap()
+    }
+
+    #[test]
+    fn migrate_from_released_layout() {
+        set_predecessor("owner.testnet");
+        env::storage_write(b"STATE", &released_state());
+
+        let c = Contract::migrate();
+        assert_eq!(c.get_state_version(), STATE_VERSION);
+        assert!(c.is_agent("agent.testnet".parse().unwrap()));
+        let dataset = c.get_dataset_meta("dw".into()).unwrap();
+        assert_eq!((dataset.dataset_hash.as_str(), dataset.version, dataset.updated_at_ns), ("h", 1, 7));
+        let log = c.get_interaction("s1".into()).unwrap().log;
+        assert_eq!(log.community_id, "dw");
+        assert_eq!(log.cost_microusd, 1234);
+        assert_eq!(c.get_log_count(), 1);
+    }
+
+    #[test]
+    fn migrate_from_upgrade_batch() {
+        let c = owner_contract();
+        env::state_write(&c);
+        let mut builder = VMContextBuilder::new();
+        builder.current_account_id("agent-contract.testnet".parse().unwrap());
+        builder.predecessor_account_id("agent-contract.testnet".parse().unwrap());
+        testing_env!(builder.build());
+        let migrated = Contract::migrate();
+        assert_eq!(migrated.owner_id, "owner.testnet".parse::<AccountId>().unwrap());
+        assert_eq!(migrated.get_state_version(), STATE_VERSION);
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported state version 7")]
+    fn migrate_rejects_unknown_state_version() {
+        let c = owner_contract();
+        env::state_write(&c);
+        env::storage_write(groupweave_utils::upgrade::STATE_VERSION_KEY, &7u32.to_le_bytes());
+        let _ = Contract::migrate();
+    }
+
+    #[test]
+    #[should_panic(expected = "code hash not approved")]
+    fn upgrade_waits_for_queued_code_hash() {
+        let mut c = owner_contract();
+        c.set_change_timelock(NS_PER_DAY);
+        let code = b"\0asm v2".to_vec();
+        let change = c.propose_upgrade(env::sha256_array(&code).into()).unwrap();
+        set_time("anyone.testnet", NS_PER_DAY);
+        c.execute_change(change);
+        assert_eq!(c.approved_code_hash, Some(env::sha256_array(&code)));
+
+        // Only the approved code, and only once
+        set_time("owner.testnet", NS_PER_DAY);
+        let _ = c.upgrade(code.clone());
+        let _ = c.upgrade(code);
+    }
+
+    #[test]
+    #[should_panic(expected = "code hash not approved")]
+    fn upgrade_rejects_unapproved_code_under_timelock() {
+        let mut c = owner_contract();
+        c.set_change_timelock(NS_PER_DAY);
+        c.propose_upgrade(env::sha256_array(b"\0asm v2").into());
+        let _ = c.upgrade(b"\0asm v2".to_vec());
+    }
+
+    #[test]
+    #[should_panic(expected = "owner only")]
+    fn upgrade_requires_owner_not_admin() {
+        let mut c = owner_contract();
+        c.grant_role(Role::Admin, "admin.testnet".parse().unwrap());
+        set_predecessor("admin.testnet");
+        let _ = c.upgrade(b"\0asm".to_vec());
     }
 }
@@ -45,28 +45,17 @@ impl StakingContract {
         }
     }
 
-    // Helper function for safe token addition
+    // Checked token arithmetic, shared with the other contracts via groupweave-utils
     fn safe_add_tokens(a: NearToken, b: NearToken) -> Result<NearToken, &'static str> {
-        a.as_yoctonear().checked_add(b.as_yoctonear())
-            .map(NearToken::from_yoctonear)
-            .ok_or("Token addition overflow")
+        groupweave_utils::add_tokens(a, b)
     }
 
-    // Helper function for safe token subtraction
     fn safe_sub_tokens(a: NearToken, b: NearToken) -> Result<NearToken, &'static str> {
-        a.as_yoctonear().checked_sub(b.as_yoctonear())
-            .map(NearToken::from_yoctonear)
-            .ok_or("Token subtraction underflow")
+        groupweave_utils::sub_tokens(a, b)
     }
 
-    // Helper function for safe reward calculation
     fn calculate_rewards_safe(stake_amount: NearToken, reward_rate: u128, time_seconds: u64) -> u128 {
-        // Use checked arithmetic to prevent overflow
-        // Divide by the scaling factor last to maintain precision
-        stake_amount.as_yoctonear()
-            .checked_mul(reward_rate)
-            .and_then(|x| x.checked_mul(time_seconds as u128))
-            .and_then(|x| x.checked_div(1_000_000_000_000_000_000_000_000))
+        groupweave_utils::accrued_rewards(stake_amount, reward_rate, time_seconds)
             .expect("Reward calculation overflow - reward rate or time period too large")
     }
 
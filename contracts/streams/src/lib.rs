@@ -0,0 +1,406 @@
+//! Payment streams – continuous NEAR payouts for creator grants and team compensation.
+//!
+//! A sender locks the full amount of a stream up front. It then vests linearly from creation
+//! until `duration_secs` later, with nothing withdrawable before the cliff:
+//! - The recipient withdraws whatever has vested and not been withdrawn yet, as often as they like.
+//! - Either side can cancel a stream that has not fully vested. The recipient is paid what had
+//!   vested at that moment and the sender gets the unvested rest back, so a grant program can
+//!   stop a stream without clawing back earned funds.
+//!
+//! The sender pays the stream's storage along with the amount; records are kept after a stream
+//! completes so both sides can look up their history.
+
+use groupweave_events::stream::{StreamCancelled, StreamCreated, StreamWithdrawn};
+use groupweave_events::Nep297Event;
+use groupweave_utils::mul_div;
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near, require, AccountId, BorshStorageKey, NearToken, PanicOnDefault, Promise};
+
+/// Smallest amount worth streaming.
+pub const MIN_STREAM_AMOUNT: NearToken = NearToken::from_millinear(100);
+/// Longest stream: ten years.
+pub const MAX_DURATION_SECS: u64 = 10 * 365 * 24 * 60 * 60;
+/// Maximum number of streams returned by one page of the list views.
+pub const MAX_STREAM_PAGE: u64 = 100;
+
+const NS_PER_SEC: u64 = 1_000_000_000;
+
+#[derive(BorshStorageKey, BorshSerialize)]
+enum StorageKey {
+    Streams,
+    StreamsByAccount,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum StreamStatus {
+    Active,
+    /// Fully vested and fully withdrawn.
+    Completed,
+    /// Stopped early; the vested part went to the recipient and the rest back to the sender.
+    Cancelled,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Stream {
+    pub sender: AccountId,
+    pub recipient: AccountId,
+    pub total: NearToken,
+    pub withdrawn: NearToken,
+    pub status: StreamStatus,
+    pub starts_at_ns: u64,
+    pub cliff_ends_at_ns: u64,
+    pub ends_at_ns: u64,
+    pub cancelled_at_ns: Option<u64>,
+}
+
+impl Stream {
+    /// Amount vested at `now`: nothing before the cliff, then linear from the start to the end.
+    /// Vesting stops when the stream is cancelled.
+    fn vested_at(&self, now: u64) -> u128 {
+        let at = self.cancelled_at_ns.map_or(now, |cancelled_at| cancelled_at.min(now));
+        let total = self.total.as_yoctonear();
+        if at < self.cliff_ends_at_ns {
+            return 0;
+        }
+        if at >= self.ends_at_ns {
+            return total;
+        }
+        let elapsed = u128::from(at - self.starts_at_ns);
+        let duration = u128::from(self.ends_at_ns - self.starts_at_ns);
+        mul_div(total, elapsed, duration).unwrap_or_else(|| env::panic_str("Vesting overflow"))
+    }
+}
+
+/// Balances of a stream at the current block.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamBalance {
+    pub vested: U128,
+    pub withdrawn: U128,
+    /// Vested and not withdrawn yet.
+    pub withdrawable: U128,
+    /// Still locked; returned to the sender if the stream is cancelled now.
+    pub unvested: U128,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreamView {
+    pub id: u64,
+    #[serde(flatten)]
+    pub stream: Stream,
+    pub balance: StreamBalance,
+}
+
+#[near(contract_state, contract_metadata(standard(standard = "nep297", version = "1.0.0")))]
+#[derive(PanicOnDefault)]
+pub struct PaymentStreams {
+    owner_id: AccountId,
+    streams: LookupMap<u64, Stream>,
+    /// Ids of the streams each account sends or receives, oldest first.
+    streams_by_account: LookupMap<AccountId, Vec<u64>>,
+    next_stream_id: u64,
+}
+
+#[near]
+impl PaymentStreams {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self {
+            owner_id,
+            streams: LookupMap::new(StorageKey::Streams),
+            streams_by_account: LookupMap::new(StorageKey::StreamsByAccount),
+            next_stream_id: 1,
+        }
+    }
+
+    // Streams
+    /// Stream `total` to `recipient` over `duration_secs`, nothing withdrawable during the first
+    /// `cliff_secs`. Attach the total plus storage; the excess is refunded.
+    #[payable]
+    pub fn create_stream(&mut self, recipient: AccountId, total: U128, duration_secs: u64, cliff_secs: u64) -> u64 {
+        let sender = env::predecessor_account_id();
+        let total = NearToken::from_yoctonear(total.0);
+        require!(sender != recipient, "Cannot stream to yourself");
+        require!(total >= MIN_STREAM_AMOUNT, "Stream total must be at least 0.1 NEAR");
+        require!((1..=MAX_DURATION_SECS).contains(&duration_secs), "Duration must be between 1 second and 10 years");
+        require!(cliff_secs <= duration_secs, "Cliff cannot be longer than the duration");
+
+        let initial_storage = env::storage_usage();
+        let stream_id = self.next_stream_id;
+        let now = env::block_timestamp();
+        let stream = Stream {
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            total,
+            withdrawn: NearToken::from_yoctonear(0),
+            status: StreamStatus::Active,
+            starts_at_ns: now,
+            cliff_ends_at_ns: now + cliff_secs * NS_PER_SEC,
+            ends_at_ns: now + duration_secs * NS_PER_SEC,
+            cancelled_at_ns: None,
+        };
+        self.streams.insert(&stream_id, &stream);
+        self.index_stream(&sender, stream_id);
+        self.index_stream(&recipient, stream_id);
+        self.next_stream_id += 1;
+
+        let storage_cost = env::storage_byte_cost().saturating_mul((env::storage_usage() - initial_storage).into());
+        let required = total.saturating_add(storage_cost);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= required,
+            format!("Attach at least {} yoctoNEAR (stream total plus storage)", required.as_yoctonear())
+        );
+        let refund = deposit.saturating_sub(required);
+        if refund > NearToken::from_yoctonear(0) {
+            Promise::new(sender.clone()).transfer(refund);
+        }
+
+        StreamCreated {
+            stream_id,
+            sender,
+            recipient,
+            total: U128(total.as_yoctonear()),
+            cliff_ends_at_ns: U64(stream.cliff_ends_at_ns),
+            ends_at_ns: U64(stream.ends_at_ns),
+        }
+        .emit();
+        stream_id
+    }
+
+    /// Withdraw everything vested so far (recipient only). Returns the amount paid out.
+    pub fn withdraw(&mut self, stream_id: u64) -> U128 {
+        let mut stream = self.expect_stream(stream_id);
+        require!(env::predecessor_account_id() == stream.recipient, "Only the recipient can withdraw");
+        require!(stream.status == StreamStatus::Active, "Stream is not active");
+
+        let vested = stream.vested_at(env::block_timestamp());
+        let amount = vested - stream.withdrawn.as_yoctonear();
+        require!(amount > 0, "Nothing to withdraw yet");
+        stream.withdrawn = NearToken::from_yoctonear(vested);
+        if stream.withdrawn == stream.total {
+            stream.status = StreamStatus::Completed;
+        }
+        self.streams.insert(&stream_id, &stream);
+
+        Promise::new(stream.recipient.clone()).transfer(NearToken::from_yoctonear(amount));
+        StreamWithdrawn { stream_id, recipient: stream.recipient, amount: U128(amount) }.emit();
+        U128(amount)
+    }
+
+    /// Stop a stream that has not fully vested (sender or recipient). The recipient is paid the
+    /// vested, unwithdrawn part and the sender is refunded the unvested rest.
+    pub fn cancel_stream(&mut self, stream_id: u64) {
+        let mut stream = self.expect_stream(stream_id);
+        let caller = env::predecessor_account_id();
+        require!(caller == stream.sender || caller == stream.recipient, "Only the sender or recipient can cancel");
+        require!(stream.status == StreamStatus::Active, "Stream is not active");
+
+        let now = env::block_timestamp();
+        let vested = stream.vested_at(now);
+        let total = stream.total.as_yoctonear();
+        require!(vested < total, "Stream has fully vested; withdraw instead");
+        let recipient_amount = vested - stream.withdrawn.as_yoctonear();
+        let sender_amount = total - vested;
+
+        stream.withdrawn = NearToken::from_yoctonear(vested);
+        stream.cancelled_at_ns = Some(now);
+        stream.status = StreamStatus::Cancelled;
+        self.streams.insert(&stream_id, &stream);
+
+        if recipient_amount > 0 {
+            Promise::new(stream.recipient.clone()).transfer(NearToken::from_yoctonear(recipient_amount));
+        }
+        Promise::new(stream.sender.clone()).transfer(NearToken::from_yoctonear(sender_amount));
+        StreamCancelled {
+            stream_id,
+            cancelled_by: caller,
+            recipient_amount: U128(recipient_amount),
+            sender_amount: U128(sender_amount),
+        }
+        .emit();
+    }
+
+    // Views
+    pub fn get_stream(&self, stream_id: u64) -> Option<StreamView> {
+        self.streams.get(&stream_id).map(|stream| self.view_stream(stream_id, stream))
+    }
+
+    pub fn get_stream_balance(&self, stream_id: u64) -> Option<StreamBalance> {
+        self.streams.get(&stream_id).map(|stream| Self::balance_of(&stream))
+    }
+
+    /// Streams by id, starting at `from_id` (default 1).
+    pub fn get_streams(&self, from_id: Option<u64>, limit: Option<u64>) -> Vec<StreamView> {
+        let from_id = from_id.unwrap_or(1).max(1);
+        (from_id..self.next_stream_id)
+            .take(Self::page_limit(limit))
+            .filter_map(|id| self.get_stream(id))
+            .collect()
+    }
+
+    /// Streams the account sends or receives, oldest first.
+    pub fn get_streams_for_account(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<StreamView> {
+        let Some(ids) = self.streams_by_account.get(&account_id) else {
+            return vec![];
+        };
+        ids.into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(Self::page_limit(limit))
+            .filter_map(|id| self.get_stream(id))
+            .collect()
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+}
+
+impl PaymentStreams {
+    fn expect_stream(&self, stream_id: u64) -> Stream {
+        self.streams.get(&stream_id).unwrap_or_else(|| env::panic_str("Stream not found"))
+    }
+
+    fn index_stream(&mut self, account_id: &AccountId, stream_id: u64) {
+        let mut ids = self.streams_by_account.get(account_id).unwrap_or_default();
+        ids.push(stream_id);
+        self.streams_by_account.insert(account_id, &ids);
+    }
+
+    fn page_limit(limit: Option<u64>) -> usize {
+        limit.unwrap_or(MAX_STREAM_PAGE).min(MAX_STREAM_PAGE) as usize
+    }
+
+    fn balance_of(stream: &Stream) -> StreamBalance {
+        let vested = stream.vested_at(env::block_timestamp());
+        let withdrawn = stream.withdrawn.as_yoctonear();
+        StreamBalance {
+            vested: U128(vested),
+            withdrawn: U128(withdrawn),
+            withdrawable: U128(vested.saturating_sub(withdrawn)),
+            unvested: U128(stream.total.as_yoctonear() - vested),
+        }
+    }
+
+    fn view_stream(&self, id: u64, stream: Stream) -> StreamView {
+        let balance = Self::balance_of(&stream);
+        StreamView { id, stream, balance }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const SEC: u64 = NS_PER_SEC;
+
+    // accounts(1) sends, accounts(2) receives
+    fn set_context(predecessor: AccountId, deposit: NearToken, timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(5))
+            .predecessor_account_id(predecessor)
+            .attached_deposit(deposit)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    /// A 100 NEAR stream over 100 seconds with a 25 second cliff.
+    fn streaming() -> (PaymentStreams, u64) {
+        set_context(accounts(0), NearToken::from_near(0), 0);
+        let mut contract = PaymentStreams::new(accounts(0));
+        set_context(accounts(1), NearToken::from_near(101), 0);
+        let total = U128(NearToken::from_near(100).as_yoctonear());
+        let id = contract.create_stream(accounts(2), total, 100, 25);
+        (contract, id)
+    }
+
+    fn near(amount: u128) -> U128 {
+        U128(NearToken::from_near(amount).as_yoctonear())
+    }
+
+    #[test]
+    fn vests_linearly_after_the_cliff() {
+        let (contract, id) = streaming();
+        set_context(accounts(3), NearToken::from_near(0), 24 * SEC);
+        assert_eq!(contract.get_stream_balance(id).unwrap().vested, U128(0));
+
+        set_context(accounts(3), NearToken::from_near(0), 25 * SEC);
+        let balance = contract.get_stream_balance(id).unwrap();
+        assert_eq!((balance.vested, balance.unvested), (near(25), near(75)));
+
+        set_context(accounts(3), NearToken::from_near(0), 200 * SEC);
+        assert_eq!(contract.get_stream_balance(id).unwrap().withdrawable, near(100));
+        assert_eq!(contract.get_streams_for_account(accounts(2), None, None).len(), 1);
+        assert_eq!(contract.get_streams_for_account(accounts(1), None, None)[0].id, id);
+    }
+
+    #[test]
+    fn recipient_withdraws_what_has_vested() {
+        let (mut contract, id) = streaming();
+        set_context(accounts(2), NearToken::from_near(0), 40 * SEC);
+        assert_eq!(contract.withdraw(id), near(40));
+        assert!(get_logs().last().unwrap().contains("\"event\":\"stream_withdrawn\""));
+
+        set_context(accounts(2), NearToken::from_near(0), 100 * SEC);
+        assert_eq!(contract.withdraw(id), near(60));
+        let stream = contract.get_stream(id).unwrap();
+        assert_eq!(stream.stream.status, StreamStatus::Completed);
+        assert_eq!(stream.balance.withdrawable, U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to withdraw yet")]
+    fn nothing_is_withdrawable_before_the_cliff() {
+        let (mut contract, id) = streaming();
+        set_context(accounts(2), NearToken::from_near(0), 10 * SEC);
+        contract.withdraw(id);
+    }
+
+    #[test]
+    fn cancel_splits_pro_rata() {
+        let (mut contract, id) = streaming();
+        set_context(accounts(2), NearToken::from_near(0), 30 * SEC);
+        contract.withdraw(id);
+
+        set_context(accounts(1), NearToken::from_near(0), 60 * SEC);
+        contract.cancel_stream(id);
+        let log = get_logs().pop().unwrap();
+        assert!(log.contains(&format!("\"recipient_amount\":\"{}\"", near(30).0)));
+        assert!(log.contains(&format!("\"sender_amount\":\"{}\"", near(40).0)));
+
+        // Vesting stops at the cancellation
+        set_context(accounts(2), NearToken::from_near(0), 100 * SEC);
+        let stream = contract.get_stream(id).unwrap();
+        assert_eq!(stream.stream.status, StreamStatus::Cancelled);
+        assert_eq!((stream.balance.vested, stream.balance.withdrawable), (near(60), U128(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the sender or recipient can cancel")]
+    fn strangers_cannot_cancel() {
+        let (mut contract, id) = streaming();
+        set_context(accounts(3), NearToken::from_near(0), SEC);
+        contract.cancel_stream(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cliff cannot be longer than the duration")]
+    fn cliff_must_fit_in_the_duration() {
+        set_context(accounts(1), NearToken::from_near(2), 0);
+        let mut contract = PaymentStreams::new(accounts(0));
+        contract.create_stream(accounts(2), near(1), 10, 11);
+    }
+}
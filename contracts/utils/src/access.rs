@@ -0,0 +1,160 @@
+//! Role-based access control shared by the contracts.
+//!
+//! Each contract keeps its `owner` as the root of trust and stores an [`AccessControl`] for
+//! everything it delegates. The owner and `Admin` holders pass every role check and manage
+//! grants; the narrower roles let hot keys run one kind of operation without the owner key.
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, IntoStorageKey};
+use std::fmt;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[borsh(crate = "near_sdk::borsh", use_discriminant = true)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+#[repr(u8)]
+pub enum Role {
+    /// Owner-level configuration, and granting or revoking roles.
+    Admin = 0,
+    /// Moves funds: withdrawals and sweeps to the treasury.
+    Treasurer,
+    /// Routine upkeep such as price updates.
+    Keeper,
+    /// Emergency brakes: closing bounties, disabling tokens or communities, blocking accounts.
+    Guardian,
+    /// Settles disputes and flags.
+    Resolver,
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [Role::Admin, Role::Treasurer, Role::Keeper, Role::Guardian, Role::Resolver];
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::Admin => "admin",
+            Role::Treasurer => "treasurer",
+            Role::Keeper => "keeper",
+            Role::Guardian => "guardian",
+            Role::Resolver => "resolver",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Role grants per account, stored as a bitmask under its own storage prefix.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct AccessControl {
+    grants: LookupMap<AccountId, u8>,
+}
+
+impl AccessControl {
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        Self { grants: LookupMap::new(prefix) }
+    }
+
+    /// Whether `account_id` holds `role` itself (owner and `Admin` are not implied here).
+    pub fn has_role(&self, role: Role, account_id: &AccountId) -> bool {
+        self.grants.get(account_id).is_some_and(|mask| mask & role.bit() != 0)
+    }
+
+    pub fn roles_of(&self, account_id: &AccountId) -> Vec<Role> {
+        Role::ALL.into_iter().filter(|role| self.has_role(*role, account_id)).collect()
+    }
+
+    /// Whether `account_id` may act as `role`: the owner, an `Admin`, or a holder of `role`.
+    pub fn is_authorized(&self, owner_id: &AccountId, role: Role, account_id: &AccountId) -> bool {
+        account_id == owner_id || self.has_role(Role::Admin, account_id) || self.has_role(role, account_id)
+    }
+
+    /// Panics unless the predecessor [`is_authorized`](Self::is_authorized) for `role`.
+    pub fn assert_role(&self, owner_id: &AccountId, role: Role) {
+        if !self.is_authorized(owner_id, role, &env::predecessor_account_id()) {
+            env::panic_str(&format!("Requires the owner or the {} role", role));
+        }
+    }
+
+    /// Grant `role`; returns false if the account already held it.
+    pub fn grant(&mut self, role: Role, account_id: &AccountId) -> bool {
+        let mask = self.grants.get(account_id).unwrap_or(0);
+        if mask & role.bit() != 0 {
+            return false;
+        }
+        self.grants.insert(account_id, &(mask | role.bit()));
+        true
+    }
+
+    /// Revoke `role`; returns false if the account did not hold it.
+    pub fn revoke(&mut self, role: Role, account_id: &AccountId) -> bool {
+        let Some(mask) = self.grants.get(account_id) else {
+            return false;
+        };
+        if mask & role.bit() == 0 {
+            return false;
+        }
+        let mask = mask & !role.bit();
+        if mask == 0 {
+            self.grants.remove(account_id);
+        } else {
+            self.grants.insert(account_id, &mask);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn grant_and_revoke_are_per_role() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut access = AccessControl::new(b"r");
+        assert!(access.grant(Role::Keeper, &accounts(1)));
+        assert!(!access.grant(Role::Keeper, &accounts(1)));
+        assert!(access.grant(Role::Guardian, &accounts(1)));
+        assert_eq!(access.roles_of(&accounts(1)), vec![Role::Keeper, Role::Guardian]);
+
+        assert!(access.revoke(Role::Keeper, &accounts(1)));
+        assert!(!access.revoke(Role::Keeper, &accounts(1)));
+        assert!(!access.has_role(Role::Keeper, &accounts(1)));
+        assert!(access.has_role(Role::Guardian, &accounts(1)));
+        assert!(access.revoke(Role::Guardian, &accounts(1)));
+        assert!(access.roles_of(&accounts(1)).is_empty());
+    }
+
+    #[test]
+    fn owner_and_admin_pass_every_check() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut access = AccessControl::new(b"r");
+        let owner = accounts(0);
+        access.grant(Role::Admin, &accounts(1));
+        access.grant(Role::Keeper, &accounts(2));
+
+        for role in Role::ALL {
+            assert!(access.is_authorized(&owner, role, &owner));
+            assert!(access.is_authorized(&owner, role, &accounts(1)));
+        }
+        assert!(access.is_authorized(&owner, Role::Keeper, &accounts(2)));
+        assert!(!access.is_authorized(&owner, Role::Treasurer, &accounts(2)));
+        assert!(!access.is_authorized(&owner, Role::Admin, &accounts(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires the owner or the treasurer role")]
+    fn assert_role_names_the_role() {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(2)).build());
+        let mut access = AccessControl::new(b"r");
+        access.grant(Role::Keeper, &accounts(2));
+        access.assert_role(&accounts(0), Role::Treasurer);
+    }
+}
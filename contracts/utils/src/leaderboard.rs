@@ -0,0 +1,30 @@
+//! Settlement reports pushed to the leaderboard contract.
+//!
+//! Reporter contracts (the bounty market) call its `record_settlement` with one of these when a
+//! market settles; the leaderboard folds them into per-account totals and bounded top-N lists.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SettlementReport {
+    /// Id of the market on the reporting contract, e.g. the bounty id.
+    pub market_id: u64,
+    pub title: String,
+    pub winner: AccountId,
+    /// Paid to the winner.
+    pub winner_earned: U128,
+    /// Everything at stake in the market: prize plus community stakes.
+    pub volume: U128,
+    /// Backers of the winning entry with their stakes.
+    pub backers: Vec<BackerStake>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BackerStake {
+    pub account_id: AccountId,
+    pub amount: U128,
+}
@@ -0,0 +1,29 @@
+//! Shared arithmetic, access control and upgrade helpers for the groupweave contracts.
+//!
+//! - [`access`]: role-based access control (admin, treasurer, keeper, guardian, resolver)
+//!   layered under each contract's owner.
+//! - [`tokens`]: checked `NearToken` addition/subtraction and staking reward accrual.
+//! - [`math`]: `a * b / c` without intermediate overflow, basis-point and percentage shares,
+//!   and proportional splits.
+//! - [`upgrade`]: deploy-and-migrate self-upgrades and the stored state layout version.
+//! - [`reputation`]: the signals contracts report to the reputation contract.
+//! - [`leaderboard`]: the settlement reports contracts push to the leaderboard contract.
+//! - [`pagination`]: the shared page size limit of list views.
+//!
+//! Every arithmetic helper rounds down and reports overflow (`None` / `Err`) instead of wrapping or
+//! saturating, so callers decide whether to panic or fall back.
+
+pub mod access;
+pub mod leaderboard;
+pub mod math;
+pub mod pagination;
+pub mod reputation;
+pub mod tokens;
+pub mod upgrade;
+
+pub use access::{AccessControl, Role};
+pub use leaderboard::{BackerStake, SettlementReport};
+pub use reputation::ReputationSignal;
+pub use pagination::{page_limit, paginate, MAX_PAGE_LIMIT};
+pub use math::{apply_bps, apply_percent, mul_div, pro_rata, split_by_bps, BPS_DENOMINATOR, PERCENT_DENOMINATOR};
+pub use tokens::{accrued_rewards, add_tokens, sub_tokens, REWARD_RATE_SCALE};
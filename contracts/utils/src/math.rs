@@ -0,0 +1,157 @@
+//! Integer share math. Results are floored; products are computed at 256-bit width, so only a
+//! result that does not fit in `u128` is an overflow.
+
+/// Denominator of basis-point rates (10_000 bps = 100%).
+pub const BPS_DENOMINATOR: u128 = 10_000;
+/// Denominator of whole-percent rates.
+pub const PERCENT_DENOMINATOR: u128 = 100;
+
+/// `floor(a * b / denominator)`. `None` if `denominator` is zero or the result exceeds `u128`.
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let (hi, lo) = mul_wide(a, b);
+    if hi == 0 {
+        return Some(lo / denominator);
+    }
+    if hi >= denominator {
+        return None;
+    }
+    // Shift-subtract division of the 256-bit product; the remainder stays below `denominator`.
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
+/// `a * b` as `(high, low)` 128-bit halves.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (cross << 64) | (lo_lo & MASK);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+    (hi, lo)
+}
+
+/// `amount * bps / 10_000`. Never overflows for `bps <= 10_000`.
+pub fn apply_bps(amount: u128, bps: u128) -> Option<u128> {
+    mul_div(amount, bps, BPS_DENOMINATOR)
+}
+
+/// `amount * percent / 100`. Never overflows for `percent <= 100`.
+pub fn apply_percent(amount: u128, percent: u128) -> Option<u128> {
+    mul_div(amount, percent, PERCENT_DENOMINATOR)
+}
+
+/// The share of `amount` owed to `part` out of `whole`, e.g. a backer's stake out of the
+/// winning stakes. Zero when `whole` is zero.
+pub fn pro_rata(amount: u128, part: u128, whole: u128) -> Option<u128> {
+    if whole == 0 {
+        return Some(0);
+    }
+    mul_div(amount, part, whole)
+}
+
+/// Split `amount` by basis-point weights that total 10_000. Every entry but the last is
+/// floored and the last absorbs the rounding dust, so the parts always sum to `amount`.
+pub fn split_by_bps(amount: u128, weights_bps: &[u16]) -> Vec<u128> {
+    let mut remaining = amount;
+    let last = weights_bps.len().saturating_sub(1);
+    weights_bps
+        .iter()
+        .enumerate()
+        .map(|(i, &bps)| {
+            let share = if i == last {
+                remaining
+            } else {
+                apply_bps(amount, u128::from(bps)).expect("bps weight above 100%").min(remaining)
+            };
+            remaining -= share;
+            share
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn mul_div_handles_wide_products() {
+        let near = 10u128.pow(24);
+        // 5 NEAR * 100 NEAR overflows u128 but the quotient fits.
+        assert_eq!(mul_div(5 * near, 100 * near, 50 * near), Some(10 * near));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+    }
+
+    #[test]
+    fn split_by_bps_gives_dust_to_last() {
+        assert_eq!(split_by_bps(10, &[3_333, 3_333, 3_334]), vec![3, 3, 4]);
+        assert_eq!(split_by_bps(7, &[10_000]), vec![7]);
+        assert!(split_by_bps(7, &[]).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn mul_div_matches_narrow_math(a in any::<u64>(), b in any::<u64>(), d in 1..u128::MAX) {
+            let (a, b) = (u128::from(a), u128::from(b));
+            prop_assert_eq!(mul_div(a, b, d), Some(a * b / d));
+        }
+
+        #[test]
+        fn mul_div_is_exact_floor(a in any::<u128>(), b in any::<u128>(), d in 1..u128::MAX) {
+            // floor(a*b/d) = q  <=>  q*d <= a*b < (q+1)*d, checked at 256-bit width.
+            if let Some(q) = mul_div(a, b, d) {
+                let product = mul_wide(a, b);
+                let low = mul_wide(q, d);
+                prop_assert!(low <= product);
+                let (hi, lo) = low;
+                let (lo, carry) = lo.overflowing_add(d);
+                prop_assert!((hi + u128::from(carry), lo) > product);
+            } else {
+                prop_assert!(mul_wide(a, b).0 >= d);
+            }
+        }
+
+        #[test]
+        fn bps_share_never_exceeds_amount(amount in any::<u128>(), bps in 0..=BPS_DENOMINATOR) {
+            let share = apply_bps(amount, bps).unwrap();
+            prop_assert!(share <= amount);
+            prop_assert!(apply_bps(amount, bps.saturating_sub(1)).unwrap() <= share);
+        }
+
+        #[test]
+        fn pro_rata_shares_fit_in_amount(amount in any::<u128>(), parts in prop::collection::vec(any::<u64>(), 1..20)) {
+            let whole: u128 = parts.iter().map(|&p| u128::from(p)).sum();
+            let total: u128 = parts.iter().map(|&p| pro_rata(amount, u128::from(p), whole).unwrap()).sum();
+            prop_assert!(total <= amount);
+            prop_assert!(amount - total < parts.len() as u128 || whole == 0);
+        }
+
+        #[test]
+        fn split_by_bps_sums_to_amount(amount in any::<u128>(), cut in 0..=10_000u16) {
+            let parts = split_by_bps(amount, &[cut, 10_000 - cut]);
+            prop_assert_eq!(parts[0] + parts[1], amount);
+        }
+    }
+}
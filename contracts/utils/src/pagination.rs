@@ -0,0 +1,39 @@
+//! Page bounds for list views, so every view caps its response the same way and stays within the
+//! view gas limit however much state the contract holds.
+
+use near_sdk::require;
+
+/// Most items one page of a list view returns.
+pub const MAX_PAGE_LIMIT: u64 = 100;
+
+/// Items per page for `limit`: [`MAX_PAGE_LIMIT`] when `None`. Panics above the maximum rather
+/// than silently returning fewer items than asked for.
+pub fn page_limit(limit: Option<u64>) -> usize {
+    let limit = limit.unwrap_or(MAX_PAGE_LIMIT);
+    require!(limit <= MAX_PAGE_LIMIT, "limit cannot exceed 100");
+    limit as usize
+}
+
+/// The page of `items` starting at `from_index` (default 0), at most [`page_limit`] long.
+pub fn paginate<T>(items: impl Iterator<Item = T>, from_index: Option<u64>, limit: Option<u64>) -> Vec<T> {
+    let limit = page_limit(limit);
+    items.skip(from_index.unwrap_or(0) as usize).take(limit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_through_items() {
+        assert_eq!(paginate(0..250, None, None).len(), 100);
+        assert_eq!(paginate(0..250, Some(240), Some(20)), (240..250).collect::<Vec<_>>());
+        assert!(paginate(0..10, Some(10), Some(5)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "limit cannot exceed 100")]
+    fn rejects_limit_over_maximum() {
+        page_limit(Some(101));
+    }
+}
@@ -0,0 +1,34 @@
+//! Signals reported to the reputation contract.
+//!
+//! Reporter contracts (the bounty market, the curation agent) call its `record` with one of
+//! these; the reputation contract counts them per account and folds the counts into a score.
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum ReputationSignal {
+    /// Created the winning submission of a bounty.
+    Win,
+    /// Backed the winning submission of a bounty.
+    SuccessfulStake,
+    /// Raised a dispute that was dismissed.
+    Dispute,
+    /// Made an interaction that a curator flagged and the flag was upheld.
+    Flag,
+}
+
+impl fmt::Display for ReputationSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReputationSignal::Win => "win",
+            ReputationSignal::SuccessfulStake => "successful_stake",
+            ReputationSignal::Dispute => "dispute",
+            ReputationSignal::Flag => "flag",
+        };
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,55 @@
+//! Checked `NearToken` arithmetic, with the error strings the contracts have always used.
+
+use near_sdk::NearToken;
+
+/// Staking reward rates are yoctoNEAR per staked NEAR per second, i.e. scaled by 10^24.
+pub const REWARD_RATE_SCALE: u128 = 1_000_000_000_000_000_000_000_000;
+
+pub fn add_tokens(a: NearToken, b: NearToken) -> Result<NearToken, &'static str> {
+    a.checked_add(b).ok_or("Token addition overflow")
+}
+
+pub fn sub_tokens(a: NearToken, b: NearToken) -> Result<NearToken, &'static str> {
+    a.checked_sub(b).ok_or("Token subtraction underflow")
+}
+
+/// Rewards earned by `stake` over `seconds` at `reward_rate` (see [`REWARD_RATE_SCALE`]).
+/// The scale is divided out last to keep precision; `None` if `stake * rate * seconds`
+/// overflows.
+pub fn accrued_rewards(stake: NearToken, reward_rate: u128, seconds: u64) -> Option<u128> {
+    stake
+        .as_yoctonear()
+        .checked_mul(reward_rate)
+        .and_then(|x| x.checked_mul(u128::from(seconds)))
+        .map(|x| x / REWARD_RATE_SCALE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn add_then_sub_roundtrips(a in any::<u128>(), b in any::<u128>()) {
+            let (a, b) = (NearToken::from_yoctonear(a), NearToken::from_yoctonear(b));
+            match add_tokens(a, b) {
+                Ok(sum) => prop_assert_eq!(sub_tokens(sum, b), Ok(a)),
+                Err(e) => prop_assert_eq!(e, "Token addition overflow"),
+            }
+        }
+
+        #[test]
+        fn sub_fails_only_below_zero(a in any::<u128>(), b in any::<u128>()) {
+            let result = sub_tokens(NearToken::from_yoctonear(a), NearToken::from_yoctonear(b));
+            prop_assert_eq!(result.is_err(), b > a);
+        }
+
+        #[test]
+        fn rewards_scale_with_stake(stake in 0..10u128.pow(28), rate in 0..10_000u128, seconds in 0..1_000_000u64) {
+            let single = accrued_rewards(NearToken::from_yoctonear(stake), rate, seconds).unwrap();
+            let double = accrued_rewards(NearToken::from_yoctonear(stake * 2), rate, seconds).unwrap();
+            prop_assert!(double >= single * 2 && double <= single * 2 + 1);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Self-upgrade with an enforced migration.
+//!
+//! [`deploy_and_migrate`] deploys new code to the current account and calls its `migrate` in the
+//! same batch, so the new code never runs against a state it has not migrated. Each contract keeps
+//! its layout version under [`STATE_VERSION_KEY`], outside the `STATE` blob, so `migrate` reads the
+//! version first and picks the matching layout instead of try-parsing old ones.
+
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::collections::LazyOption;
+use near_sdk::{env, Gas, NearToken, Promise};
+
+/// Storage key of the state layout version.
+pub const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+/// Gas for the `migrate` call that follows the deploy.
+pub const GAS_FOR_MIGRATE: Gas = Gas::from_tgas(100);
+
+/// Deploy `code` to this account and call `migrate` with JSON `migrate_args` in the same batch.
+/// If `migrate` panics the whole batch reverts, including the deploy.
+pub fn deploy_and_migrate(code: Vec<u8>, migrate_args: Vec<u8>) -> Promise {
+    require_code(&code);
+    Promise::new(env::current_account_id()).deploy_contract(code).function_call(
+        "migrate".to_string(),
+        migrate_args,
+        NearToken::from_yoctonear(0),
+        GAS_FOR_MIGRATE,
+    )
+}
+
+/// The stored layout version; `None` for state written before versioning was introduced.
+pub fn stored_state_version() -> Option<u32> {
+    env::storage_read(STATE_VERSION_KEY)
+        .map(|bytes| u32::try_from_slice(&bytes).unwrap_or_else(|_| env::panic_str("Corrupt state version")))
+}
+
+/// Record `version` and return the handle kept in the contract state.
+pub fn record_state_version(version: u32) -> LazyOption<u32> {
+    LazyOption::new(STATE_VERSION_KEY, Some(&version))
+}
+
+fn require_code(code: &[u8]) {
+    // Every wasm module starts with the `\0asm` magic
+    if !code.starts_with(b"\0asm") {
+        env::panic_str("Code is not a wasm module");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn version_is_none_until_recorded() {
+        testing_env!(VMContextBuilder::new().build());
+        assert_eq!(stored_state_version(), None);
+        let version = record_state_version(3);
+        assert_eq!(stored_state_version(), Some(3));
+        assert_eq!(version.get(), Some(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Code is not a wasm module")]
+    fn rejects_non_wasm_code() {
+        testing_env!(VMContextBuilder::new().build());
+        deploy_and_migrate(b"not wasm".to_vec(), b"{}".to_vec());
+    }
+}
@@ -346,7 +346,8 @@ impl VotingContract {
             for (i, &v) in poll.votes.iter().enumerate() { if v == max_votes && max_votes > 0 { winner_indices.push(i); } }
 
             // Compute platform fee and per-winner payout
-            let fee = (poll.reward_yocto as u128) * (self.platform_fee_bps as u128) / 10_000u128;
+            let fee = groupweave_utils::apply_bps(poll.reward_yocto, self.platform_fee_bps as u128)
+                .expect("Fee calculation overflow");
             let net = poll.reward_yocto.saturating_sub(fee);
             if fee > 0 {
                 Promise::new(self.owner.clone()).transfer(NearToken::from_yoctonear(fee));